@@ -11,7 +11,9 @@ use tokio::sync::{Mutex, mpsc};
 
 use serde_json::Value;
 
+use super::fs::{DEFAULT_MAX_READ_BYTES, read_text_file, write_text_file};
 use super::protocol::{AskUserOption, AskUserRequestParams, AskUserResponse, *};
+use super::recorder::{Direction, MessageRecorder};
 use crate::log;
 use crate::session::AgentType;
 
@@ -37,6 +39,7 @@ pub enum AgentEvent {
     SessionCreated {
         session_id: String,
         models: Option<ModelsState>,
+        modes: Option<ModesState>,
     },
     Update {
         session_id: String,
@@ -47,6 +50,7 @@ pub enum AgentEvent {
         session_id: String,
         tool_call_id: String,
         title: Option<String>,
+        kind: Option<ToolCallKind>,
         options: Vec<PermissionOptionInfo>,
     },
     AskUserRequest {
@@ -62,14 +66,140 @@ pub enum AgentEvent {
     FileWritten {
         session_id: String,
         path: String,
+        /// Whether the write created the file
+        created: bool,
+        old_content: String,
+        new_content: String,
         diff: String,
     },
     Error {
         message: String,
     },
+    /// A line the agent process wrote to stderr
+    Stderr {
+        line: String,
+    },
+    /// A line on stdout that isn't JSON-RPC, e.g. log output of the agent
+    StdoutNoise {
+        line: String,
+    },
+    /// A line of output from the worktree's post-create hook
+    HookOutput {
+        line: String,
+    },
+    /// The post-create hook exited; `error` says why it failed, if it did
+    HookFinished {
+        error: Option<String>,
+    },
     Disconnected,
 }
 
+impl AgentEvent {
+    /// Whether this is the agent finishing its turn normally, as opposed to
+    /// stopping on an error, a cancel or a limit
+    pub fn ends_turn(&self) -> bool {
+        matches!(
+            self,
+            AgentEvent::PromptComplete {
+                stop_reason: StopReason::EndTurn
+            }
+        )
+    }
+}
+
+/// Default bytes of output a terminal keeps when the agent sets no limit
+pub const DEFAULT_TERMINAL_OUTPUT_LIMIT: usize = 1024 * 1024;
+
+/// Default shell `terminal/create` commands run in
+pub const DEFAULT_AGENT_SHELL: &str = "sh";
+
+/// Variables of amux's environment `terminal/create` commands keep with
+/// `ClientOptions::clean_env`
+const BASE_ENV_VARS: &[&str] = &["PATH", "HOME", "USER", "LANG", "TERM", "TMPDIR"];
+
+/// Marker put in front of terminal output whose start was cut off
+const TRUNCATED_MARKER: &str = "… truncated …\n";
+
+/// Limits on what the client side of the protocol does for an agent
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Most bytes one `fs/read_text_file` request returns
+    pub max_read_bytes: u64,
+    /// Bytes of output a terminal keeps when `terminal/create` sets no limit
+    pub terminal_output_limit: usize,
+    /// Shell `terminal/create` commands run in (with `-c`)
+    pub shell: String,
+    /// Run that shell as a login shell, so commands get the user's profile
+    /// environment (e.g. PATH) on top of amux's own
+    pub login_shell: bool,
+    /// Give commands only `BASE_ENV_VARS` of amux's environment, not all of
+    /// it, under the env the agent passes
+    pub clean_env: bool,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+            terminal_output_limit: DEFAULT_TERMINAL_OUTPUT_LIMIT,
+            shell: DEFAULT_AGENT_SHELL.to_string(),
+            login_shell: false,
+            clean_env: false,
+        }
+    }
+}
+
+/// The command a `terminal/create` request runs: `command` in `options.shell`,
+/// with the agent's `env` on top of amux's environment (or its basics)
+fn terminal_command(
+    options: &ClientOptions,
+    command: &str,
+    cwd: Option<&str>,
+    env: &[(String, String)],
+) -> Command {
+    let mut cmd = Command::new(&options.shell);
+    if options.clean_env {
+        cmd.env_clear();
+        for name in BASE_ENV_VARS {
+            if let Some(value) = std::env::var_os(name) {
+                cmd.env(name, value);
+            }
+        }
+    }
+    if options.login_shell {
+        cmd.arg("-l");
+    }
+    cmd.arg("-c");
+    cmd.arg(command);
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd);
+    }
+    cmd.envs(env.iter().map(|(name, value)| (name, value)));
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+    cmd
+}
+
+/// Keep at most `limit` bytes of `output`, dropping from the start on a char
+/// boundary behind a "… truncated …" marker. Returns whether anything was cut.
+fn truncate_output_start(output: &mut String, limit: usize) -> bool {
+    if output.len() <= limit {
+        return false;
+    }
+    // The marker counts against the limit, unless the limit is too small for it
+    let marker = if limit > TRUNCATED_MARKER.len() {
+        TRUNCATED_MARKER
+    } else {
+        ""
+    };
+    let mut start = output.len() - (limit - marker.len());
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    *output = format!("{}{}", marker, &output[start..]);
+    true
+}
+
 /// Connection to an ACP agent
 pub struct AgentConnection {
     child: Child,
@@ -87,13 +217,19 @@ impl AgentConnection {
         agent_type: AgentType,
         cwd: &Path,
         event_tx: mpsc::Sender<AgentEvent>,
+        recorder: MessageRecorder,
+        options: ClientOptions,
     ) -> Result<Self> {
         let mut cmd = Command::new(agent_type.command());
         cmd.args(agent_type.args())
             .current_dir(cwd)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::null());
+            .stderr(Stdio::piped());
+
+        if let Some(env) = agent_type.env() {
+            cmd.envs(env);
+        }
 
         // For Claude Code ACP adapter, pass custom Claude executable if available
         if matches!(agent_type, AgentType::ClaudeCode)
@@ -106,15 +242,18 @@ impl AgentConnection {
 
         let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
         let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
+        let stderr = child.stderr.take().ok_or_else(|| anyhow!("No stderr"))?;
 
         // Channel for sending messages to the write task
         let (tx, mut rx) = mpsc::channel::<String>(32);
 
         // Spawn write task
         let mut stdin = stdin;
+        let outgoing_recorder = recorder.clone();
         tokio::spawn(async move {
             while let Some(msg) = rx.recv().await {
                 log::log_outgoing(&msg);
+                outgoing_recorder.record(Direction::Outgoing, &msg);
                 if stdin.write_all(msg.as_bytes()).await.is_err() {
                     break;
                 }
@@ -125,6 +264,19 @@ impl AgentConnection {
             }
         });
 
+        // Spawn stderr task - forwards diagnostics (crashes, warnings) line by line
+        let stderr_tx = event_tx.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                log::log(&format!("Agent stderr: {}", line));
+                let _ = stderr_tx.send(AgentEvent::Stderr { line }).await;
+            }
+        });
+
         // Spawn read task
         let event_tx_clone = event_tx.clone();
         let response_tx = tx.clone(); // For sending responses to fs/terminal requests
@@ -143,6 +295,7 @@ impl AgentConnection {
                 }
 
                 log::log_incoming(&line);
+                recorder.record(Direction::Incoming, &line);
 
                 match IncomingMessage::parse(&line) {
                     Ok(IncomingMessage::Response(resp)) => {
@@ -171,6 +324,7 @@ impl AgentConnection {
                                     .send(AgentEvent::SessionCreated {
                                         session_id: session.session_id,
                                         models: session.models,
+                                        modes: session.modes,
                                     })
                                     .await;
                             } else if let Ok(prompt) =
@@ -189,6 +343,7 @@ impl AgentConnection {
                                     .send(AgentEvent::SessionCreated {
                                         session_id: String::new(),
                                         models: None,
+                                        modes: None,
                                     })
                                     .await;
                             }
@@ -262,6 +417,7 @@ impl AgentConnection {
                                                 session_id: perm_req.session_id,
                                                 tool_call_id: perm_req.tool_call.tool_call_id,
                                                 title: perm_req.tool_call.title,
+                                                kind: perm_req.tool_call.kind,
                                                 options: perm_req.options,
                                             })
                                             .await;
@@ -317,31 +473,17 @@ impl AgentConnection {
                                 match serde_json::from_value::<FsReadTextFileParams>(params.clone())
                                 {
                                     Ok(fs_params) => {
-                                        // Read the file
-                                        let result = match tokio::fs::read_to_string(
-                                            &fs_params.path,
+                                        // Read the file (or the requested lines of it),
+                                        // refusing binary and oversized files
+                                        let result = match read_text_file(
+                                            Path::new(&fs_params.path),
+                                            fs_params.line,
+                                            fs_params.limit,
+                                            options.max_read_bytes,
                                         )
                                         .await
                                         {
-                                            Ok(mut content) => {
-                                                // Apply line/limit if specified
-                                                if fs_params.line.is_some()
-                                                    || fs_params.limit.is_some()
-                                                {
-                                                    let lines: Vec<&str> =
-                                                        content.lines().collect();
-                                                    let start = fs_params
-                                                        .line
-                                                        .unwrap_or(1)
-                                                        .saturating_sub(1)
-                                                        as usize;
-                                                    let limit = fs_params.limit.unwrap_or(u32::MAX)
-                                                        as usize;
-                                                    // Clamp start to file length to prevent panic
-                                                    let start = start.min(lines.len());
-                                                    let end = (start + limit).min(lines.len());
-                                                    content = lines[start..end].join("\n");
-                                                }
+                                            Ok(content) => {
                                                 serde_json::json!({
                                                     "jsonrpc": "2.0",
                                                     "id": id,
@@ -357,7 +499,7 @@ impl AgentConnection {
                                                     "id": id,
                                                     "error": {
                                                         "code": -32000,
-                                                        "message": format!("Failed to read file: {}", e)
+                                                        "message": e
                                                     }
                                                 })
                                             }
@@ -389,20 +531,17 @@ impl AgentConnection {
                                     params.clone(),
                                 ) {
                                     Ok(fs_params) => {
-                                        // Read old content for diff (if file exists)
-                                        let old_content =
-                                            tokio::fs::read_to_string(&fs_params.path).await.ok();
-
-                                        let result = match tokio::fs::write(
-                                            &fs_params.path,
+                                        // Write, getting the content it replaced for the diff
+                                        let result = match write_text_file(
+                                            Path::new(&fs_params.path),
                                             &fs_params.content,
                                         )
                                         .await
                                         {
-                                            Ok(()) => {
+                                            Ok(replaced) => {
                                                 // Generate and send diff
                                                 let diff = generate_diff(
-                                                    old_content.as_deref().unwrap_or(""),
+                                                    &replaced.old_content,
                                                     &fs_params.content,
                                                     &fs_params.path,
                                                 );
@@ -410,6 +549,9 @@ impl AgentConnection {
                                                     .send(AgentEvent::FileWritten {
                                                         session_id: fs_params.session_id.clone(),
                                                         path: fs_params.path.clone(),
+                                                        created: replaced.created,
+                                                        old_content: replaced.old_content,
+                                                        new_content: fs_params.content.clone(),
                                                         diff,
                                                     })
                                                     .await;
@@ -507,26 +649,24 @@ impl AgentConnection {
                                             .iter()
                                             .map(|e| (e.name.clone(), e.value.clone()))
                                             .collect();
-                                        let output_limit = term_params.output_byte_limit;
+                                        let output_limit = term_params
+                                            .output_byte_limit
+                                            .unwrap_or(options.terminal_output_limit);
+                                        let options = options.clone();
 
                                         // Spawn command execution in background - doesn't block message loop
                                         let terminals_clone = Arc::clone(&terminals);
                                         let terminal_id_clone = terminal_id.clone();
                                         tokio::spawn(async move {
                                             // Use tokio::process::Command directly (async native)
-                                            let mut cmd = Command::new("sh");
-                                            cmd.arg("-c");
-                                            cmd.arg(&full_command);
-                                            if let Some(cwd) = &cwd {
-                                                cmd.current_dir(cwd);
-                                            }
-                                            for (name, value) in &env_vars {
-                                                cmd.env(name, value);
-                                            }
-                                            cmd.stdout(Stdio::piped());
-                                            cmd.stderr(Stdio::piped());
-
-                                            let result = cmd.output().await;
+                                            let result = terminal_command(
+                                                &options,
+                                                &full_command,
+                                                cwd.as_deref(),
+                                                &env_vars,
+                                            )
+                                            .output()
+                                            .await;
 
                                             // Update terminal with results
                                             let mut terms = terminals_clone.lock().await;
@@ -542,17 +682,11 @@ impl AgentConnection {
                                                             &output.stderr,
                                                         ));
 
-                                                        // Apply output byte limit if specified
-                                                        let was_truncated = if let Some(limit) =
-                                                            output_limit
-                                                            && out.len() > limit
-                                                        {
-                                                            out = out[out.len() - limit..]
-                                                                .to_string();
-                                                            true
-                                                        } else {
-                                                            false
-                                                        };
+                                                        // Keep the end, where results and errors are
+                                                        let was_truncated = truncate_output_start(
+                                                            &mut out,
+                                                            output_limit,
+                                                        );
 
                                                         terminal.output = out;
                                                         terminal.truncated = was_truncated;
@@ -771,6 +905,12 @@ impl AgentConnection {
                                 .await;
                         }
                     }
+                    // Not JSON at all: log output some agents print to stdout.
+                    // Show it as a diagnostic rather than failing the prompt.
+                    Err(e) if e.is_syntax() || e.is_eof() => {
+                        log::log(&format!("Agent stdout (not JSON): {}", line));
+                        let _ = event_tx_clone.send(AgentEvent::StdoutNoise { line }).await;
+                    }
                     Err(e) => {
                         let _ = event_tx_clone
                             .send(AgentEvent::Error {
@@ -1048,3 +1188,75 @@ fn generate_diff(old: &str, new: &str, _path: &str) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_a_normal_stop_ends_the_turn() {
+        let complete = |stop_reason| AgentEvent::PromptComplete { stop_reason };
+        assert!(complete(StopReason::EndTurn).ends_turn());
+        assert!(!complete(StopReason::Cancelled).ends_turn());
+        assert!(!complete(StopReason::Refusal).ends_turn());
+        let error = AgentEvent::Error {
+            message: "boom".to_string(),
+        };
+        assert!(!error.ends_turn());
+        assert!(!AgentEvent::Disconnected.ends_turn());
+    }
+
+    /// Names of the variables `terminal_command` gives a command
+    async fn command_env(options: &ClientOptions) -> Vec<String> {
+        let env = [("AMUX_AGENT_VAR".to_string(), "1".to_string())];
+        let output = terminal_command(options, "env", None, &env)
+            .output()
+            .await
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('=').map(|(name, _)| name.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_terminal_command_env() {
+        // Set by cargo for the tests, and not one of the basics
+        assert!(std::env::var_os("CARGO_MANIFEST_DIR").is_some());
+
+        let inherited = command_env(&ClientOptions::default()).await;
+        assert!(inherited.iter().any(|name| name == "CARGO_MANIFEST_DIR"));
+        assert!(inherited.iter().any(|name| name == "AMUX_AGENT_VAR"));
+
+        let options = ClientOptions {
+            clean_env: true,
+            ..ClientOptions::default()
+        };
+        let clean = command_env(&options).await;
+        assert!(!clean.iter().any(|name| name == "CARGO_MANIFEST_DIR"));
+        assert!(clean.iter().any(|name| name == "AMUX_AGENT_VAR"));
+        assert!(clean.iter().any(|name| name == "PATH"));
+    }
+
+    #[test]
+    fn test_truncate_output_start_respects_char_boundaries() {
+        let mut output = "short".to_string();
+        assert!(!truncate_output_start(&mut output, 5));
+        assert_eq!(output, "short");
+
+        // "é" is two bytes; a cut through the middle of one moves past it
+        let body = "é".repeat(40);
+        for limit in TRUNCATED_MARKER.len() + 4..TRUNCATED_MARKER.len() + 12 {
+            let mut output = format!("{}done", body);
+            assert!(truncate_output_start(&mut output, limit));
+            assert!(output.len() <= limit);
+            assert!(output.starts_with(TRUNCATED_MARKER));
+            assert!(output.ends_with("done"));
+        }
+
+        // Too small for the marker: just the tail
+        let mut output = "ééé".to_string();
+        assert!(truncate_output_start(&mut output, 3));
+        assert_eq!(output, "é");
+    }
+}
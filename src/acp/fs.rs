@@ -0,0 +1,324 @@
+//! Reading and writing files for the agent (`fs/read_text_file` and
+//! `fs/write_text_file`).
+//!
+//! Whole-file reads are refused above a size limit, and files that aren't
+//! UTF-8 text are refused outright, so an agent asking for a binary asset or a
+//! huge log gets an error it can act on instead of a failed (or stalled) read.
+//! Reads of a line range stream the file, so they work on files of any size,
+//! and never hold more than the limit, not even of a single huge line. Only
+//! regular files are read: devices and FIFOs have no size to check.
+//!
+//! Writes to the same path are serialized across all sessions, so the prior
+//! content each write reports (and its diff is built from) is what it replaced.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::sync::Mutex;
+
+/// Default cap in bytes on what one `fs/read_text_file` request returns
+pub const DEFAULT_MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+/// How much of a whole file is checked for NUL bytes before reading it as text
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Read `path` as text, optionally only `limit` lines from the 1-based `line`.
+/// Errors are messages meant for the agent.
+pub async fn read_text_file(
+    path: &Path,
+    line: Option<u32>,
+    limit: Option<u32>,
+    max_bytes: u64,
+) -> Result<String, String> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a regular file", path.display()));
+    }
+
+    if line.is_some() || limit.is_some() {
+        let start = line.unwrap_or(1).saturating_sub(1) as usize;
+        let limit = limit.unwrap_or(u32::MAX) as usize;
+        return read_lines(path, start, limit, max_bytes).await;
+    }
+
+    let size = metadata.len();
+    if size > max_bytes {
+        return Err(format!(
+            "File is {} bytes, over the {} byte read limit; read it in parts with line and limit",
+            size, max_bytes
+        ));
+    }
+
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    decode_text(bytes, BINARY_SNIFF_BYTES).ok_or_else(|| binary_error(path))
+}
+
+/// Stream lines `start..start + limit`, joined with `\n` like `str::lines`
+async fn read_lines(
+    path: &Path,
+    start: usize,
+    limit: usize,
+    max_bytes: u64,
+) -> Result<String, String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut reader = BufReader::new(file);
+
+    for _ in 0..start {
+        let skipped = skip_line(&mut reader)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if !skipped {
+            return Ok(String::new());
+        }
+    }
+
+    let mut lines = vec![];
+    let mut total = 0u64;
+    let mut buf = vec![];
+    while lines.len() < limit {
+        // Read no more than fits the limit, plus room for a "\r\n"; a line
+        // that doesn't end by then is over it
+        buf.clear();
+        let room = max_bytes.saturating_sub(total) + 2;
+        let read = (&mut reader)
+            .take(room)
+            .read_until(b'\n', &mut buf)
+            .await
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        if buf.ends_with(b"\n") {
+            buf.pop();
+            if buf.ends_with(b"\r") {
+                buf.pop();
+            }
+        }
+        total += buf.len() as u64 + 1;
+        if total > max_bytes {
+            return Err(format!(
+                "The requested lines are over the {} byte read limit; request fewer lines",
+                max_bytes
+            ));
+        }
+        let text =
+            decode_text(std::mem::take(&mut buf), usize::MAX).ok_or_else(|| binary_error(path))?;
+        lines.push(text);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Move past the next line without keeping it. Returns false at the end of
+/// the file.
+async fn skip_line(reader: &mut BufReader<tokio::fs::File>) -> std::io::Result<bool> {
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok(false);
+        }
+        match available.iter().position(|&b| b == b'\n') {
+            Some(end) => {
+                reader.consume(end + 1);
+                return Ok(true);
+            }
+            None => {
+                let len = available.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
+/// One lock per path being written, shared by every agent connection
+type WriteLocks = std::sync::Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>;
+static WRITE_LOCKS: LazyLock<WriteLocks> = LazyLock::new(Default::default);
+
+/// What a write replaced
+#[derive(Debug, PartialEq)]
+pub struct Replaced {
+    /// Whether the write created the file
+    pub created: bool,
+    /// What the file held before (empty if it didn't exist or wasn't text)
+    pub old_content: String,
+}
+
+/// Write `content` to `path`, returning what it replaced
+pub async fn write_text_file(path: &Path, content: &str) -> std::io::Result<Replaced> {
+    let lock = WRITE_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(path.to_path_buf())
+        .or_default()
+        .clone();
+
+    let result = {
+        let _guard = lock.lock().await;
+        let created = !tokio::fs::try_exists(path).await.unwrap_or(false);
+        let old_content = tokio::fs::read_to_string(path).await.unwrap_or_default();
+        tokio::fs::write(path, content).await.map(|()| Replaced {
+            created,
+            old_content,
+        })
+    };
+
+    // Forget the lock once nobody else is waiting on it
+    let mut locks = WRITE_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    if Arc::strong_count(&lock) == 2 {
+        locks.remove(path);
+    }
+    result
+}
+
+/// The bytes as a string, or None if they look binary: a NUL byte within the
+/// first `sniff` bytes, or invalid UTF-8 anywhere
+fn decode_text(bytes: Vec<u8>, sniff: usize) -> Option<String> {
+    if bytes[..bytes.len().min(sniff)].contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn binary_error(path: &Path) -> String {
+    format!("{} is a binary file, not text", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_text_file() {
+        let root = std::env::temp_dir().join(format!("amux_fs_read_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let text = root.join("notes.txt");
+        std::fs::write(&text, "one\r\ntwo\nthree\n").unwrap();
+        let binary = root.join("logo.png");
+        std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 0, 0, 13]).unwrap();
+
+        assert_eq!(
+            read_text_file(&text, None, None, 100).await.unwrap(),
+            "one\r\ntwo\nthree\n"
+        );
+        assert_eq!(
+            read_text_file(&text, Some(2), Some(5), 100).await.unwrap(),
+            "two\nthree"
+        );
+        assert_eq!(
+            read_text_file(&text, None, Some(1), 100).await.unwrap(),
+            "one"
+        );
+        assert_eq!(
+            read_text_file(&text, Some(10), None, 100).await.unwrap(),
+            ""
+        );
+
+        // Too big to read whole, but a slice within the limit still works
+        assert!(
+            read_text_file(&text, None, None, 5)
+                .await
+                .unwrap_err()
+                .contains("over the 5 byte read limit")
+        );
+        assert_eq!(
+            read_text_file(&text, Some(1), Some(1), 5).await.unwrap(),
+            "one"
+        );
+
+        assert!(
+            read_text_file(&binary, None, None, 100)
+                .await
+                .unwrap_err()
+                .contains("is a binary file")
+        );
+        // A huge line is never read whole: skipped ones are streamed past,
+        // and requested ones stop at the limit
+        let long = root.join("bundle.min.js");
+        std::fs::write(&long, format!("{}\nshort", "x".repeat(100_000))).unwrap();
+        assert_eq!(
+            read_text_file(&long, Some(2), Some(1), 10).await.unwrap(),
+            "short"
+        );
+        assert!(
+            read_text_file(&long, Some(1), Some(1), 10)
+                .await
+                .unwrap_err()
+                .contains("over the 10 byte read limit")
+        );
+
+        // Devices and FIFOs have no size to check
+        for line in [None, Some(1)] {
+            assert!(
+                read_text_file(Path::new("/dev/zero"), line, None, 100)
+                    .await
+                    .unwrap_err()
+                    .contains("is not a regular file")
+            );
+        }
+
+        assert!(
+            read_text_file(&root.join("missing"), None, None, 100)
+                .await
+                .unwrap_err()
+                .starts_with("Failed to read file")
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_see_each_others_content() {
+        let root = std::env::temp_dir().join(format!("amux_fs_write_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("main.rs");
+        std::fs::write(&path, "original").unwrap();
+
+        let (first, second) = tokio::join!(
+            write_text_file(&path, "first"),
+            write_text_file(&path, "second")
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        // Whichever ran second replaced the other's content, not the original
+        let last = std::fs::read_to_string(&path).unwrap();
+        if last == "second" {
+            assert_eq!(first.old_content, "original");
+            assert_eq!(second.old_content, "first");
+        } else {
+            assert_eq!(second.old_content, "original");
+            assert_eq!(first.old_content, "second");
+        }
+        assert!(!first.created && !second.created);
+        assert!(!WRITE_LOCKS.lock().unwrap().contains_key(&path));
+
+        assert_eq!(
+            write_text_file(&root.join("new.rs"), "fn main() {}")
+                .await
+                .unwrap(),
+            Replaced {
+                created: true,
+                old_content: String::new(),
+            }
+        );
+
+        // An existing file that isn't UTF-8 is still overwritten, not created
+        let binary = root.join("logo.png");
+        std::fs::write(&binary, [0x89, 0xff, 0x00]).unwrap();
+        assert_eq!(
+            write_text_file(&binary, "text").await.unwrap(),
+            Replaced {
+                created: false,
+                old_content: String::new(),
+            }
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,8 +1,16 @@
 mod client;
+mod fs;
 pub mod protocol;
+mod recorder;
 
-pub use client::{AgentConnection, AgentEvent};
+pub use client::{
+    AgentConnection, AgentEvent, ClientOptions, DEFAULT_AGENT_SHELL, DEFAULT_TERMINAL_OUTPUT_LIMIT,
+};
+pub use fs::DEFAULT_MAX_READ_BYTES;
+pub use protocol::accepts_images;
 pub use protocol::{
-    AgentCommand, AskUserOption, AskUserResponse, ContentBlock, McpServer, ModelInfo,
-    PermissionKind, PermissionOptionId, PermissionOptionInfo, PlanEntry, PlanStatus, SessionUpdate,
+    AgentCommand, AskUserOption, AskUserResponse, ContentBlock, FollowUp, McpServer, ModeInfo,
+    ModelInfo, PermissionKind, PermissionOptionId, PermissionOptionInfo, PlanEntry, PlanStatus,
+    SessionUpdate, ToolCallKind,
 };
+pub use recorder::{MessageRecorder, export_file_name};
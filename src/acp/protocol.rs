@@ -116,11 +116,20 @@ pub struct AgentInfo {
     pub version: Option<String>,
 }
 
+/// Whether an agent's capabilities (from `initialize`) accept images in
+/// prompts. The ACP baseline is text only, so an unreported flag means no.
+pub fn accepts_images(agent_capabilities: &Value) -> bool {
+    agent_capabilities
+        .pointer("/promptCapabilities/image")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
 // ============================================================================
 // Session types
 // ============================================================================
 
-/// Environment variable for MCP server
+/// Environment variable (or HTTP header) for MCP server
 #[derive(Debug, Serialize, Clone)]
 pub struct McpEnvVar {
     pub name: String,
@@ -129,29 +138,50 @@ pub struct McpEnvVar {
 
 /// MCP server configuration for ACP protocol
 #[derive(Debug, Serialize, Clone)]
-pub struct McpServer {
-    pub name: String,
-    pub command: String,
-    /// Command-line arguments (required, can be empty array)
-    pub args: Vec<String>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub env: Vec<McpEnvVar>,
+#[serde(untagged)]
+pub enum McpServer {
+    /// Started by the agent as a subprocess (no `type` field)
+    Stdio {
+        name: String,
+        command: String,
+        /// Command-line arguments (required, can be empty array)
+        args: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        env: Vec<McpEnvVar>,
+    },
+    /// Reached over the network: `type` is "http" or "sse"
+    Remote {
+        #[serde(rename = "type")]
+        transport: &'static str,
+        name: String,
+        url: String,
+        headers: Vec<McpEnvVar>,
+    },
 }
 
 impl From<&crate::config::McpServerConfig> for McpServer {
     fn from(config: &crate::config::McpServerConfig) -> Self {
-        Self {
-            name: config.name.clone(),
-            command: config.command.clone(),
-            args: config.args.clone(),
-            env: config
-                .env
-                .iter()
+        let pairs = |map: &std::collections::HashMap<String, String>| {
+            map.iter()
                 .map(|(k, v)| McpEnvVar {
                     name: k.clone(),
                     value: v.clone(),
                 })
-                .collect(),
+                .collect()
+        };
+        match config.transport {
+            crate::config::McpTransport::Stdio => Self::Stdio {
+                name: config.name.clone(),
+                command: config.command.clone(),
+                args: config.args.clone(),
+                env: pairs(&config.env),
+            },
+            transport => Self::Remote {
+                transport: transport.label(),
+                name: config.name.clone(),
+                url: config.url.clone(),
+                headers: pairs(&config.headers),
+            },
         }
     }
 }
@@ -183,6 +213,24 @@ pub struct SetModeParams {
 pub struct NewSessionResult {
     pub session_id: String,
     pub models: Option<ModelsState>,
+    pub modes: Option<ModesState>,
+}
+
+/// Mode selection state from session (e.g. plan / act / auto)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModesState {
+    pub available_modes: Vec<ModeInfo>,
+    pub current_mode_id: String,
+}
+
+/// Information about an available mode
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeInfo {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
 }
 
 /// Model selection state from session
@@ -315,6 +363,38 @@ pub struct AgentCommand {
     pub input: Option<CommandInput>,
 }
 
+/// A prompt the agent suggests sending next
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowUp {
+    /// Short text shown on the chip
+    pub label: String,
+    /// Prompt sent when the chip is picked
+    pub prompt: String,
+}
+
+impl FollowUp {
+    /// Parse a suggestion given as a plain string or as an object with a
+    /// `prompt` (or `text`) and an optional `label` (or `title`)
+    fn from_value(value: &Value) -> Option<Self> {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| value.get(name).and_then(|v| v.as_str()))
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        let (label, prompt) = match value.as_str().map(str::trim) {
+            Some(text) => (None, Some(text).filter(|s| !s.is_empty())),
+            None => (field(&["label", "title"]), field(&["prompt", "text"])),
+        };
+        let prompt = prompt?;
+        Some(Self {
+            label: label.unwrap_or(prompt).to_string(),
+            prompt: prompt.to_string(),
+        })
+    }
+}
+
 /// Input specification for a command
 #[derive(Debug, Deserialize, Clone)]
 pub struct CommandInput {
@@ -383,6 +463,11 @@ pub enum SessionUpdate {
     AvailableCommandsUpdate {
         commands: Vec<AgentCommand>,
     },
+    /// Prompts the agent suggests sending next. Not part of ACP; agents that
+    /// offer follow-ups send `follow_up_suggestions` at the end of a turn.
+    FollowUpSuggestions {
+        suggestions: Vec<FollowUp>,
+    },
     Other {
         raw_type: Option<String>,
     },
@@ -494,6 +579,14 @@ impl<'de> serde::Deserialize<'de> for SessionUpdate {
                     .unwrap_or_default();
                 Ok(SessionUpdate::AvailableCommandsUpdate { commands })
             }
+            Some("follow_up_suggestions") => {
+                let suggestions = value
+                    .get("suggestions")
+                    .and_then(|v| v.as_array())
+                    .map(|items| items.iter().filter_map(FollowUp::from_value).collect())
+                    .unwrap_or_default();
+                Ok(SessionUpdate::FollowUpSuggestions { suggestions })
+            }
             other => Ok(SessionUpdate::Other {
                 raw_type: other.map(|s| s.to_string()),
             }),
@@ -572,6 +665,8 @@ pub struct ToolCallInfo {
     pub tool_call_id: String,
     #[serde(default)]
     pub title: Option<String>,
+    #[serde(default)]
+    pub kind: Option<ToolCallKind>,
 }
 
 /// Permission option info (for parsing, maps to PermissionOption)
@@ -777,3 +872,60 @@ impl IncomingMessage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors_distinguish_noise_from_bad_messages() {
+        // Plain log lines and truncated JSON aren't JSON-RPC at all
+        for noise in ["Starting server on :8080", "[INFO] ready", "{\"id\": 1"] {
+            let err = IncomingMessage::parse(noise).unwrap_err();
+            assert!(err.is_syntax() || err.is_eof(), "{noise}");
+        }
+
+        // Valid JSON of the wrong shape is a real protocol error
+        let err = IncomingMessage::parse(r#"{"result": 1}"#).unwrap_err();
+        assert!(err.is_data());
+    }
+
+    #[test]
+    fn test_parse_follow_up_suggestions() {
+        let update: SessionUpdate = serde_json::from_value(serde_json::json!({
+            "sessionUpdate": "follow_up_suggestions",
+            "suggestions": [
+                "Run the tests",
+                {"label": "Commit", "prompt": "Commit the change with a short message"},
+                {"text": "Open a PR"},
+                {"label": "Nothing to send"},
+                " ",
+            ]
+        }))
+        .unwrap();
+        let SessionUpdate::FollowUpSuggestions { suggestions } = update else {
+            panic!("expected follow-up suggestions, got {:?}", update);
+        };
+        let pairs: Vec<(&str, &str)> = suggestions
+            .iter()
+            .map(|s| (s.label.as_str(), s.prompt.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("Run the tests", "Run the tests"),
+                ("Commit", "Commit the change with a short message"),
+                ("Open a PR", "Open a PR"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accepts_images() {
+        let caps = serde_json::json!({"promptCapabilities": {"image": true}});
+        assert!(accepts_images(&caps));
+        let caps = serde_json::json!({"promptCapabilities": {"embeddedContext": true}});
+        assert!(!accepts_images(&caps));
+        assert!(!accepts_images(&serde_json::json!({})));
+    }
+}
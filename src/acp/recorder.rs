@@ -0,0 +1,202 @@
+//! Per-session record of the raw ACP messages exchanged with an agent.
+//!
+//! The global log interleaves every session's traffic and truncates long
+//! messages. Each session instead keeps its own complete transcript, which
+//! survives agent restarts and can be exported as JSON Lines to share a
+//! reproducible trace with agent developers. It's bounded in both messages
+//! and bytes, dropping the oldest first.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Local};
+use serde_json::{Value, json};
+
+use crate::log;
+
+/// Messages kept per session; the oldest are dropped beyond this
+pub const MAX_RECORDED_MESSAGES: usize = 20_000;
+
+/// Bytes of messages kept per session; the oldest are dropped beyond this
+pub const MAX_RECORDED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Which way a message went
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// From the agent to amux
+    Incoming,
+    /// From amux to the agent
+    Outgoing,
+}
+
+impl Direction {
+    fn label(&self) -> &'static str {
+        match self {
+            Direction::Incoming => "incoming",
+            Direction::Outgoing => "outgoing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RecordedMessage {
+    time: DateTime<Local>,
+    direction: Direction,
+    line: String,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    messages: VecDeque<RecordedMessage>,
+    /// Total length of the messages' lines
+    bytes: usize,
+}
+
+/// Shared handle to a session's transcript; clones record into the same one
+#[derive(Debug, Clone, Default)]
+pub struct MessageRecorder(Arc<Mutex<Recording>>);
+
+impl MessageRecorder {
+    /// Record a raw protocol line
+    pub fn record(&self, direction: Direction, line: &str) {
+        if let Ok(mut recording) = self.0.lock() {
+            recording.bytes += line.len();
+            recording.messages.push_back(RecordedMessage {
+                time: Local::now(),
+                direction,
+                line: line.to_string(),
+            });
+            while recording.messages.len() > MAX_RECORDED_MESSAGES
+                || (recording.bytes > MAX_RECORDED_BYTES && recording.messages.len() > 1)
+            {
+                if let Some(dropped) = recording.messages.pop_front() {
+                    recording.bytes -= dropped.line.len();
+                }
+            }
+        }
+    }
+
+    /// Number of recorded messages
+    pub fn len(&self) -> usize {
+        self.0
+            .lock()
+            .map(|recording| recording.messages.len())
+            .unwrap_or(0)
+    }
+
+    /// The transcript as JSON Lines, one `{time, direction, message}` object per
+    /// message with secrets redacted. Lines that aren't JSON are kept as strings.
+    pub fn to_jsonl(&self) -> String {
+        let Ok(recording) = self.0.lock() else {
+            return String::new();
+        };
+        recording
+            .messages
+            .iter()
+            .map(|m| {
+                let line = log::redact_secrets(&m.line);
+                let message = serde_json::from_str::<Value>(&line).unwrap_or(Value::String(line));
+                let entry = json!({
+                    "time": m.time.to_rfc3339(),
+                    "direction": m.direction.label(),
+                    "message": message,
+                });
+                format!("{}\n", entry)
+            })
+            .collect()
+    }
+
+    /// Write the transcript to `path`, creating its directory if needed, and
+    /// return the number of messages written
+    pub fn export(&self, path: &Path) -> std::io::Result<usize> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_jsonl())?;
+        Ok(self.len())
+    }
+}
+
+/// File name for an exported transcript, after the agent's session ID when
+/// there is one (amux's own otherwise)
+pub fn export_file_name(acp_session_id: Option<&str>, session_id: &str) -> String {
+    let id: String = acp_session_id
+        .unwrap_or(session_id)
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("amux-acp-{}.jsonl", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_keeps_order_and_redacts() {
+        let recorder = MessageRecorder::default();
+        let shared = recorder.clone();
+        recorder.record(
+            Direction::Outgoing,
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"apiKey":"abc"}}"#,
+        );
+        shared.record(Direction::Incoming, "not json");
+
+        let lines: Vec<Value> = recorder
+            .to_jsonl()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["direction"], "outgoing");
+        assert_eq!(lines[0]["message"]["method"], "initialize");
+        assert_eq!(lines[0]["message"]["params"]["apiKey"], "[REDACTED]");
+        assert_eq!(lines[1]["direction"], "incoming");
+        assert_eq!(lines[1]["message"], "not json");
+    }
+
+    #[test]
+    fn test_oldest_messages_are_dropped() {
+        let recorder = MessageRecorder::default();
+        for i in 0..MAX_RECORDED_MESSAGES + 5 {
+            recorder.record(Direction::Incoming, &i.to_string());
+        }
+        assert_eq!(recorder.len(), MAX_RECORDED_MESSAGES);
+        assert!(
+            recorder
+                .to_jsonl()
+                .starts_with(r#"{"direction":"incoming","message":5,"#)
+        );
+    }
+
+    #[test]
+    fn test_recording_stays_within_byte_limit() {
+        let recorder = MessageRecorder::default();
+        let line = "x".repeat(MAX_RECORDED_BYTES / 4);
+        for _ in 0..10 {
+            recorder.record(Direction::Incoming, &line);
+        }
+        assert_eq!(recorder.len(), 4);
+        assert_eq!(recorder.0.lock().unwrap().bytes, MAX_RECORDED_BYTES);
+
+        // A single message over the limit is still kept
+        recorder.record(Direction::Incoming, &"y".repeat(MAX_RECORDED_BYTES + 1));
+        assert_eq!(recorder.len(), 1);
+    }
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(
+            export_file_name(Some("sess/01:ab"), "3"),
+            "amux-acp-sess_01_ab.jsonl"
+        );
+        assert_eq!(export_file_name(None, "3"), "amux-acp-3.jsonl");
+    }
+}
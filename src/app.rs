@@ -1,13 +1,33 @@
 use std::path::PathBuf;
-
-use crate::config::McpServerConfig;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::acp::{ClientOptions, PlanStatus};
+use crate::clipboard::ClipboardContent;
+use crate::config::{
+    DEFAULT_BUG_REPORT_REPO, DEFAULT_EXPLAIN_ERROR_PROMPT, DEFAULT_IDLE_TICK_RATE_MS,
+    DEFAULT_LARGE_PROMPT_THRESHOLD, DEFAULT_REPEAT_KEY, DEFAULT_TICK_RATE_MS, McpServerConfig,
+    SidebarWidth, SubmitKey, TrustedDirs,
+};
+use crate::diff::{FileDiff, SideBySideRow};
+use crate::events::Action;
+use crate::file_ref::FileLocation;
+use crate::mention::{MentionCompletion, mention_at_cursor, split_fragment};
 use crate::notification::{NotificationConfig, NotificationManager};
-use crate::picker::Picker;
-use crate::session::{AgentAvailability, AgentType, Session, SessionManager};
+use crate::picker::{Picker, fuzzy_match};
+use crate::session::{
+    AgentAvailability, AgentType, DEFAULT_MAX_SCROLLBACK, OutputType, Session, SessionManager,
+    SessionState, ToolPermissionRules, Transcript,
+};
+use crate::slash_command::{SlashCommandCompletion, command_at_cursor};
+use crate::snippets::{SnippetPickerState, Snippets};
 use crate::tui::interaction::InteractionRegistry;
+use crate::ui_state::UiState;
 
 /// Sort/view mode for the session list
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum SortMode {
     /// Flat list in creation order
     #[default]
@@ -65,6 +85,36 @@ pub enum InputMode {
     WorktreeCleanupRepoPicker, // Selecting git repo for worktree cleanup
     BugReport,                 // Entering bug report description
     ClearConfirm,              // Confirming session clear
+    DiffView,                  // Full-screen side-by-side diff
+    OutputFilter,              // Entering a regex to filter session output
+    ConfirmLargePrompt,        // Confirming a prompt above the size threshold
+    PlanFocus,                 // Navigating the plan entries in the sidebar
+    Broadcast,                 // Typing a prompt to send to every session
+    LogView,                   // Full-screen tail of the log file
+    ToolJson,                  // Inspecting one tool call's raw JSON
+    SessionSwitcher,           // Fuzzy-finding a session to jump to
+    QuitConfirm,               // Confirming quit while sessions are working
+    McpManager,                // Toggling MCP servers for new sessions
+    SessionLimitConfirm,       // Confirming a session beyond `max_sessions`
+    SnippetSave,               // Naming the prompt to save as a snippet
+    SnippetPicker,             // Fuzzy-finding a snippet to insert into the prompt
+}
+
+/// A session waiting for confirmation because `max_sessions` is reached
+#[derive(Debug, Clone)]
+pub struct PendingSpawn {
+    pub agent_type: AgentType,
+    pub cwd: PathBuf,
+    pub is_worktree: bool,
+}
+
+/// A session started at launch (`--agent` / `--prompt-file`) instead of
+/// opening the folder picker
+#[derive(Debug, Clone)]
+pub struct StartupSession {
+    pub agent_type: AgentType,
+    /// Sent as the first prompt once the agent is ready
+    pub prompt: Option<String>,
 }
 
 /// Entry in the folder picker
@@ -89,6 +139,8 @@ pub struct FolderPickerState {
     pub query: String,
     /// Cursor position in the query input
     pub query_cursor: usize,
+    /// Visible entries (updated during render)
+    pub viewport_height: usize,
 }
 
 impl FolderPickerState {
@@ -100,24 +152,26 @@ impl FolderPickerState {
             all_entries: vec![],
             query: String::new(),
             query_cursor: 0,
+            viewport_height: 10, // Default, updated on render
         }
     }
 
     /// Update the filtered list based on the current query
     pub fn update_filter(&mut self) {
-        let query_lower = self.query.to_lowercase();
-        self.entries = self
+        let mut scored: Vec<(i64, &FolderEntry)> = self
             .all_entries
             .iter()
-            .filter(|e| {
+            .filter_map(|e| {
                 // When filtering, skip parent entry so it doesn't always match first
-                if e.is_parent {
-                    return query_lower.is_empty();
+                if e.is_parent && !self.query.is_empty() {
+                    return None;
                 }
-                e.name.to_lowercase().contains(&query_lower)
+                fuzzy_match(&self.query, &e.name).map(|score| (score, e))
             })
-            .cloned()
             .collect();
+        // Stable sort keeps directory order for equal scores (and empty queries)
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.entries = scored.into_iter().map(|(_, e)| e.clone()).collect();
 
         // Adjust selected index to stay within bounds
         if self.entries.is_empty() {
@@ -231,6 +285,85 @@ impl Picker for SessionPickerState {
     }
 }
 
+/// Searchable text for one session in the quick switcher
+#[derive(Debug, Clone)]
+pub struct SwitcherCandidate {
+    /// Internal index into the session list
+    pub index: usize,
+    pub name: String,
+    pub git_branch: String,
+}
+
+/// State for the session quick switcher
+#[derive(Debug, Clone)]
+pub struct SessionSwitcherState {
+    /// Every session, in display order
+    pub candidates: Vec<SwitcherCandidate>,
+    /// Internal session indices matching the query, best match first
+    pub filtered: Vec<usize>,
+    /// Filter query string
+    pub query: String,
+    pub selected: usize,
+}
+
+impl SessionSwitcherState {
+    pub fn new(candidates: Vec<SwitcherCandidate>) -> Self {
+        let filtered = candidates.iter().map(|c| c.index).collect();
+        Self {
+            candidates,
+            filtered,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Update the filtered list based on the current query.
+    /// A session scores by its better match of name and branch; ties keep display order.
+    pub fn update_filter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .candidates
+            .iter()
+            .filter_map(|c| {
+                let name = fuzzy_match(&self.query, &c.name);
+                let branch = fuzzy_match(&self.query, &c.git_branch);
+                name.max(branch).map(|score| (score, c.index))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.filtered = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+
+    /// Add a character to the query
+    pub fn query_input_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_filter();
+    }
+
+    /// Delete the last character of the query
+    pub fn query_backspace(&mut self) {
+        if self.query.pop().is_some() {
+            self.update_filter();
+        }
+    }
+}
+
+impl Picker for SessionSwitcherState {
+    type Item = usize;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.filtered
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.selected = index;
+    }
+}
+
 impl FolderPickerState {
     pub fn selected_entry(&self) -> Option<&FolderEntry> {
         self.selected_item()
@@ -267,6 +400,8 @@ pub struct AgentPickerState {
     pub query: String,
     /// Cursor position in the query input
     pub query_cursor: usize,
+    /// Visible entries (updated during render)
+    pub viewport_height: usize,
 }
 
 impl AgentPickerState {
@@ -282,23 +417,21 @@ impl AgentPickerState {
             agents,
             query: String::new(),
             query_cursor: 0,
+            viewport_height: 3, // Default, updated on render
         }
     }
 
     /// Update the filtered list based on the current query
     pub fn update_filter(&mut self) {
-        let query_lower = self.query.to_lowercase();
-        self.filtered = self
+        let mut scored: Vec<(i64, &AgentAvailability)> = self
             .agents
             .iter()
-            .filter(|a| {
-                a.agent_type
-                    .display_name()
-                    .to_lowercase()
-                    .contains(&query_lower)
+            .filter_map(|a| {
+                fuzzy_match(&self.query, a.agent_type.display_name()).map(|score| (score, a))
             })
-            .cloned()
             .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.filtered = scored.into_iter().map(|(_, a)| a.clone()).collect();
 
         // Adjust selected index to stay within bounds
         if self.filtered.is_empty() {
@@ -369,7 +502,7 @@ impl AgentPickerState {
     }
 
     pub fn selected_agent(&self) -> Option<AgentType> {
-        self.selected_item().map(|a| a.agent_type)
+        self.selected_item().map(|a| a.agent_type.clone())
     }
 
     /// Check if any agent is available
@@ -412,6 +545,10 @@ pub struct WorktreeEntry {
 pub struct WorktreePickerState {
     pub entries: Vec<WorktreeEntry>,
     pub selected: usize,
+    /// Set after selecting a dirty worktree; a second select opens it anyway
+    pub confirm_dirty: bool,
+    /// Visible entries (updated during render)
+    pub viewport_height: usize,
 }
 
 impl WorktreePickerState {
@@ -419,6 +556,8 @@ impl WorktreePickerState {
         Self {
             entries,
             selected: 0,
+            confirm_dirty: false,
+            viewport_height: 9, // Default, updated on render
         }
     }
 
@@ -458,8 +597,12 @@ pub struct CleanupEntry {
     pub branch: Option<String>,
     pub is_clean: bool,
     pub is_merged: bool,
+    /// Created on a throwaway branch for a quick experiment ([X])
+    pub is_experiment: bool,
     pub selected: bool,    // Whether this entry is selected for cleanup
     pub is_deleting: bool, // Whether this entry is currently being deleted
+    /// Why the last attempt to delete this entry failed
+    pub error: Option<String>,
 }
 
 /// State for the worktree cleanup picker
@@ -469,6 +612,8 @@ pub struct WorktreeCleanupState {
     pub entries: Vec<CleanupEntry>,
     pub cursor: usize,
     pub delete_branches: bool, // Whether to also delete branches
+    /// Delete dirty worktrees and unmerged branches too, losing their changes
+    pub force: bool,
 }
 
 impl WorktreeCleanupState {
@@ -478,6 +623,7 @@ impl WorktreeCleanupState {
             entries,
             cursor: 0,
             delete_branches: true,
+            force: false,
         }
     }
 
@@ -491,9 +637,14 @@ impl WorktreeCleanupState {
         self.delete_branches = !self.delete_branches;
     }
 
+    pub fn toggle_force(&mut self) {
+        self.force = !self.force;
+    }
+
+    /// Select every clean worktree that is merged, or was only an experiment
     pub fn select_all_cleanable(&mut self) {
         for entry in &mut self.entries {
-            if entry.is_clean && entry.is_merged {
+            if entry.is_clean && (entry.is_merged || entry.is_experiment) {
                 entry.selected = true;
             }
         }
@@ -515,6 +666,18 @@ impl WorktreeCleanupState {
             .filter(|e| e.is_clean && e.is_merged)
             .count()
     }
+
+    /// Number of entries whose deletion failed
+    pub fn failed_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.error.is_some()).count()
+    }
+
+    /// Select only the entries whose deletion failed, to try them again
+    pub fn select_failed(&mut self) {
+        for entry in &mut self.entries {
+            entry.selected = entry.error.is_some();
+        }
+    }
 }
 
 impl Picker for WorktreeCleanupState {
@@ -543,6 +706,16 @@ pub struct BranchInputState {
     pub filtered: Vec<BranchEntry>,
     pub selected: usize,
     pub show_autocomplete: bool,
+    /// Why the current input isn't a valid branch name (set on submit)
+    pub error: Option<&'static str>,
+    /// Sanitized alternative offered alongside `error`, accepted with Tab
+    pub suggestion: Option<String>,
+    /// Agent to start in the new worktree; None asks with the agent picker
+    pub agent_type: Option<AgentType>,
+    /// Whether the input is still choosing the base branch, before the name
+    pub picking_base: bool,
+    /// Where a newly created branch starts; None branches from the repo's HEAD
+    pub base: Option<BranchEntry>,
 }
 
 impl BranchInputState {
@@ -567,10 +740,62 @@ impl BranchInputState {
         self.show_autocomplete = false;
     }
 
+    /// Take the highlighted branch (or the typed ref, if nothing matches) as
+    /// the base, then move on to entering the new branch's name. Choosing
+    /// the checked-out branch keeps branching from HEAD.
+    pub fn choose_base(&mut self) {
+        let typed = self.input.trim();
+        self.base = match self.selected_item() {
+            Some(branch) if branch.is_current => None,
+            Some(branch) => Some(branch.clone()),
+            None if typed.is_empty() => None,
+            None => Some(BranchEntry {
+                name: typed.to_string(),
+                is_current: false,
+                is_remote: false,
+            }),
+        };
+        self.picking_base = false;
+        self.input.clear();
+        self.cursor_position = 0;
+        self.selected = 0;
+        self.show_autocomplete = true;
+        self.update_filter();
+    }
+
     /// Get the branch name to use
     pub fn branch_name(&self) -> &str {
         &self.input
     }
+
+    /// Validate the input against git's ref-name rules, recording an error
+    /// and a sanitized suggestion if it's invalid. Returns true if valid.
+    pub fn validate(&mut self) -> bool {
+        self.error = crate::git::branch_name_error(&self.input);
+        self.suggestion = self
+            .error
+            .and_then(|_| crate::git::sanitize_branch_name(&self.input));
+        self.error.is_none()
+    }
+
+    /// Drop any validation error after the input changes
+    pub fn clear_error(&mut self) {
+        self.error = None;
+        self.suggestion = None;
+    }
+
+    /// Replace the input with the suggested sanitized name, if any.
+    /// Returns true if a suggestion was accepted.
+    pub fn accept_suggestion(&mut self) -> bool {
+        let Some(suggestion) = self.suggestion.take() else {
+            return false;
+        };
+        self.input = suggestion;
+        self.cursor_position = self.input.len();
+        self.error = None;
+        self.update_filter();
+        true
+    }
 }
 
 impl Picker for BranchInputState {
@@ -589,12 +814,75 @@ impl Picker for BranchInputState {
     }
 }
 
+/// A configured MCP server and whether new sessions get it
+#[derive(Debug, Clone)]
+pub struct McpServerEntry {
+    pub config: McpServerConfig,
+    pub enabled: bool,
+}
+
+/// Configured MCP servers, toggled at runtime in the MCP manager
+#[derive(Debug, Clone, Default)]
+pub struct McpServerList {
+    pub servers: Vec<McpServerEntry>,
+    pub selected: usize,
+}
+
+impl McpServerList {
+    /// All servers start enabled
+    pub fn new(configs: Vec<McpServerConfig>) -> Self {
+        Self {
+            servers: configs
+                .into_iter()
+                .map(|config| McpServerEntry {
+                    config,
+                    enabled: true,
+                })
+                .collect(),
+            selected: 0,
+        }
+    }
+
+    /// Servers passed to newly spawned sessions
+    pub fn enabled(&self) -> impl Iterator<Item = &McpServerConfig> {
+        self.servers
+            .iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| &entry.config)
+    }
+
+    /// Enable or disable the selected server
+    pub fn toggle_selected(&mut self) {
+        if let Some(entry) = self.servers.get_mut(self.selected) {
+            entry.enabled = !entry.enabled;
+        }
+    }
+}
+
+impl Picker for McpServerList {
+    type Item = McpServerEntry;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.servers
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.selected = index;
+    }
+}
+
 /// State for bug report input
 #[derive(Debug, Clone)]
 pub struct BugReportState {
     pub description: String,
     pub cursor_position: usize,
     pub log_path: PathBuf,
+    /// Issue body awaiting confirmation, once the description is entered
+    pub preview: Option<String>,
 }
 
 impl BugReportState {
@@ -603,6 +891,22 @@ impl BugReportState {
             description: String::new(),
             cursor_position: 0,
             log_path,
+            preview: None,
+        }
+    }
+
+    /// Issue title: the description's first line, shortened to fit GitHub's lists
+    pub fn title(&self) -> String {
+        const MAX_TITLE_CHARS: usize = 72;
+        let first_line = self.description.trim().lines().next().unwrap_or_default();
+        if first_line.is_empty() {
+            return "Bug report".to_string();
+        }
+        if first_line.chars().count() > MAX_TITLE_CHARS {
+            let short: String = first_line.chars().take(MAX_TITLE_CHARS - 1).collect();
+            format!("{}…", short.trim_end())
+        } else {
+            first_line.to_string()
         }
     }
 
@@ -611,6 +915,28 @@ impl BugReportState {
         self.cursor_position += c.len_utf8();
     }
 
+    /// Markdown body for the issue: the description, recent agent stderr and
+    /// the last ACP messages (already redacted), each in its own section
+    pub fn issue_body(&self, stderr_tail: &[&str], protocol_messages: &[String]) -> String {
+        let mut body = format!("## Description\n\n{}\n", self.description.trim());
+        if !stderr_tail.is_empty() {
+            body.push_str(&format!(
+                "\n## Agent stderr (last {} lines)\n\n```\n{}\n```\n",
+                stderr_tail.len(),
+                stderr_tail.join("\n")
+            ));
+        }
+        if !protocol_messages.is_empty() {
+            body.push_str(&format!(
+                "\n## Recent Protocol Messages (last {})\n\n```\n{}\n```\n",
+                protocol_messages.len(),
+                protocol_messages.join("\n")
+            ));
+        }
+        body.push_str(&format!("\nFull log: `{}`\n", self.log_path.display()));
+        body
+    }
+
     pub fn input_backspace(&mut self) {
         if self.cursor_position > 0 {
             let mut new_pos = self.cursor_position - 1;
@@ -653,43 +979,367 @@ impl BugReportState {
     }
 }
 
+/// State for the full-screen side-by-side diff view
+#[derive(Debug, Clone)]
+pub struct DiffViewState {
+    pub path: String,
+    pub rows: Vec<SideBySideRow>,
+    /// First visible row
+    pub scroll: usize,
+    /// Horizontal scroll offset in characters (applied to both sides)
+    pub h_scroll: usize,
+    /// Visible rows (updated during render)
+    pub viewport_height: usize,
+}
+
+impl DiffViewState {
+    /// Horizontal scroll step in characters
+    const H_SCROLL_STEP: usize = 8;
+
+    pub fn new(file_diff: &FileDiff) -> Self {
+        Self {
+            path: file_diff.path.clone(),
+            rows: file_diff.side_by_side(),
+            scroll: 0,
+            h_scroll: 0,
+            viewport_height: 20, // Default, updated on render
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.rows.len().saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = (self.scroll + n).min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_sub(Self::H_SCROLL_STEP);
+    }
+
+    pub fn scroll_right(&mut self) {
+        let longest = self
+            .rows
+            .iter()
+            .flat_map(|r| [&r.left, &r.right])
+            .filter_map(|side| side.as_ref().map(|(_, text)| text.chars().count()))
+            .max()
+            .unwrap_or(0);
+        if self.h_scroll + Self::H_SCROLL_STEP < longest {
+            self.h_scroll += Self::H_SCROLL_STEP;
+        }
+    }
+}
+
+/// State for the tool call JSON inspector
+#[derive(Debug, Clone)]
+pub struct ToolJsonState {
+    /// Title of the inspected tool call
+    pub title: String,
+    pub lines: Vec<String>,
+    /// First visible row
+    pub scroll: usize,
+    /// Visible rows (updated during render)
+    pub viewport_height: usize,
+    /// Rows the lines take once wrapped (updated during render)
+    pub row_count: usize,
+}
+
+impl ToolJsonState {
+    /// Show the JSON messages of a tool call one after another
+    pub fn new(title: &str, raw_json: &[String]) -> Self {
+        let lines = if raw_json.is_empty() {
+            vec!["No JSON was recorded for this tool call".to_string()]
+        } else {
+            raw_json
+                .iter()
+                .enumerate()
+                .flat_map(|(i, json)| {
+                    let separator = (i > 0).then(String::new);
+                    separator
+                        .into_iter()
+                        .chain(json.lines().map(str::to_string))
+                })
+                .collect()
+        };
+        Self {
+            title: title.to_string(),
+            row_count: lines.len(),
+            lines,
+            scroll: 0,
+            viewport_height: 20, // Default, updated on render
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.row_count.saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = (self.scroll + n).min(self.max_scroll());
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+}
+
+/// State for the log viewer overlay, tailing the current log file
+#[derive(Debug, Clone)]
+pub struct LogViewState {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    /// Bytes of the log file already read
+    read_offset: u64,
+    /// First visible line
+    pub scroll: usize,
+    /// Keep the newest line in view as the log grows
+    pub follow: bool,
+    /// Visible lines (updated during render)
+    pub viewport_height: usize,
+}
+
+impl LogViewState {
+    /// Lines kept in the viewer; older ones are dropped
+    const MAX_LINES: usize = 5000;
+
+    pub fn new(path: PathBuf) -> Self {
+        let mut state = Self {
+            path,
+            lines: vec![],
+            read_offset: 0,
+            scroll: 0,
+            follow: true,
+            viewport_height: 20, // Default, updated on render
+        };
+        state.refresh();
+        state
+    }
+
+    /// Read lines appended to the log file since the last refresh
+    pub fn refresh(&mut self) {
+        let new_lines = crate::log::read_new_lines(&self.path, &mut self.read_offset);
+        if new_lines.is_empty() {
+            return;
+        }
+        self.lines.extend(new_lines);
+        if self.lines.len() > Self::MAX_LINES {
+            let excess = self.lines.len() - Self::MAX_LINES;
+            self.lines.drain(..excess);
+            self.scroll = self.scroll.saturating_sub(excess);
+        }
+        if self.follow {
+            self.scroll_to_bottom();
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.viewport_height)
+    }
+
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+        self.follow = false;
+    }
+
+    /// Scroll down; reaching the bottom resumes following
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll = (self.scroll + n).min(self.max_scroll());
+        self.follow = self.scroll == self.max_scroll();
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+        self.follow = false;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+        self.follow = true;
+    }
+}
+
+/// A worktree created just now, and the repository it belongs to
+#[derive(Debug, Clone)]
+pub struct NewWorktree {
+    pub path: PathBuf,
+    pub repo: PathBuf,
+}
+
 /// Configuration for git worktrees
 #[derive(Debug, Clone)]
 pub struct WorktreeConfig {
     pub worktree_dir: PathBuf,
+    /// Naming template for new worktrees, relative to `worktree_dir`
+    pub template: String,
 }
 
 impl WorktreeConfig {
-    /// Load worktree config with precedence: cli_override > env var > default
-    pub fn load(cli_override: Option<PathBuf>) -> Self {
+    /// Load worktree config with precedence: cli_override > env var > default.
+    /// An invalid naming template is logged and replaced by the default.
+    pub fn load(cli_override: Option<PathBuf>, template: Option<String>) -> Self {
         let worktree_dir = cli_override
             .or_else(|| std::env::var("AMUX_WORKTREE_DIR").ok().map(PathBuf::from))
-            .unwrap_or_else(|| {
-                dirs::home_dir()
-                    .unwrap_or_else(|| PathBuf::from("."))
-                    .join(".amux/worktrees")
-            });
+            .unwrap_or_else(crate::config::default_worktree_dir);
+
+        let template = match template {
+            Some(template) => match crate::git::worktree_template_error(&template) {
+                None => template,
+                Some(e) => {
+                    crate::log::warn(&format!(
+                        "Invalid worktree_template {:?}: {}, using default",
+                        template, e
+                    ));
+                    crate::git::DEFAULT_WORKTREE_TEMPLATE.to_string()
+                }
+            },
+            None => crate::git::DEFAULT_WORKTREE_TEMPLATE.to_string(),
+        };
 
-        Self { worktree_dir }
+        Self {
+            worktree_dir,
+            template,
+        }
     }
 
     /// Generate worktree path for a repo and branch
     pub fn worktree_path(&self, repo_name: &str, branch_name: &str) -> PathBuf {
-        // Sanitize branch name for filesystem (replace / with -)
-        let safe_branch = branch_name.replace('/', "-");
-        self.worktree_dir
-            .join(format!("{}-{}", repo_name, safe_branch))
+        self.worktree_dir.join(crate::git::render_worktree_template(
+            &self.template,
+            repo_name,
+            branch_name,
+        ))
     }
 }
 
+/// State for the regex output filter input
+#[derive(Debug, Clone, Default)]
+pub struct OutputFilterState {
+    /// Regex being typed
+    pub input: String,
+    /// Compile error for the current input, shown inline
+    pub error: Option<String>,
+}
+
+/// How a broadcast prompt reaches a session
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BroadcastDelivery {
+    /// Idle session: the prompt is sent right away
+    Send,
+    /// Busy session: the prompt is sent once it's idle
+    Queue,
+    /// Busy session: the prompt isn't delivered
+    Skip,
+}
+
+/// State for the broadcast prompt popup
+#[derive(Debug, Clone, Default)]
+pub struct BroadcastState {
+    /// Prompt being typed
+    pub input: String,
+    /// Session names and how they got the prompt, once it has been sent
+    pub results: Option<Vec<(String, BroadcastDelivery)>>,
+}
+
 /// Spinner frames for loading animation
 pub const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
+/// Spinner animation preset, selected with `spinner` in config
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SpinnerStyle {
+    /// Rotating braille dots (the original spinner)
+    #[default]
+    Braille,
+    /// Filled braille block with a moving gap
+    Dots,
+    /// ASCII line rotating through - \ | /
+    Line,
+    /// Moon phases
+    Moon,
+}
+
+impl SpinnerStyle {
+    /// Parse a config name, falling back to the default with a log warning
+    pub fn from_config(name: Option<&str>) -> Self {
+        match name {
+            None | Some("braille") => SpinnerStyle::Braille,
+            Some("dots") => SpinnerStyle::Dots,
+            Some("line") => SpinnerStyle::Line,
+            Some("moon") => SpinnerStyle::Moon,
+            Some(other) => {
+                crate::log::warn(&format!("Unknown spinner style '{}', using braille", other));
+                SpinnerStyle::default()
+            }
+        }
+    }
+
+    /// Animation frames for this style
+    pub fn frames(self) -> &'static [&'static str] {
+        match self {
+            SpinnerStyle::Braille => SPINNER_FRAMES,
+            SpinnerStyle::Dots => &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"],
+            SpinnerStyle::Line => &["-", "\\", "|", "/"],
+            SpinnerStyle::Moon => &["🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘"],
+        }
+    }
+}
+
+/// How markdown headings in agent output are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HeadingStyle {
+    /// Bold, in the text color
+    Bold,
+    /// Bold, colored by level
+    #[default]
+    Colored,
+    /// Bold, colored by level and underlined
+    Underlined,
+}
+
+impl HeadingStyle {
+    /// Parse a config name, falling back to the default with a log warning
+    pub fn from_config(name: Option<&str>) -> Self {
+        match name {
+            None | Some("colored") => HeadingStyle::Colored,
+            Some("bold") => HeadingStyle::Bold,
+            Some("underlined") => HeadingStyle::Underlined,
+            Some(other) => {
+                crate::log::warn(&format!("Unknown heading style '{}', using colored", other));
+                HeadingStyle::default()
+            }
+        }
+    }
+}
+
 /// State for a running bash command
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct RunningBashCommand {
+    pub session_id: String,
     pub command: String,
     pub started_at: std::time::Instant,
+    /// Signals the executing task to kill the command's process group
+    pub interrupt: Option<tokio::sync::oneshot::Sender<()>>,
 }
 
 /// A clickable region in the UI
@@ -726,6 +1376,12 @@ pub struct SessionDisplayOrder {
     pub display_to_internal: Vec<usize>,
 }
 
+/// How often sessions' repos are fetched when `auto_fetch` is on
+pub const AUTO_FETCH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Maximum number of images attached to one prompt
+pub const MAX_ATTACHMENTS: usize = 10;
+
 /// An image attachment ready to be sent with a prompt
 #[derive(Debug, Clone)]
 pub struct ImageAttachment {
@@ -747,8 +1403,22 @@ pub struct App {
     pub branch_input: Option<BranchInputState>,
     pub worktree_cleanup: Option<WorktreeCleanupState>,
     pub bug_report: Option<BugReportState>,
+    pub diff_view: Option<DiffViewState>,
+    pub log_view: Option<LogViewState>,
+    pub tool_json: Option<ToolJsonState>,
+    pub output_filter: Option<OutputFilterState>,
+    pub broadcast: Option<BroadcastState>,
+    pub session_switcher: Option<SessionSwitcherState>,
+    /// Query narrowing the bindings shown in the help popup
+    pub help_filter: String,
+    /// Selected plan entry while the plan panel is focused
+    pub plan_selected: usize,
     pub spinner_frame: usize,
     pub spinner_tick: usize,
+    /// Configured spinner animation
+    pub spinner_style: SpinnerStyle,
+    /// Configured look of markdown headings
+    pub heading_style: HeadingStyle,
     pub attachments: Vec<ImageAttachment>,
     pub selected_attachment: Option<usize>,
     pub start_dir: PathBuf,
@@ -767,16 +1437,105 @@ pub struct App {
     pub session_id: Option<String>,
     /// Debug mode: show raw ACP JSON under tool calls (toggle with 't')
     pub debug_tool_json: bool,
-    /// MCP servers to pass to agent sessions
-    pub mcp_servers: Vec<McpServerConfig>,
+    /// Collapse tool calls to their title, hiding their output (toggle with 'z')
+    pub collapse_tools: bool,
+    /// Prefix output entries with the time they arrived (toggle with 'T')
+    pub show_timestamps: bool,
+    /// Show tool call paths in full rather than relative to the session dir (toggle with 'P')
+    pub absolute_paths: bool,
+    /// Hide the sidebar so the conversation gets the full width (toggle with 'Z')
+    pub zen_mode: bool,
+    /// Configured width of the sidebar
+    pub sidebar_width: SidebarWidth,
+    /// Widest the conversation column gets, centered in the space it has
+    pub max_conversation_width: Option<u16>,
+    /// List archived sessions in the sidebar too (toggle with 'A')
+    pub show_archived: bool,
+    /// Show agent stderr lines in session output
+    pub show_agent_stderr: bool,
+    /// Respawn and resume sessions whose agent process crashed
+    pub auto_reconnect: bool,
+    /// Queue broadcast prompts for busy sessions instead of skipping them
+    pub queue_broadcast_when_busy: bool,
+    /// Ask before quitting while sessions are working
+    pub confirm_quit: bool,
+    /// Float sessions with unread output up in priority sort mode
+    pub unread_first: bool,
+    /// Per-tool auto-accept overrides of the permission mode
+    pub tool_permission_rules: ToolPermissionRules,
+    /// Configured editor command (falls back to $EDITOR)
+    pub editor: Option<String>,
+    /// Maximum output lines kept per session (0 = unlimited)
+    pub max_scrollback: usize,
+    /// Prompt size in bytes above which sending needs confirmation (0 = never)
+    pub large_prompt_threshold: usize,
+    /// Limits on what agents may ask of amux (e.g. file read size)
+    pub client_options: ClientOptions,
+    /// Redraw interval while a spinner is animating
+    pub tick_rate: Duration,
+    /// Redraw interval when nothing is animating
+    pub idle_tick_rate: Duration,
+    /// Normal-mode key that repeats the last repeatable action
+    pub repeat_key: char,
+    /// Which Enter sends the prompt in insert mode
+    pub submit_key: SubmitKey,
+    /// Prompt template for "explain this error"
+    pub explain_error_prompt: String,
+    /// GitHub repository bug reports are filed against
+    pub bug_report_repo: String,
+    /// Last action the repeat key runs again
+    last_action: Option<Action>,
+    /// Kill sessions idle for this long (the selected one is exempt)
+    pub idle_timeout: Option<Duration>,
+    /// Starting more sessions than this needs confirmation
+    pub max_sessions: Option<usize>,
+    /// Directories whose sessions start in an auto-accept permission mode
+    pub trusted_dirs: TrustedDirs,
+    /// Mirror each new session's output to a transcript file
+    pub save_transcripts: bool,
+    /// Drawing on the primary screen rather than the alternate one
+    pub inline: bool,
+    /// Session to start once the user confirms going past `max_sessions`
+    pub pending_spawn: Option<PendingSpawn>,
+    /// Session to start right away at launch
+    pub startup_session: Option<StartupSession>,
+    /// Directory to open in the editor on the next loop iteration
+    pub pending_editor: Option<PathBuf>,
+    /// File position to open instead of the whole directory
+    pub pending_editor_location: Option<FileLocation>,
+    /// Configured MCP servers, and which of them new sessions get
+    pub mcp_servers: McpServerList,
+    /// Global preamble for new sessions (a repo's `.amux.toml` overrides it)
+    pub preamble: Option<String>,
+    /// Global post-create hook for new worktrees (a repo's `.amux.toml` overrides it)
+    pub post_create_hook: Option<String>,
+    /// Worktree created just now, whose first agent runs the post-create hook
+    pub new_worktree: Option<NewWorktree>,
+    /// User-defined agents from config, offered after the built-in ones
+    pub custom_agents: Vec<AgentType>,
     /// Whether the input is in bash mode (first char is '!')
     pub bash_mode: bool,
+    /// `@` file completion open at the cursor
+    pub mention: Option<MentionCompletion>,
+    /// `/` completion of the agent's commands, open at the start of the prompt
+    pub slash_command: Option<SlashCommandCompletion>,
+    /// Position of an `@` or `/` whose completion was dismissed, so it stays closed
+    pub completion_dismissed: Option<usize>,
+    /// Saved prompt snippets (`~/.amux/snippets.json`)
+    pub snippets: Snippets,
+    /// Name being typed for the prompt to save as a snippet
+    pub snippet_name: String,
+    pub snippet_picker: Option<SnippetPickerState>,
     /// Currently running bash command (for timer display)
     pub running_bash_command: Option<RunningBashCommand>,
     /// Desktop notification manager
     pub notifications: NotificationManager,
     /// Last time git diff stats were refreshed
     pub last_git_refresh: std::time::Instant,
+    /// Periodically fetch from origin for accurate ahead/behind counts
+    pub auto_fetch: bool,
+    /// Last time sessions' repos were fetched (None = not yet)
+    pub last_auto_fetch: Option<std::time::Instant>,
 }
 
 impl App {
@@ -785,6 +1544,7 @@ impl App {
         worktree_config: WorktreeConfig,
         mcp_servers: Vec<McpServerConfig>,
         notification_config: NotificationConfig,
+        ui_state: UiState,
     ) -> Self {
         Self {
             sessions: SessionManager::new(),
@@ -799,8 +1559,18 @@ impl App {
             branch_input: None,
             worktree_cleanup: None,
             bug_report: None,
+            diff_view: None,
+            log_view: None,
+            tool_json: None,
+            output_filter: None,
+            broadcast: None,
+            session_switcher: None,
+            help_filter: String::new(),
+            plan_selected: 0,
             spinner_frame: 0,
             spinner_tick: 0,
+            spinner_style: SpinnerStyle::default(),
+            heading_style: HeadingStyle::default(),
             attachments: Vec::new(),
             selected_attachment: None,
             start_dir,
@@ -808,25 +1578,185 @@ impl App {
             interactions: InteractionRegistry::new(),
             session_display_order: SessionDisplayOrder::default(),
             next_session_id: 1,
-            sort_mode: SortMode::default(),
+            sort_mode: ui_state.sort_mode,
             log_path: None,
             session_id: None,
-            debug_tool_json: false,
-            mcp_servers,
+            debug_tool_json: ui_state.debug_tool_json,
+            collapse_tools: ui_state.collapse_tools,
+            show_timestamps: ui_state.show_timestamps,
+            absolute_paths: ui_state.absolute_paths,
+            zen_mode: false,
+            sidebar_width: SidebarWidth::default(),
+            max_conversation_width: None,
+            show_archived: false,
+            show_agent_stderr: false,
+            auto_reconnect: false,
+            queue_broadcast_when_busy: false,
+            confirm_quit: false,
+            unread_first: false,
+            tool_permission_rules: ToolPermissionRules::default(),
+            editor: None,
+            max_scrollback: DEFAULT_MAX_SCROLLBACK,
+            large_prompt_threshold: DEFAULT_LARGE_PROMPT_THRESHOLD,
+            client_options: ClientOptions::default(),
+            tick_rate: Duration::from_millis(DEFAULT_TICK_RATE_MS),
+            idle_tick_rate: Duration::from_millis(DEFAULT_IDLE_TICK_RATE_MS),
+            repeat_key: DEFAULT_REPEAT_KEY,
+            submit_key: SubmitKey::default(),
+            explain_error_prompt: DEFAULT_EXPLAIN_ERROR_PROMPT.to_string(),
+            bug_report_repo: DEFAULT_BUG_REPORT_REPO.to_string(),
+            last_action: None,
+            idle_timeout: None,
+            max_sessions: None,
+            trusted_dirs: TrustedDirs::default(),
+            save_transcripts: false,
+            inline: false,
+            pending_spawn: None,
+            startup_session: None,
+            pending_editor: None,
+            pending_editor_location: None,
+            mcp_servers: McpServerList::new(mcp_servers),
+            preamble: None,
+            post_create_hook: None,
+            new_worktree: None,
+            custom_agents: Vec::new(),
             bash_mode: false,
+            mention: None,
+            slash_command: None,
+            completion_dismissed: None,
+            snippets: Snippets::load(),
+            snippet_name: String::new(),
+            snippet_picker: None,
             running_bash_command: None,
             notifications: NotificationManager::new(notification_config),
             last_git_refresh: std::time::Instant::now(),
+            auto_fetch: false,
+            last_auto_fetch: None,
         }
     }
 
     /// Toggle debug mode for tool JSON display
     pub fn toggle_debug_tool_json(&mut self) {
         self.debug_tool_json = !self.debug_tool_json;
+        self.save_ui_state();
     }
 
-    /// Get the internal session index for a display index (1-9 hotkeys)
-    /// Returns None if the display index is out of bounds
+    /// The terminal was resized: keep each session's output anchored at the
+    /// same content once it's rewrapped to the new width
+    pub fn handle_resize(&mut self) {
+        for session in self.sessions.sessions_mut() {
+            session.reflow_pending = true;
+        }
+    }
+
+    /// Toggle collapsing every tool call to a one-line summary
+    pub fn toggle_collapse_tools(&mut self) {
+        self.collapse_tools = !self.collapse_tools;
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.expanded_tool = None;
+        }
+        self.save_ui_state();
+    }
+
+    /// Toggle the time prefix on output entries
+    pub fn toggle_timestamps(&mut self) {
+        self.show_timestamps = !self.show_timestamps;
+        self.save_ui_state();
+    }
+
+    /// Toggle between full paths and paths relative to the session dir
+    pub fn toggle_absolute_paths(&mut self) {
+        self.absolute_paths = !self.absolute_paths;
+        self.save_ui_state();
+    }
+
+    /// Toggle hiding the sidebar. The conversation width changes, so output is
+    /// rewrapped the same way as on a resize.
+    pub fn toggle_zen_mode(&mut self) {
+        self.zen_mode = !self.zen_mode;
+        self.handle_resize();
+    }
+
+    /// Archive the selected session, or bring it back. An archived session
+    /// drops out of the sidebar, so the selection moves on to the next one.
+    pub fn toggle_archived(&mut self) {
+        let Some(session) = self.sessions.selected_session_mut() else {
+            return;
+        };
+        session.archived = !session.archived;
+        if session.archived && !self.show_archived {
+            self.next_session();
+        }
+    }
+
+    /// Show or hide archived sessions in the sidebar
+    pub fn toggle_show_archived(&mut self) {
+        self.show_archived = !self.show_archived;
+        let selected_hidden = self.sessions.selected_session().is_some_and(|s| s.archived);
+        if !self.show_archived && selected_hidden {
+            self.next_session();
+        }
+    }
+
+    /// Write the selected session's raw ACP messages to a `.jsonl` file in
+    /// `~/.amux/exports` and report where
+    pub fn export_acp_log(&mut self) {
+        let Some(session) = self.sessions.selected_session_mut() else {
+            return;
+        };
+        let file_name =
+            crate::acp::export_file_name(session.acp_session_id.as_deref(), &session.id);
+        let path = crate::state_file::path("exports").join(file_name);
+        let message = match session.acp_messages.export(&path) {
+            Ok(count) => format!("Exported {} ACP messages to {}", count, path.display()),
+            Err(e) => {
+                crate::log::error(&format!("Failed to export ACP log: {}", e));
+                format!("ACP log export failed: {}", e)
+            }
+        };
+        session.add_output(message, OutputType::SystemMessage);
+    }
+
+    /// Expand the previous or next tool call in the selected session and scroll to it
+    pub fn focus_adjacent_tool(&mut self, forward: bool) {
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.focus_adjacent_tool(forward);
+        }
+    }
+
+    /// Scroll the selected session to the previous or next message the user sent
+    pub fn focus_adjacent_user_input(&mut self, forward: bool) {
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.focus_adjacent_user_input(forward);
+        }
+    }
+
+    /// Write out buffered transcript output of every session
+    pub fn flush_transcripts(&self) {
+        for transcript in self
+            .sessions
+            .sessions()
+            .iter()
+            .filter_map(|s| s.transcript.as_ref())
+        {
+            transcript.flush();
+        }
+    }
+
+    /// Remember the view preferences for the next launch
+    fn save_ui_state(&self) {
+        UiState {
+            debug_tool_json: self.debug_tool_json,
+            sort_mode: self.sort_mode,
+            collapse_tools: self.collapse_tools,
+            show_timestamps: self.show_timestamps,
+            absolute_paths: self.absolute_paths,
+        }
+        .save();
+    }
+
+    /// Get the internal session index for a display index (1-9 hotkeys)
+    /// Returns None if the display index is out of bounds
     pub fn internal_index_for_display(&self, display_idx: usize) -> Option<usize> {
         self.session_display_order
             .display_to_internal
@@ -837,14 +1767,70 @@ impl App {
     /// Cycle through sort modes
     pub fn cycle_sort_mode(&mut self) {
         self.sort_mode = self.sort_mode.next();
+        self.save_ui_state();
     }
 
-    /// Add an image attachment
+    /// Add an image attachment, unless the prompt already has `MAX_ATTACHMENTS`
     pub fn add_attachment(&mut self, attachment: ImageAttachment) {
+        if self.attachments.len() >= MAX_ATTACHMENTS {
+            crate::log::warn(&format!(
+                "Attachment limit of {} reached, dropping {}",
+                MAX_ATTACHMENTS, attachment.filename
+            ));
+            return;
+        }
         self.attachments.push(attachment);
         // Don't auto-select, user stays in input mode
     }
 
+    /// Remember a repeatable action, or resolve the repeat key to the action
+    /// it repeats (`Action::None` before there is one)
+    pub fn record_action(&mut self, action: Action) -> Action {
+        if matches!(action, Action::RepeatLast) {
+            return self.last_action.clone().unwrap_or(Action::None);
+        }
+        if action.is_repeatable() {
+            self.last_action = Some(action.clone());
+        }
+        action
+    }
+
+    /// Whether the prompt has images the selected session's agent doesn't accept
+    pub fn images_rejected(&self) -> bool {
+        self.has_attachments()
+            && self
+                .selected_session()
+                .is_some_and(|s| s.accepts_images == Some(false))
+    }
+
+    /// Whether the pending input can be sent: bash commands always, prompts
+    /// unless they carry images the agent refuses
+    pub fn can_submit_prompt(&self) -> bool {
+        self.is_bash_mode() || !self.images_rejected()
+    }
+
+    /// Paste clipboard contents into the prompt: images are attached, text is inserted
+    pub fn paste(&mut self, contents: Vec<ClipboardContent>) {
+        for content in contents {
+            match content {
+                ClipboardContent::Image {
+                    filename,
+                    data,
+                    mime_type,
+                } => self.add_attachment(ImageAttachment {
+                    filename,
+                    mime_type,
+                    data,
+                }),
+                ClipboardContent::Text(text) => {
+                    for c in text.chars() {
+                        self.input_char(c);
+                    }
+                }
+            }
+        }
+    }
+
     /// Clear all attachments
     pub fn clear_attachments(&mut self) {
         self.attachments.clear();
@@ -856,6 +1842,16 @@ impl App {
         !self.attachments.is_empty()
     }
 
+    /// Size of the pending prompt in bytes (text plus encoded attachments)
+    pub fn prompt_bytes(&self) -> usize {
+        self.input_buffer.len() + self.attachments.iter().map(|a| a.data.len()).sum::<usize>()
+    }
+
+    /// Whether the pending prompt is large enough to need confirmation before sending
+    pub fn needs_large_prompt_confirm(&self) -> bool {
+        self.large_prompt_threshold > 0 && self.prompt_bytes() > self.large_prompt_threshold
+    }
+
     /// Select the attachment list (move focus up from input)
     pub fn select_attachments(&mut self) {
         if !self.attachments.is_empty() {
@@ -905,13 +1901,41 @@ impl App {
     pub fn tick_spinner(&mut self) {
         self.spinner_tick += 1;
         if self.spinner_tick.is_multiple_of(2) {
-            self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len();
+            self.spinner_frame = (self.spinner_frame + 1) % self.spinner_frames().len();
+        }
+
+        // Scroll the activity sparklines along even when no output arrives
+        let now = std::time::Instant::now();
+        for session in self.sessions.sessions_mut() {
+            session.activity.advance(now);
         }
     }
 
+    /// Animation frames of the configured spinner style
+    pub fn spinner_frames(&self) -> &'static [&'static str] {
+        self.spinner_style.frames()
+    }
+
     /// Get current spinner character
     pub fn spinner(&self) -> &'static str {
-        SPINNER_FRAMES[self.spinner_frame]
+        let frames = self.spinner_frames();
+        frames[self.spinner_frame % frames.len()]
+    }
+
+    /// Whether anything on screen is animating (agent working or bash command running)
+    pub fn is_animating(&self) -> bool {
+        self.has_running_bash_command()
+            || self.sessions.sessions().iter().any(|s| s.state.is_active())
+    }
+
+    /// How long the main loop may sleep before redrawing.
+    /// Stays at the fast tick rate only while a spinner is visible.
+    pub fn tick_interval(&self) -> Duration {
+        if self.is_animating() {
+            self.tick_rate
+        } else {
+            self.idle_tick_rate
+        }
     }
 
     /// Check if git diff stats should be refreshed (every 5 seconds)
@@ -924,6 +1948,19 @@ impl App {
         self.last_git_refresh = std::time::Instant::now();
     }
 
+    /// Check if auto-fetch is due (enabled, and every `AUTO_FETCH_INTERVAL`), marking it done
+    pub fn take_auto_fetch_due(&mut self) -> bool {
+        if !self.auto_fetch
+            || self
+                .last_auto_fetch
+                .is_some_and(|last| last.elapsed() < AUTO_FETCH_INTERVAL)
+        {
+            return false;
+        }
+        self.last_auto_fetch = Some(std::time::Instant::now());
+        true
+    }
+
     /// Open the folder picker starting at the given directory
     pub fn open_folder_picker(&mut self, start_dir: PathBuf) {
         self.folder_picker = Some(FolderPickerState::new(start_dir));
@@ -983,6 +2020,40 @@ impl App {
         self.input_mode = InputMode::AgentPicker;
     }
 
+    /// Move the open picker's selection by a page, or half of one
+    pub fn picker_page(&mut self, down: bool, half: bool) {
+        fn page<P: Picker>(picker: &mut P, rows: usize, down: bool, half: bool) {
+            let rows = if half { rows / 2 } else { rows }.max(1);
+            if down {
+                picker.select_page_down(rows);
+            } else {
+                picker.select_page_up(rows);
+            }
+        }
+        match self.input_mode {
+            InputMode::FolderPicker | InputMode::WorktreeFolderPicker => {
+                if let Some(picker) = &mut self.folder_picker {
+                    let rows = picker.viewport_height;
+                    page(picker, rows, down, half);
+                }
+            }
+            InputMode::WorktreePicker => {
+                if let Some(picker) = &mut self.worktree_picker {
+                    let rows = picker.viewport_height;
+                    page(picker, rows, down, half);
+                    picker.confirm_dirty = false;
+                }
+            }
+            InputMode::AgentPicker => {
+                if let Some(picker) = &mut self.agent_picker {
+                    let rows = picker.viewport_height;
+                    page(picker, rows, down, half);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Close the agent picker without selecting
     pub fn close_agent_picker(&mut self) {
         self.agent_picker = None;
@@ -1002,6 +2073,127 @@ impl App {
         self.input_mode = InputMode::Normal;
     }
 
+    /// Open the quick switcher listing every session in display order
+    pub fn open_session_switcher(&mut self) {
+        let sessions = self.sessions.sessions();
+        if sessions.is_empty() {
+            return;
+        }
+        // Display order is only known once the sidebar has rendered, and misses
+        // sessions added since then: keep it and append whatever is missing
+        let mut order: Vec<usize> = self
+            .session_display_order
+            .display_to_internal
+            .iter()
+            .copied()
+            .filter(|&i| i < sessions.len())
+            .collect();
+        if order.len() != sessions.len() {
+            let missing: Vec<usize> = (0..sessions.len())
+                .filter(|i| !order.contains(i))
+                .filter(|&i| self.show_archived || !sessions[i].archived)
+                .collect();
+            order.extend(missing);
+        }
+        let candidates = order
+            .into_iter()
+            .map(|index| SwitcherCandidate {
+                index,
+                name: sessions[index].name.clone(),
+                git_branch: sessions[index].git_branch.clone(),
+            })
+            .collect();
+        self.session_switcher = Some(SessionSwitcherState::new(candidates));
+        self.input_mode = InputMode::SessionSwitcher;
+    }
+
+    /// Close the quick switcher without switching
+    pub fn close_session_switcher(&mut self) {
+        self.session_switcher = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Switch to the highlighted session and close the quick switcher
+    pub fn session_switcher_select(&mut self) {
+        let index = self
+            .session_switcher
+            .as_ref()
+            .and_then(|s| s.selected_item().copied());
+        self.close_session_switcher();
+        if let Some(index) = index {
+            self.select_session(index);
+        }
+    }
+
+    /// Ask for a name to save the prompt under, unless it's empty
+    pub fn open_snippet_save(&mut self) {
+        if self.input_buffer.trim().is_empty() {
+            return;
+        }
+        self.snippet_name.clear();
+        self.input_mode = InputMode::SnippetSave;
+    }
+
+    /// Go back to editing the prompt without saving it
+    pub fn close_snippet_save(&mut self) {
+        self.input_mode = InputMode::Insert;
+    }
+
+    /// Save the prompt under the typed name, replacing a snippet with that name
+    pub fn save_snippet(&mut self) {
+        let name = self.snippet_name.trim();
+        if name.is_empty() {
+            return;
+        }
+        self.snippets.insert(name, &self.input_buffer);
+        self.snippets.save();
+        self.close_snippet_save();
+    }
+
+    /// Open the picker over the saved snippets
+    pub fn open_snippet_picker(&mut self) {
+        self.snippet_picker = Some(SnippetPickerState::new(self.snippets.snippets.clone()));
+        self.input_mode = InputMode::SnippetPicker;
+    }
+
+    /// Go back to editing the prompt without inserting a snippet
+    pub fn close_snippet_picker(&mut self) {
+        self.snippet_picker = None;
+        self.input_mode = InputMode::Insert;
+    }
+
+    /// Insert the highlighted snippet at the cursor and keep editing
+    pub fn insert_snippet(&mut self) {
+        let text = self
+            .snippet_picker
+            .as_ref()
+            .and_then(|p| p.selected_snippet())
+            .map(|s| s.text.clone());
+        self.close_snippet_picker();
+        if let Some(text) = text {
+            self.input_buffer.insert_str(self.cursor_position, &text);
+            self.cursor_position += text.len();
+        }
+    }
+
+    /// Delete the highlighted snippet, keeping the picker's query
+    pub fn delete_snippet(&mut self) {
+        let Some(picker) = &self.snippet_picker else {
+            return;
+        };
+        let Some(snippet) = picker.selected_snippet() else {
+            return;
+        };
+        let query = picker.query.clone();
+        self.snippets.remove(&snippet.name.clone());
+        self.snippets.save();
+
+        let mut picker = SnippetPickerState::new(self.snippets.snippets.clone());
+        picker.query = query;
+        picker.update_filter();
+        self.snippet_picker = Some(picker);
+    }
+
     /// Open the worktree picker with existing worktrees
     pub fn open_worktree_picker(&mut self, entries: Vec<WorktreeEntry>) {
         self.worktree_picker = Some(WorktreePickerState::new(entries));
@@ -1021,6 +2213,38 @@ impl App {
     }
 
     /// Open branch input with autocomplete
+    pub fn open_branch_input(
+        &mut self,
+        repo_path: PathBuf,
+        branches: Vec<BranchEntry>,
+        agent_type: Option<AgentType>,
+        pick_base: bool,
+    ) {
+        // Picking a base starts on the checked-out branch, which keeps today's behavior
+        let selected = if pick_base {
+            branches.iter().position(|b| b.is_current).unwrap_or(0)
+        } else {
+            0
+        };
+        let mut state = BranchInputState {
+            repo_path,
+            input: String::new(),
+            cursor_position: 0,
+            filtered: vec![],
+            branches,
+            selected,
+            show_autocomplete: true,
+            error: None,
+            suggestion: None,
+            agent_type,
+            picking_base: pick_base,
+            base: None,
+        };
+        state.update_filter();
+        self.branch_input = Some(state);
+        self.input_mode = InputMode::BranchInput;
+    }
+
     /// Close branch input
     pub fn close_branch_input(&mut self) {
         self.branch_input = None;
@@ -1044,11 +2268,13 @@ impl App {
 
     /// Open the help popup
     pub fn open_help(&mut self) {
+        self.help_filter.clear();
         self.input_mode = InputMode::Help;
     }
 
     /// Close the help popup
     pub fn close_help(&mut self) {
+        self.help_filter.clear();
         self.input_mode = InputMode::Normal;
     }
 
@@ -1070,11 +2296,384 @@ impl App {
         self.input_mode = InputMode::ClearConfirm;
     }
 
+    /// Ask for confirmation before sending a large prompt
+    pub fn open_large_prompt_confirm(&mut self) {
+        self.input_mode = InputMode::ConfirmLargePrompt;
+    }
+
+    /// Return to editing the large prompt without sending
+    pub fn close_large_prompt_confirm(&mut self) {
+        self.input_mode = InputMode::Insert;
+    }
+
+    /// Confirm sending the large prompt. Returns whether it can go out; if
+    /// not, it's back to editing, where the attachment bar says why.
+    pub fn confirm_large_prompt(&mut self) -> bool {
+        if self.can_submit_prompt() {
+            return true;
+        }
+        self.close_large_prompt_confirm();
+        false
+    }
+
     /// Close the clear session confirmation dialog
     pub fn close_clear_confirm(&mut self) {
         self.input_mode = InputMode::Normal;
     }
 
+    /// Sessions in the middle of a prompt, whose work is lost on quit
+    pub fn busy_sessions(&self) -> Vec<&Session> {
+        self.sessions
+            .sessions()
+            .iter()
+            .filter(|s| {
+                matches!(
+                    s.state,
+                    SessionState::Prompting
+                        | SessionState::AwaitingPermission
+                        | SessionState::AwaitingUserInput
+                )
+            })
+            .collect()
+    }
+
+    /// Handle a quit request: returns true to quit now, or opens the
+    /// confirmation dialog if `confirm_quit` is set and sessions are working
+    pub fn request_quit(&mut self) -> bool {
+        if self.confirm_quit && !self.busy_sessions().is_empty() {
+            self.input_mode = InputMode::QuitConfirm;
+            return false;
+        }
+        true
+    }
+
+    /// Close the quit confirmation dialog without quitting
+    pub fn close_quit_confirm(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Whether starting another session would go past `max_sessions`
+    pub fn session_limit_reached(&self) -> bool {
+        self.max_sessions
+            .is_some_and(|max| self.sessions.sessions().len() >= max)
+    }
+
+    /// Hold a session back and ask whether to start it past the limit
+    pub fn open_session_limit_confirm(&mut self, spawn: PendingSpawn) {
+        self.pending_spawn = Some(spawn);
+        self.input_mode = InputMode::SessionLimitConfirm;
+    }
+
+    /// Close the session limit dialog, handing back the held session
+    pub fn close_session_limit_confirm(&mut self) -> Option<PendingSpawn> {
+        self.input_mode = InputMode::Normal;
+        self.pending_spawn.take()
+    }
+
+    /// Request opening the editor in the selected session's directory.
+    /// The main loop suspends the TUI and runs it before the next render.
+    pub fn request_editor(&mut self) {
+        if let Some(session) = self.selected_session() {
+            self.pending_editor = Some(session.cwd.clone());
+            self.pending_editor_location = None;
+        }
+    }
+
+    /// Request opening the editor at a file position (e.g. a clicked stack
+    /// trace line), from the selected session's directory
+    pub fn request_editor_at(&mut self, location: FileLocation) {
+        if let Some(session) = self.selected_session() {
+            self.pending_editor = Some(session.cwd.clone());
+            self.pending_editor_location = Some(location);
+        }
+    }
+
+    /// Editor command to launch: config value, then $EDITOR
+    pub fn editor_command(&self) -> Option<String> {
+        self.editor
+            .clone()
+            .or_else(|| std::env::var("EDITOR").ok())
+            .filter(|e| !e.trim().is_empty())
+    }
+
+    /// Open the side-by-side diff view for the written file nearest the viewport
+    pub fn open_diff_view(&mut self) {
+        let diff_view = self
+            .selected_session()
+            .and_then(|s| s.file_diff_near_view())
+            .map(DiffViewState::new);
+        if diff_view.is_some() {
+            self.diff_view = diff_view;
+            self.input_mode = InputMode::DiffView;
+        }
+    }
+
+    /// Copy the file path (or, with `diff`, the unified diff of the written file)
+    /// nearest the viewport, confirming in the session output
+    pub fn copy_near_view(&mut self, diff: bool) {
+        let Some(session) = self.sessions.selected_session_mut() else {
+            return;
+        };
+        let copied = if diff {
+            session
+                .file_diff_near_view()
+                .map(|d| (d.unified(), format!("Copied diff of {}", d.path)))
+        } else {
+            session
+                .path_near_view()
+                .map(|path| (path.clone(), format!("Copied {}", path)))
+        };
+        let message = match copied {
+            Some((text, message)) => match crate::clipboard::write_clipboard(&text) {
+                Ok(()) => message,
+                Err(e) => {
+                    crate::log::error(&format!("Failed to copy to clipboard: {}", e));
+                    format!("Copy failed: {}", e)
+                }
+            },
+            None if diff => "No file diff to copy".to_string(),
+            None => "No file path to copy".to_string(),
+        };
+        session.add_output(message, OutputType::SystemMessage);
+    }
+
+    /// Copy the selected session's file changes as one patch, confirming in the
+    /// session output
+    pub fn copy_patch(&mut self) {
+        let Some(session) = self.sessions.selected_session_mut() else {
+            return;
+        };
+        let (patch, files) = crate::diff::git_patch(session.file_diffs(), &session.cwd);
+        let message = if files == 0 {
+            "No file changes to copy".to_string()
+        } else {
+            match crate::clipboard::write_clipboard(&patch) {
+                Ok(()) if files == 1 => "Copied patch of 1 file".to_string(),
+                Ok(()) => format!("Copied patch of {} files", files),
+                Err(e) => {
+                    crate::log::error(&format!("Failed to copy to clipboard: {}", e));
+                    format!("Copy failed: {}", e)
+                }
+            }
+        };
+        session.add_output(message, OutputType::SystemMessage);
+    }
+
+    /// Close the side-by-side diff view
+    pub fn close_diff_view(&mut self) {
+        self.diff_view = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Open the MCP server manager
+    pub fn open_mcp_manager(&mut self) {
+        self.input_mode = InputMode::McpManager;
+    }
+
+    /// Close the MCP server manager
+    pub fn close_mcp_manager(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Open the log viewer on the current log file
+    pub fn open_log_view(&mut self) {
+        if let Some(path) = self.log_path.clone() {
+            self.log_view = Some(LogViewState::new(path));
+            self.input_mode = InputMode::LogView;
+        }
+    }
+
+    /// Close the log viewer
+    pub fn close_log_view(&mut self) {
+        self.log_view = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Open the JSON inspector on the selected session's expanded tool call,
+    /// or the last one in view. Returns false if there's no tool call.
+    pub fn open_tool_json(&mut self) -> bool {
+        let Some((title, raw_json)) = self
+            .selected_session()
+            .and_then(|session| session.inspected_tool_call())
+        else {
+            return false;
+        };
+        self.tool_json = Some(ToolJsonState::new(title, raw_json));
+        self.input_mode = InputMode::ToolJson;
+        true
+    }
+
+    /// Close the JSON inspector
+    pub fn close_tool_json(&mut self) {
+        self.tool_json = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Focus the plan panel, starting at the entry currently in progress
+    pub fn focus_plan(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        if session.plan_entries.is_empty() {
+            return;
+        }
+        self.plan_selected = session
+            .plan_entries
+            .iter()
+            .position(|e| e.status == PlanStatus::InProgress)
+            .unwrap_or(0);
+        self.input_mode = InputMode::PlanFocus;
+    }
+
+    /// Return focus from the plan panel to the session list
+    pub fn unfocus_plan(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Move the plan selection down
+    pub fn plan_next(&mut self) {
+        let count = self
+            .selected_session()
+            .map(|s| s.plan_entries.len())
+            .unwrap_or(0);
+        if self.plan_selected + 1 < count {
+            self.plan_selected += 1;
+        }
+    }
+
+    /// Move the plan selection up
+    pub fn plan_prev(&mut self) {
+        self.plan_selected = self.plan_selected.saturating_sub(1);
+    }
+
+    /// Scroll the conversation to where work on the selected plan entry started
+    pub fn jump_to_plan_entry(&mut self) {
+        let index = self.plan_selected;
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.scroll_to_plan_entry(index);
+        }
+    }
+
+    /// Open the output filter input, starting from the session's current filter
+    pub fn open_output_filter(&mut self) {
+        let Some(session) = self.selected_session() else {
+            return;
+        };
+        let input = session
+            .output_filter
+            .as_ref()
+            .filter(|_| session.filter_active)
+            .map(|re| re.as_str().to_string())
+            .unwrap_or_default();
+        self.output_filter = Some(OutputFilterState { input, error: None });
+        self.input_mode = InputMode::OutputFilter;
+    }
+
+    /// Type a character into the output filter
+    pub fn output_filter_char(&mut self, c: char) {
+        if let Some(filter) = &mut self.output_filter {
+            filter.input.push(c);
+            self.apply_output_filter();
+        }
+    }
+
+    /// Delete the last character of the output filter
+    pub fn output_filter_backspace(&mut self) {
+        if let Some(filter) = &mut self.output_filter {
+            filter.input.pop();
+            self.apply_output_filter();
+        }
+    }
+
+    /// Compile the filter input and apply it to the selected session.
+    /// An invalid regex records an error and leaves the output unfiltered.
+    fn apply_output_filter(&mut self) {
+        let Some(filter) = &mut self.output_filter else {
+            return;
+        };
+        let regex = if filter.input.is_empty() {
+            filter.error = None;
+            None
+        } else {
+            match Regex::new(&filter.input) {
+                Ok(regex) => {
+                    filter.error = None;
+                    Some(regex)
+                }
+                Err(e) => {
+                    // Only the last line of the error describes the problem
+                    let message = e.to_string();
+                    filter.error = message.lines().last().map(|l| l.trim().to_string());
+                    None
+                }
+            }
+        };
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.set_output_filter(regex);
+        }
+    }
+
+    /// Keep the current filter and return to normal mode
+    pub fn confirm_output_filter(&mut self) {
+        self.output_filter = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Remove the selected session's filter and return to normal mode
+    pub fn clear_output_filter(&mut self) {
+        self.output_filter = None;
+        if self.input_mode == InputMode::OutputFilter {
+            self.input_mode = InputMode::Normal;
+        }
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.set_output_filter(None);
+        }
+    }
+
+    /// Open the broadcast prompt popup
+    pub fn open_broadcast(&mut self) {
+        if self.sessions.sessions().is_empty() {
+            return;
+        }
+        self.broadcast = Some(BroadcastState::default());
+        self.input_mode = InputMode::Broadcast;
+    }
+
+    /// Close the broadcast popup
+    pub fn close_broadcast(&mut self) {
+        self.broadcast = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Type a character into the broadcast prompt
+    pub fn broadcast_char(&mut self, c: char) {
+        if let Some(broadcast) = &mut self.broadcast
+            && broadcast.results.is_none()
+        {
+            broadcast.input.push(c);
+        }
+    }
+
+    /// Delete the last character of the broadcast prompt
+    pub fn broadcast_backspace(&mut self) {
+        if let Some(broadcast) = &mut self.broadcast
+            && broadcast.results.is_none()
+        {
+            broadcast.input.pop();
+        }
+    }
+
+    /// How a broadcast prompt would reach this session right now
+    pub fn broadcast_delivery(&self, session: &Session) -> BroadcastDelivery {
+        if session.state == SessionState::Idle {
+            BroadcastDelivery::Send
+        } else if self.queue_broadcast_when_busy {
+            BroadcastDelivery::Queue
+        } else {
+            BroadcastDelivery::Skip
+        }
+    }
+
     /// Scroll current session up
     pub fn scroll_up(&mut self, n: usize) {
         let viewport = self.viewport_height;
@@ -1102,6 +2701,18 @@ impl App {
         }
     }
 
+    /// Scroll so rendered line `offset` is at the top; the last page follows new output
+    pub fn scroll_to(&mut self, offset: usize) {
+        let viewport = self.viewport_height;
+        if let Some(session) = self.sessions.selected_session_mut() {
+            if offset >= session.total_rendered_lines.saturating_sub(viewport) {
+                session.scroll_to_bottom();
+            } else {
+                session.scroll_offset = offset;
+            }
+        }
+    }
+
     /// Scroll to bottom of output
     pub fn scroll_to_bottom(&mut self) {
         if let Some(session) = self.sessions.selected_session_mut() {
@@ -1127,16 +2738,25 @@ impl App {
         }
     }
 
+    /// Clear the unread marker of the session that was just selected
+    fn mark_selected_read(&mut self) {
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.has_unread = false;
+        }
+    }
+
     pub fn next_session(&mut self) {
         self.save_input_to_session();
-        self.sessions.select_next();
+        self.sessions.select_adjacent(true, self.show_archived);
         self.restore_input_from_session();
+        self.mark_selected_read();
     }
 
     pub fn prev_session(&mut self) {
         self.save_input_to_session();
-        self.sessions.select_prev();
+        self.sessions.select_adjacent(false, self.show_archived);
         self.restore_input_from_session();
+        self.mark_selected_read();
     }
 
     /// Select session by index, saving/restoring input buffers
@@ -1144,6 +2764,78 @@ impl App {
         self.save_input_to_session();
         self.sessions.select_index(index);
         self.restore_input_from_session();
+        self.mark_selected_read();
+    }
+
+    /// Number of sessions waiting for a permission decision or an answer
+    pub fn awaiting_session_count(&self) -> usize {
+        self.sessions
+            .sessions()
+            .iter()
+            .filter(|s| s.is_awaiting_user())
+            .count()
+    }
+
+    /// Internal index of the next session (after the selected one, wrapping)
+    /// that is waiting for a permission decision or an answer
+    pub fn next_awaiting_session(&self) -> Option<usize> {
+        let sessions = self.sessions.sessions();
+        let selected = self.sessions.selected_index();
+        (1..=sessions.len())
+            .map(|offset| (selected + offset) % sessions.len())
+            .find(|&idx| sessions[idx].is_awaiting_user())
+    }
+
+    /// Clear the selected session's output, keeping the agent running
+    pub fn clear_selected_output(&mut self) {
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.clear_output();
+        }
+    }
+
+    /// Move the last queued prompt back into an empty input for editing
+    pub fn edit_last_queued_prompt(&mut self) {
+        if !self.input_buffer.is_empty() {
+            return;
+        }
+        if let Some(text) = self
+            .sessions
+            .selected_session_mut()
+            .and_then(|s| s.unqueue_last_prompt())
+        {
+            self.input_buffer = text;
+            self.cursor_position = self.input_buffer.len();
+        }
+    }
+
+    /// Drop all prompts queued for the selected session
+    pub fn clear_queued_prompts(&mut self) {
+        if let Some(session) = self.sessions.selected_session_mut() {
+            session.queued_prompts.clear();
+        }
+    }
+
+    /// Whether the selected session has prompts waiting to be sent
+    pub fn has_queued_prompts(&self) -> bool {
+        self.selected_session()
+            .is_some_and(|s| !s.queued_prompts.is_empty())
+    }
+
+    /// Jump to the next session awaiting permission or input
+    pub fn jump_to_awaiting_session(&mut self) {
+        if let Some(index) = self.next_awaiting_session() {
+            self.select_session(index);
+        }
+    }
+
+    /// Flip back to the session selected before the current one, unless it's
+    /// hidden in the archive
+    pub fn select_previous_session(&mut self) {
+        if let Some(index) = self.sessions.previous_index()
+            && (self.show_archived || !self.sessions.sessions()[index].archived)
+        {
+            self.select_session(index);
+        }
     }
 
     pub fn selected_session(&self) -> Option<&Session> {
@@ -1165,15 +2857,61 @@ impl App {
 
         let id = format!("session_{}", self.next_session_id);
         self.next_session_id += 1;
-        let session = Session::new(id.clone(), name, agent_type, cwd, is_worktree);
+        let trusted_mode = self.trusted_dirs.mode_for(&cwd);
+        let transcript = self
+            .save_transcripts
+            .then(|| Transcript::create(&Transcript::dir(), &name, &id))
+            .flatten();
+        if let Some(transcript) = &transcript {
+            crate::log::log(&format!(
+                "Writing transcript of {} to {}",
+                id,
+                transcript.path().display()
+            ));
+        }
+        let mut session = Session::new(id.clone(), name, agent_type, cwd, is_worktree);
+        session.max_scrollback = self.max_scrollback;
+        session.transcript = transcript;
+        if let Some(mode) = trusted_mode {
+            session.permission_mode = mode;
+            session.trusted = true;
+        }
 
         // Save current session's input before switching to the new session
         self.save_input_to_session();
         self.sessions.add_session(session);
         // New session has empty input, so no need to restore
+        self.mark_selected_read();
         id
     }
 
+    /// IDs of sessions idle past `idle_timeout`, except the selected one
+    pub fn expired_idle_sessions(&self) -> Vec<String> {
+        let Some(timeout) = self.idle_timeout else {
+            return vec![];
+        };
+        let selected_id = self.selected_session().map(|s| s.id.as_str());
+        self.sessions
+            .sessions()
+            .iter()
+            .filter(|s| Some(s.id.as_str()) != selected_id && s.is_idle_expired(timeout))
+            .map(|s| s.id.clone())
+            .collect()
+    }
+
+    /// Kill a session that isn't necessarily the selected one
+    pub fn kill_session(&mut self, id: &str) -> Option<Session> {
+        if self.selected_session().is_none_or(|s| s.id != id) {
+            return self.sessions.remove_by_id(id);
+        }
+        self.input_buffer.clear();
+        self.cursor_position = 0;
+        let removed = self.sessions.remove_selected();
+        self.restore_input_from_session();
+        self.mark_selected_read();
+        removed
+    }
+
     /// Kill the currently selected session
     pub fn kill_selected_session(&mut self) {
         // Clear current input (it belongs to the session being killed)
@@ -1182,6 +2920,7 @@ impl App {
         self.sessions.remove_selected();
         // Restore input from the newly selected session
         self.restore_input_from_session();
+        self.mark_selected_read();
     }
 
     /// Enter insert mode
@@ -1199,6 +2938,135 @@ impl App {
         self.bash_mode = false;
     }
 
+    /// Open, update or close completion for the `@` mention or `/` command at
+    /// the cursor. Returns the directory to scan when `@` completion needs new files.
+    pub fn update_completion(&mut self) -> Option<(PathBuf, String)> {
+        let typing = self.input_mode == InputMode::Insert && !self.bash_mode;
+        let command = typing
+            .then(|| command_at_cursor(&self.input_buffer, self.cursor_position))
+            .flatten();
+        let mention = typing
+            .then(|| mention_at_cursor(&self.input_buffer, self.cursor_position))
+            .flatten();
+        let start = match (command, mention) {
+            (Some(_), _) => 0,
+            (None, Some((start, _))) => start,
+            (None, None) => {
+                self.mention = None;
+                self.slash_command = None;
+                self.completion_dismissed = None;
+                return None;
+            }
+        };
+        if self.completion_dismissed == Some(start) {
+            return None;
+        }
+        self.completion_dismissed = None;
+
+        if let Some(query) = command {
+            self.mention = None;
+            let commands = self
+                .sessions
+                .selected_session()
+                .map(|s| s.available_commands.as_slice())
+                .unwrap_or_default();
+            // Agents that advertise no commands get no picker
+            if commands.is_empty() {
+                self.slash_command = None;
+                return None;
+            }
+            match &mut self.slash_command {
+                Some(completion) => completion.set_query(query, commands),
+                None => self.slash_command = Some(SlashCommandCompletion::new(query, commands)),
+            }
+            return None;
+        }
+
+        self.slash_command = None;
+        let (start, fragment) = mention?;
+        let cwd = self.selected_session()?.cwd.clone();
+        let (dir, query) = split_fragment(fragment);
+        if let Some(mention) = &mut self.mention
+            && mention.start == start
+            && mention.wants(&cwd, dir)
+        {
+            mention.set_query(query);
+            return None;
+        }
+        self.mention = Some(MentionCompletion::new(start, cwd.clone(), dir, query));
+        Some((cwd, dir.to_string()))
+    }
+
+    /// Files from a completion scan arrived
+    pub fn set_mention_files(&mut self, cwd: &std::path::Path, dir: &str, files: Vec<String>) {
+        if let Some(mention) = &mut self.mention
+            && mention.wants(cwd, dir)
+        {
+            mention.set_files(files);
+        }
+    }
+
+    /// Whether an open completion has something to insert
+    pub fn has_completion_matches(&self) -> bool {
+        self.mention.as_ref().is_some_and(|m| !m.matches.is_empty())
+            || self
+                .slash_command
+                .as_ref()
+                .is_some_and(|c| !c.matches.is_empty())
+    }
+
+    /// Whether `@` or `/` completion is open
+    pub fn has_completion(&self) -> bool {
+        self.mention.is_some() || self.slash_command.is_some()
+    }
+
+    /// Select the next completion
+    pub fn completion_next(&mut self) {
+        if let Some(mention) = &mut self.mention {
+            mention.select_next();
+        } else if let Some(completion) = &mut self.slash_command {
+            completion.select_next();
+        }
+    }
+
+    /// Select the previous completion
+    pub fn completion_prev(&mut self) {
+        if let Some(mention) = &mut self.mention {
+            mention.select_prev();
+        } else if let Some(completion) = &mut self.slash_command {
+            completion.select_prev();
+        }
+    }
+
+    /// Replace the mention or command at the cursor with the selected completion
+    pub fn accept_completion(&mut self) {
+        let (start, replacement) = if let Some(mention) = self.mention.take() {
+            let Some(path) = mention.selected_item() else {
+                return;
+            };
+            (mention.start, format!("@{} ", path))
+        } else if let Some(completion) = self.slash_command.take() {
+            let Some(command) = completion.selected_item() else {
+                return;
+            };
+            (0, format!("/{} ", command.name))
+        } else {
+            return;
+        };
+        self.input_buffer
+            .replace_range(start..self.cursor_position, &replacement);
+        self.cursor_position = start + replacement.len();
+    }
+
+    /// Close completion until the cursor leaves this mention or command
+    pub fn dismiss_completion(&mut self) {
+        if let Some(mention) = self.mention.take() {
+            self.completion_dismissed = Some(mention.start);
+        } else if self.slash_command.take().is_some() {
+            self.completion_dismissed = Some(0);
+        }
+    }
+
     /// Add a character to input buffer
     pub fn input_char(&mut self, c: char) {
         // Check if typing '!' as first character enters bash mode
@@ -1379,15 +3247,320 @@ impl App {
     }
 
     /// Start tracking a running bash command
-    pub fn start_bash_command(&mut self, command: String) {
+    pub fn start_bash_command(
+        &mut self,
+        session_id: String,
+        command: String,
+        interrupt: tokio::sync::oneshot::Sender<()>,
+    ) {
         self.running_bash_command = Some(RunningBashCommand {
+            session_id,
             command,
             started_at: std::time::Instant::now(),
+            interrupt: Some(interrupt),
         });
     }
 
+    /// Check if a bash command is currently running
+    pub fn has_running_bash_command(&self) -> bool {
+        self.running_bash_command.is_some()
+    }
+
+    /// Interrupt the running bash command (Ctrl+C)
+    pub fn interrupt_bash_command(&mut self) {
+        let Some(mut running) = self.running_bash_command.take() else {
+            return;
+        };
+        if let Some(interrupt) = running.interrupt.take() {
+            let _ = interrupt.send(());
+        }
+        if let Some(session) = self.sessions.get_by_id_mut(&running.session_id) {
+            session.add_output("^C interrupted".to_string(), OutputType::SystemMessage);
+            session.add_output(String::new(), OutputType::Text);
+            session.scroll_to_bottom();
+        }
+    }
+
     /// Complete the running bash command
     pub fn complete_bash_command(&mut self) {
         self.running_bash_command = None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::AgentType;
+
+    fn app() -> App {
+        App::new(
+            PathBuf::from("/tmp"),
+            WorktreeConfig {
+                worktree_dir: PathBuf::from("/tmp/worktrees"),
+                template: crate::git::DEFAULT_WORKTREE_TEMPLATE.to_string(),
+            },
+            vec![],
+            NotificationConfig::default(),
+            UiState::default(),
+        )
+    }
+
+    fn image() -> ImageAttachment {
+        ImageAttachment {
+            filename: "screenshot.png".to_string(),
+            mime_type: "image/png".to_string(),
+            data: "aGVsbG8=".to_string(),
+        }
+    }
+
+    fn mcp_server(name: &str) -> McpServerConfig {
+        toml::from_str(&format!("name = \"{}\"\ncommand = \"{}-mcp\"", name, name)).unwrap()
+    }
+
+    #[test]
+    fn test_mcp_server_list_toggle() {
+        let mut list = McpServerList::new(vec![mcp_server("github"), mcp_server("sentry")]);
+        let enabled = |list: &McpServerList| -> Vec<String> {
+            list.enabled().map(|server| server.name.clone()).collect()
+        };
+        assert_eq!(enabled(&list), vec!["github", "sentry"]);
+
+        list.select_next();
+        list.toggle_selected();
+        assert!(!list.servers[1].enabled);
+        assert_eq!(enabled(&list), vec!["github"]);
+
+        list.select_next(); // Wraps around
+        list.toggle_selected();
+        assert!(enabled(&list).is_empty());
+
+        list.toggle_selected();
+        assert_eq!(enabled(&list), vec!["github"]);
+
+        // Nothing to toggle in an empty list
+        let mut empty = McpServerList::new(vec![]);
+        empty.toggle_selected();
+        assert_eq!(empty.enabled().count(), 0);
+    }
+
+    #[test]
+    fn test_prompts_with_refused_images_cant_be_submitted() {
+        let mut app = app();
+        let mut session = Session::mock("1", "test", AgentType::ClaudeCode, "main");
+        session.accepts_images = Some(false);
+        app.sessions.add_session(session);
+        assert!(app.can_submit_prompt());
+
+        app.add_attachment(image());
+        assert!(!app.can_submit_prompt());
+
+        // Confirming a large prompt goes through the same check
+        app.large_prompt_threshold = 1;
+        assert!(app.needs_large_prompt_confirm());
+        app.open_large_prompt_confirm();
+        assert!(!app.confirm_large_prompt());
+        assert_eq!(app.input_mode, InputMode::Insert);
+
+        app.clear_attachments();
+        app.open_large_prompt_confirm();
+        assert!(app.confirm_large_prompt());
+        app.add_attachment(image());
+
+        // Bash commands don't send the images
+        app.bash_mode = true;
+        assert!(app.can_submit_prompt());
+    }
+
+    #[test]
+    fn test_picker_pages_by_rendered_height() {
+        let mut app = app();
+        let entries = (0..20)
+            .map(|i| WorktreeEntry {
+                name: format!("wt-{i}"),
+                path: PathBuf::from(format!("/tmp/wt-{i}")),
+                is_create_new: false,
+                is_clean: true,
+                is_merged: false,
+            })
+            .collect();
+        let mut picker = WorktreePickerState::new(entries);
+        picker.viewport_height = 4;
+        app.worktree_picker = Some(picker);
+        app.input_mode = InputMode::WorktreePicker;
+        let selected = |app: &App| app.worktree_picker.as_ref().unwrap().selected;
+
+        app.picker_page(true, false);
+        assert_eq!(selected(&app), 4);
+        app.picker_page(true, true);
+        assert_eq!(selected(&app), 6);
+        app.picker_page(false, false);
+        assert_eq!(selected(&app), 2);
+
+        // A picker too small to show anything still moves
+        app.worktree_picker.as_mut().unwrap().viewport_height = 0;
+        app.picker_page(true, true);
+        assert_eq!(selected(&app), 3);
+    }
+
+    #[test]
+    fn test_session_switcher_lists_sessions_added_since_render() {
+        let mut app = app();
+        for id in ["1", "2", "3", "4"] {
+            let session = Session::mock(id, id, AgentType::ClaudeCode, "main");
+            app.sessions.add_session(session);
+        }
+        app.sessions.sessions_mut()[1].archived = true;
+        // Rendered before the last session was added
+        app.session_display_order.display_to_internal = vec![2, 0];
+
+        app.open_session_switcher();
+        let order: Vec<usize> = app
+            .session_switcher
+            .as_ref()
+            .unwrap()
+            .candidates
+            .iter()
+            .map(|c| c.index)
+            .collect();
+        assert_eq!(order, vec![2, 0, 3]);
+    }
+
+    fn branch_input(branches: &[(&str, bool)]) -> BranchInputState {
+        let branches: Vec<BranchEntry> = branches
+            .iter()
+            .map(|&(name, is_current)| BranchEntry {
+                name: name.to_string(),
+                is_current,
+                is_remote: false,
+            })
+            .collect();
+        let mut state = BranchInputState {
+            repo_path: PathBuf::from("/tmp/repo"),
+            input: String::new(),
+            cursor_position: 0,
+            filtered: vec![],
+            branches,
+            selected: 0,
+            show_autocomplete: true,
+            error: None,
+            suggestion: None,
+            agent_type: None,
+            picking_base: true,
+            base: None,
+        };
+        state.update_filter();
+        state
+    }
+
+    #[test]
+    fn test_choose_base() {
+        let branches = [("main", true), ("develop", false)];
+
+        // The highlighted branch becomes the base
+        let mut state = branch_input(&branches);
+        state.input = "dev".to_string();
+        state.update_filter();
+        state.choose_base();
+        assert_eq!(
+            state.base.as_ref().map(|b| b.name.as_str()),
+            Some("develop")
+        );
+        assert!(!state.picking_base);
+        assert!(state.input.is_empty());
+        assert_eq!(state.filtered.len(), 2);
+
+        // The checked-out branch keeps branching from HEAD
+        let mut state = branch_input(&branches);
+        state.choose_base();
+        assert!(state.base.is_none());
+
+        // A typed ref that matches no branch is used as is
+        let mut state = branch_input(&branches);
+        state.input = "v1.2".to_string();
+        state.update_filter();
+        state.choose_base();
+        assert_eq!(state.base.as_ref().map(|b| b.name.as_str()), Some("v1.2"));
+
+        // Nothing typed and nothing to pick branches from HEAD
+        let mut state = branch_input(&[]);
+        state.choose_base();
+        assert!(state.base.is_none());
+        assert!(!state.picking_base);
+    }
+
+    fn cleanup_entry(name: &str, error: Option<&str>) -> CleanupEntry {
+        CleanupEntry {
+            path: PathBuf::from(format!("/tmp/worktrees/{name}")),
+            branch: Some(name.to_string()),
+            is_clean: true,
+            is_merged: true,
+            is_experiment: false,
+            selected: false,
+            is_deleting: false,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_select_failed_cleanup_entries() {
+        let mut state = WorktreeCleanupState::new(
+            PathBuf::from("/tmp/repo"),
+            vec![
+                cleanup_entry("a", None),
+                cleanup_entry("b", Some("branch not merged")),
+                cleanup_entry("c", Some("worktree is dirty")),
+            ],
+        );
+        state.entries[0].selected = true;
+        assert_eq!(state.failed_count(), 2);
+
+        // Only the failed entries stay selected for the retry
+        state.select_failed();
+        let selected: Vec<_> = state
+            .selected_entries()
+            .iter()
+            .map(|e| e.branch.as_deref())
+            .collect();
+        assert_eq!(selected, vec![Some("b"), Some("c")]);
+    }
+
+    #[test]
+    fn test_cleanup_toggle_force() {
+        let mut state =
+            WorktreeCleanupState::new(PathBuf::from("/tmp/repo"), vec![cleanup_entry("a", None)]);
+        // Changes are never thrown away unless asked for
+        assert!(!state.force);
+
+        state.toggle_force();
+        assert!(state.force);
+        state.toggle_force();
+        assert!(!state.force);
+        // Independent of deleting branches
+        assert!(state.delete_branches);
+    }
+
+    #[test]
+    fn test_selecting_a_session_clears_unread() {
+        let mut app = app();
+        for id in ["1", "2", "3", "4"] {
+            let mut session = Session::mock(id, id, AgentType::ClaudeCode, "main");
+            session.has_unread = true;
+            app.sessions.add_session(session);
+        }
+        let unread = |app: &App, id: &str| app.sessions.get_by_id(id).unwrap().has_unread;
+
+        app.select_session(0);
+        assert!(!unread(&app, "1"));
+        app.next_session();
+        assert!(!unread(&app, "2"));
+        assert!(unread(&app, "3"));
+
+        // Killing a session selects the next one, which is then read
+        app.kill_selected_session();
+        assert_eq!(app.selected_session().unwrap().id, "3");
+        assert!(!unread(&app, "3"));
+        app.kill_session("3");
+        assert_eq!(app.selected_session().unwrap().id, "4");
+        assert!(!unread(&app, "4"));
+    }
+}
@@ -1,47 +1,170 @@
-//! Clipboard handling for image paste support
+//! Clipboard handling for image paste support, and copying text out
 
 use anyhow::Result;
 use arboard::Clipboard;
 use base64::Engine;
 use image::ImageEncoder;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Content read from the clipboard
+#[derive(Debug)]
 pub enum ClipboardContent {
     /// Plain text content
     Text(String),
-    /// Image content with base64-encoded PNG data
+    /// Image content with base64-encoded data
     Image {
+        filename: String,
         data: String, // base64 encoded
         mime_type: String,
     },
-    /// No content available
-    None,
 }
 
 /// Read content from the system clipboard
-/// Prioritizes images over text
-pub fn read_clipboard() -> Result<ClipboardContent> {
+/// Prioritizes copied files, then image data, then text. Returns nothing if empty.
+pub fn read_clipboard() -> Result<Vec<ClipboardContent>> {
     let mut clipboard = Clipboard::new()?;
 
-    // Try image first
+    // Copied files (possibly several): images are attached, other paths pasted
+    if let Ok(files) = clipboard.get().file_list()
+        && !files.is_empty()
+    {
+        return Ok(contents_from_paths(&files));
+    }
+
+    // Then raw image data
     if let Ok(img) = clipboard.get_image() {
         let png_data = encode_as_png(&img)?;
         let base64_data = base64::engine::general_purpose::STANDARD.encode(&png_data);
-        return Ok(ClipboardContent::Image {
+        return Ok(vec![ClipboardContent::Image {
+            filename: "clipboard".to_string(),
             data: base64_data,
             mime_type: "image/png".to_string(),
-        });
+        }]);
     }
 
     // Fall back to text
     if let Ok(text) = clipboard.get_text()
         && !text.is_empty()
     {
-        return Ok(ClipboardContent::Text(text));
+        return Ok(contents_from_text(&text));
+    }
+
+    Ok(vec![])
+}
+
+/// Clipboard kept alive after writing: on X11/Wayland the copied text is
+/// served by its owner and would vanish as soon as it's dropped
+static WRITE_CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Copy text to the system clipboard
+pub fn write_clipboard(text: &str) -> Result<()> {
+    let mut guard = WRITE_CLIPBOARD
+        .lock()
+        .map_err(|_| anyhow::anyhow!("clipboard lock poisoned"))?;
+    let clipboard = match guard.as_mut() {
+        Some(clipboard) => clipboard,
+        None => guard.insert(Clipboard::new()?),
+    };
+    clipboard.set_text(text)?;
+    Ok(())
+}
+
+/// Images for the paths that are image files, and the remaining paths as text
+fn contents_from_paths(paths: &[PathBuf]) -> Vec<ClipboardContent> {
+    let mut contents = vec![];
+    let mut other_paths = vec![];
+    for path in paths {
+        match load_image_from_path(path) {
+            Some((filename, mime_type, data)) => contents.push(ClipboardContent::Image {
+                filename,
+                data,
+                mime_type,
+            }),
+            None => other_paths.push(path.display().to_string()),
+        }
+    }
+    if !other_paths.is_empty() {
+        contents.push(ClipboardContent::Text(other_paths.join(" ")));
+    }
+    contents
+}
+
+/// Interpret pasted text: if every line is a path to an image file (one or
+/// several dropped files), the images are attached; otherwise it's plain text.
+/// An image sent as a Kitty or iTerm2 graphics escape is attached too.
+pub fn contents_from_text(text: &str) -> Vec<ClipboardContent> {
+    if let Some(image) = image_from_escape(text) {
+        return vec![image];
+    }
+    let images: Option<Vec<ClipboardContent>> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let path = try_parse_image_path(line)?;
+            let (filename, mime_type, data) = load_image_from_path(&path)?;
+            Some(ClipboardContent::Image {
+                filename,
+                data,
+                mime_type,
+            })
+        })
+        .collect();
+    match images {
+        Some(images) if !images.is_empty() => images,
+        _ => vec![ClipboardContent::Text(text.to_string())],
     }
+}
+
+/// An image transmitted inline with a terminal graphics escape: Kitty's
+/// `ESC _G f=100,...;<base64> ESC \` or iTerm2's `ESC ]1337;File=...:<base64> BEL`
+fn image_from_escape(text: &str) -> Option<ClipboardContent> {
+    let text = text.trim();
+    let (data, filename) = if let Some(rest) = text.strip_prefix("\x1b_G") {
+        let rest = rest.strip_suffix("\x1b\\")?;
+        let (control, payload) = rest.split_once(';')?;
+        // Only directly transmitted PNG data (f=100), not files or raw pixels
+        let keys: Vec<&str> = control.split(',').collect();
+        if !keys.contains(&"f=100") || keys.iter().any(|k| k.starts_with("t=") && *k != "t=d") {
+            return None;
+        }
+        (payload, "image".to_string())
+    } else if let Some(rest) = text.strip_prefix("\x1b]1337;File=") {
+        let rest = rest
+            .strip_suffix('\x07')
+            .or_else(|| rest.strip_suffix("\x1b\\"))?;
+        let (args, payload) = rest.split_once(':')?;
+        let filename = args
+            .split(';')
+            .find_map(|arg| arg.strip_prefix("name="))
+            .and_then(|name| base64::engine::general_purpose::STANDARD.decode(name).ok())
+            .and_then(|name| String::from_utf8(name).ok())
+            .unwrap_or_else(|| "image".to_string());
+        (payload, filename)
+    } else {
+        return None;
+    };
 
-    Ok(ClipboardContent::None)
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .ok()?;
+    let mime_type = image_mime_type(&bytes)?;
+    Some(ClipboardContent::Image {
+        filename,
+        data: data.trim().to_string(),
+        mime_type: mime_type.to_string(),
+    })
+}
+
+/// MIME type of image data agents accept, going by its content
+fn image_mime_type(data: &[u8]) -> Option<&'static str> {
+    match image::guess_format(data).ok()? {
+        image::ImageFormat::Png => Some("image/png"),
+        image::ImageFormat::Jpeg => Some("image/jpeg"),
+        image::ImageFormat::Gif => Some("image/gif"),
+        image::ImageFormat::WebP => Some("image/webp"),
+        _ => None,
+    }
 }
 
 /// Encode an arboard ImageData as PNG
@@ -73,15 +196,13 @@ pub fn load_image_from_path(path: &Path) -> Option<(String, String, String)> {
     }
 
     let extension = path.extension()?.to_str()?;
-    let mime_type = match extension.to_lowercase().as_str() {
-        "png" => "image/png",
-        "jpg" | "jpeg" => "image/jpeg",
-        "gif" => "image/gif",
-        "webp" => "image/webp",
-        _ => return None,
-    };
+    if !is_image_extension(extension) {
+        return None;
+    }
 
+    // Trust the content over the name, so a misnamed file isn't sent as an image
     let data = std::fs::read(path).ok()?;
+    let mime_type = image_mime_type(&data)?;
     let base64_data = base64::engine::general_purpose::STANDARD.encode(&data);
 
     let filename = path
@@ -93,6 +214,13 @@ pub fn load_image_from_path(path: &Path) -> Option<(String, String, String)> {
     Some((filename, mime_type.to_string(), base64_data))
 }
 
+fn is_image_extension(extension: &str) -> bool {
+    matches!(
+        extension.to_lowercase().as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "webp"
+    )
+}
+
 /// Check if a string looks like a file path to an image
 pub fn try_parse_image_path(text: &str) -> Option<std::path::PathBuf> {
     let trimmed = text.trim();
@@ -106,29 +234,161 @@ pub fn try_parse_image_path(text: &str) -> Option<std::path::PathBuf> {
     // 1. Direct path (may contain spaces)
     // 2. Path with escaped spaces (backslash before space)
     // 3. Quoted path
+    // 4. file:// URI, as dropped by some terminals and file managers
+    let unquoted = trimmed.trim_matches('"').trim_matches('\'');
     let candidates = [
-        trimmed.to_string(),
+        Some(trimmed.to_string()),
         // Remove surrounding quotes if present
-        trimmed.trim_matches('"').trim_matches('\'').to_string(),
+        Some(unquoted.to_string()),
         // Unescape backslash-escaped spaces
-        trimmed.replace("\\ ", " "),
+        Some(trimmed.replace("\\ ", " ")),
+        file_uri_path(unquoted),
     ];
 
-    for candidate in &candidates {
+    for candidate in candidates.iter().flatten() {
         let path = Path::new(candidate);
 
         // Check if extension is an image type
-        if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
-            let is_image = matches!(
-                extension.to_lowercase().as_str(),
-                "png" | "jpg" | "jpeg" | "gif" | "webp"
-            );
-
-            if is_image && path.exists() {
-                return Some(path.to_path_buf());
-            }
+        if let Some(extension) = path.extension().and_then(|e| e.to_str())
+            && is_image_extension(extension)
+            && path.is_file()
+        {
+            return Some(path.to_path_buf());
         }
     }
 
     None
 }
+
+/// The local path a `file://` URI points to, percent-decoded
+/// (`file:///tmp/My%20Shot.png` is `/tmp/My Shot.png`)
+fn file_uri_path(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file://")?;
+    // Only local files: an empty host or localhost
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_image(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("amux_clipboard_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        // Only the signature: images are recognized by their content
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n").unwrap();
+        path
+    }
+
+    fn filenames(contents: &[ClipboardContent]) -> Vec<&str> {
+        contents
+            .iter()
+            .map(|content| match content {
+                ClipboardContent::Image { filename, .. } => filename.as_str(),
+                ClipboardContent::Text(text) => text.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_multiple_image_paths_become_images() {
+        let a = temp_image("a.png");
+        let b = temp_image("b.jpg");
+        let text = format!("{}\n{}\n", a.display(), b.display());
+        let contents = contents_from_text(&text);
+        assert!(
+            contents
+                .iter()
+                .all(|c| matches!(c, ClipboardContent::Image { .. }))
+        );
+        assert_eq!(filenames(&contents), vec!["a.png", "b.jpg"]);
+    }
+
+    #[test]
+    fn test_mixed_text_stays_text() {
+        let a = temp_image("c.png");
+        let text = format!("{}\nsee above", a.display());
+        let contents = contents_from_text(&text);
+        assert!(matches!(&contents[..], [ClipboardContent::Text(t)] if *t == text));
+    }
+
+    #[test]
+    fn test_file_uri_paths() {
+        let image = temp_image("drop me.png");
+        let uri = format!("file://{}", image.display()).replace(' ', "%20");
+        assert_eq!(try_parse_image_path(&uri), Some(image.clone()));
+        let uri = format!("file://localhost{}", image.display()).replace(' ', "%20");
+        assert_eq!(try_parse_image_path(&format!("'{}'", uri)), Some(image));
+
+        assert_eq!(
+            file_uri_path("file:///a/%C3%A9.png").as_deref(),
+            Some("/a/é.png")
+        );
+        assert_eq!(file_uri_path("file://server/share/a.png"), None);
+        assert_eq!(file_uri_path("file:///bad%2"), None);
+        assert_eq!(file_uri_path("/plain/path.png"), None);
+    }
+
+    #[test]
+    fn test_only_real_images_are_attached() {
+        let fake = temp_image("fake.png");
+        std::fs::write(&fake, b"not really a png").unwrap();
+        assert!(matches!(
+            &contents_from_text(&fake.display().to_string())[..],
+            [ClipboardContent::Text(_)]
+        ));
+    }
+
+    #[test]
+    fn test_graphics_escape_images() {
+        let png = base64::engine::general_purpose::STANDARD.encode(b"\x89PNG\r\n\x1a\n");
+        let name = base64::engine::general_purpose::STANDARD.encode("shot.png");
+
+        let kitty = format!("\x1b_Gf=100,a=T;{}\x1b\\", png);
+        let iterm = format!("\x1b]1337;File=name={};inline=1:{}\x07", name, png);
+        for (escape, filename) in [(kitty, "image"), (iterm, "shot.png")] {
+            let contents = contents_from_text(&escape);
+            assert!(matches!(
+                &contents[..],
+                [ClipboardContent::Image { filename: f, data, mime_type }]
+                    if f == filename && *data == png && mime_type == "image/png"
+            ));
+        }
+
+        // Raw pixels, or data that isn't an image, stay text
+        let raw = format!("\x1b_Gf=32,a=T;{}\x1b\\", png);
+        let text = base64::engine::general_purpose::STANDARD.encode("hello");
+        let not_image = format!("\x1b_Gf=100;{}\x1b\\", text);
+        for escape in [raw, not_image] {
+            assert!(matches!(
+                &contents_from_text(&escape)[..],
+                [ClipboardContent::Text(_)]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_copied_files_split_into_images_and_paths() {
+        let image = temp_image("d.webp");
+        let other = PathBuf::from("/tmp/notes.txt");
+        let contents = contents_from_paths(&[image, other]);
+        assert_eq!(filenames(&contents), vec!["d.webp", "/tmp/notes.txt"]);
+    }
+}
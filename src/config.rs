@@ -6,13 +6,59 @@
 //! 3. Configuration file
 //! 4. Default values (lowest priority)
 //!
+//! The configuration file is the first of these that exists:
+//! 1. `$XDG_CONFIG_HOME/amux/config.toml` (when the variable is set)
+//! 2. The platform config directory: `~/.config/amux/config.toml` on Linux,
+//!    `~/Library/Application Support/amux/config.toml` on macOS
+//! 3. `~/.amux/config.toml` (legacy location)
+//!
+//! Worktrees default to `~/.amux/worktrees` if that directory exists; otherwise
+//! to `$XDG_DATA_HOME/amux/worktrees`, or `~/.local/share/amux/worktrees` on
+//! Linux. macOS keeps `~/.amux/worktrees` unless `XDG_DATA_HOME` is set.
+//!
 //! # Example Configuration
 //!
 //! ```toml
 //! # ~/.config/amux/config.toml
 //! worktree_dir = "~/.amux/worktrees"
+//! worktree_template = "{repo}-{branch_safe}"  # also {branch}, e.g. "{repo}/{branch}"
 //! default_agent = "ClaudeCode"
 //! theme = "dark"
+//! show_agent_stderr = false  # show agent stderr lines in the session output (non-JSON stdout always is)
+//! auto_reconnect = true      # respawn and resume sessions whose agent crashed
+//! auto_fetch = false         # fetch every few minutes to keep ahead/behind counts current
+//! queue_broadcast_when_busy = false  # queue [b] broadcasts for busy sessions instead of skipping
+//! confirm_quit = true        # ask before [q] quits while sessions are working
+//! unread_first = true        # float sessions with unread output (●) up in priority sort
+//! auto_accept_tools = ["read", "search"]  # accept these tool kinds without asking (never commands)
+//! never_auto_accept = ["execute"]         # always ask for these, even in accept-all mode
+//! editor = "nvim"            # command for [o] (defaults to $EDITOR)
+//! max_scrollback = 10000     # output lines kept per session (0 = unlimited)
+//! tick_rate_ms = 16          # redraw interval while agents are working
+//! idle_tick_rate_ms = 250    # redraw interval when nothing is animating
+//! spinner = "braille"        # "braille", "dots", "line" or "moon"
+//! heading_style = "colored"  # markdown headings: "bold", "colored" or "underlined"
+//! repeat_key = "."           # normal-mode key repeating the last repeatable action
+//! submit_key = "enter"       # or "ctrl+enter", making Enter insert a new line
+//! explain_error_prompt = "Explain and fix this error:\n\n{error}"
+//! bug_report_repo = "raphi011/amux"  # GitHub repo bug reports are filed against
+//! save_transcripts = false   # mirror each session's output to ~/.amux/transcripts
+//! inline = false             # keep the last frame in the scrollback after quitting
+//! large_prompt_threshold = 20480  # confirm prompts above this many bytes (0 = never)
+//! max_read_file_bytes = 10485760  # larger files are only read by agents in line ranges
+//! terminal_output_limit = 1048576  # command output kept for agents (the end of it)
+//! agent_shell = "bash"       # shell agents' commands run in (default "sh")
+//! agent_shell_login = false  # run it as a login shell, for your profile's PATH and tools
+//! agent_clean_env = false    # give those commands only PATH, HOME, ... of amux's environment
+//! log_level = "info"         # "error", "warn", "info" or "debug" (default)
+//! idle_timeout_mins = 60     # kill sessions idle this long, except the selected one
+//! max_sessions = 8           # ask before starting more sessions than this at once
+//! preamble = "Follow the conventions in CONTRIBUTING.md."  # sent with each new session's first prompt
+//! post_create_hook = "npm install"  # run in new worktrees before the agent starts
+//! trusted_dirs = ["~/code/mine"]  # sessions under these start auto-accepting permissions
+//! trusted_mode = "accept_all"     # or "accept_edits"
+//! sidebar_width = 40         # columns, or a share of the terminal like "30%"
+//! max_conversation_width = 120  # center the conversation in at most this many columns
 //!
 //! # MCP servers available to all sessions
 //! [[mcp_servers]]
@@ -25,17 +71,71 @@
 //! command = "npx"
 //! args = ["-y", "@modelcontextprotocol/server-github"]
 //! env = { GITHUB_TOKEN = "xxx" }
+//!
+//! [[mcp_servers]]
+//! name = "docs"
+//! type = "http"              # or "sse"; stdio when omitted
+//! url = "https://example.com/mcp"
+//! headers = { Authorization = "Bearer xxx" }
+//!
+//! # Additional ACP agents shown in the agent picker
+//! [[agents]]
+//! name = "In-house"
+//! command = "my-agent"
+//! args = ["--acp"]
+//! env = { MY_AGENT_TOKEN = "xxx" }
+//! ```
+//!
+//! A repository can set its own preamble and post-create hook in a `.amux.toml`
+//! at its root (or any directory above the session's), which replace the
+//! global ones. The repo's hook only runs if the repo is in `trusted_dirs`:
+//!
+//! ```toml
+//! preamble = """
+//! Run `cargo clippy` before you finish. Never edit generated files.
+//! """
+//! post_create_hook = "cargo fetch"
 //! ```
 
 #![allow(dead_code)]
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::Deserialize;
 
+use crate::acp::{DEFAULT_AGENT_SHELL, DEFAULT_MAX_READ_BYTES, DEFAULT_TERMINAL_OUTPUT_LIMIT};
 use crate::notification::NotificationConfig;
-use crate::session::AgentType;
+use crate::session::{AgentType, CustomAgent, DEFAULT_MAX_SCROLLBACK, PermissionMode};
+
+/// Default redraw interval while agents are working (~60 FPS)
+pub const DEFAULT_TICK_RATE_MS: u64 = 16;
+
+/// Default redraw interval when no spinner is animating
+pub const DEFAULT_IDLE_TICK_RATE_MS: u64 = 250;
+
+/// Default size in bytes above which a prompt needs confirmation
+pub const DEFAULT_LARGE_PROMPT_THRESHOLD: usize = 20 * 1024;
+
+/// Default normal-mode key repeating the last repeatable action
+pub const DEFAULT_REPEAT_KEY: char = '.';
+
+/// Default GitHub repository bug reports (B) are filed against
+pub const DEFAULT_BUG_REPORT_REPO: &str = "raphi011/amux";
+
+/// Default prompt sent by "explain this error"; `{error}` is replaced by the error
+pub const DEFAULT_EXPLAIN_ERROR_PROMPT: &str = "Explain and fix this error:\n\n{error}";
+
+/// Fill an "explain this error" prompt template. Templates without `{error}`
+/// get the error appended.
+pub fn explain_error_prompt(template: &str, error: &str) -> String {
+    if template.contains("{error}") {
+        template.replace("{error}", error)
+    } else {
+        format!("{}\n\n{}", template.trim_end(), error)
+    }
+}
 
 /// Main configuration structure.
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -44,6 +144,10 @@ pub struct Config {
     /// Directory for git worktrees
     pub worktree_dir: Option<PathBuf>,
 
+    /// Naming template for new worktrees inside `worktree_dir`, using the
+    /// `{repo}`, `{branch}` and `{branch_safe}` placeholders
+    pub worktree_template: Option<String>,
+
     /// Default agent to use for new sessions
     pub default_agent: Option<AgentType>,
 
@@ -58,9 +162,302 @@ pub struct Config {
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
 
+    /// User-defined ACP agents, offered alongside the built-in ones
+    #[serde(default)]
+    pub agents: Vec<CustomAgent>,
+
     /// Desktop notification settings
     #[serde(default)]
     pub notifications: NotificationConfigFile,
+
+    /// Show agent stderr lines in the session output (always captured for bug reports).
+    /// Off by default since agents log a lot there; non-JSON lines on stdout are
+    /// shown dimmed either way.
+    pub show_agent_stderr: bool,
+
+    /// Respawn and resume a session when its agent process dies unexpectedly
+    pub auto_reconnect: bool,
+
+    /// Periodically `git fetch` in session directories so ahead/behind counts stay current
+    pub auto_fetch: bool,
+
+    /// Queue broadcast prompts for busy sessions until they're idle (otherwise skip them)
+    pub queue_broadcast_when_busy: bool,
+
+    /// Ask for confirmation when quitting while sessions are mid-prompt
+    pub confirm_quit: bool,
+
+    /// In priority sort mode, list sessions with unread output before others of the same priority
+    pub unread_first: bool,
+
+    /// Tool kinds (e.g. "read", "search") whose permission requests are accepted
+    /// without asking, in any permission mode; commands ("execute") never are
+    pub auto_accept_tools: Vec<String>,
+
+    /// Tool kinds or names that always ask for permission, even in accept-all mode (but not yolo)
+    pub never_auto_accept: Vec<String>,
+
+    /// Editor command opened in the session's directory (falls back to $EDITOR)
+    pub editor: Option<String>,
+
+    /// Maximum output lines kept per session before trimming (0 = unlimited)
+    pub max_scrollback: Option<usize>,
+
+    /// Redraw interval in milliseconds while a spinner is animating
+    pub tick_rate_ms: Option<u64>,
+
+    /// Redraw interval in milliseconds when nothing is animating
+    pub idle_tick_rate_ms: Option<u64>,
+
+    /// Spinner animation style ("braille", "dots", "line" or "moon")
+    pub spinner: Option<String>,
+
+    /// Markdown heading style ("bold", "colored" or "underlined")
+    pub heading_style: Option<String>,
+
+    /// Normal-mode key repeating the last scroll, model/mode/permission cycle or duplicate
+    pub repeat_key: Option<char>,
+
+    /// Key that sends the prompt in insert mode ("enter" or "ctrl+enter")
+    pub submit_key: SubmitKey,
+
+    /// GitHub repository ("owner/name") bug reports are filed against
+    pub bug_report_repo: Option<String>,
+
+    /// Prompt sent by "explain this error" (e); `{error}` is replaced by the error text
+    pub explain_error_prompt: Option<String>,
+
+    /// Write each session's output to a timestamped log in `~/.amux/transcripts` as it arrives
+    pub save_transcripts: bool,
+
+    /// Render on the primary screen instead of the alternate one, so the last
+    /// frame stays in the terminal's scrollback after quitting
+    pub inline: bool,
+
+    /// Prompts larger than this many bytes (text plus attachments) need confirmation (0 = never)
+    pub large_prompt_threshold: Option<usize>,
+
+    /// Files larger than this many bytes aren't handed to agents whole
+    pub max_read_file_bytes: Option<u64>,
+
+    /// Bytes of command output kept for agents that don't set their own limit
+    pub terminal_output_limit: Option<usize>,
+
+    /// Shell the commands agents run are started with (`<shell> -c <command>`)
+    pub agent_shell: Option<String>,
+
+    /// Start that shell as a login shell, so commands see the user's profile
+    /// environment (PATH, version managers) and not only amux's own
+    pub agent_shell_login: bool,
+
+    /// Give those commands only PATH, HOME, USER, LANG, TERM and TMPDIR of
+    /// amux's environment (plus what the agent sets), not all of it
+    pub agent_clean_env: bool,
+
+    /// Most verbose log level written ("error", "warn", "info" or "debug")
+    pub log_level: Option<String>,
+
+    /// Kill sessions idle for this many minutes (unset or 0 = never)
+    pub idle_timeout_mins: Option<u64>,
+
+    /// Ask before starting a session beyond this many (unset or 0 = unlimited)
+    pub max_sessions: Option<usize>,
+
+    /// Text sent ahead of the first prompt of every new session
+    pub preamble: Option<String>,
+
+    /// Shell command run in each new worktree before its agent starts
+    pub post_create_hook: Option<String>,
+
+    /// Sessions started under these directories begin in `trusted_mode`
+    pub trusted_dirs: Vec<PathBuf>,
+
+    /// Permission mode for sessions in a trusted directory
+    pub trusted_mode: TrustedMode,
+
+    /// Sidebar width in columns (`40`) or as a share of the terminal (`"30%"`)
+    pub sidebar_width: SidebarWidth,
+
+    /// Widest the conversation column gets, centered in the space beside the
+    /// sidebar (unset or 0 = full width)
+    pub max_conversation_width: Option<u16>,
+}
+
+/// Narrowest sidebar a setting can ask for
+const MIN_SIDEBAR_WIDTH: u16 = 20;
+
+/// Widest share of the terminal the sidebar may take, in percent
+const MAX_SIDEBAR_SHARE: u16 = 60;
+
+/// Width of the session sidebar
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "SidebarWidthValue")]
+pub enum SidebarWidth {
+    Columns(u16),
+    Percent(u16),
+}
+
+impl Default for SidebarWidth {
+    fn default() -> Self {
+        SidebarWidth::Columns(40)
+    }
+}
+
+impl SidebarWidth {
+    /// Columns the sidebar takes in a terminal `terminal_width` wide, kept
+    /// between `MIN_SIDEBAR_WIDTH` and `MAX_SIDEBAR_SHARE` of the terminal
+    pub fn columns(&self, terminal_width: u16) -> u16 {
+        let width = match *self {
+            SidebarWidth::Columns(columns) => columns,
+            SidebarWidth::Percent(percent) => {
+                (u32::from(terminal_width) * u32::from(percent) / 100) as u16
+            }
+        };
+        let max = (u32::from(terminal_width) * u32::from(MAX_SIDEBAR_SHARE) / 100) as u16;
+        width.max(MIN_SIDEBAR_WIDTH).min(max)
+    }
+}
+
+/// `sidebar_width` as written in the config file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SidebarWidthValue {
+    Columns(u16),
+    Text(String),
+}
+
+impl TryFrom<SidebarWidthValue> for SidebarWidth {
+    type Error = String;
+
+    fn try_from(value: SidebarWidthValue) -> Result<Self, Self::Error> {
+        match value {
+            SidebarWidthValue::Columns(columns) => Ok(SidebarWidth::Columns(columns)),
+            SidebarWidthValue::Text(text) => text
+                .trim()
+                .strip_suffix('%')
+                .and_then(|percent| percent.trim().parse().ok())
+                .filter(|percent| (1..=100).contains(percent))
+                .map(SidebarWidth::Percent)
+                .ok_or_else(|| format!("invalid sidebar_width {:?}: use columns or \"N%\"", text)),
+        }
+    }
+}
+
+/// Which Enter sends the prompt; the other one inserts a new line
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum SubmitKey {
+    /// Enter sends, Shift+Enter / Ctrl+j insert a new line
+    #[default]
+    #[serde(rename = "enter")]
+    Enter,
+    /// Enter inserts a new line, Ctrl+Enter / Shift+Enter / Ctrl+j send
+    #[serde(rename = "ctrl+enter")]
+    CtrlEnter,
+}
+
+/// Permission mode a session in a trusted directory starts in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustedMode {
+    AcceptEdits,
+    #[default]
+    AcceptAll,
+}
+
+impl From<TrustedMode> for PermissionMode {
+    fn from(mode: TrustedMode) -> Self {
+        match mode {
+            TrustedMode::AcceptEdits => PermissionMode::AcceptEdits,
+            TrustedMode::AcceptAll => PermissionMode::AcceptAll,
+        }
+    }
+}
+
+/// Directories whose sessions skip the usual permission prompts
+#[derive(Debug, Clone, Default)]
+pub struct TrustedDirs {
+    pub dirs: Vec<PathBuf>,
+    pub mode: PermissionMode,
+}
+
+impl TrustedDirs {
+    /// The permission mode to start a session in `cwd` with, if it's trusted.
+    /// Paths are compared by component, so `~/code` doesn't trust `~/code-other`.
+    pub fn mode_for(&self, cwd: &Path) -> Option<PermissionMode> {
+        self.dirs
+            .iter()
+            .any(|dir| cwd.starts_with(dir))
+            .then_some(self.mode)
+    }
+}
+
+/// Per-repository settings from a `.amux.toml`
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct RepoConfig {
+    /// Text sent ahead of the first prompt of new sessions in this repo
+    pub preamble: Option<String>,
+    /// Shell command run in new worktrees of this repo before the agent starts
+    pub post_create_hook: Option<String>,
+}
+
+/// Name of the per-repository config file
+pub const REPO_CONFIG_FILE: &str = ".amux.toml";
+
+impl RepoConfig {
+    /// Load the nearest `.amux.toml` in `dir` or its ancestors.
+    ///
+    /// Returns `None` if there is none; an unreadable file is logged and skipped.
+    pub fn find(dir: &Path) -> Option<Self> {
+        let path = dir
+            .ancestors()
+            .map(|ancestor| ancestor.join(REPO_CONFIG_FILE))
+            .find(|path| path.is_file())?;
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| toml::from_str(&contents).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(config) => Some(config),
+            Err(e) => {
+                crate::log::warn(&format!("Ignoring {}: {}", path.display(), e));
+                None
+            }
+        }
+    }
+}
+
+/// The preamble for new sessions in `dir`: the repo's own if it sets one,
+/// otherwise the global one. Blank preambles count as unset.
+pub fn session_preamble(dir: &Path, global: Option<&str>) -> Option<String> {
+    RepoConfig::find(dir)
+        .and_then(|repo| repo.preamble)
+        .or_else(|| global.map(str::to_string))
+        .filter(|preamble| !preamble.trim().is_empty())
+}
+
+/// A post-create hook for a new worktree
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostCreateHook {
+    /// Run this command
+    Run(String),
+    /// The repo's own command, not run since the repo isn't trusted
+    Untrusted(String),
+}
+
+/// The hook to run in a freshly created worktree at `dir`: the repo's own
+/// if it sets one, otherwise the global one. Blank commands count as unset.
+/// Anyone can commit a `.amux.toml`, so the repo's own hook only runs if the
+/// repo is `trusted`.
+pub fn post_create_hook(dir: &Path, global: Option<&str>, trusted: bool) -> Option<PostCreateHook> {
+    let not_blank = |hook: &String| !hook.trim().is_empty();
+    match RepoConfig::find(dir).and_then(|repo| repo.post_create_hook) {
+        Some(hook) if trusted => Some(hook).filter(not_blank).map(PostCreateHook::Run),
+        Some(hook) => Some(hook).filter(not_blank).map(PostCreateHook::Untrusted),
+        None => global
+            .map(str::to_string)
+            .filter(not_blank)
+            .map(PostCreateHook::Run),
+    }
 }
 
 /// Notification configuration from config file.
@@ -95,13 +492,42 @@ impl From<NotificationConfigFile> for NotificationConfig {
     }
 }
 
+/// How agents connect to an MCP server
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// Agent runs `command` and talks over stdin/stdout
+    #[default]
+    Stdio,
+    /// Streamable HTTP at `url`
+    Http,
+    /// Server-sent events at `url`
+    Sse,
+}
+
+impl McpTransport {
+    /// Short name shown in the MCP manager
+    pub fn label(self) -> &'static str {
+        match self {
+            McpTransport::Stdio => "stdio",
+            McpTransport::Http => "http",
+            McpTransport::Sse => "sse",
+        }
+    }
+}
+
 /// MCP server configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct McpServerConfig {
     /// Unique name for this MCP server
     pub name: String,
 
+    /// Transport: "stdio" (default), "http" or "sse"
+    #[serde(default, rename = "type")]
+    pub transport: McpTransport,
+
     /// Command to run (for stdio transport)
+    #[serde(default)]
     pub command: String,
 
     /// Arguments to pass to the command
@@ -111,6 +537,14 @@ pub struct McpServerConfig {
     /// Environment variables (name -> value)
     #[serde(default)]
     pub env: HashMap<String, String>,
+
+    /// Server URL (for http and sse transports)
+    #[serde(default)]
+    pub url: String,
+
+    /// HTTP headers (name -> value, for http and sse transports)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 /// Custom keybinding configuration (reserved for future use).
@@ -148,19 +582,28 @@ impl Config {
         }
     }
 
-    /// Get the default configuration file path.
+    /// Get the configuration file path: the first candidate that exists, or
+    /// the preferred location if there's none yet.
     pub fn config_path() -> PathBuf {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("amux")
-            .join("config.toml")
+        let candidates = config_path_candidates(
+            xdg_dir("XDG_CONFIG_HOME"),
+            dirs::config_dir(),
+            dirs::home_dir(),
+        );
+        candidates
+            .iter()
+            .find(|path| path.exists())
+            .or(candidates.first())
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("config.toml"))
     }
 
     /// Get the configuration directory path.
     pub fn config_dir() -> PathBuf {
-        dirs::config_dir()
+        Self::config_path()
+            .parent()
+            .map(Path::to_path_buf)
             .unwrap_or_else(|| PathBuf::from("."))
-            .join("amux")
     }
 
     /// Merge with CLI overrides.
@@ -185,16 +628,173 @@ impl Config {
         self.worktree_dir
             .clone()
             .or_else(|| std::env::var("AMUX_WORKTREE_DIR").ok().map(PathBuf::from))
-            .unwrap_or_else(|| {
-                dirs::home_dir()
-                    .unwrap_or_else(|| PathBuf::from("."))
-                    .join(".amux/worktrees")
-            })
+            .unwrap_or_else(default_worktree_dir)
     }
 
     /// Get the default agent type.
     pub fn default_agent(&self) -> AgentType {
-        self.default_agent.unwrap_or(AgentType::ClaudeCode)
+        self.default_agent.clone().unwrap_or(AgentType::ClaudeCode)
+    }
+
+    /// Get the user-defined agents as agent types.
+    pub fn custom_agents(&self) -> Vec<AgentType> {
+        self.agents
+            .iter()
+            .map(|agent| AgentType::Custom(std::sync::Arc::new(agent.clone())))
+            .collect()
+    }
+
+    /// Get the per-session scrollback limit.
+    pub fn max_scrollback(&self) -> usize {
+        self.max_scrollback.unwrap_or(DEFAULT_MAX_SCROLLBACK)
+    }
+
+    /// Get the prompt size in bytes above which sending needs confirmation.
+    pub fn large_prompt_threshold(&self) -> usize {
+        self.large_prompt_threshold
+            .unwrap_or(DEFAULT_LARGE_PROMPT_THRESHOLD)
+    }
+
+    /// Get the most bytes of a file one agent read request returns.
+    pub fn max_read_file_bytes(&self) -> u64 {
+        self.max_read_file_bytes.unwrap_or(DEFAULT_MAX_READ_BYTES)
+    }
+
+    /// Get how many bytes of a command's output agents get by default.
+    pub fn terminal_output_limit(&self) -> usize {
+        self.terminal_output_limit
+            .unwrap_or(DEFAULT_TERMINAL_OUTPUT_LIMIT)
+    }
+
+    /// Get the shell agents' commands run in.
+    pub fn agent_shell(&self) -> String {
+        self.agent_shell
+            .as_deref()
+            .map(str::trim)
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or(DEFAULT_AGENT_SHELL)
+            .to_string()
+    }
+
+    /// Get the widest the conversation column may be, if it's capped.
+    pub fn max_conversation_width(&self) -> Option<u16> {
+        self.max_conversation_width.filter(|&width| width > 0)
+    }
+
+    /// Get the redraw interval used while agents are working.
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms.unwrap_or(DEFAULT_TICK_RATE_MS).max(1))
+    }
+
+    /// Get how long a session may sit idle before it's killed, if ever.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout_mins
+            .filter(|&mins| mins > 0)
+            .map(|mins| Duration::from_secs(mins * 60))
+    }
+
+    /// Get the trusted directories, with `~` expanded.
+    pub fn trusted_dirs(&self) -> TrustedDirs {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        TrustedDirs {
+            dirs: self
+                .trusted_dirs
+                .iter()
+                .map(|dir| expand_home(dir, &home))
+                .collect(),
+            mode: self.trusted_mode.into(),
+        }
+    }
+
+    /// Get the normal-mode key that repeats the last repeatable action.
+    pub fn repeat_key(&self) -> char {
+        self.repeat_key.unwrap_or(DEFAULT_REPEAT_KEY)
+    }
+
+    /// Get the GitHub repository bug reports are filed against.
+    pub fn bug_report_repo(&self) -> String {
+        self.bug_report_repo
+            .as_deref()
+            .map(str::trim)
+            .filter(|repo| !repo.is_empty())
+            .unwrap_or(DEFAULT_BUG_REPORT_REPO)
+            .to_string()
+    }
+
+    /// Get how many sessions may run before starting another needs confirmation.
+    pub fn max_sessions(&self) -> Option<usize> {
+        self.max_sessions.filter(|&max| max > 0)
+    }
+
+    /// Get the redraw interval used when nothing is animating.
+    pub fn idle_tick_rate(&self) -> Duration {
+        Duration::from_millis(
+            self.idle_tick_rate_ms
+                .unwrap_or(DEFAULT_IDLE_TICK_RATE_MS)
+                .max(1),
+        )
+    }
+}
+
+/// `path` with a leading `~` replaced by `home`
+fn expand_home(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// An XDG base directory from the environment. Per the spec, unset, empty
+/// and relative values are ignored.
+fn xdg_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+/// Config file locations in lookup order: XDG, platform, then legacy `~/.amux`
+fn config_path_candidates(
+    xdg_config_home: Option<PathBuf>,
+    platform_config_dir: Option<PathBuf>,
+    home: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = [xdg_config_home, platform_config_dir]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join("amux").join("config.toml"))
+        .collect();
+    candidates.extend(home.map(|home| home.join(".amux").join("config.toml")));
+    candidates.dedup();
+    candidates
+}
+
+/// Default directory for git worktrees (see the module docs for the lookup order)
+pub fn default_worktree_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let legacy = home.join(".amux").join("worktrees");
+    let legacy_exists = legacy.is_dir();
+    resolve_worktree_dir(
+        &home,
+        xdg_dir("XDG_DATA_HOME"),
+        legacy_exists,
+        cfg!(target_os = "linux"),
+    )
+}
+
+fn resolve_worktree_dir(
+    home: &Path,
+    xdg_data_home: Option<PathBuf>,
+    legacy_exists: bool,
+    linux: bool,
+) -> PathBuf {
+    let legacy = home.join(".amux").join("worktrees");
+    if legacy_exists {
+        return legacy;
+    }
+    match xdg_data_home {
+        Some(data_home) => data_home.join("amux").join("worktrees"),
+        None if linux => home.join(".local/share/amux/worktrees"),
+        None => legacy,
     }
 }
 
@@ -208,6 +808,124 @@ mod tests {
         assert!(config.worktree_dir.is_none());
         assert!(config.default_agent.is_none());
         assert!(config.theme.is_none());
+        assert!(!config.show_agent_stderr);
+        assert_eq!(config.max_scrollback(), DEFAULT_MAX_SCROLLBACK);
+        assert_eq!(
+            config.large_prompt_threshold(),
+            DEFAULT_LARGE_PROMPT_THRESHOLD
+        );
+        assert_eq!(
+            config.tick_rate(),
+            Duration::from_millis(DEFAULT_TICK_RATE_MS)
+        );
+        assert_eq!(
+            config.idle_tick_rate(),
+            Duration::from_millis(DEFAULT_IDLE_TICK_RATE_MS)
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_agents() {
+        let toml = r#"
+            [[agents]]
+            name = "In-house"
+            command = "my-agent"
+            args = ["--acp"]
+            env = { TOKEN = "secret" }
+
+            [[agents]]
+            name = "Minimal"
+            command = "other-agent"
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.agents.len(), 2);
+        assert_eq!(config.agents[0].args, vec!["--acp"]);
+        assert_eq!(config.agents[0].env.get("TOKEN").unwrap(), "secret");
+        assert!(config.agents[1].args.is_empty());
+
+        let agents = config.custom_agents();
+        assert_eq!(agents[0].display_name(), "In-house");
+        assert_eq!(agents[0].command(), "my-agent");
+        assert_eq!(agents[0].args(), vec!["--acp"]);
+        assert_eq!(agents[1].display_name(), "Minimal");
+    }
+
+    #[test]
+    fn test_parse_mcp_transports() {
+        let toml = r#"
+            [[mcp_servers]]
+            name = "fs"
+            command = "npx"
+
+            [[mcp_servers]]
+            name = "docs"
+            type = "sse"
+            url = "https://example.com/sse"
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert_eq!(config.mcp_servers[0].transport, McpTransport::Stdio);
+        assert_eq!(config.mcp_servers[1].transport, McpTransport::Sse);
+        assert_eq!(config.mcp_servers[1].url, "https://example.com/sse");
+    }
+
+    #[test]
+    fn test_repo_preamble_overrides_global() {
+        let root = std::env::temp_dir().join(format!("amux_repo_config_{}", std::process::id()));
+        let nested = root.join("src").join("deep");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(
+            session_preamble(&nested, Some("global")).as_deref(),
+            Some("global")
+        );
+        assert_eq!(session_preamble(&nested, Some("  ")), None);
+
+        std::fs::write(root.join(REPO_CONFIG_FILE), "preamble = \"repo\"\n").unwrap();
+        assert_eq!(
+            session_preamble(&nested, Some("global")).as_deref(),
+            Some("repo")
+        );
+
+        // A repo file without a preamble keeps the global one
+        std::fs::write(root.join(REPO_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            session_preamble(&nested, Some("global")).as_deref(),
+            Some("global")
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_repo_post_create_hook_overrides_global() {
+        let root = std::env::temp_dir().join(format!("amux_repo_hook_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let run = |hook: &str| Some(PostCreateHook::Run(hook.to_string()));
+        assert_eq!(
+            post_create_hook(&root, Some("make setup"), false),
+            run("make setup")
+        );
+        assert_eq!(post_create_hook(&root, Some(""), true), None);
+
+        std::fs::write(
+            root.join(REPO_CONFIG_FILE),
+            "post_create_hook = \"npm install\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            post_create_hook(&root, Some("make setup"), true),
+            run("npm install")
+        );
+
+        // A cloned repo's hook doesn't run unless the repo is trusted
+        assert_eq!(
+            post_create_hook(&root, Some("make setup"), false),
+            Some(PostCreateHook::Untrusted("npm install".to_string()))
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
     }
 
     #[test]
@@ -216,11 +934,207 @@ mod tests {
             worktree_dir = "/tmp/worktrees"
             default_agent = "ClaudeCode"
             theme = "dark"
+            show_agent_stderr = true
+            max_scrollback = 500
+            tick_rate_ms = 33
+            idle_tick_rate_ms = 0
+            idle_timeout_mins = 30
         "#;
 
         let config: Config = toml::from_str(toml).unwrap();
         assert_eq!(config.worktree_dir, Some(PathBuf::from("/tmp/worktrees")));
         assert_eq!(config.default_agent, Some(AgentType::ClaudeCode));
         assert_eq!(config.theme, Some("dark".to_string()));
+        assert!(config.show_agent_stderr);
+        assert_eq!(config.max_scrollback(), 500);
+        assert_eq!(config.tick_rate(), Duration::from_millis(33));
+        // A zero interval would busy-loop, so it's clamped
+        assert_eq!(config.idle_tick_rate(), Duration::from_millis(1));
+        assert_eq!(config.idle_timeout(), Some(Duration::from_secs(30 * 60)));
+    }
+
+    #[test]
+    fn test_sidebar_width() {
+        assert_eq!(Config::default().sidebar_width.columns(200), 40);
+
+        let config: Config = toml::from_str("sidebar_width = 50").unwrap();
+        assert_eq!(config.sidebar_width, SidebarWidth::Columns(50));
+        // Clamped to 60% of narrow terminals, and never below 20 columns
+        assert_eq!(config.sidebar_width.columns(60), 36);
+        assert_eq!(SidebarWidth::Columns(5).columns(200), 20);
+
+        let config: Config = toml::from_str(r#"sidebar_width = "25%""#).unwrap();
+        assert_eq!(config.sidebar_width, SidebarWidth::Percent(25));
+        assert_eq!(config.sidebar_width.columns(200), 50);
+        assert_eq!(config.sidebar_width.columns(40), 20);
+
+        for bad in [r#""wide""#, r#""0%""#, r#""150%""#] {
+            assert!(toml::from_str::<Config>(&format!("sidebar_width = {}", bad)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_explain_error_prompt() {
+        assert_eq!(
+            explain_error_prompt(DEFAULT_EXPLAIN_ERROR_PROMPT, "boom"),
+            "Explain and fix this error:\n\nboom"
+        );
+        assert_eq!(
+            explain_error_prompt("Why `{error}`?", "boom"),
+            "Why `boom`?"
+        );
+        assert_eq!(
+            explain_error_prompt("Fix this:\n", "boom"),
+            "Fix this:\n\nboom"
+        );
+    }
+
+    #[test]
+    fn test_repeat_key() {
+        assert_eq!(Config::default().repeat_key(), '.');
+        let config: Config = toml::from_str(r#"repeat_key = ";""#).unwrap();
+        assert_eq!(config.repeat_key(), ';');
+        assert!(toml::from_str::<Config>(r#"repeat_key = "ab""#).is_err());
+    }
+
+    #[test]
+    fn test_submit_key() {
+        assert_eq!(Config::default().submit_key, SubmitKey::Enter);
+        let config: Config = toml::from_str(r#"submit_key = "ctrl+enter""#).unwrap();
+        assert_eq!(config.submit_key, SubmitKey::CtrlEnter);
+        assert!(toml::from_str::<Config>(r#"submit_key = "space""#).is_err());
+    }
+
+    #[test]
+    fn test_bug_report_repo() {
+        assert_eq!(Config::default().bug_report_repo(), "raphi011/amux");
+        let config: Config = toml::from_str(r#"bug_report_repo = "acme/amux-fork""#).unwrap();
+        assert_eq!(config.bug_report_repo(), "acme/amux-fork");
+        let config: Config = toml::from_str(r#"bug_report_repo = " ""#).unwrap();
+        assert_eq!(config.bug_report_repo(), "raphi011/amux");
+    }
+
+    #[test]
+    fn test_max_conversation_width() {
+        assert_eq!(Config::default().max_conversation_width(), None);
+        let config: Config = toml::from_str("max_conversation_width = 0").unwrap();
+        assert_eq!(config.max_conversation_width(), None);
+        let config: Config = toml::from_str("max_conversation_width = 100").unwrap();
+        assert_eq!(config.max_conversation_width(), Some(100));
+    }
+
+    #[test]
+    fn test_agent_shell() {
+        let config = Config::default();
+        assert_eq!(config.agent_shell(), "sh");
+        assert!(!config.agent_shell_login);
+        assert!(!config.agent_clean_env);
+        let config: Config = toml::from_str(
+            r#"
+agent_shell = "bash"
+agent_shell_login = true
+agent_clean_env = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.agent_shell(), "bash");
+        assert!(config.agent_shell_login);
+        assert!(config.agent_clean_env);
+    }
+
+    #[test]
+    fn test_max_sessions_unlimited_by_default_and_zero() {
+        assert_eq!(Config::default().max_sessions(), None);
+        let config: Config = toml::from_str("max_sessions = 0").unwrap();
+        assert_eq!(config.max_sessions(), None);
+        let config: Config = toml::from_str("max_sessions = 4").unwrap();
+        assert_eq!(config.max_sessions(), Some(4));
+    }
+
+    #[test]
+    fn test_trusted_dirs() {
+        let config: Config =
+            toml::from_str(r#"trusted_dirs = ["~/code/mine", "/srv/app"]"#).unwrap();
+        let trusted = config.trusted_dirs();
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        assert_eq!(
+            trusted.mode_for(&home.join("code/mine/amux")),
+            Some(PermissionMode::AcceptAll)
+        );
+        assert_eq!(
+            trusted.mode_for(Path::new("/srv/app")),
+            Some(PermissionMode::AcceptAll)
+        );
+        assert_eq!(trusted.mode_for(Path::new("/srv/app-other")), None);
+        assert_eq!(trusted.mode_for(Path::new("/tmp")), None);
+
+        let config: Config =
+            toml::from_str("trusted_dirs = [\"/srv\"]\ntrusted_mode = \"accept_edits\"").unwrap();
+        assert_eq!(
+            config.trusted_dirs().mode_for(Path::new("/srv/app")),
+            Some(PermissionMode::AcceptEdits)
+        );
+        assert_eq!(
+            Config::default().trusted_dirs().mode_for(Path::new("/")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_idle_timeout_disabled_by_default_and_zero() {
+        assert_eq!(Config::default().idle_timeout(), None);
+        let config: Config = toml::from_str("idle_timeout_mins = 0").unwrap();
+        assert_eq!(config.idle_timeout(), None);
+    }
+
+    #[test]
+    fn test_config_path_candidates_order() {
+        let candidates = config_path_candidates(
+            Some(PathBuf::from("/xdg")),
+            Some(PathBuf::from("/home/u/.config")),
+            Some(PathBuf::from("/home/u")),
+        );
+        assert_eq!(
+            candidates,
+            vec![
+                PathBuf::from("/xdg/amux/config.toml"),
+                PathBuf::from("/home/u/.config/amux/config.toml"),
+                PathBuf::from("/home/u/.amux/config.toml"),
+            ]
+        );
+
+        // On Linux the platform dir already is $XDG_CONFIG_HOME
+        let candidates = config_path_candidates(
+            Some(PathBuf::from("/home/u/.config")),
+            Some(PathBuf::from("/home/u/.config")),
+            None,
+        );
+        assert_eq!(
+            candidates,
+            vec![PathBuf::from("/home/u/.config/amux/config.toml")]
+        );
+    }
+
+    #[test]
+    fn test_resolve_worktree_dir() {
+        let home = Path::new("/home/u");
+        let xdg = Some(PathBuf::from("/data"));
+        assert_eq!(
+            resolve_worktree_dir(home, xdg.clone(), true, true),
+            PathBuf::from("/home/u/.amux/worktrees")
+        );
+        assert_eq!(
+            resolve_worktree_dir(home, xdg.clone(), false, false),
+            PathBuf::from("/data/amux/worktrees")
+        );
+        assert_eq!(
+            resolve_worktree_dir(home, None, false, true),
+            PathBuf::from("/home/u/.local/share/amux/worktrees")
+        );
+        // macOS is unchanged without XDG variables
+        assert_eq!(
+            resolve_worktree_dir(home, None, false, false),
+            PathBuf::from("/home/u/.amux/worktrees")
+        );
     }
 }
@@ -0,0 +1,300 @@
+//! Side-by-side diff alignment for the full-screen diff view, and
+//! word-level highlighting for changed lines in the conversation view.
+
+use std::ops::Range;
+use std::path::Path;
+
+use similar::{ChangeTag, DiffTag, TextDiff};
+
+/// Below this similarity a replaced line is treated as rewritten rather than
+/// edited, and word highlights would just be noise
+const MIN_WORD_DIFF_RATIO: f32 = 0.5;
+
+/// A file change written by an agent via `fs/write_text_file`
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub path: String,
+    /// Whether the write created the file
+    pub created: bool,
+    pub old_content: String,
+    pub new_content: String,
+}
+
+/// How a side-by-side row relates the old and new file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowKind {
+    /// Line unchanged on both sides
+    Equal,
+    /// Line only exists in the old file
+    Delete,
+    /// Line only exists in the new file
+    Insert,
+    /// Old line replaced by new line
+    Replace,
+}
+
+/// One aligned row of a side-by-side diff.
+/// Each side holds a 1-based line number and the line text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SideBySideRow {
+    pub kind: RowKind,
+    pub left: Option<(usize, String)>,
+    pub right: Option<(usize, String)>,
+}
+
+impl FileDiff {
+    /// Align old and new content into side-by-side rows
+    pub fn side_by_side(&self) -> Vec<SideBySideRow> {
+        let diff = TextDiff::from_lines(&self.old_content, &self.new_content);
+        let old_lines: Vec<&str> = self.old_content.lines().collect();
+        let new_lines: Vec<&str> = self.new_content.lines().collect();
+
+        let line = |lines: &[&str], idx: usize| -> Option<(usize, String)> {
+            lines
+                .get(idx)
+                .map(|text| (idx + 1, text.replace('\t', "    ")))
+        };
+
+        let mut rows = Vec::new();
+        for op in diff.ops() {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            let kind = match tag {
+                DiffTag::Equal => RowKind::Equal,
+                DiffTag::Delete => RowKind::Delete,
+                DiffTag::Insert => RowKind::Insert,
+                DiffTag::Replace => RowKind::Replace,
+            };
+            // Pair lines up; the shorter side is padded with empty cells
+            let len = old_range.len().max(new_range.len());
+            for i in 0..len {
+                let left = (i < old_range.len())
+                    .then(|| line(&old_lines, old_range.start + i))
+                    .flatten();
+                let right = (i < new_range.len())
+                    .then(|| line(&new_lines, new_range.start + i))
+                    .flatten();
+                rows.push(SideBySideRow { kind, left, right });
+            }
+        }
+        rows
+    }
+
+    /// Unified diff of the change with `---`/`+++` headers, as copied with [Y]
+    pub fn unified(&self) -> String {
+        TextDiff::from_lines(&self.old_content, &self.new_content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&self.path, &self.path)
+            .to_string()
+    }
+}
+
+/// A `git apply`-able patch of file changes, one entry per file going from
+/// its content before the first change to its content after the last.
+///
+/// Paths are made relative to `root`, the directory to apply the patch in;
+/// files outside it are left out. Returns the patch and how many files it covers.
+pub fn git_patch<'a>(
+    diffs: impl IntoIterator<Item = &'a FileDiff>,
+    root: &Path,
+) -> (String, usize) {
+    // (path, created, before, after) in the order files were first written
+    let mut files: Vec<(&str, bool, &str, &str)> = Vec::new();
+    for diff in diffs {
+        match files.iter_mut().find(|(path, ..)| *path == diff.path) {
+            Some(file) => file.3 = &diff.new_content,
+            None => files.push((
+                &diff.path,
+                diff.created,
+                &diff.old_content,
+                &diff.new_content,
+            )),
+        }
+    }
+
+    let mut patch = String::new();
+    let mut count = 0;
+    for (path, created, before, after) in files {
+        if before == after {
+            continue;
+        }
+        let Ok(relative) = Path::new(path).strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy();
+        let new = format!("b/{}", relative);
+        let old = if created {
+            "/dev/null".to_string()
+        } else {
+            format!("a/{}", relative)
+        };
+
+        patch.push_str(&format!("diff --git a/{} {}\n", relative, new));
+        if created {
+            patch.push_str("new file mode 100644\n");
+        }
+        patch.push_str(
+            &TextDiff::from_lines(before, after)
+                .unified_diff()
+                .context_radius(3)
+                .header(&old, &new)
+                .to_string(),
+        );
+        count += 1;
+    }
+    (patch, count)
+}
+
+/// Byte ranges of the words that changed between an old and a new line.
+///
+/// Returns `(removed, added)`: ranges into `old` that were removed and ranges
+/// into `new` that were added, with adjacent ranges merged. Both are empty when
+/// the lines are too different for word highlights to help.
+pub fn word_changes(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let diff = TextDiff::from_words(old, new);
+    if diff.ratio() < MIN_WORD_DIFF_RATIO {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mut removed: Vec<Range<usize>> = Vec::new();
+    let mut added: Vec<Range<usize>> = Vec::new();
+    let (mut old_pos, mut new_pos) = (0, 0);
+
+    let push = |ranges: &mut Vec<Range<usize>>, range: Range<usize>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+
+    for change in diff.iter_all_changes() {
+        let len = change.value().len();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_pos += len;
+                new_pos += len;
+            }
+            ChangeTag::Delete => {
+                push(&mut removed, old_pos..old_pos + len);
+                old_pos += len;
+            }
+            ChangeTag::Insert => {
+                push(&mut added, new_pos..new_pos + len);
+                new_pos += len;
+            }
+        }
+    }
+    (removed, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_diff(old: &str, new: &str) -> FileDiff {
+        FileDiff {
+            path: "test.txt".to_string(),
+            created: false,
+            old_content: old.to_string(),
+            new_content: new.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_side_by_side_aligns_replacements() {
+        let rows = file_diff("a\nb\nc\n", "a\nB\nc\n").side_by_side();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].kind, RowKind::Equal);
+        assert_eq!(rows[1].kind, RowKind::Replace);
+        assert_eq!(rows[1].left, Some((2, "b".to_string())));
+        assert_eq!(rows[1].right, Some((2, "B".to_string())));
+        assert_eq!(rows[2].left, Some((3, "c".to_string())));
+        assert_eq!(rows[2].right, Some((3, "c".to_string())));
+    }
+
+    #[test]
+    fn test_side_by_side_pads_insertions() {
+        let rows = file_diff("a\n", "a\nb\nc\n").side_by_side();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].kind, RowKind::Insert);
+        assert_eq!(rows[1].left, None);
+        assert_eq!(rows[2].right, Some((3, "c".to_string())));
+    }
+
+    #[test]
+    fn test_side_by_side_new_file() {
+        let rows = file_diff("", "x\ny\n").side_by_side();
+        assert_eq!(rows.len(), 2);
+        assert!(
+            rows.iter()
+                .all(|r| r.kind == RowKind::Insert && r.left.is_none())
+        );
+    }
+
+    #[test]
+    fn test_word_changes_highlights_edited_word() {
+        let old = "let total = count + 1;";
+        let new = "let total = count + 2;";
+        let (removed, added) = word_changes(old, new);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(added.len(), 1);
+        assert_eq!(&old[removed[0].clone()], "1;");
+        assert_eq!(&new[added[0].clone()], "2;");
+    }
+
+    #[test]
+    fn test_word_changes_merges_adjacent_words() {
+        let old = "fn greet(name: &str)";
+        let new = "fn greet(first: &str, last: &str)";
+        let (removed, added) = word_changes(old, new);
+        assert!(!added.is_empty());
+        // Every added range covers text that isn't in the old line
+        for range in &added {
+            assert!(!new[range.clone()].trim().is_empty());
+        }
+        assert!(removed.iter().all(|r| r.end <= old.len()));
+    }
+
+    #[test]
+    fn test_word_changes_skips_rewritten_lines() {
+        let (removed, added) = word_changes("use std::io;", "fn main() {}");
+        assert!(removed.is_empty());
+        assert!(added.is_empty());
+    }
+
+    #[test]
+    fn test_unified_has_headers_and_hunks() {
+        let unified = file_diff("a\nb\n", "a\nc\n").unified();
+        assert_eq!(
+            unified,
+            "--- test.txt\n+++ test.txt\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+        );
+    }
+
+    #[test]
+    fn test_git_patch_combines_writes_per_file() {
+        let write = |path: &str, created, old: &str, new: &str| FileDiff {
+            path: path.to_string(),
+            created,
+            old_content: old.to_string(),
+            new_content: new.to_string(),
+        };
+        let diffs = [
+            write("/repo/src/lib.rs", false, "a\nb\n", "a\nc\n"),
+            write("/repo/new.txt", true, "", "x\n"),
+            write("/repo/src/lib.rs", false, "a\nc\n", "a\nd\n"),
+            write("/elsewhere/notes.md", false, "", "y\n"),
+            // Written and reverted: nothing to apply
+            write("/repo/tmp.txt", false, "t\n", "u\n"),
+            write("/repo/tmp.txt", false, "u\n", "t\n"),
+        ];
+
+        let (patch, files) = git_patch(&diffs, Path::new("/repo"));
+        assert_eq!(files, 2);
+        assert_eq!(
+            patch,
+            "diff --git a/src/lib.rs b/src/lib.rs\n\
+             --- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n a\n-b\n+d\n\
+             diff --git a/new.txt b/new.txt\nnew file mode 100644\n\
+             --- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+x\n"
+        );
+    }
+}
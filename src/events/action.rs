@@ -7,6 +7,7 @@
 use std::path::PathBuf;
 
 use crate::acp::PermissionOptionId;
+use crate::file_ref::FileLocation;
 use crate::session::AgentType;
 
 /// Actions that can be dispatched from event handlers.
@@ -17,6 +18,14 @@ pub enum Action {
     // === Application ===
     /// Quit the application
     Quit,
+    /// Close the quit confirmation dialog without quitting
+    CloseQuitConfirm,
+    /// Start the session held back by `max_sessions` anyway
+    ConfirmSessionLimit,
+    /// Drop the session held back by `max_sessions`
+    CancelSessionLimit,
+    /// Run the last repeatable action again (vim's `.`)
+    RepeatLast,
 
     // === Mode switching ===
     /// Enter insert mode for typing
@@ -29,6 +38,10 @@ pub enum Action {
     OpenHelp,
     /// Close help popup
     CloseHelp,
+    /// Input character into the help filter
+    HelpFilterChar(char),
+    /// Delete character in the help filter
+    HelpFilterBackspace,
 
     // === Session navigation ===
     /// Select next session in list
@@ -37,6 +50,10 @@ pub enum Action {
     PrevSession,
     /// Select session by index (1-9)
     SelectSession(usize),
+    /// Jump to the next session awaiting permission or input
+    JumpToAwaitingSession,
+    /// Flip back to the session selected before the current one
+    SelectPreviousSession,
 
     // === Session management ===
     /// Open folder picker starting at path
@@ -49,6 +66,8 @@ pub enum Action {
     CloseWorktreePicker,
     /// Open agent picker for directory
     OpenAgentPicker { cwd: PathBuf, is_worktree: bool },
+    /// Open agent picker in the selected session's main repo
+    OpenParentRepoPicker,
     /// Close agent picker
     CloseAgentPicker,
     /// Spawn agent in directory
@@ -59,16 +78,40 @@ pub enum Action {
     },
     /// Duplicate current session (same folder, same agent)
     DuplicateSession,
+    /// Duplicate current session into a new worktree of its repo
+    DuplicateIntoWorktree,
+    /// Start the current session's agent in a worktree on a throwaway branch
+    StartExperiment,
+    /// Restart the current session's agent, keeping its output
+    RestartAgent,
     /// Clear current session (replace with fresh session)
     ClearSession,
     /// Open clear session confirmation dialog
     OpenClearConfirm,
     /// Close clear session confirmation dialog
     CloseClearConfirm,
+    /// Clear the output of the current session, keeping the agent running
+    ClearOutput,
+    /// Drop the prompts queued for the current session
+    ClearQueuedPrompts,
+    /// Resend the last prompt of the current session
+    RetryLastPrompt,
+    /// Ask the agent to explain and fix the error nearest the viewport
+    ExplainError,
+    /// Send the agent's nth suggested follow-up prompt
+    SendFollowUp(usize),
     /// Kill selected session
     KillSession,
 
     // === Input handling ===
+    /// Insert the selected `@` file or `/` command completion
+    AcceptCompletion,
+    /// Select next completion
+    CompletionNext,
+    /// Select previous completion
+    CompletionPrev,
+    /// Close completion for the mention or command being typed
+    DismissCompletion,
     /// Add character to input buffer
     InputChar(char),
     /// Delete character before cursor
@@ -99,8 +142,14 @@ pub enum Action {
     InputNewline,
     /// Clear input buffer (Ctrl+C)
     ClearInput,
+    /// Interrupt the running bash command (Ctrl+C)
+    InterruptBashCommand,
     /// Submit prompt
     SubmitPrompt,
+    /// Send a large prompt after confirmation
+    ConfirmLargePrompt,
+    /// Return to editing instead of sending a large prompt
+    CancelLargePrompt,
 
     // === Scrolling ===
     /// Scroll up by n lines
@@ -111,6 +160,8 @@ pub enum Action {
     ScrollToTop,
     /// Scroll to bottom
     ScrollToBottom,
+    /// Scroll so the given rendered line is at the top (e.g. a scrollbar click)
+    ScrollTo(usize),
 
     // === Permissions ===
     /// Allow permission request
@@ -123,6 +174,8 @@ pub enum Action {
     PermissionDown,
     /// Select permission option by index (for mouse clicks)
     SelectPermissionOption(usize),
+    /// Highlight permission option by index without answering (for mouse hover)
+    HoverPermissionOption(usize),
     /// Respond to permission with specific option
     RespondPermission {
         request_id: u64,
@@ -168,6 +221,10 @@ pub enum Action {
     FolderPickerUp,
     /// Navigate folder picker down
     FolderPickerDown,
+    /// Move the open picker's selection a page (or half a page) down
+    PickerPageDown { half: bool },
+    /// Move the open picker's selection a page (or half a page) up
+    PickerPageUp { half: bool },
     /// Input character into folder picker filter
     FolderPickerInputChar(char),
     /// Delete character in folder picker filter
@@ -198,7 +255,7 @@ pub enum Action {
     CloseBranchInput,
     /// Submit branch name
     SubmitBranchInput,
-    /// Accept autocomplete selection
+    /// Accept suggested branch name, or the autocomplete selection
     BranchInputAcceptAutocomplete,
     /// Navigate autocomplete up
     BranchInputUp,
@@ -260,8 +317,12 @@ pub enum Action {
     WorktreeCleanupDeselectAll,
     /// Toggle delete branches option
     WorktreeCleanupToggleBranches,
+    /// Toggle force-deleting dirty worktrees and unmerged branches
+    WorktreeCleanupToggleForce,
     /// Execute cleanup
     WorktreeCleanupExecute,
+    /// Delete the entries whose deletion failed again
+    WorktreeCleanupRetryFailed,
 
     // === Permission mode ===
     /// Cycle permission mode (normal -> plan -> accept all)
@@ -274,6 +335,8 @@ pub enum Action {
     // === Model selection ===
     /// Cycle to next model
     CycleModel,
+    /// Cycle to next agent mode (session/set_mode)
+    CycleMode,
     /// Set specific model
     SetModel {
         session_id: String,
@@ -287,6 +350,8 @@ pub enum Action {
     ClearAttachments,
     /// Select attachment row (move focus up from input)
     SelectAttachments,
+    /// Move the last queued prompt back into the input for editing
+    EditQueuedPrompt,
     /// Deselect attachments (move focus back to input)
     DeselectAttachments,
     /// Move attachment selection left
@@ -301,7 +366,11 @@ pub enum Action {
     OpenBugReport,
     /// Close bug report dialog
     CloseBugReport,
-    /// Submit bug report
+    /// Show the issue that would be filed, for confirmation
+    PreviewBugReport,
+    /// Go back from the preview to editing the description
+    EditBugReport,
+    /// Submit the previewed bug report
     SubmitBugReport,
     /// Input character into bug report
     BugReportInputChar(char),
@@ -318,6 +387,180 @@ pub enum Action {
     /// Move cursor to end in bug report
     BugReportInputEnd,
 
+    // === Plan panel ===
+    /// Focus the plan entries in the sidebar
+    FocusPlan,
+    /// Leave the plan panel
+    UnfocusPlan,
+    /// Select next plan entry
+    PlanNext,
+    /// Select previous plan entry
+    PlanPrev,
+    /// Scroll the conversation to the selected plan entry's work
+    JumpToPlanEntry,
+
+    // === Output filter ===
+    /// Open the regex filter input for the session output
+    OpenOutputFilter,
+    /// Input character into the output filter
+    OutputFilterChar(char),
+    /// Delete character in the output filter
+    OutputFilterBackspace,
+    /// Keep the filter and close the input
+    ConfirmOutputFilter,
+    /// Remove the output filter
+    ClearOutputFilter,
+
+    // === Broadcast ===
+    /// Open the popup for sending one prompt to every session
+    OpenBroadcast,
+    /// Close the broadcast popup
+    CloseBroadcast,
+    /// Input character into the broadcast prompt
+    BroadcastChar(char),
+    /// Delete character in the broadcast prompt
+    BroadcastBackspace,
+    /// Send the broadcast prompt (or close the popup once sent)
+    SubmitBroadcast,
+
+    // === Snippets ===
+    /// Ask for a name to save the prompt as a snippet
+    OpenSnippetSave,
+    /// Go back to the prompt without saving
+    CloseSnippetSave,
+    /// Input character into the snippet name
+    SnippetNameChar(char),
+    /// Delete character in the snippet name
+    SnippetNameBackspace,
+    /// Save the prompt under the typed name
+    SaveSnippet,
+    /// Open the picker over saved snippets
+    OpenSnippetPicker,
+    /// Go back to the prompt without inserting
+    CloseSnippetPicker,
+    /// Highlight the next snippet
+    SnippetPickerDown,
+    /// Highlight the previous snippet
+    SnippetPickerUp,
+    /// Input character into the snippet picker query
+    SnippetPickerInputChar(char),
+    /// Delete character in the snippet picker query
+    SnippetPickerInputBackspace,
+    /// Insert the highlighted snippet at the cursor
+    InsertSnippet,
+    /// Delete the highlighted snippet
+    DeleteSnippet,
+
+    // === Session switcher ===
+    /// Open the fuzzy quick switcher over all sessions
+    OpenSessionSwitcher,
+    /// Close the quick switcher without switching
+    CloseSessionSwitcher,
+    /// Highlight the next match
+    SessionSwitcherDown,
+    /// Highlight the previous match
+    SessionSwitcherUp,
+    /// Switch to the highlighted session
+    SessionSwitcherSelect,
+    /// Input character into the switcher query
+    SessionSwitcherInputChar(char),
+    /// Delete character in the switcher query
+    SessionSwitcherInputBackspace,
+
+    // === Editor ===
+    /// Open $EDITOR in the selected session's directory
+    OpenEditor,
+    /// Open $EDITOR at a file position referenced in the output
+    OpenEditorAt(FileLocation),
+
+    // === Tool calls ===
+    /// Toggle collapsing tool calls to one line
+    ToggleCollapseTools,
+    /// Toggle the time prefix on output entries
+    ToggleTimestamps,
+    /// Toggle full paths in tool calls, instead of relative to the session dir
+    ToggleAbsolutePaths,
+    /// Toggle hiding the sidebar
+    ToggleZenMode,
+    /// Archive or unarchive the selected session
+    ToggleArchived,
+    /// Toggle listing archived sessions in the sidebar
+    ToggleShowArchived,
+    /// Write the selected session's raw ACP messages to a file
+    ExportAcpLog,
+    /// Expand the previous tool call and scroll to it
+    PrevToolCall,
+    /// Expand the next tool call and scroll to it
+    NextToolCall,
+    /// Scroll to the previous message the user sent
+    PrevUserMessage,
+    /// Scroll to the next message the user sent
+    NextUserMessage,
+
+    // === MCP manager ===
+    /// Open MCP server manager
+    OpenMcpManager,
+    /// Close MCP server manager
+    CloseMcpManager,
+    /// Move selection down in MCP manager
+    McpManagerDown,
+    /// Move selection up in MCP manager
+    McpManagerUp,
+    /// Enable/disable the selected MCP server for new sessions
+    McpManagerToggle,
+
+    // === Diff view ===
+    /// Open side-by-side diff view for the nearest written file
+    OpenDiffView,
+    /// Close side-by-side diff view
+    CloseDiffView,
+    /// Copy the nearest file path to the clipboard
+    CopyPath,
+    /// Copy the nearest written file's diff to the clipboard
+    CopyDiff,
+    /// Copy all of the session's file changes as a `git apply`-able patch
+    CopyPatch,
+    /// Scroll diff view up by n rows
+    DiffViewScrollUp(usize),
+    /// Scroll diff view down by n rows
+    DiffViewScrollDown(usize),
+    /// Scroll diff view left
+    DiffViewScrollLeft,
+    /// Scroll diff view right
+    DiffViewScrollRight,
+    /// Scroll diff view to top
+    DiffViewScrollToTop,
+    /// Scroll diff view to bottom
+    DiffViewScrollToBottom,
+
+    // === Log view ===
+    /// Open the log viewer
+    OpenLogView,
+    /// Close the log viewer
+    CloseLogView,
+    /// Scroll the log viewer up by n lines
+    LogViewScrollUp(usize),
+    /// Scroll the log viewer down by n lines
+    LogViewScrollDown(usize),
+    /// Jump to the oldest log line
+    LogViewScrollToTop,
+    /// Jump to the newest log line and follow new ones
+    LogViewScrollToBottom,
+
+    // === Tool call JSON ===
+    /// Inspect the raw JSON of the expanded (or last visible) tool call
+    OpenToolJson,
+    /// Close the tool call JSON inspector
+    CloseToolJson,
+    /// Scroll the JSON inspector up by n lines
+    ToolJsonScrollUp(usize),
+    /// Scroll the JSON inspector down by n lines
+    ToolJsonScrollDown(usize),
+    /// Jump to the start of the JSON
+    ToolJsonScrollToTop,
+    /// Jump to the end of the JSON
+    ToolJsonScrollToBottom,
+
     // === Debug ===
     /// Toggle debug mode for tool JSON display
     ToggleDebugToolJson,
@@ -326,3 +569,18 @@ pub enum Action {
     /// No action to take
     None,
 }
+
+impl Action {
+    /// Whether the repeat key can run this action again
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Action::ScrollUp(_)
+                | Action::ScrollDown(_)
+                | Action::CycleModel
+                | Action::CycleMode
+                | Action::CyclePermissionMode
+                | Action::DuplicateSession
+        )
+    }
+}
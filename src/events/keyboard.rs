@@ -5,6 +5,7 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use crate::app::{App, InputMode};
+use crate::config::SubmitKey;
 use crate::session::SessionState;
 
 use super::Action;
@@ -23,12 +24,25 @@ pub fn handle_key_event(app: &App, key: KeyEvent) -> Action {
         InputMode::WorktreeCleanup => handle_worktree_cleanup_mode(key),
         InputMode::WorktreeCleanupRepoPicker => handle_worktree_cleanup_repo_picker_mode(key),
         InputMode::Help => handle_help_mode(key),
-        InputMode::BugReport => handle_bug_report_mode(key),
+        InputMode::BugReport => handle_bug_report_mode(app, key),
         InputMode::ClearConfirm => handle_clear_confirm_mode(key),
+        InputMode::DiffView => handle_diff_view_mode(app, key),
+        InputMode::LogView => handle_log_view_mode(app, key),
+        InputMode::ToolJson => handle_tool_json_mode(app, key),
+        InputMode::OutputFilter => handle_output_filter_mode(key),
+        InputMode::ConfirmLargePrompt => handle_large_prompt_confirm_mode(key),
+        InputMode::PlanFocus => handle_plan_focus_mode(key),
+        InputMode::Broadcast => handle_broadcast_mode(key),
+        InputMode::SessionSwitcher => handle_session_switcher_mode(key),
+        InputMode::QuitConfirm => handle_quit_confirm_mode(key),
+        InputMode::McpManager => handle_mcp_manager_mode(key),
+        InputMode::SessionLimitConfirm => handle_session_limit_confirm_mode(key),
+        InputMode::SnippetSave => handle_snippet_save_mode(key),
+        InputMode::SnippetPicker => handle_snippet_picker_mode(key),
     }
 }
 
-fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
+pub fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
     // Check for pending permission or question
     let has_permission = app
         .sessions
@@ -57,10 +71,21 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
         .map(|s| s.state == SessionState::Prompting)
         .unwrap_or(false);
 
+    let has_filter = app
+        .sessions
+        .selected_session()
+        .map(|s| s.filter_active)
+        .unwrap_or(false);
+
     // Normal navigation mode
     match key.code {
         // Cancel running prompt with Esc
         KeyCode::Esc if is_prompting => Action::CancelPrompt,
+        // Otherwise Esc removes the output filter
+        KeyCode::Esc if has_filter => Action::ClearOutputFilter,
+
+        // The repeat key wins over any other binding it was configured onto
+        KeyCode::Char(c) if c == app.repeat_key => Action::RepeatLast,
 
         KeyCode::Char('q') => Action::Quit,
         KeyCode::Char('?') => Action::OpenHelp,
@@ -71,6 +96,18 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
 
         // Model cycling
         KeyCode::Char('m') => Action::CycleModel,
+        KeyCode::Char('M') => Action::CycleMode,
+
+        // Flip back to the previously selected session (Ctrl+^ as in vim)
+        KeyCode::Char('`') => Action::SelectPreviousSession,
+        KeyCode::Char('^' | '6') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::SelectPreviousSession
+        }
+
+        // Send one of the agent's suggested follow-ups
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+            Action::SendFollowUp((c as usize) - ('1' as usize))
+        }
 
         // Session selection by number (using display order)
         KeyCode::Char(c @ '1'..='9') => {
@@ -86,6 +123,23 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
         // Session navigation
         KeyCode::Char('j') | KeyCode::Down => Action::NextSession,
         KeyCode::Char('k') | KeyCode::Up => Action::PrevSession,
+        KeyCode::Char('!') => Action::JumpToAwaitingSession,
+
+        // Filter output by regex
+        KeyCode::Char('&') => Action::OpenOutputFilter,
+
+        // Fuzzy-find a session to switch to
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::OpenSessionSwitcher
+        }
+
+        // Focus the plan panel
+        KeyCode::Char('p') => Action::FocusPlan,
+
+        // Broadcast a prompt to all sessions
+        KeyCode::Char('b') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::OpenBroadcast
+        }
 
         // Enter insert mode
         KeyCode::Char('i') | KeyCode::Enter => {
@@ -99,6 +153,9 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
         // New session
         KeyCode::Char('n') => Action::OpenFolderPicker(app.start_dir.clone()),
 
+        // New session in the selected session's main repo
+        KeyCode::Char('N') => Action::OpenParentRepoPicker,
+
         // Worktree picker
         KeyCode::Char('w') => Action::OpenWorktreePicker,
 
@@ -109,9 +166,35 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
         KeyCode::Char('d') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
             Action::DuplicateSession
         }
+        KeyCode::Char('W') => Action::DuplicateIntoWorktree,
+        KeyCode::Char('X') => Action::StartExperiment,
+
+        // Interrupt running bash command
+        KeyCode::Char('c')
+            if key.modifiers.contains(KeyModifiers::CONTROL) && app.has_running_bash_command() =>
+        {
+            Action::InterruptBashCommand
+        }
+
+        // Clear session (with confirmation); Ctrl+C never clears
+        KeyCode::Char('c') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::OpenClearConfirm
+        }
+
+        // Clear output only, keeping the agent and its context
+        KeyCode::Char('C') => Action::ClearOutput,
+
+        // Drop prompts queued while the agent was busy
+        KeyCode::Char('Q') => Action::ClearQueuedPrompts,
+
+        // Restart the agent process, keeping the output
+        KeyCode::Char('r') => Action::RestartAgent,
+
+        // Resend the last prompt
+        KeyCode::Char('R') => Action::RetryLastPrompt,
 
-        // Clear session (with confirmation)
-        KeyCode::Char('c') => Action::OpenClearConfirm,
+        // Ask the agent about the error in view
+        KeyCode::Char('e') => Action::ExplainError,
 
         // Cycle sort mode
         KeyCode::Char('v') => Action::CycleSortMode,
@@ -119,6 +202,31 @@ fn handle_normal_mode(app: &App, key: KeyEvent) -> Action {
         // Toggle debug tool JSON display
         KeyCode::Char('t') => Action::ToggleDebugToolJson,
 
+        // Collapse tool calls, and step through them
+        KeyCode::Char('z') => Action::ToggleCollapseTools,
+        KeyCode::Char('T') => Action::ToggleTimestamps,
+        KeyCode::Char('P') => Action::ToggleAbsolutePaths,
+        KeyCode::Char('Z') => Action::ToggleZenMode,
+        KeyCode::Char('a') => Action::ToggleArchived,
+        KeyCode::Char('A') => Action::ToggleShowArchived,
+        KeyCode::Char('E') => Action::ExportAcpLog,
+        KeyCode::Char('[') => Action::PrevToolCall,
+        KeyCode::Char(']') => Action::NextToolCall,
+        KeyCode::Char('{') => Action::PrevUserMessage,
+        KeyCode::Char('}') => Action::NextUserMessage,
+
+        // Side-by-side diff view
+        KeyCode::Char('D') => Action::OpenDiffView,
+        KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::CopyPatch,
+        KeyCode::Char('y') => Action::CopyPath,
+        KeyCode::Char('Y') => Action::CopyDiff,
+        KeyCode::Char('L') => Action::OpenLogView,
+        KeyCode::Char('J') => Action::OpenToolJson,
+        KeyCode::Char('S') => Action::OpenMcpManager,
+
+        // Open editor in session directory
+        KeyCode::Char('o') => Action::OpenEditor,
+
         // Scroll - vim style
         KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             let half_page = app.viewport_height / 2;
@@ -205,18 +313,48 @@ pub fn handle_insert_mode(app: &App, key: KeyEvent) -> Action {
         .map(|s| s.pending_question.is_some())
         .unwrap_or(false);
 
+    // `@` and `/` completion take the keys they need while open
+    let has_matches = app.has_completion_matches();
+
     match key.code {
+        KeyCode::Tab | KeyCode::Enter if has_matches && key.modifiers.is_empty() => {
+            Action::AcceptCompletion
+        }
+        KeyCode::Down if has_matches => Action::CompletionNext,
+        KeyCode::Up if has_matches => Action::CompletionPrev,
+        KeyCode::Esc if app.has_completion() => Action::DismissCompletion,
         KeyCode::Esc if app.bash_mode => Action::ExitBashMode,
         KeyCode::Esc if has_permission => Action::DenyPermission,
         KeyCode::Esc => Action::ExitInsertMode,
 
+        KeyCode::Char('c')
+            if key.modifiers.contains(KeyModifiers::CONTROL) && app.has_running_bash_command() =>
+        {
+            Action::InterruptBashCommand
+        }
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::ClearInput,
 
         KeyCode::Enter if has_permission => Action::AllowPermission,
         KeyCode::Enter if has_question => Action::SubmitAnswer,
-        KeyCode::Enter if key.modifiers.contains(KeyModifiers::SHIFT) => Action::InputNewline,
-        KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::InputNewline,
-        KeyCode::Enter => Action::SubmitPrompt,
+        KeyCode::Enter => match app.submit_key {
+            SubmitKey::Enter if key.modifiers.contains(KeyModifiers::SHIFT) => Action::InputNewline,
+            SubmitKey::Enter => Action::SubmitPrompt,
+            SubmitKey::CtrlEnter
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::SHIFT | KeyModifiers::CONTROL) =>
+            {
+                Action::SubmitPrompt
+            }
+            SubmitKey::CtrlEnter => Action::InputNewline,
+        },
+        // Terminals without key disambiguation send Ctrl+Enter as Ctrl+j
+        KeyCode::Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            match app.submit_key {
+                SubmitKey::Enter => Action::InputNewline,
+                SubmitKey::CtrlEnter => Action::SubmitPrompt,
+            }
+        }
 
         // Clipboard
         KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -226,6 +364,14 @@ pub fn handle_insert_mode(app: &App, key: KeyEvent) -> Action {
             Action::ClearAttachments
         }
 
+        // Snippets
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::OpenSnippetSave
+        }
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::OpenSnippetPicker
+        }
+
         // Permission mode cycling
         KeyCode::Tab => Action::CyclePermissionMode,
 
@@ -235,6 +381,11 @@ pub fn handle_insert_mode(app: &App, key: KeyEvent) -> Action {
         KeyCode::Home => Action::InputHome,
         KeyCode::End => Action::InputEnd,
 
+        // Send one of the agent's suggested follow-ups
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+            Action::SendFollowUp((c as usize) - ('1' as usize))
+        }
+
         // Word navigation
         KeyCode::Left if key.modifiers.contains(KeyModifiers::ALT) => Action::InputWordLeft,
         KeyCode::Right if key.modifiers.contains(KeyModifiers::ALT) => Action::InputWordRight,
@@ -259,6 +410,8 @@ pub fn handle_insert_mode(app: &App, key: KeyEvent) -> Action {
         KeyCode::Up => {
             if app.has_attachments() && app.selected_attachment.is_none() {
                 Action::SelectAttachments
+            } else if app.input_buffer.is_empty() && app.has_queued_prompts() {
+                Action::EditQueuedPrompt
             } else {
                 Action::None
             }
@@ -315,7 +468,27 @@ pub fn handle_insert_mode(app: &App, key: KeyEvent) -> Action {
     }
 }
 
+/// Paging keys shared by the list pickers: PageUp/PageDown move a full page,
+/// Ctrl+u/Ctrl+d half a page.
+fn picker_page_key(key: KeyEvent) -> Option<Action> {
+    match key.code {
+        KeyCode::PageDown => Some(Action::PickerPageDown { half: false }),
+        KeyCode::PageUp => Some(Action::PickerPageUp { half: false }),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Action::PickerPageDown { half: true })
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Action::PickerPageUp { half: true })
+        }
+        _ => None,
+    }
+}
+
 pub fn handle_folder_picker_mode(key: KeyEvent) -> Action {
+    if let Some(action) = picker_page_key(key) {
+        return action;
+    }
+
     match key.code {
         KeyCode::Esc => Action::CloseFolderPicker,
         KeyCode::Down => Action::FolderPickerDown,
@@ -336,6 +509,10 @@ pub fn handle_folder_picker_mode(key: KeyEvent) -> Action {
 }
 
 pub fn handle_worktree_folder_picker_mode(key: KeyEvent) -> Action {
+    if let Some(action) = picker_page_key(key) {
+        return action;
+    }
+
     // Same as folder picker but with different selection behavior
     match key.code {
         KeyCode::Esc => Action::CloseFolderPicker,
@@ -356,7 +533,21 @@ pub fn handle_worktree_folder_picker_mode(key: KeyEvent) -> Action {
     }
 }
 
+pub fn handle_mcp_manager_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => Action::CloseMcpManager,
+        KeyCode::Char('j') | KeyCode::Down => Action::McpManagerDown,
+        KeyCode::Char('k') | KeyCode::Up => Action::McpManagerUp,
+        KeyCode::Char(' ') | KeyCode::Enter => Action::McpManagerToggle,
+        _ => Action::None,
+    }
+}
+
 pub fn handle_worktree_picker_mode(key: KeyEvent) -> Action {
+    if let Some(action) = picker_page_key(key) {
+        return action;
+    }
+
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => Action::CloseWorktreePicker,
         KeyCode::Char('j') | KeyCode::Down => Action::WorktreePickerDown,
@@ -383,6 +574,10 @@ pub fn handle_branch_input_mode(key: KeyEvent) -> Action {
 }
 
 pub fn handle_agent_picker_mode(key: KeyEvent) -> Action {
+    if let Some(action) = picker_page_key(key) {
+        return action;
+    }
+
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => Action::CloseAgentPicker,
         KeyCode::Char('j') | KeyCode::Down => Action::AgentPickerDown,
@@ -421,6 +616,8 @@ pub fn handle_worktree_cleanup_mode(key: KeyEvent) -> Action {
         KeyCode::Char('a') => Action::WorktreeCleanupSelectAll,
         KeyCode::Char('n') => Action::WorktreeCleanupDeselectAll,
         KeyCode::Char('b') => Action::WorktreeCleanupToggleBranches,
+        KeyCode::Char('f') => Action::WorktreeCleanupToggleForce,
+        KeyCode::Char('r') => Action::WorktreeCleanupRetryFailed,
         KeyCode::Enter => Action::WorktreeCleanupExecute,
         _ => Action::None,
     }
@@ -449,7 +646,9 @@ pub fn handle_worktree_cleanup_repo_picker_mode(key: KeyEvent) -> Action {
 
 pub fn handle_help_mode(key: KeyEvent) -> Action {
     match key.code {
-        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => Action::CloseHelp,
+        KeyCode::Esc | KeyCode::Char('?') => Action::CloseHelp,
+        KeyCode::Char(c) => Action::HelpFilterChar(c),
+        KeyCode::Backspace => Action::HelpFilterBackspace,
         _ => Action::None,
     }
 }
@@ -462,10 +661,192 @@ pub fn handle_clear_confirm_mode(key: KeyEvent) -> Action {
     }
 }
 
-pub fn handle_bug_report_mode(key: KeyEvent) -> Action {
+pub fn handle_quit_confirm_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('y') | KeyCode::Enter => Action::Quit,
+        KeyCode::Char('n') | KeyCode::Esc => Action::CloseQuitConfirm,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_session_limit_confirm_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => Action::ConfirmSessionLimit,
+        KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => Action::CancelSessionLimit,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_large_prompt_confirm_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Enter => Action::ConfirmLargePrompt,
+        KeyCode::Esc => Action::CancelLargePrompt,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_diff_view_mode(app: &App, key: KeyEvent) -> Action {
+    let viewport = app
+        .diff_view
+        .as_ref()
+        .map(|d| d.viewport_height)
+        .unwrap_or(0);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => Action::CloseDiffView,
+        KeyCode::Char('j') | KeyCode::Down => Action::DiffViewScrollDown(1),
+        KeyCode::Char('k') | KeyCode::Up => Action::DiffViewScrollUp(1),
+        KeyCode::Char('h') | KeyCode::Left => Action::DiffViewScrollLeft,
+        KeyCode::Char('l') | KeyCode::Right => Action::DiffViewScrollRight,
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::DiffViewScrollDown(viewport / 2)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::DiffViewScrollUp(viewport / 2)
+        }
+        KeyCode::PageDown => Action::DiffViewScrollDown(viewport),
+        KeyCode::PageUp => Action::DiffViewScrollUp(viewport),
+        KeyCode::Char('g') => Action::DiffViewScrollToTop,
+        KeyCode::Char('G') => Action::DiffViewScrollToBottom,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_tool_json_mode(app: &App, key: KeyEvent) -> Action {
+    let viewport = app
+        .tool_json
+        .as_ref()
+        .map(|t| t.viewport_height)
+        .unwrap_or(0);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('J') => Action::CloseToolJson,
+        KeyCode::Char('j') | KeyCode::Down => Action::ToolJsonScrollDown(1),
+        KeyCode::Char('k') | KeyCode::Up => Action::ToolJsonScrollUp(1),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::ToolJsonScrollDown(viewport / 2)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::ToolJsonScrollUp(viewport / 2)
+        }
+        KeyCode::PageDown => Action::ToolJsonScrollDown(viewport),
+        KeyCode::PageUp => Action::ToolJsonScrollUp(viewport),
+        KeyCode::Char('g') => Action::ToolJsonScrollToTop,
+        KeyCode::Char('G') => Action::ToolJsonScrollToBottom,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_log_view_mode(app: &App, key: KeyEvent) -> Action {
+    let viewport = app
+        .log_view
+        .as_ref()
+        .map(|l| l.viewport_height)
+        .unwrap_or(0);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => Action::CloseLogView,
+        KeyCode::Char('j') | KeyCode::Down => Action::LogViewScrollDown(1),
+        KeyCode::Char('k') | KeyCode::Up => Action::LogViewScrollUp(1),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::LogViewScrollDown(viewport / 2)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::LogViewScrollUp(viewport / 2)
+        }
+        KeyCode::PageDown => Action::LogViewScrollDown(viewport),
+        KeyCode::PageUp => Action::LogViewScrollUp(viewport),
+        KeyCode::Char('g') => Action::LogViewScrollToTop,
+        KeyCode::Char('G') => Action::LogViewScrollToBottom,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_plan_focus_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p') => Action::UnfocusPlan,
+        KeyCode::Char('j') | KeyCode::Down => Action::PlanNext,
+        KeyCode::Char('k') | KeyCode::Up => Action::PlanPrev,
+        KeyCode::Enter => Action::JumpToPlanEntry,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_broadcast_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::CloseBroadcast,
+        KeyCode::Enter => Action::SubmitBroadcast,
+        KeyCode::Char(c) => Action::BroadcastChar(c),
+        KeyCode::Backspace => Action::BroadcastBackspace,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_session_switcher_mode(key: KeyEvent) -> Action {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => Action::CloseSessionSwitcher,
+        KeyCode::Enter => Action::SessionSwitcherSelect,
+        KeyCode::Down => Action::SessionSwitcherDown,
+        KeyCode::Up => Action::SessionSwitcherUp,
+        KeyCode::Char('n') if ctrl => Action::SessionSwitcherDown,
+        KeyCode::Char('p') if ctrl => Action::SessionSwitcherUp,
+        KeyCode::Char(c) if !ctrl => Action::SessionSwitcherInputChar(c),
+        KeyCode::Backspace => Action::SessionSwitcherInputBackspace,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_snippet_save_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::CloseSnippetSave,
+        KeyCode::Enter => Action::SaveSnippet,
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Action::SnippetNameChar(c)
+        }
+        KeyCode::Backspace => Action::SnippetNameBackspace,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_snippet_picker_mode(key: KeyEvent) -> Action {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => Action::CloseSnippetPicker,
+        KeyCode::Enter => Action::InsertSnippet,
+        KeyCode::Down => Action::SnippetPickerDown,
+        KeyCode::Up => Action::SnippetPickerUp,
+        KeyCode::Char('n') if ctrl => Action::SnippetPickerDown,
+        KeyCode::Char('p') if ctrl => Action::SnippetPickerUp,
+        KeyCode::Char('d') if ctrl => Action::DeleteSnippet,
+        KeyCode::Char(c) if !ctrl => Action::SnippetPickerInputChar(c),
+        KeyCode::Backspace => Action::SnippetPickerInputBackspace,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_output_filter_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Esc => Action::ClearOutputFilter,
+        KeyCode::Enter => Action::ConfirmOutputFilter,
+        KeyCode::Char(c) => Action::OutputFilterChar(c),
+        KeyCode::Backspace => Action::OutputFilterBackspace,
+        _ => Action::None,
+    }
+}
+
+pub fn handle_bug_report_mode(app: &App, key: KeyEvent) -> Action {
+    let previewing = app
+        .bug_report
+        .as_ref()
+        .is_some_and(|bug_report| bug_report.preview.is_some());
+    if previewing {
+        return match key.code {
+            KeyCode::Enter => Action::SubmitBugReport,
+            KeyCode::Esc => Action::EditBugReport,
+            _ => Action::None,
+        };
+    }
     match key.code {
         KeyCode::Esc => Action::CloseBugReport,
-        KeyCode::Enter => Action::SubmitBugReport,
+        KeyCode::Enter => Action::PreviewBugReport,
         KeyCode::Char(c) => Action::BugReportInputChar(c),
         KeyCode::Backspace => Action::BugReportInputBackspace,
         KeyCode::Delete => Action::BugReportInputDelete,
@@ -476,3 +857,94 @@ pub fn handle_bug_report_mode(key: KeyEvent) -> Action {
         _ => Action::None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::app::WorktreeConfig;
+    use crate::notification::NotificationConfig;
+    use crate::ui_state::UiState;
+
+    fn app_with(submit_key: SubmitKey) -> App {
+        let mut app = App::new(
+            PathBuf::from("/tmp"),
+            WorktreeConfig {
+                worktree_dir: PathBuf::from("/tmp/worktrees"),
+                template: crate::git::DEFAULT_WORKTREE_TEMPLATE.to_string(),
+            },
+            vec![],
+            NotificationConfig::default(),
+            UiState::default(),
+        );
+        app.submit_key = submit_key;
+        app
+    }
+
+    /// Whether `key` sends the prompt (true) or inserts a new line (false)
+    fn submits(app: &App, key: KeyEvent) -> bool {
+        match handle_insert_mode(app, key) {
+            Action::SubmitPrompt => true,
+            Action::InputNewline => false,
+            other => panic!("unexpected action {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_submit_key() {
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let shift_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT);
+        let ctrl_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL);
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+
+        let app = app_with(SubmitKey::Enter);
+        assert!(submits(&app, enter));
+        assert!(!submits(&app, shift_enter));
+        assert!(submits(&app, ctrl_enter));
+        assert!(!submits(&app, ctrl_j));
+
+        let app = app_with(SubmitKey::CtrlEnter);
+        assert!(!submits(&app, enter));
+        assert!(submits(&app, shift_enter));
+        assert!(submits(&app, ctrl_enter));
+        assert!(submits(&app, ctrl_j));
+    }
+
+    #[test]
+    fn test_picker_paging_keys() {
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let plain = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let handlers: [fn(KeyEvent) -> Action; 4] = [
+            handle_folder_picker_mode,
+            handle_worktree_folder_picker_mode,
+            handle_worktree_picker_mode,
+            handle_agent_picker_mode,
+        ];
+
+        for handle in handlers {
+            assert!(matches!(
+                handle(plain(KeyCode::PageDown)),
+                Action::PickerPageDown { half: false }
+            ));
+            assert!(matches!(
+                handle(plain(KeyCode::PageUp)),
+                Action::PickerPageUp { half: false }
+            ));
+            assert!(matches!(
+                handle(ctrl('d')),
+                Action::PickerPageDown { half: true }
+            ));
+            assert!(matches!(
+                handle(ctrl('u')),
+                Action::PickerPageUp { half: true }
+            ));
+        }
+
+        // Without Ctrl, 'd' stays filter input
+        assert!(matches!(
+            handle_folder_picker_mode(plain(KeyCode::Char('d'))),
+            Action::FolderPickerInputChar('d')
+        ));
+    }
+}
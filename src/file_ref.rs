@@ -0,0 +1,240 @@
+//! File references in output, like `src/main.rs:42:7` in a stack trace.
+//!
+//! References are found in rendered conversation lines so they can be
+//! highlighted and clicked to open the editor at that location.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// `path/to/file.ext:line[:column]`, as printed by compilers, linters and most
+/// runtimes. The extension keeps plain `word:123` text from matching.
+static PATH_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"((?:[A-Za-z]:)?[\w./~-]*[\w-]\.[A-Za-z0-9]+):(\d+)(?::(\d+))?").unwrap()
+});
+
+/// Python tracebacks: `File "path/to/file.py", line 42`
+static PYTHON_FRAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"File "([^"]+)", line (\d+)"#).unwrap());
+
+/// A place in a file to open the editor at
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLocation {
+    pub path: PathBuf,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+/// A file reference found in a line of text
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileRef {
+    /// Byte range of the whole reference in the text
+    pub range: Range<usize>,
+    /// Path as written (absolute or relative)
+    pub path: String,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+impl FileRef {
+    /// The location this refers to, if the path is an existing file
+    /// (absolute, or relative to `cwd`)
+    pub fn resolve(&self, cwd: &Path) -> Option<FileLocation> {
+        let path = Path::new(self.path.strip_prefix("./").unwrap_or(&self.path));
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            cwd.join(path)
+        };
+        path.is_file().then_some(FileLocation {
+            path,
+            line: self.line,
+            column: self.column,
+        })
+    }
+}
+
+/// `text` with paths under `cwd` made relative to it, e.g. `/repo/src/main.rs`
+/// becomes `src/main.rs` for a `/repo` cwd. Other paths are left absolute.
+pub fn relative_paths<'a>(text: &'a str, cwd: &Path) -> Cow<'a, str> {
+    let Some(cwd) = cwd.to_str().map(|cwd| cwd.trim_end_matches('/')) else {
+        return Cow::Borrowed(text);
+    };
+    let prefix = format!("{}/", cwd);
+    if cwd.is_empty() || !text.contains(&prefix) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut shortened = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(&prefix) {
+        shortened.push_str(&rest[..pos]);
+        // Only where a path starts, not inside a longer one like `/other/repo/`
+        let in_path = shortened
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || "/._-~".contains(c));
+        if in_path {
+            shortened.push_str(&prefix);
+        }
+        rest = &rest[pos + prefix.len()..];
+    }
+    shortened.push_str(rest);
+    Cow::Owned(shortened)
+}
+
+/// Every file reference in `text`, in order
+pub fn find_file_refs(text: &str) -> Vec<FileRef> {
+    // Cheap check first: every form has a colon or a `line` marker
+    if !text.contains(':') && !text.contains("line") {
+        return vec![];
+    }
+    let mut refs: Vec<FileRef> = vec![];
+    for caps in PYTHON_FRAME.captures_iter(text) {
+        refs.push(FileRef {
+            range: caps.get(0).unwrap().range(),
+            path: caps[1].to_string(),
+            line: caps[2].parse().unwrap_or(1),
+            column: None,
+        });
+    }
+    for caps in PATH_LINE.captures_iter(text) {
+        let range = caps.get(0).unwrap().range();
+        if refs
+            .iter()
+            .any(|r| r.range.start < range.end && range.start < r.range.end)
+        {
+            continue;
+        }
+        let Ok(line) = caps[2].parse() else {
+            continue;
+        };
+        refs.push(FileRef {
+            range,
+            path: caps[1].to_string(),
+            line,
+            column: caps.get(3).and_then(|c| c.as_str().parse().ok()),
+        });
+    }
+    refs.sort_by_key(|r| r.range.start);
+    refs
+}
+
+/// Arguments that make `program` open `location`, for the editors whose
+/// syntax differs from the `+line file` most terminal editors accept
+pub fn editor_args(program: &str, location: &FileLocation) -> Vec<String> {
+    let name = Path::new(program)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let path = location.path.display();
+    let with_position = match location.column {
+        Some(column) => format!("{}:{}:{}", path, location.line, column),
+        None => format!("{}:{}", path, location.line),
+    };
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" | "windsurf" => {
+            vec!["--goto".to_string(), with_position]
+        }
+        "hx" | "helix" | "zed" | "subl" => vec![with_position],
+        _ => vec![format!("+{}", location.line), path.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<(String, u32, Option<u32>)> {
+        find_file_refs(text)
+            .into_iter()
+            .map(|r| (r.path, r.line, r.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_common_trace_forms() {
+        assert_eq!(
+            found("  --> src/main.rs:42:7"),
+            vec![("src/main.rs".to_string(), 42, Some(7))]
+        );
+        assert_eq!(
+            found("    at run (/home/me/app/index.js:10:5)"),
+            vec![("/home/me/app/index.js".to_string(), 10, Some(5))]
+        );
+        assert_eq!(
+            found(r#"  File "app/models.py", line 88, in save"#),
+            vec![("app/models.py".to_string(), 88, None)]
+        );
+        assert_eq!(
+            found("main.go:12: undefined: x and lib.go:3"),
+            vec![
+                ("main.go".to_string(), 12, None),
+                ("lib.go".to_string(), 3, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_text_without_file_extension() {
+        assert!(found("took 12:30 at step:4").is_empty());
+        assert!(found("no references here").is_empty());
+    }
+
+    #[test]
+    fn test_resolves_only_existing_files() {
+        let cwd = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let refs = find_file_refs("./src/main.rs:1 and src/missing.rs:2");
+        let location = refs[0].resolve(cwd).unwrap();
+        assert_eq!(location.path, cwd.join("src/main.rs"));
+        assert!(refs[1].resolve(cwd).is_none());
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        let cwd = Path::new("/home/me/repo");
+        assert_eq!(
+            relative_paths("Read /home/me/repo/src/main.rs", cwd),
+            "Read src/main.rs"
+        );
+        assert_eq!(
+            relative_paths("Edit `/home/me/repo/a.rs` and /home/me/repo/b.rs", cwd),
+            "Edit `a.rs` and b.rs"
+        );
+        assert_eq!(
+            relative_paths("/home/me/repo/x.rs", Path::new("/home/me/repo/")),
+            "x.rs"
+        );
+        // Outside the cwd, or only the end of a longer path
+        assert_eq!(
+            relative_paths("Read /home/me/repo2/a.rs", cwd),
+            "Read /home/me/repo2/a.rs"
+        );
+        assert_eq!(
+            relative_paths("/mnt/home/me/repo/a.rs", cwd),
+            "/mnt/home/me/repo/a.rs"
+        );
+        assert!(matches!(
+            relative_paths("Read /etc/hosts", cwd),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_editor_args() {
+        let location = FileLocation {
+            path: PathBuf::from("/r/a.rs"),
+            line: 3,
+            column: Some(9),
+        };
+        assert_eq!(editor_args("nvim", &location), vec!["+3", "/r/a.rs"]);
+        assert_eq!(
+            editor_args("/usr/bin/code", &location),
+            vec!["--goto", "/r/a.rs:3:9"]
+        );
+        assert_eq!(editor_args("hx", &location), vec!["/r/a.rs:3:9"]);
+    }
+}
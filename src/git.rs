@@ -1,5 +1,5 @@
 use anyhow::{Result, bail};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Get the git remote origin URL for a repository, normalized for grouping
 pub async fn get_origin_url(repo_path: &Path) -> Option<String> {
@@ -49,7 +49,103 @@ fn normalize_git_url(url: &str) -> String {
     url.to_string()
 }
 
-/// List all branches (local and remote) for a git repository
+/// List all branches (local and remote) for a git repository as
+/// `(name, is_remote)`, with remote branches named without their remote
+pub async fn list_branches(repo_path: &Path) -> Result<Vec<(String, bool)>> {
+    let output = tokio::process::Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(refname)",
+            "refs/heads",
+            "refs/remotes",
+        ])
+        .current_dir(repo_path)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to list branches: {}", stderr.trim());
+    }
+
+    Ok(parse_branch_refs(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse full ref names into `(name, is_remote)`, skipping remote HEADs and
+/// remote branches that also exist locally
+fn parse_branch_refs(output: &str) -> Vec<(String, bool)> {
+    let mut local = vec![];
+    let mut remote = vec![];
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("refs/heads/") {
+            local.push(name.to_string());
+        } else if let Some((_, name)) = line
+            .strip_prefix("refs/remotes/")
+            .and_then(|rest| rest.split_once('/'))
+            && name != "HEAD"
+        {
+            remote.push(name.to_string());
+        }
+    }
+    remote.retain(|name| !local.contains(name));
+    remote.dedup();
+
+    local
+        .into_iter()
+        .map(|name| (name, false))
+        .chain(remote.into_iter().map(|name| (name, true)))
+        .collect()
+}
+
+/// Root of the main repository containing `path`, also when `path` is inside
+/// one of its worktrees. None if `path` isn't in a git repository.
+pub async fn main_repo_root(path: &Path) -> Option<PathBuf> {
+    let output = tokio::process::Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(path)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    let common_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    common_dir.parent().map(Path::to_path_buf)
+}
+
+/// Prefix of the throwaway branches experiment worktrees are created on
+pub const EXPERIMENT_BRANCH_PREFIX: &str = "amux/exp-";
+
+/// Branch name for an experiment started at `time`, e.g. `amux/exp-20250102-150405`
+pub fn experiment_branch_name(time: chrono::DateTime<chrono::Local>) -> String {
+    format!(
+        "{}{}",
+        EXPERIMENT_BRANCH_PREFIX,
+        time.format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Whether `branch_name` is an experiment worktree's throwaway branch
+pub fn is_experiment_branch(branch_name: &str) -> bool {
+    branch_name.starts_with(EXPERIMENT_BRANCH_PREFIX)
+}
+
+/// The branch checked out at `path`, None if HEAD is detached or it isn't a repo
+pub async fn current_branch(path: &Path) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .args(["symbolic-ref", "--short", "HEAD"])
+        .current_dir(path)
+        .output()
+        .await
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 /// Check if a branch exists locally
 pub async fn branch_exists(repo_path: &Path, branch_name: &str) -> Result<bool> {
     let output = tokio::process::Command::new("git")
@@ -67,7 +163,12 @@ pub async fn branch_exists(repo_path: &Path, branch_name: &str) -> Result<bool>
 
 /// Check if a branch exists as a remote tracking branch
 pub async fn remote_branch_exists(repo_path: &Path, branch_name: &str) -> Result<bool> {
-    // Check common remotes
+    Ok(remote_branch_ref(repo_path, branch_name).await.is_some())
+}
+
+/// The remote tracking branch (e.g. "origin/main") for `branch_name` on one
+/// of the common remotes, if it exists
+pub async fn remote_branch_ref(repo_path: &Path, branch_name: &str) -> Option<String> {
     for remote in &["origin", "upstream"] {
         let output = tokio::process::Command::new("git")
             .args([
@@ -77,20 +178,23 @@ pub async fn remote_branch_exists(repo_path: &Path, branch_name: &str) -> Result
             ])
             .current_dir(repo_path)
             .output()
-            .await?;
+            .await
+            .ok()?;
 
         if output.status.success() {
-            return Ok(true);
+            return Some(format!("{}/{}", remote, branch_name));
         }
     }
-    Ok(false)
+    None
 }
 
-/// Create a git worktree
+/// Create a git worktree. A newly created branch starts at `base`, or at the
+/// repo's HEAD without one.
 pub async fn create_worktree(
     repo_path: &Path,
     worktree_path: &Path,
     branch_name: &str,
+    base: Option<&str>,
     create_branch: bool,
 ) -> Result<()> {
     // Ensure parent directory exists
@@ -103,9 +207,10 @@ pub async fn create_worktree(
         .ok_or_else(|| anyhow::anyhow!("Invalid worktree path"))?;
 
     let output = if create_branch {
-        // Branch doesn't exist: git worktree add -b <branch> <path>
+        // Branch doesn't exist: git worktree add -b <branch> <path> [<base>]
         tokio::process::Command::new("git")
             .args(["worktree", "add", "-b", branch_name, worktree_str])
+            .args(base)
             .current_dir(repo_path)
             .output()
             .await?
@@ -126,6 +231,175 @@ pub async fn create_worktree(
     Ok(())
 }
 
+/// Characters git never allows in a ref name
+fn is_forbidden_ref_char(c: char) -> bool {
+    c.is_ascii_control()
+        || c.is_whitespace()
+        || matches!(c, '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+}
+
+/// Check a branch name against git's ref-name rules (see `git check-ref-format`).
+/// Returns a short description of the first problem found, or `None` if valid.
+pub fn branch_name_error(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        return Some("branch name is empty");
+    }
+    if name == "@" {
+        return Some("branch name cannot be '@'");
+    }
+    if name.starts_with('-') {
+        return Some("branch name cannot start with '-'");
+    }
+    if name.chars().any(|c| c.is_whitespace()) {
+        return Some("branch name cannot contain spaces");
+    }
+    if name.chars().any(is_forbidden_ref_char) {
+        return Some("branch name cannot contain ~ ^ : ? * [ \\ or control characters");
+    }
+    if name.contains("..") {
+        return Some("branch name cannot contain '..'");
+    }
+    if name.contains("@{") {
+        return Some("branch name cannot contain '@{'");
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        return Some("branch name cannot have empty path components");
+    }
+    if name.ends_with('.') {
+        return Some("branch name cannot end with '.'");
+    }
+    for component in name.split('/') {
+        if component.starts_with('.') {
+            return Some("path components cannot start with '.'");
+        }
+        if component.ends_with(".lock") {
+            return Some("path components cannot end with '.lock'");
+        }
+    }
+    None
+}
+
+/// Turn an arbitrary string into a valid branch name, replacing forbidden
+/// characters with `-`. Returns `None` if nothing usable is left.
+pub fn sanitize_branch_name(name: &str) -> Option<String> {
+    let replaced: String = name
+        .trim()
+        .replace("@{", "-")
+        .chars()
+        .map(|c| if is_forbidden_ref_char(c) { '-' } else { c })
+        .collect();
+
+    let components: Vec<String> = replaced
+        .split('/')
+        .map(|component| {
+            let mut component = component.to_string();
+            while component.contains("..") {
+                component = component.replace("..", ".");
+            }
+            while component.contains("--") {
+                component = component.replace("--", "-");
+            }
+            // Stripping one affix can expose another, so repeat until stable
+            loop {
+                let trimmed = component
+                    .trim_start_matches('.')
+                    .trim_end_matches(['.', '-']);
+                let trimmed = trimmed.strip_suffix(".lock").unwrap_or(trimmed);
+                if trimmed.len() == component.len() {
+                    break component;
+                }
+                component = trimmed.to_string();
+            }
+        })
+        .filter(|component| !component.is_empty())
+        .collect();
+
+    let sanitized = components.join("/");
+    let sanitized = sanitized.trim_start_matches('-');
+    if sanitized.is_empty() || sanitized == "@" {
+        None
+    } else {
+        Some(sanitized.to_string())
+    }
+}
+
+/// Default worktree directory name: `<repo>-<branch with / replaced by ->`
+pub const DEFAULT_WORKTREE_TEMPLATE: &str = "{repo}-{branch_safe}";
+
+/// Placeholders a worktree template may use
+const WORKTREE_PLACEHOLDERS: &[&str] = &["repo", "branch", "branch_safe"];
+
+/// Check a worktree naming template, returning why it's unusable.
+///
+/// The template must name each worktree by its branch (so paths are unique),
+/// only use known placeholders, and stay relative to the worktree directory.
+pub fn worktree_template_error(template: &str) -> Option<String> {
+    let mut rest = template;
+    let mut has_branch = false;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Some("unclosed '{' in template".to_string());
+        };
+        let name = &rest[start + 1..start + len];
+        if !WORKTREE_PLACEHOLDERS.contains(&name) {
+            return Some(format!(
+                "unknown placeholder '{{{}}}' (use {{repo}}, {{branch}} or {{branch_safe}})",
+                name
+            ));
+        }
+        has_branch |= name.starts_with("branch");
+        rest = &rest[start + len + 1..];
+    }
+    if !has_branch {
+        return Some("template must contain {branch} or {branch_safe}".to_string());
+    }
+    if template.starts_with('/') || template.starts_with('~') {
+        return Some("template must be relative to worktree_dir".to_string());
+    }
+    if template
+        .split('/')
+        .any(|component| component.is_empty() || component == "." || component == "..")
+    {
+        return Some("template must not contain empty, '.' or '..' path components".to_string());
+    }
+    None
+}
+
+/// Replace characters that are awkward or unsafe in file names with `-`
+fn sanitize_path_component(value: &str) -> String {
+    let sanitized: String = value
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match sanitized.as_str() {
+        "" | "." | ".." => "-".to_string(),
+        _ => sanitized,
+    }
+}
+
+/// Build a worktree's path relative to the worktree directory from a template
+/// checked with [`worktree_template_error`].
+///
+/// `{branch}` keeps `/` as directory separators, `{branch_safe}` replaces them with `-`;
+/// every path component is sanitized so the result can't leave the worktree directory.
+pub fn render_worktree_template(template: &str, repo_name: &str, branch_name: &str) -> PathBuf {
+    let branch_safe = branch_name.replace('/', "-");
+    template
+        .replace("{repo}", &repo_name.replace('/', "-"))
+        .replace("{branch_safe}", &branch_safe)
+        .replace("{branch}", branch_name)
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .map(sanitize_path_component)
+        .collect()
+}
+
 /// Get repository name from path
 pub fn repo_name(repo_path: &Path) -> String {
     repo_path
@@ -151,21 +425,14 @@ pub async fn is_worktree_clean(worktree_path: &Path) -> Result<bool> {
     Ok(output.stdout.is_empty())
 }
 
-/// Check if a branch has been merged into origin's default branch
+/// Check if a branch has been merged into the repo's default branch
 pub async fn is_branch_merged(repo_path: &Path, branch_name: &str) -> Result<bool> {
-    // First, determine the default branch (main or master)
-    let default_branch = get_default_branch(repo_path).await?;
-
-    // Check if the branch tip is an ancestor of origin/<default_branch>
-    // This is more reliable than `git branch --merged` as it checks against
-    // the remote branch, which reflects merged PRs after a fetch
+    // Check if the branch tip is an ancestor of the default branch, preferring
+    // the remote one: it reflects merged PRs after a fetch, where
+    // `git branch --merged` against the local branch wouldn't
+    let default_ref = default_branch_ref(repo_path).await?;
     let output = tokio::process::Command::new("git")
-        .args([
-            "merge-base",
-            "--is-ancestor",
-            branch_name,
-            &format!("origin/{}", default_branch),
-        ])
+        .args(["merge-base", "--is-ancestor", branch_name, &default_ref])
         .current_dir(repo_path)
         .output()
         .await?;
@@ -174,9 +441,11 @@ pub async fn is_branch_merged(repo_path: &Path, branch_name: &str) -> Result<boo
     Ok(output.status.success())
 }
 
-/// Get the default branch (main or master)
-pub async fn get_default_branch(repo_path: &Path) -> Result<String> {
-    // Try to get the default branch from origin
+/// The repo's default branch name (e.g. "main", "master" or "trunk")
+///
+/// Read from `origin/HEAD`, falling back to a `main` or `master` branch on
+/// origin or locally, then to `init.defaultBranch` if that branch exists.
+pub async fn default_branch(repo_path: &Path) -> Result<String> {
     let output = tokio::process::Command::new("git")
         .args(["symbolic-ref", "refs/remotes/origin/HEAD"])
         .current_dir(repo_path)
@@ -190,22 +459,46 @@ pub async fn get_default_branch(repo_path: &Path) -> Result<String> {
         }
     }
 
-    // Fallback: check if main or master exists
-    for branch in &["main", "master"] {
-        let output = tokio::process::Command::new("git")
-            .args(["rev-parse", "--verify", &format!("refs/heads/{}", branch)])
-            .current_dir(repo_path)
-            .output()
-            .await?;
-
-        if output.status.success() {
-            return Ok(branch.to_string());
+    for branch in ["main", "master"] {
+        for prefix in ["refs/remotes/origin", "refs/heads"] {
+            if ref_exists(repo_path, &format!("{}/{}", prefix, branch)).await {
+                return Ok(branch.to_string());
+            }
         }
     }
 
+    let output = tokio::process::Command::new("git")
+        .args(["config", "init.defaultBranch"])
+        .current_dir(repo_path)
+        .output()
+        .await?;
+    let configured = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !configured.is_empty() && ref_exists(repo_path, &format!("refs/heads/{}", configured)).await
+    {
+        return Ok(configured);
+    }
+
     bail!("Could not determine default branch")
 }
 
+/// The ref to compare against for the default branch: its remote tracking
+/// branch (e.g. "origin/master") if there is one, the local branch otherwise
+pub async fn default_branch_ref(repo_path: &Path) -> Result<String> {
+    let branch = default_branch(repo_path).await?;
+    Ok(remote_branch_ref(repo_path, &branch)
+        .await
+        .unwrap_or(branch))
+}
+
+async fn ref_exists(repo_path: &Path, refname: &str) -> bool {
+    tokio::process::Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", refname])
+        .current_dir(repo_path)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
 /// Remove a git worktree
 pub async fn remove_worktree(repo_path: &Path, worktree_path: &Path, force: bool) -> Result<()> {
     let worktree_str = worktree_path
@@ -247,6 +540,31 @@ pub async fn fetch_origin(repo_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Commits the checked-out branch is (ahead of, behind) its upstream.
+/// Returns None when the branch has no upstream (or isn't a branch).
+pub async fn ahead_behind(repo_path: &Path) -> Option<(usize, usize)> {
+    let output = tokio::process::Command::new("git")
+        .args(["rev-list", "--left-right", "--count", "@{upstream}...HEAD"])
+        .current_dir(repo_path)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    parse_ahead_behind(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `git rev-list --left-right --count @{upstream}...HEAD` output
+/// ("<behind>\t<ahead>") into (ahead, behind)
+fn parse_ahead_behind(output: &str) -> Option<(usize, usize)> {
+    let mut counts = output.split_whitespace().map(|n| n.parse::<usize>().ok());
+    let behind = counts.next()??;
+    let ahead = counts.next()??;
+    Some((ahead, behind))
+}
+
 /// Delete the branch associated with a worktree
 pub async fn delete_branch(repo_path: &Path, branch_name: &str, force: bool) -> Result<()> {
     let flag = if force { "-D" } else { "-d" };
@@ -276,7 +594,7 @@ pub struct DiffStats {
 /// Get git diff statistics between current branch and base branch (usually origin/main)
 pub async fn get_diff_stats(repo_path: &Path, current_branch: &str) -> Result<DiffStats> {
     // Get the default branch
-    let base_branch = get_default_branch(repo_path).await?;
+    let base_branch = default_branch(repo_path).await?;
 
     // If we're on the base branch, show uncommitted changes (working directory + staged)
     if current_branch == base_branch {
@@ -294,7 +612,9 @@ pub async fn get_diff_stats(repo_path: &Path, current_branch: &str) -> Result<Di
     }
 
     // Run: git diff --shortstat origin/<base_branch>...<current_branch>
-    let base_ref = format!("origin/{}", base_branch);
+    let base_ref = remote_branch_ref(repo_path, &base_branch)
+        .await
+        .unwrap_or_else(|| base_branch.clone());
     let compare_ref = format!("{}...{}", base_ref, current_branch);
 
     let output = tokio::process::Command::new("git")
@@ -359,3 +679,194 @@ fn parse_diff_stats(output: &str) -> Result<DiffStats> {
 
     Ok(stats)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_name_error_accepts_valid_names() {
+        for name in ["main", "feature/login", "fix-123", "user/raphi/wip", "v1.2"] {
+            assert_eq!(branch_name_error(name), None, "{name} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_branch_name_error_rejects_invalid_names() {
+        for name in [
+            "",
+            "@",
+            "-foo",
+            "my branch",
+            "foo~1",
+            "a:b",
+            "what?",
+            "a..b",
+            "foo@{1}",
+            "/foo",
+            "foo/",
+            "a//b",
+            "foo.",
+            ".hidden",
+            "feature/.hidden",
+            "foo.lock",
+            "back\\slash",
+        ] {
+            assert!(
+                branch_name_error(name).is_some(),
+                "{name:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sanitize_branch_name() {
+        assert_eq!(
+            sanitize_branch_name("my new feature").as_deref(),
+            Some("my-new-feature")
+        );
+        assert_eq!(
+            sanitize_branch_name(".hidden/foo.lock").as_deref(),
+            Some("hidden/foo")
+        );
+        assert_eq!(sanitize_branch_name("a..b//c.").as_deref(), Some("a.b/c"));
+        assert_eq!(
+            sanitize_branch_name("-fix: crash?").as_deref(),
+            Some("fix-crash")
+        );
+        assert_eq!(sanitize_branch_name("  "), None);
+        assert_eq!(sanitize_branch_name("@"), None);
+    }
+
+    #[test]
+    fn test_sanitized_names_are_valid() {
+        for name in ["my branch", "..a..", "x@{y}", "feat/ .lock /z", "a\\b*c"] {
+            if let Some(sanitized) = sanitize_branch_name(name) {
+                assert_eq!(branch_name_error(&sanitized), None, "{sanitized:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_worktree_template_validation() {
+        for template in [
+            DEFAULT_WORKTREE_TEMPLATE,
+            "{repo}/{branch}",
+            "wt-{branch_safe}",
+        ] {
+            assert_eq!(worktree_template_error(template), None, "{template:?}");
+        }
+        for template in [
+            "{repo}",         // not unique per branch
+            "{repo}-{name}",  // unknown placeholder
+            "{repo}-{branch", // unclosed
+            "/tmp/{branch}",  // absolute
+            "../{branch}",    // escapes worktree_dir
+            "{repo}//{branch}",
+        ] {
+            assert!(worktree_template_error(template).is_some(), "{template:?}");
+        }
+    }
+
+    #[test]
+    fn test_render_worktree_template() {
+        assert_eq!(
+            render_worktree_template(DEFAULT_WORKTREE_TEMPLATE, "amux", "feature/login"),
+            PathBuf::from("amux-feature-login")
+        );
+        assert_eq!(
+            render_worktree_template("{repo}/{branch}", "amux", "feature/login"),
+            PathBuf::from("amux/feature/login")
+        );
+        assert_eq!(
+            render_worktree_template("wt-{branch_safe}", "amux", "fix:a*b"),
+            PathBuf::from("wt-fix-a-b")
+        );
+        // Branch components can't climb out of the worktree directory
+        assert_eq!(
+            render_worktree_template("{branch}", "amux", "../../etc"),
+            PathBuf::from("-/-/etc")
+        );
+    }
+
+    #[test]
+    fn test_parse_ahead_behind() {
+        assert_eq!(parse_ahead_behind("1\t2\n"), Some((2, 1)));
+        assert_eq!(parse_ahead_behind("0\t0"), Some((0, 0)));
+        assert_eq!(parse_ahead_behind(""), None);
+        assert_eq!(parse_ahead_behind("fatal"), None);
+    }
+
+    #[test]
+    fn test_parse_branch_refs() {
+        let output = "refs/heads/main\nrefs/heads/feature/x\nrefs/remotes/origin/HEAD\n\
+                      refs/remotes/origin/main\nrefs/remotes/origin/fix/y\nrefs/remotes/upstream/fix/y\n";
+        assert_eq!(
+            parse_branch_refs(output),
+            vec![
+                ("main".to_string(), false),
+                ("feature/x".to_string(), false),
+                ("fix/y".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_experiment_branch_name() {
+        use chrono::TimeZone;
+
+        let time = chrono::Local
+            .with_ymd_and_hms(2025, 1, 2, 15, 4, 5)
+            .unwrap();
+        let name = experiment_branch_name(time);
+        assert_eq!(name, "amux/exp-20250102-150405");
+        assert!(branch_name_error(&name).is_none());
+        assert!(is_experiment_branch(&name));
+        assert!(!is_experiment_branch("feature/exp-1"));
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=amux", "-c", "user.email=amux@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_default_branch_master_repo() {
+        let root = std::env::temp_dir().join(format!("amux_git_default_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let origin = root.join("origin");
+        std::fs::create_dir_all(&origin).unwrap();
+        git(&origin, &["init", "-q", "-b", "master"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+        git(&origin, &["branch", "merged"]);
+        git(&origin, &["checkout", "-q", "-b", "open"]);
+        git(&origin, &["commit", "-q", "--allow-empty", "-m", "wip"]);
+        git(&origin, &["checkout", "-q", "master"]);
+
+        // No remote: the local master branch
+        assert_eq!(default_branch(&origin).await.unwrap(), "master");
+        assert_eq!(default_branch_ref(&origin).await.unwrap(), "master");
+        assert!(is_branch_merged(&origin, "merged").await.unwrap());
+        assert!(!is_branch_merged(&origin, "open").await.unwrap());
+
+        // A clone: origin/HEAD points at master
+        git(&root, &["clone", "-q", "origin", "clone"]);
+        let clone = root.join("clone");
+        assert_eq!(default_branch(&clone).await.unwrap(), "master");
+        assert_eq!(default_branch_ref(&clone).await.unwrap(), "origin/master");
+        assert!(is_branch_merged(&clone, "origin/merged").await.unwrap());
+        assert!(!is_branch_merged(&clone, "origin/open").await.unwrap());
+
+        // Without origin/HEAD, origin/master is still found
+        git(&clone, &["remote", "set-head", "origin", "--delete"]);
+        git(&clone, &["branch", "-q", "-m", "master", "work"]);
+        assert_eq!(default_branch(&clone).await.unwrap(), "master");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
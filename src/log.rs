@@ -1,14 +1,119 @@
 use chrono::Local;
 use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::VecDeque;
 use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 static LOG_FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| Mutex::new(None));
 static TOOL_LOG_FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| Mutex::new(None));
 static SESSION_ID: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+/// Last ACP messages in both directions, for bug reports
+static RECENT_MESSAGES: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Number of ACP messages kept for bug reports
+pub const RECENT_MESSAGE_COUNT: usize = 50;
+/// Bytes kept of each recent ACP message
+const RECENT_MESSAGE_MAX_BYTES: usize = 2000;
+
+/// Patterns for secrets that must not end up in bug reports, with their replacements
+static SECRET_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    let sensitive =
+        r#"[^"]*(?i:token|secret|password|passwd|api[_-]?key|authorization|credential)[^"]*"#;
+    [
+        // "apiKey": "..." style JSON fields
+        (
+            format!(r#"("{sensitive}"\s*:\s*)"(?:[^"\\]|\\.)*""#),
+            r#"$1"[REDACTED]""#,
+        ),
+        // {"name": "GITHUB_TOKEN", "value": "..."} environment entries
+        (
+            format!(r#"("name"\s*:\s*"{sensitive}"\s*,\s*"value"\s*:\s*)"(?:[^"\\]|\\.)*""#),
+            r#"$1"[REDACTED]""#,
+        ),
+        // GITHUB_TOKEN=... on command lines
+        (
+            r#"(?i)\b([A-Z0-9_]*(?:TOKEN|SECRET|PASSWORD|API_KEY)[A-Z0-9_]*=)[^\s\\"]+"#.to_string(),
+            "${1}[REDACTED]",
+        ),
+        (
+            r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+".to_string(),
+            "Bearer [REDACTED]",
+        ),
+        // Well-known key formats (OpenAI/Anthropic, GitHub, Slack, AWS)
+        (
+            r"\b(?:sk-[A-Za-z0-9_-]{16,}|gh[pousr]_[A-Za-z0-9]{20,}|xox[abpr]-[A-Za-z0-9-]{10,}|AKIA[0-9A-Z]{16})\b"
+                .to_string(),
+            "[REDACTED]",
+        ),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(&pattern).unwrap(), replacement))
+    .collect()
+});
+
+/// Most verbose level written to the log file
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Debug as u8);
+
+/// Severity of a log line, from most to least severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    /// ACP traffic and event processing (the default, so everything is logged)
+    #[default]
+    Debug,
+}
+
+impl LogLevel {
+    /// Tag written in front of each line
+    pub fn tag(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        }
+    }
+
+    /// Parse a config name, falling back to the default with a log warning
+    pub fn from_config(name: Option<&str>) -> Self {
+        match name.map(str::to_lowercase).as_deref() {
+            None | Some("debug") => LogLevel::Debug,
+            Some("info") => LogLevel::Info,
+            Some("warn") => LogLevel::Warn,
+            Some("error") => LogLevel::Error,
+            Some(other) => {
+                warn(&format!("Unknown log level '{}', using debug", other));
+                LogLevel::default()
+            }
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            2 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+/// Only write lines at `level` or more severe from now on
+pub fn set_level(level: LogLevel) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether lines at `level` are currently written
+pub fn enabled(level: LogLevel) -> bool {
+    level <= LogLevel::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
 
 /// Generate a short unique session ID (6 hex chars)
 fn generate_session_id() -> String {
@@ -46,7 +151,7 @@ pub fn install_panic_hook() {
             "unknown location".to_string()
         };
 
-        log(&format!("[PANIC] {} at {}", msg, location));
+        error(&format!("[PANIC] {} at {}", msg, location));
 
         // Also call the default hook to print to stderr
         default_hook(panic_info);
@@ -96,10 +201,33 @@ pub fn init() -> std::io::Result<(PathBuf, String)> {
     Ok((log_path, sid))
 }
 
-/// Log a message with timestamp
+/// Log a message at info level
 pub fn log(msg: &str) {
+    log_at(LogLevel::Info, msg);
+}
+
+/// Log a failure that affects the user
+pub fn error(msg: &str) {
+    log_at(LogLevel::Error, msg);
+}
+
+/// Log something unexpected that amux recovered from
+pub fn warn(msg: &str) {
+    log_at(LogLevel::Warn, msg);
+}
+
+/// Log protocol and event details
+pub fn debug(msg: &str) {
+    log_at(LogLevel::Debug, msg);
+}
+
+/// Log a message with timestamp and level tag, if the level is enabled
+pub fn log_at(level: LogLevel, msg: &str) {
+    if !enabled(level) {
+        return;
+    }
     let timestamp = Local::now().format("%H:%M:%S%.3f");
-    let line = format!("[{}] {}\n", timestamp, msg);
+    let line = format!("[{}] [{}] {}\n", timestamp, level.tag(), msg);
 
     if let Ok(mut guard) = LOG_FILE.lock()
         && let Some(ref mut file) = *guard
@@ -122,31 +250,73 @@ fn truncate_at_char_boundary(s: &str, max_bytes: usize) -> &str {
     &s[..end]
 }
 
+/// Replace tokens, keys and passwords in `text` with a placeholder
+pub fn redact_secrets(text: &str) -> String {
+    SECRET_PATTERNS
+        .iter()
+        .fold(text.to_string(), |text, (pattern, replacement)| {
+            pattern.replace_all(&text, *replacement).into_owned()
+        })
+}
+
+/// Remember an ACP message for bug reports, dropping the oldest beyond the limit
+fn record_message(direction: &str, line: &str) {
+    let timestamp = Local::now().format("%H:%M:%S%.3f");
+    let body = truncate_at_char_boundary(line, RECENT_MESSAGE_MAX_BYTES);
+    let entry = if body.len() < line.len() {
+        format!(
+            "[{}] {} {}... ({} bytes total)",
+            timestamp,
+            direction,
+            body,
+            line.len()
+        )
+    } else {
+        format!("[{}] {} {}", timestamp, direction, body)
+    };
+    if let Ok(mut messages) = RECENT_MESSAGES.lock() {
+        if messages.len() >= RECENT_MESSAGE_COUNT {
+            messages.pop_front();
+        }
+        messages.push_back(entry);
+    }
+}
+
+/// The last ACP messages sent and received, oldest first, with secrets redacted
+pub fn recent_protocol_messages() -> Vec<String> {
+    RECENT_MESSAGES
+        .lock()
+        .map(|messages| messages.iter().map(|m| redact_secrets(m)).collect())
+        .unwrap_or_default()
+}
+
 /// Log incoming ACP message (truncated for readability)
 pub fn log_incoming(line: &str) {
+    record_message("<--", line);
     let display = if line.len() > 500 {
         let truncated = truncate_at_char_boundary(line, 500);
         format!("{}... ({} bytes total)", truncated, line.len())
     } else {
         line.to_string()
     };
-    log(&format!("<-- {}", display));
+    debug(&format!("<-- {}", display));
 }
 
 /// Log outgoing ACP message
 pub fn log_outgoing(line: &str) {
+    record_message("-->", line);
     let display = if line.len() > 500 {
         let truncated = truncate_at_char_boundary(line, 500);
         format!("{}... ({} bytes total)", truncated, line.len())
     } else {
         line.to_string()
     };
-    log(&format!("--> {}", display));
+    debug(&format!("--> {}", display));
 }
 
 /// Log an event
 pub fn log_event(event: &str) {
-    log(&format!("[EVENT] {}", event));
+    debug(&format!("[EVENT] {}", event));
 }
 
 /// Log a tool call JSON to the dedicated tools log file
@@ -167,3 +337,93 @@ pub fn log_tool_json(tool_name: &str, json: &serde_json::Value) {
     let pretty = serde_json::to_string_pretty(json).unwrap_or_else(|_| json.to_string());
     log_tool(&format!("=== {} ===\n{}", tool_name, pretty));
 }
+
+/// Read the complete lines appended to a log file since `offset`, advancing it.
+/// A trailing line without a newline is left for the next call.
+pub fn read_new_lines(path: &Path, offset: &mut u64) -> Vec<String> {
+    let mut buf = Vec::new();
+    let read = File::open(path).and_then(|mut file| {
+        file.seek(SeekFrom::Start(*offset))?;
+        file.read_to_end(&mut buf)
+    });
+    if read.is_err() {
+        return vec![];
+    }
+    let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+        return vec![];
+    };
+    *offset += end as u64 + 1;
+    String::from_utf8_lossy(&buf[..end])
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_ordering() {
+        assert!(LogLevel::Error < LogLevel::Warn);
+        assert!(LogLevel::Info < LogLevel::Debug);
+        assert_eq!(LogLevel::from_config(None), LogLevel::Debug);
+        assert_eq!(LogLevel::from_config(Some("WARN")), LogLevel::Warn);
+        assert_eq!(LogLevel::from_config(Some("bogus")), LogLevel::Debug);
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        let message =
+            r#"{"env":[{"name":"GITHUB_TOKEN","value":"abc123"}],"apiKey":"s3cret","cwd":"/tmp"}"#;
+        assert_eq!(
+            redact_secrets(message),
+            r#"{"env":[{"name":"GITHUB_TOKEN","value":"[REDACTED]"}],"apiKey":"[REDACTED]","cwd":"/tmp"}"#
+        );
+        assert_eq!(
+            redact_secrets("Authorization: Bearer eyJhbGciOi.payload"),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(
+            redact_secrets("run ANTHROPIC_API_KEY=xyz ./agent"),
+            "run ANTHROPIC_API_KEY=[REDACTED] ./agent"
+        );
+        assert_eq!(
+            redact_secrets("key ghp_abcdefghijklmnopqrstuvwx0123 used"),
+            "key [REDACTED] used"
+        );
+        assert_eq!(redact_secrets(r#"{"path":"/src"}"#), r#"{"path":"/src"}"#);
+    }
+
+    #[test]
+    fn test_recent_messages_are_bounded() {
+        for i in 0..RECENT_MESSAGE_COUNT + 5 {
+            log_outgoing(&format!(r#"{{"id":{}}}"#, i));
+        }
+        let messages = recent_protocol_messages();
+        assert_eq!(messages.len(), RECENT_MESSAGE_COUNT);
+        assert!(
+            messages
+                .last()
+                .unwrap()
+                .ends_with(&format!(r#"--> {{"id":{}}}"#, RECENT_MESSAGE_COUNT + 4))
+        );
+    }
+
+    #[test]
+    fn test_read_new_lines_tails_complete_lines() {
+        let path = std::env::temp_dir().join(format!("amux_log_tail_{}.log", std::process::id()));
+        std::fs::write(&path, "one\ntwo\npart").unwrap();
+
+        let mut offset = 0;
+        assert_eq!(read_new_lines(&path, &mut offset), vec!["one", "two"]);
+        assert_eq!(offset, 8);
+        assert!(read_new_lines(&path, &mut offset).is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"ial\nthree\n").unwrap();
+        assert_eq!(read_new_lines(&path, &mut offset), vec!["partial", "three"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -2,50 +2,67 @@ mod acp;
 mod app;
 mod clipboard;
 mod config;
+mod diff;
 mod events;
+mod file_ref;
 mod git;
 mod log;
+mod mention;
 mod notification;
 mod picker;
 mod scroll;
 mod session;
+mod slash_command;
+mod snippets;
+mod state_file;
 mod tui;
+mod ui_state;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use crossterm::{
     event::{
         DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
         Event, EventStream, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind,
     },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
+    },
 };
 use futures::{FutureExt, StreamExt};
 use ratatui::prelude::*;
+use ratatui::{TerminalOptions, Viewport};
 use std::collections::HashMap;
 use std::io::stdout;
 use std::path::PathBuf;
-use std::time::Duration;
 use tokio::sync::mpsc;
 
 use acp::{
     AgentConnection, AgentEvent, AskUserResponse, ContentBlock, PermissionOptionId, SessionUpdate,
 };
 use app::{
-    App, CleanupEntry, FolderEntry, ImageAttachment, InputMode, WorktreeConfig, WorktreeEntry,
+    App, BranchEntry, BroadcastDelivery, CleanupEntry, FolderEntry, HeadingStyle, ImageAttachment,
+    InputMode, NewWorktree, PendingSpawn, SpinnerStyle, StartupSession, WorktreeConfig,
+    WorktreeEntry,
 };
-use clipboard::ClipboardContent;
+use diff::FileDiff;
 use events::Action;
 use events::keyboard::{
-    handle_agent_picker_mode, handle_branch_input_mode, handle_bug_report_mode,
-    handle_clear_confirm_mode, handle_folder_picker_mode, handle_help_mode, handle_insert_mode,
-    handle_session_picker_mode, handle_worktree_cleanup_mode,
-    handle_worktree_cleanup_repo_picker_mode, handle_worktree_folder_picker_mode,
-    handle_worktree_picker_mode,
+    handle_agent_picker_mode, handle_branch_input_mode, handle_broadcast_mode,
+    handle_bug_report_mode, handle_clear_confirm_mode, handle_diff_view_mode,
+    handle_folder_picker_mode, handle_help_mode, handle_insert_mode,
+    handle_large_prompt_confirm_mode, handle_log_view_mode, handle_mcp_manager_mode,
+    handle_normal_mode, handle_output_filter_mode, handle_plan_focus_mode,
+    handle_quit_confirm_mode, handle_session_limit_confirm_mode, handle_session_picker_mode,
+    handle_session_switcher_mode, handle_snippet_picker_mode, handle_snippet_save_mode,
+    handle_tool_json_mode, handle_worktree_cleanup_mode, handle_worktree_cleanup_repo_picker_mode,
+    handle_worktree_folder_picker_mode, handle_worktree_picker_mode,
 };
+use file_ref::FileLocation;
 use picker::Picker;
 use session::{
-    AgentType, OutputType, PendingPermission, PendingQuestion, SessionState, check_all_agents,
+    AgentType, OutputType, PendingPermission, PendingQuestion, Session, SessionState,
+    SessionSummary, ToolPermissionRules, check_all_agents, is_edit_permission,
 };
 
 /// Internal app events for async operations
@@ -62,6 +79,12 @@ enum AppEvent {
         output: String,
         success: bool,
     },
+    /// Files for `@` completion were scanned (session dir, fragment dir, files)
+    MentionFiles {
+        cwd: std::path::PathBuf,
+        dir: String,
+        files: Vec<String>,
+    },
 }
 
 /// Get the current git branch for a directory
@@ -79,6 +102,27 @@ async fn get_git_branch(cwd: &std::path::Path) -> String {
     }
 }
 
+/// Re-read the selected session's branch, which its agent (or a bash command)
+/// may have switched since it was last read
+async fn refresh_selected_git_branch(app: &mut App) {
+    let Some(session) = app.selected_session() else {
+        return;
+    };
+    // Sessions outside a repository have no branch to follow
+    if session.git_branch.is_empty() {
+        return;
+    }
+    let branch = get_git_branch(&session.cwd).await;
+    if let Some(session) = app.sessions.selected_session_mut()
+        && session.update_git_branch(branch)
+    {
+        log::debug(&format!(
+            "Session {} is now on branch {}",
+            session.name, session.git_branch
+        ));
+    }
+}
+
 /// Check if a directory is a git repository and get its branch
 async fn get_git_branch_if_repo(dir: &std::path::Path) -> Option<String> {
     let git_dir = dir.join(".git");
@@ -190,7 +234,7 @@ async fn scan_folder_entries(dir: &std::path::Path) -> Vec<FolderEntry> {
         }
 
         // Sort alphabetically
-        dirs.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        dirs.sort_by_key(|d| d.0.to_lowercase());
 
         // Check for git repos
         for (name, path) in dirs {
@@ -221,18 +265,28 @@ async fn scan_worktrees(worktree_dir: &std::path::Path, fetch_first: bool) -> Ve
         is_merged: false,
     });
 
-    // Scan existing worktrees
-    if let Ok(mut read_dir) = tokio::fs::read_dir(worktree_dir).await {
+    // Scan existing worktrees. Naming templates like "{repo}/{branch}" nest them,
+    // so descend into plain directories a few levels deep.
+    const MAX_WORKTREE_DEPTH: usize = 4;
+    if tokio::fs::metadata(worktree_dir).await.is_ok() {
         let mut worktree_paths = vec![];
-        while let Ok(Some(entry)) = read_dir.next_entry().await {
-            if let Ok(file_type) = entry.file_type().await
-                && file_type.is_dir()
-            {
-                let path = entry.path();
-                // Only include if it looks like a git worktree (has .git file or directory)
-                let git_path = path.join(".git");
-                if git_path.exists() {
-                    worktree_paths.push(path);
+        let mut pending = vec![(worktree_dir.to_path_buf(), 1)];
+        while let Some((dir, depth)) = pending.pop() {
+            let Ok(mut read_dir) = tokio::fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = read_dir.next_entry().await {
+                if let Ok(file_type) = entry.file_type().await
+                    && file_type.is_dir()
+                {
+                    let path = entry.path();
+                    // Only include if it looks like a git worktree (has .git file or directory)
+                    let git_path = path.join(".git");
+                    if git_path.exists() {
+                        worktree_paths.push(path);
+                    } else if depth < MAX_WORKTREE_DEPTH {
+                        pending.push((path, depth + 1));
+                    }
                 }
             }
         }
@@ -249,7 +303,7 @@ async fn scan_worktrees(worktree_dir: &std::path::Path, fetch_first: bool) -> Ve
                         parent_repo.display()
                     ));
                     if let Err(e) = git::fetch_origin(&parent_repo).await {
-                        log::log(&format!("Failed to fetch: {}", e));
+                        log::warn(&format!("Failed to fetch: {}", e));
                     }
                 }
             }
@@ -258,9 +312,10 @@ async fn scan_worktrees(worktree_dir: &std::path::Path, fetch_first: bool) -> Ve
         // Now get status for each worktree
         let mut worktrees = vec![];
         for path in worktree_paths {
+            // Nested worktrees are named by their path below the worktree directory
             let name = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
+                .strip_prefix(worktree_dir)
+                .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_default();
             let is_clean = git::is_worktree_clean(&path).await.unwrap_or(false);
             let is_merged = get_worktree_merged_status(&path).await;
@@ -268,7 +323,7 @@ async fn scan_worktrees(worktree_dir: &std::path::Path, fetch_first: bool) -> Ve
         }
 
         // Sort alphabetically
-        worktrees.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        worktrees.sort_by_key(|w| w.0.to_lowercase());
 
         for (name, path, is_clean, is_merged) in worktrees {
             entries.push(WorktreeEntry {
@@ -363,9 +418,20 @@ enum AgentCommand {
         session_id: String,
         model_id: String,
     },
+    SetMode {
+        session_id: String,
+        mode_id: String,
+    },
     CancelPrompt,
+    /// Cancel any running prompt and kill the agent, then report back on `done`
+    Shutdown {
+        done: tokio::sync::oneshot::Sender<()>,
+    },
 }
 
+/// How long quitting waits for agents to cancel and exit
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Info for resuming a session
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -381,18 +447,68 @@ ARGS:
 
 OPTIONS:
     -w, --worktree-dir <PATH>    Directory for git worktrees
+    -a, --agent <NAME>           Start a session with this agent in DIRECTORY right away
+    -p, --prompt-file <PATH>     Send this file's contents as the first prompt of that
+                                 session (`-` reads stdin)
+        --inline                 Draw on the normal screen, leaving the last frame in
+                                 the scrollback after quitting
+        --list-agents            List supported agents and whether they're installed
     -V, --version                Print version information
     -h, --help                   Print this help message
 "
     );
 }
 
+/// Read the first prompt from `--prompt-file`, or from stdin for `-`.
+/// Stdin is only read when asked for: without a tty it may be /dev/null, a
+/// supervisor's pipe or anything else that isn't meant for the agent.
+/// Empty input means no prompt.
+fn read_startup_prompt(prompt_file: Option<&std::path::Path>) -> Result<Option<String>> {
+    let text = match prompt_file {
+        Some(path) if path != std::path::Path::new("-") => std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("cannot read prompt file '{}': {}", path.display(), e))?,
+        Some(_) => std::io::read_to_string(std::io::stdin())?,
+        None => return Ok(None),
+    };
+    let text = text.trim();
+    Ok((!text.is_empty()).then(|| text.to_string()))
+}
+
+/// Print each supported agent with its command and availability
+fn print_agents(custom_agents: &[AgentType]) {
+    for agent in check_all_agents(custom_agents) {
+        let agent_type = &agent.agent_type;
+        let command = std::iter::once(agent_type.command())
+            .chain(agent_type.args())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let status = if agent.is_available() {
+            "available"
+        } else {
+            "not available"
+        };
+        println!("{} ({})", agent_type.display_name(), status);
+        println!("    command: {}", command);
+        match &agent.binary_path {
+            Some(path) => println!("    path:    {}", path.display()),
+            None => println!("    path:    {} not found in PATH", agent_type.command()),
+        }
+        for precondition in &agent.preconditions {
+            let mark = if precondition.satisfied { "x" } else { " " };
+            println!("    [{}] {}", mark, precondition.description);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse CLI arguments first (before initializing terminal)
     let args: Vec<String> = std::env::args().collect();
     let mut start_dir = std::env::current_dir().unwrap_or_default();
     let mut worktree_dir_override: Option<std::path::PathBuf> = None;
+    let mut agent_name: Option<String> = None;
+    let mut prompt_file: Option<std::path::PathBuf> = None;
+    let mut inline = false;
 
     let mut i = 1;
     while i < args.len() {
@@ -405,6 +521,13 @@ async fn main() -> Result<()> {
                 print_help();
                 return Ok(());
             }
+            "--inline" => {
+                inline = true;
+            }
+            "--list-agents" => {
+                print_agents(&config::Config::load().custom_agents());
+                return Ok(());
+            }
             "--worktree-dir" | "-w" => {
                 if i + 1 < args.len() {
                     let path = std::path::PathBuf::from(&args[i + 1]);
@@ -421,6 +544,26 @@ async fn main() -> Result<()> {
                     i += 1;
                 }
             }
+            "--agent" | "-a" => {
+                if i + 1 < args.len() {
+                    agent_name = Some(args[i + 1].clone());
+                    i += 2;
+                    continue;
+                } else {
+                    eprintln!("Warning: --agent requires a name argument");
+                    i += 1;
+                }
+            }
+            "--prompt-file" | "-p" => {
+                if i + 1 < args.len() {
+                    prompt_file = Some(std::path::PathBuf::from(&args[i + 1]));
+                    i += 2;
+                    continue;
+                } else {
+                    eprintln!("Warning: --prompt-file requires a path argument");
+                    i += 1;
+                }
+            }
             arg if !arg.starts_with('-') => {
                 let path = std::path::PathBuf::from(arg);
                 if path.is_dir() {
@@ -450,33 +593,118 @@ async fn main() -> Result<()> {
 
     // Load config
     let config = config::Config::load();
+    log::set_level(log::LogLevel::from_config(config.log_level.as_deref()));
+
+    // Resolve the startup session before the terminal is taken over, so a bad
+    // agent name or unreadable prompt file is reported on the plain terminal
+    let custom_agents = config.custom_agents();
+    let startup_agent = match &agent_name {
+        Some(name) => Some(AgentType::find(name, &custom_agents).ok_or_else(|| {
+            let known: Vec<&str> = AgentType::BUILT_IN
+                .iter()
+                .chain(&custom_agents)
+                .map(|agent| agent.display_name())
+                .collect();
+            anyhow!("unknown agent '{}' (known: {})", name, known.join(", "))
+        })?),
+        None => None,
+    };
+    let startup_prompt = read_startup_prompt(prompt_file.as_deref())?;
+    let startup_session = match (startup_agent, startup_prompt) {
+        (None, None) => None,
+        (agent_type, prompt) => Some(StartupSession {
+            agent_type: agent_type.unwrap_or_else(|| config.default_agent()),
+            prompt,
+        }),
+    };
 
     // Load worktree config with precedence: CLI > env var > config file > default
-    let worktree_config =
-        WorktreeConfig::load(worktree_dir_override.or(config.worktree_dir.clone()));
+    let worktree_config = WorktreeConfig::load(
+        worktree_dir_override.or(config.worktree_dir.clone()),
+        config.worktree_template.clone(),
+    );
 
-    // Setup terminal
+    // Setup terminal. Inline, the UI takes the screen's height below the
+    // cursor on the primary screen instead of switching to the alternate one.
+    let inline = inline || config.inline;
     enable_raw_mode()?;
     let mut stdout = stdout();
-    execute!(
-        stdout,
-        EnterAlternateScreen,
-        EnableBracketedPaste,
-        EnableMouseCapture
-    )?;
+    if !inline {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
+    execute!(stdout, EnableBracketedPaste, EnableMouseCapture)?;
+    let viewport = if inline {
+        Viewport::Inline(crossterm::terminal::size()?.1)
+    } else {
+        Viewport::Fullscreen
+    };
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
 
     // Create app state
+    let max_scrollback = config.max_scrollback();
+    let large_prompt_threshold = config.large_prompt_threshold();
+    let client_options = acp::ClientOptions {
+        max_read_bytes: config.max_read_file_bytes(),
+        terminal_output_limit: config.terminal_output_limit(),
+        shell: config.agent_shell(),
+        login_shell: config.agent_shell_login,
+        clean_env: config.agent_clean_env,
+    };
+    let tick_rate = config.tick_rate();
+    let idle_tick_rate = config.idle_tick_rate();
+    let idle_timeout = config.idle_timeout();
+    let max_sessions = config.max_sessions();
+    let trusted_dirs = config.trusted_dirs();
+    let repeat_key = config.repeat_key();
+    let submit_key = config.submit_key;
+    let max_conversation_width = config.max_conversation_width();
+    let bug_report_repo = config.bug_report_repo();
     let notification_config = config.notifications.into();
     let mut app = App::new(
         start_dir,
         worktree_config,
         config.mcp_servers,
         notification_config,
+        ui_state::UiState::load(),
     );
     app.log_path = log_path;
     app.session_id = session_id;
+    app.show_agent_stderr = config.show_agent_stderr;
+    app.auto_reconnect = config.auto_reconnect;
+    app.auto_fetch = config.auto_fetch;
+    app.queue_broadcast_when_busy = config.queue_broadcast_when_busy;
+    app.confirm_quit = config.confirm_quit;
+    app.unread_first = config.unread_first;
+    app.save_transcripts = config.save_transcripts;
+    app.inline = inline;
+    app.tool_permission_rules = ToolPermissionRules {
+        auto_accept: config.auto_accept_tools.clone(),
+        never_auto_accept: config.never_auto_accept.clone(),
+    };
+    app.max_scrollback = max_scrollback;
+    app.large_prompt_threshold = large_prompt_threshold;
+    app.client_options = client_options;
+    app.tick_rate = tick_rate;
+    app.idle_tick_rate = idle_tick_rate;
+    app.idle_timeout = idle_timeout;
+    app.max_sessions = max_sessions;
+    app.trusted_dirs = trusted_dirs;
+    app.spinner_style = SpinnerStyle::from_config(config.spinner.as_deref());
+    app.heading_style = HeadingStyle::from_config(config.heading_style.as_deref());
+    app.repeat_key = repeat_key;
+    app.submit_key = submit_key;
+    app.bug_report_repo = bug_report_repo;
+    if let Some(template) = config.explain_error_prompt {
+        app.explain_error_prompt = template;
+    }
+    app.sidebar_width = config.sidebar_width;
+    app.max_conversation_width = max_conversation_width;
+    app.editor = config.editor;
+    app.preamble = config.preamble;
+    app.post_create_hook = config.post_create_hook;
+    app.custom_agents = custom_agents;
+    app.startup_session = startup_session;
 
     // Run the app
     let result = run_app(&mut terminal, &mut app).await;
@@ -486,9 +714,16 @@ async fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         DisableMouseCapture,
-        DisableBracketedPaste,
-        LeaveAlternateScreen
+        DisableBracketedPaste
     )?;
+    if inline {
+        // Leave the last frame in place and continue on the line below it
+        let last_row = terminal.get_frame().area().bottom().saturating_sub(1);
+        terminal.set_cursor_position((0, last_row))?;
+        println!();
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
     terminal.show_cursor()?;
 
     result
@@ -510,16 +745,94 @@ where
     // Event stream for keyboard
     let mut event_stream = EventStream::new();
 
-    // Open folder picker on startup
+    // Session whose branch was re-read when it got selected
+    let mut branch_checked_session: Option<String> = None;
+
+    // Session counts last shown in the terminal title
+    let mut title_summary: Option<SessionSummary> = None;
+
+    // Start the session requested on the command line, or open the folder picker
     let start = app.start_dir.clone();
-    app.open_folder_picker(start.clone());
-    let entries = scan_folder_entries(&start).await;
-    app.set_folder_entries(entries);
+    if let Some(startup) = app.startup_session.take() {
+        if let Err(e) = start_session_in_dir(
+            app,
+            &agent_tx,
+            &mut agent_commands,
+            startup.agent_type,
+            start,
+            false,
+        )
+        .await
+        {
+            log::error(&format!("Failed to start session: {}", e));
+        }
+        // Queued prompts are sent as soon as the agent is idle
+        if let Some(prompt) = startup.prompt
+            && let Some(session) = app.sessions.selected_session_mut()
+        {
+            session.queued_prompts.push_back(prompt);
+        }
+    } else {
+        app.open_folder_picker(start.clone());
+        let entries = scan_folder_entries(&start).await;
+        app.set_folder_entries(entries);
+    }
 
     loop {
+        // Run a requested editor with the TUI suspended
+        if let Some(cwd) = app.pending_editor.take() {
+            let location = app.pending_editor_location.take();
+            match app.editor_command() {
+                Some(editor) => {
+                    if let Err(e) = run_editor(
+                        terminal,
+                        &mut event_stream,
+                        app.inline,
+                        &editor,
+                        &cwd,
+                        location.as_ref(),
+                    )
+                    .await
+                    {
+                        log::error(&format!("Failed to run editor '{}': {}", editor, e));
+                        if let Some(session) = app.sessions.selected_session_mut() {
+                            session.add_output(
+                                format!("Failed to run editor '{}': {}", editor, e),
+                                OutputType::Error,
+                            );
+                        }
+                    }
+                }
+                None => {
+                    log::warn("Cannot open editor: $EDITOR is not set");
+                    if let Some(session) = app.sessions.selected_session_mut() {
+                        session.add_output(
+                            "Cannot open editor: set $EDITOR or `editor` in config.toml"
+                                .to_string(),
+                            OutputType::Error,
+                        );
+                    }
+                }
+            }
+        }
+
+        // A session's branch may have changed while it wasn't selected
+        let selected_id = app.selected_session().map(|s| s.id.clone());
+        if selected_id != branch_checked_session {
+            branch_checked_session = selected_id;
+            refresh_selected_git_branch(app).await;
+        }
+
         // Render
         terminal.draw(|frame| tui::ui::render(frame, app))?;
 
+        // Mirror the session states in the window title, rewriting it only on changes
+        let summary = app.sessions.summary();
+        if title_summary != Some(summary) {
+            let _ = execute!(stdout(), SetTitle(format!("amux — {}", summary)));
+            title_summary = Some(summary);
+        }
+
         // Handle events with timeout for responsiveness
         // Use biased select to prioritize keyboard input over agent events
         tokio::select! {
@@ -527,6 +840,11 @@ where
             // Terminal events (keyboard, paste, etc.)
             maybe_event = event_stream.next() => {
                 if let Some(Ok(event)) = maybe_event {
+                    // Rewrapping changes line counts; keep the scrolled-to content in view
+                    if let Event::Resize(..) = &event {
+                        app.handle_resize();
+                    }
+
                     // Handle paste events (from drag & drop or Cmd+V in some terminals)
                     if let Event::Paste(text) = &event {
                         // Auto-switch to insert mode if in normal mode with a session selected
@@ -535,26 +853,8 @@ where
                         }
 
                         if app.input_mode == InputMode::Insert {
-                            // Check if it's a path to an image file
-                            if let Some(path) = clipboard::try_parse_image_path(text) {
-                                if let Some((filename, mime_type, data)) = clipboard::load_image_from_path(&path) {
-                                    app.add_attachment(ImageAttachment {
-                                        filename,
-                                        mime_type,
-                                        data,
-                                    });
-                                } else {
-                                    // Not a valid image, paste as text
-                                    for c in text.chars() {
-                                        app.input_char(c);
-                                    }
-                                }
-                            } else {
-                                // Regular text, paste it
-                                for c in text.chars() {
-                                    app.input_char(c);
-                                }
-                            }
+                            // Paths to image files (one per line) are attached, anything else is text
+                            app.paste(clipboard::contents_from_text(text));
                         }
                         continue;
                     }
@@ -584,6 +884,7 @@ where
                             MouseEventKind::Down(crossterm::event::MouseButton::Left) => {
                                 app.interactions.handle_click(x, y)
                             }
+                            MouseEventKind::Moved => app.interactions.handle_hover(x, y),
                             _ => Action::None,
                         };
 
@@ -597,6 +898,35 @@ where
                                 app.scroll_down(n);
                                 continue;
                             }
+                            Action::ScrollTo(offset) => {
+                                // Scrollbar clicks
+                                app.scroll_to(offset);
+                                continue;
+                            }
+                            Action::DiffViewScrollUp(n) => {
+                                if let Some(diff_view) = &mut app.diff_view {
+                                    diff_view.scroll_up(n);
+                                }
+                                continue;
+                            }
+                            Action::DiffViewScrollDown(n) => {
+                                if let Some(diff_view) = &mut app.diff_view {
+                                    diff_view.scroll_down(n);
+                                }
+                                continue;
+                            }
+                            Action::LogViewScrollUp(n) => {
+                                if let Some(log_view) = &mut app.log_view {
+                                    log_view.scroll_up(n);
+                                }
+                                continue;
+                            }
+                            Action::LogViewScrollDown(n) => {
+                                if let Some(log_view) = &mut app.log_view {
+                                    log_view.scroll_down(n);
+                                }
+                                continue;
+                            }
                             Action::EnterInsertMode => {
                                 if app.sessions.selected_session().is_some() {
                                     app.enter_insert_mode();
@@ -655,6 +985,13 @@ where
                                 }
                                 continue;
                             }
+                            Action::AllowPermission | Action::DenyPermission | Action::HoverPermissionOption(_) => {
+                                // Permission dialog buttons and option hover
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                                continue;
+                            }
                             Action::CancelPrompt => {
                                 // Cancel the running prompt
                                 if let Some(session) = app.sessions.selected_session_mut() {
@@ -662,11 +999,33 @@ where
                                     if let Some(cmd_tx) = agent_commands.get(&session_id) {
                                         let _ = cmd_tx.send(AgentCommand::CancelPrompt).await;
                                     }
-                                    session.state = SessionState::Idle;
-                                    session.add_output(
-                                        "Cancelled".to_string(),
-                                        OutputType::SystemMessage,
-                                    );
+                                    session.cancel_prompt();
+                                }
+                                continue;
+                            }
+                            Action::ToolJsonScrollUp(n) => {
+                                if let Some(tool_json) = &mut app.tool_json {
+                                    tool_json.scroll_up(n);
+                                }
+                                continue;
+                            }
+                            Action::ToolJsonScrollDown(n) => {
+                                if let Some(tool_json) = &mut app.tool_json {
+                                    tool_json.scroll_down(n);
+                                }
+                                continue;
+                            }
+                            Action::OpenEditorAt(_) => {
+                                // file:line references in the output
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                                continue;
+                            }
+                            Action::SendFollowUp(_) => {
+                                // Follow-up chips
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
                                 }
                                 continue;
                             }
@@ -870,9 +1229,30 @@ where
                                         _ => {}
                                     }
                                 } else {
-                                    // Normal mode keys
+                                    // Normal mode keys. Repeatable actions go through
+                                    // `Action` so the repeat key can run them again.
+                                    let action = handle_normal_mode(app, key);
+                                    let repeatable = action.is_repeatable()
+                                        || matches!(action, Action::RepeatLast);
                                     match key.code {
-                                        KeyCode::Char('q') => return Ok(()),
+                                        _ if repeatable => {
+                                            let action = app.record_action(action);
+                                            if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                                handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                            }
+                                        }
+                                        // Keys bound only in `handle_normal_mode`, so the
+                                        // binding lives in one table
+                                        _ if matches!(action, Action::SelectPreviousSession | Action::SendFollowUp(_)) => {
+                                            if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                                handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                            }
+                                        }
+                                        // Quit, unless confirmation is needed for busy sessions
+                                        KeyCode::Char('q') if app.request_quit() => {
+                                            shutdown_agents(&mut agent_commands).await;
+                                            return Ok(());
+                                        }
                                         KeyCode::Esc => {
                                             // Cancel running prompt
                                             if let Some(session) = app.sessions.selected_session_mut()
@@ -882,11 +1262,10 @@ where
                                                 if let Some(cmd_tx) = agent_commands.get(&session_id) {
                                                     let _ = cmd_tx.send(AgentCommand::CancelPrompt).await;
                                                 }
-                                                session.state = SessionState::Idle;
-                                                session.add_output(
-                                                    "Cancelled".to_string(),
-                                                    OutputType::SystemMessage,
-                                                );
+                                                session.cancel_prompt();
+                                            } else if app.selected_session().is_some_and(|s| s.filter_active) {
+                                                // Otherwise remove the output filter
+                                                app.clear_output_filter();
                                             }
                                         }
                                         KeyCode::Char('?') => {
@@ -896,26 +1275,6 @@ where
                                             app.open_bug_report();
                                         }
 
-                                        KeyCode::Tab => {
-                                            // Cycle permission mode for selected session
-                                            if let Some(session) = app.sessions.selected_session_mut() {
-                                                session.cycle_permission_mode();
-                                            }
-                                        }
-                                        KeyCode::Char('m') => {
-                                            // Cycle model for selected session
-                                            if let Some(session) = app.sessions.selected_session_mut()
-                                                && let Some(model_id) = session.cycle_model() {
-                                                    let local_id = session.id.clone();
-                                                    let acp_session_id = session.acp_session_id.clone().unwrap_or_default();
-                                                    if let Some(cmd_tx) = agent_commands.get(&local_id) {
-                                                        let _ = cmd_tx.send(AgentCommand::SetModel {
-                                                            session_id: acp_session_id,
-                                                            model_id,
-                                                        }).await;
-                                                    }
-                                                }
-                                        }
                                         // Number keys to select session directly (using display order)
                                         KeyCode::Char(c @ '1'..='9') => {
                                             let display_idx = (c as usize) - ('1' as usize);
@@ -926,10 +1285,15 @@ where
                                         }
                                         KeyCode::Char('j') | KeyCode::Down => app.next_session(),
                                         KeyCode::Char('k') | KeyCode::Up => app.prev_session(),
-                                        KeyCode::Char('i') | KeyCode::Enter => {
-                                            if app.sessions.selected_session().is_some() {
-                                                app.enter_insert_mode();
-                                            }
+                                        KeyCode::Char('!') => app.jump_to_awaiting_session(),
+                                        KeyCode::Char('&') => app.open_output_filter(),
+                                        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.open_session_switcher(),
+                                        KeyCode::Char('p') => app.focus_plan(),
+                                        KeyCode::Char('b') if !key.modifiers.contains(KeyModifiers::CONTROL) => app.open_broadcast(),
+                                        KeyCode::Char('i') | KeyCode::Enter
+                                            if app.sessions.selected_session().is_some() =>
+                                        {
+                                            app.enter_insert_mode();
                                         }
                                         KeyCode::Char('n') => {
                                             // Open folder picker starting from configured directory
@@ -938,6 +1302,10 @@ where
                                             let entries = scan_folder_entries(&start).await;
                                             app.set_folder_entries(entries);
                                         }
+                                        KeyCode::Char('N') => {
+                                            // New session in the selected session's main repo
+                                            open_parent_repo_picker(app).await;
+                                        }
                                         KeyCode::Char('w') => {
                                             // Open worktree picker (existing worktrees or create new)
                                             // Don't fetch here - only fetch when opening cleanup view
@@ -945,6 +1313,14 @@ where
                                             let entries = scan_worktrees(&worktree_dir, false).await;
                                             app.open_worktree_picker(entries);
                                         }
+                                        KeyCode::Char('W') => {
+                                            // Duplicate into a new worktree (asks for the branch)
+                                            duplicate_into_worktree(app, &agent_tx, &mut agent_commands).await?;
+                                        }
+                                        KeyCode::Char('X') => {
+                                            // Try something out in a throwaway worktree
+                                            start_experiment(app, &agent_tx, &mut agent_commands).await?;
+                                        }
                                         KeyCode::Char('x') => {
                                             if let Some(session) = app.sessions.selected_session() {
                                                 let session_id = session.id.clone();
@@ -952,20 +1328,36 @@ where
                                             }
                                             app.kill_selected_session();
                                         }
-                                        KeyCode::Char('d') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                            // Duplicate current session (same folder, same agent)
-                                            if let Some(session) = app.sessions.selected_session() {
-                                                let agent_type = session.agent_type;
-                                                let cwd = session.cwd.clone();
-                                                let is_worktree = session.is_worktree;
-                                                spawn_agent_in_dir(app, &agent_tx, &mut agent_commands, agent_type, cwd, is_worktree).await?;
-                                            }
+                                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) && app.has_running_bash_command() => {
+                                            // Ctrl+C: interrupt running bash command
+                                            app.interrupt_bash_command();
                                         }
-                                        KeyCode::Char('c') => {
+                                        KeyCode::Char('c')
+                                            if !key.modifiers.contains(KeyModifiers::CONTROL)
+                                                && app.sessions.selected_session().is_some() =>
+                                        {
                                             // Clear session (with confirmation)
-                                            if app.sessions.selected_session().is_some() {
-                                                app.open_clear_confirm();
-                                            }
+                                            app.open_clear_confirm();
+                                        }
+                                        KeyCode::Char('C') => {
+                                            // Clear output only, keeping the agent and its context
+                                            app.clear_selected_output();
+                                        }
+                                        KeyCode::Char('Q') => {
+                                            // Drop prompts queued while the agent was busy
+                                            app.clear_queued_prompts();
+                                        }
+                                        KeyCode::Char('r') => {
+                                            // Restart the agent, keeping the output
+                                            restart_agent(app, &agent_tx, &mut agent_commands);
+                                        }
+                                        KeyCode::Char('R') => {
+                                            // Resend the last prompt (when idle)
+                                            retry_last_prompt(app, &agent_commands).await;
+                                        }
+                                        KeyCode::Char('e') => {
+                                            // Ask the agent about the error in view (when idle)
+                                            explain_error(app, &agent_commands).await;
                                         }
                                         KeyCode::Char('v') => {
                                             // Cycle through sort modes
@@ -975,28 +1367,86 @@ where
                                             // Toggle debug tool JSON display
                                             app.toggle_debug_tool_json();
                                         }
-
-                                        // Scroll output - vim style
-                                        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                            // Ctrl+u: half page up
-                                            let half_page = app.viewport_height / 2;
-                                            app.scroll_up(half_page);
+                                        KeyCode::Char('z') => {
+                                            // Collapse tool calls to one line
+                                            app.toggle_collapse_tools();
+                                        }
+                                        KeyCode::Char('T') => {
+                                            // Prefix output entries with their time
+                                            app.toggle_timestamps();
+                                        }
+                                        KeyCode::Char('P') => {
+                                            // Show tool paths relative to the session directory or in full
+                                            app.toggle_absolute_paths();
+                                        }
+                                        KeyCode::Char('Z') => {
+                                            // Hide the sidebar for a full-width conversation
+                                            app.toggle_zen_mode();
+                                        }
+                                        KeyCode::Char('a') => {
+                                            // Hide a finished session without killing it
+                                            app.toggle_archived();
+                                        }
+                                        KeyCode::Char('A') => {
+                                            // List archived sessions too
+                                            app.toggle_show_archived();
                                         }
-                                        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                            // Ctrl+d: half page down
-                                            let half_page = app.viewport_height / 2;
-                                            app.scroll_down(half_page);
+                                        KeyCode::Char('E') => {
+                                            // Dump the session's raw ACP messages to a file
+                                            app.export_acp_log();
                                         }
-                                        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                            // Ctrl+b: full page up (back)
-                                            app.scroll_up(app.viewport_height);
+                                        KeyCode::Char('[') => {
+                                            // Expand the previous tool call
+                                            app.focus_adjacent_tool(false);
                                         }
-                                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                            // Ctrl+f: full page down (forward)
-                                            app.scroll_down(app.viewport_height);
+                                        KeyCode::Char(']') => {
+                                            // Expand the next tool call
+                                            app.focus_adjacent_tool(true);
                                         }
-                                        KeyCode::PageUp => app.scroll_up(app.viewport_height),
-                                        KeyCode::PageDown => app.scroll_down(app.viewport_height),
+                                        KeyCode::Char('{') => {
+                                            // Scroll to the previous message you sent
+                                            app.focus_adjacent_user_input(false);
+                                        }
+                                        KeyCode::Char('}') => {
+                                            // Scroll to the next message you sent
+                                            app.focus_adjacent_user_input(true);
+                                        }
+                                        KeyCode::Char('D') => {
+                                            // Open side-by-side view of the nearest file diff
+                                            app.open_diff_view();
+                                        }
+                                        KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                            // Copy all of the session's file changes as a patch
+                                            app.copy_patch();
+                                        }
+                                        KeyCode::Char('y') => {
+                                            // Copy the nearest file path
+                                            app.copy_near_view(false);
+                                        }
+                                        KeyCode::Char('Y') => {
+                                            // Copy the nearest file diff
+                                            app.copy_near_view(true);
+                                        }
+                                        KeyCode::Char('S') => {
+                                            // MCP servers for new sessions
+                                            app.open_mcp_manager();
+                                        }
+                                        KeyCode::Char('L') => {
+                                            // Open the log viewer
+                                            app.open_log_view();
+                                        }
+                                        KeyCode::Char('J') => {
+                                            // Inspect the raw JSON of the tool call in view
+                                            if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                                handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                            }
+                                        }
+                                        KeyCode::Char('o') => {
+                                            // Open editor in the session directory
+                                            app.request_editor();
+                                        }
+
+                                        // Scroll output - vim style (paging is repeatable, above)
                                         KeyCode::Char('g') => app.scroll_to_top(),
                                         KeyCode::Char('G') => app.scroll_to_bottom(),
                                         _ => {}
@@ -1051,8 +1501,93 @@ where
                                     handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
                                 }
                             }
+                            InputMode::QuitConfirm => {
+                                match handle_quit_confirm_mode(key) {
+                                    Action::Quit => {
+                                        shutdown_agents(&mut agent_commands).await;
+                                        return Ok(());
+                                    }
+                                    action => {
+                                        if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                            handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                        }
+                                    }
+                                }
+                            }
+                            InputMode::SnippetSave => {
+                                let action = handle_snippet_save_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::SnippetPicker => {
+                                let action = handle_snippet_picker_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::SessionLimitConfirm => {
+                                let action = handle_session_limit_confirm_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::McpManager => {
+                                let action = handle_mcp_manager_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
                             InputMode::BugReport => {
-                                let action = handle_bug_report_mode(key);
+                                let action = handle_bug_report_mode(app, key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::PlanFocus => {
+                                let action = handle_plan_focus_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::Broadcast => {
+                                let action = handle_broadcast_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::SessionSwitcher => {
+                                let action = handle_session_switcher_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::ConfirmLargePrompt => {
+                                let action = handle_large_prompt_confirm_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::OutputFilter => {
+                                let action = handle_output_filter_mode(key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::DiffView => {
+                                let action = handle_diff_view_mode(app, key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::LogView => {
+                                let action = handle_log_view_mode(app, key);
+                                if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
+                                    handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
+                                }
+                            }
+                            InputMode::ToolJson => {
+                                let action = handle_tool_json_mode(app, key);
                                 if let Some(async_action) = process_action(app, action, &agent_commands, &app_event_tx).await {
                                     handle_async_in_loop(app, async_action, &agent_tx, &mut agent_commands, &app_event_tx).await?;
                                 }
@@ -1083,15 +1618,7 @@ where
                     while let Some(Some(Ok(event))) = event_stream.next().now_or_never() {
                         // Handle paste events
                         if let Event::Paste(text) = &event {
-                            if let Some(path) = clipboard::try_parse_image_path(text) {
-                                if let Some((filename, mime_type, data)) = clipboard::load_image_from_path(&path) {
-                                    app.add_attachment(ImageAttachment { filename, mime_type, data });
-                                } else {
-                                    for c in text.chars() { app.input_char(c); }
-                                }
-                            } else {
-                                for c in text.chars() { app.input_char(c); }
-                            }
+                            app.paste(clipboard::contents_from_text(text));
                             continue;
                         }
 
@@ -1114,15 +1641,38 @@ where
                         }
                     }
                 }
+
+                // Follow the `@` mention or `/` command at the cursor, scanning for files
+                // when `@` completion needs them
+                if let Some((cwd, dir)) = app.update_completion() {
+                    let tx = app_event_tx.clone();
+                    tokio::spawn(async move {
+                        let files = mention::scan_files(cwd.clone(), dir.clone()).await;
+                        let _ = tx.send(AppEvent::MentionFiles { cwd, dir, files }).await;
+                    });
+                }
             }
 
             // Agent events
             Some((session_id, event)) = agent_rx.recv() => {
+                let ends_turn = event.ends_turn();
                 let result = handle_agent_event(app, &session_id, event);
 
+                // Send the next queued prompt once the agent finished its turn; after
+                // an error or a disconnect the queue waits
+                if ends_turn
+                    && let Some(session) = app.sessions.get_by_id_mut(&session_id)
+                    && let Some(text) = session.take_queued_prompt()
+                {
+                    dispatch_prompt(session, &agent_commands, &text, Vec::new()).await;
+                }
+
                 // Process the result
                 match result {
                     EventResult::None => {}
+                    EventResult::Reconnect => {
+                        reconnect_agent(app, &agent_tx, &mut agent_commands, &session_id);
+                    }
                     EventResult::AutoAcceptPermission { request_id, option_id } => {
                         if let Some(cmd_tx) = agent_commands.get(&session_id) {
                             let _ = cmd_tx.send(AgentCommand::PermissionResponse {
@@ -1131,9 +1681,26 @@ where
                             }).await;
                         }
                     }
+                    EventResult::CancelPermission { request_id } => {
+                        if let Some(cmd_tx) = agent_commands.get(&session_id) {
+                            let _ = cmd_tx.send(AgentCommand::PermissionResponse {
+                                request_id,
+                                option_id: None,
+                            }).await;
+                        }
+                    }
                     EventResult::Notification(notification) => {
                         process_notification(&mut app.notifications, notification);
                     }
+                    EventResult::SetModel { model_id } => {
+                        if let Some(session) = app.sessions.get_by_id(&session_id)
+                            && let Some(cmd_tx) = agent_commands.get(&session_id) {
+                                let _ = cmd_tx.send(AgentCommand::SetModel {
+                                    session_id: session.acp_session_id.clone().unwrap_or_default(),
+                                    model_id,
+                                }).await;
+                            }
+                    }
                     EventResult::AutoAcceptWithNotification { request_id, option_id, notification } => {
                         if let Some(cmd_tx) = agent_commands.get(&session_id) {
                             let _ = cmd_tx.send(AgentCommand::PermissionResponse {
@@ -1174,15 +1741,19 @@ where
                         }
                     }
                     AppEvent::WorktreeDeletionFailed(path, error) => {
-                        log::log(&format!("Failed to delete worktree {}: {}", path.display(), error));
-                        // Mark entry as no longer deleting (so user can retry)
+                        log::error(&format!("Failed to delete worktree {}: {}", path.display(), error));
+                        // Show why next to the entry, so it can be fixed and retried
                         if let Some(cleanup) = &mut app.worktree_cleanup
                             && let Some(entry) = cleanup.entries.iter_mut().find(|e| e.path == path)
                         {
                             entry.is_deleting = false;
                             entry.selected = false;
+                            entry.error = Some(error);
                         }
                     }
+                    AppEvent::MentionFiles { cwd, dir, files } => {
+                        app.set_mention_files(&cwd, &dir, files);
+                    }
                     #[allow(unused_variables)]
                     AppEvent::BashCommandCompleted { session_id, command, output, success } => {
                         // Clear the running command tracker
@@ -1210,35 +1781,147 @@ where
                 }
             }
 
-            // Timeout to keep UI responsive and tick spinner (16ms = ~60 FPS)
-            _ = tokio::time::sleep(Duration::from_millis(16)) => {
+            // Timeout to tick the spinner (fast while agents work, slow when idle)
+            _ = tokio::time::sleep(app.tick_interval()) => {
                 app.tick_spinner();
 
+                // Output has paused; get the rest of it into the transcripts
+                app.flush_transcripts();
+
+                // Tail the log file while the log viewer is open
+                if let Some(log_view) = &mut app.log_view {
+                    log_view.refresh();
+                }
+
+                // Kill background sessions left idle past `idle_timeout_mins`
+                for session_id in app.expired_idle_sessions() {
+                    agent_commands.remove(&session_id);
+                    if let Some(session) = app.kill_session(&session_id) {
+                        log::log(&format!("Killed idle session {} ({})", session.name, session.cwd.display()));
+                    }
+                }
+
                 // Refresh git diff stats periodically (every 5 seconds)
                 if app.should_refresh_git_stats() {
                     app.mark_git_refreshed();
+                    refresh_selected_git_branch(app).await;
 
                     // Collect sessions to refresh
                     let sessions_to_refresh: Vec<_> = app.sessions.sessions()
                         .iter()
-                        .map(|s| (s.id.clone(), s.cwd.clone(), s.git_branch.clone()))
+                        .map(|s| (s.id.clone(), s.cwd.clone(), s.git_branch.clone(), s.git_origin.clone()))
                         .collect();
 
-                    // Refresh each session's diff stats
-                    for (session_id, cwd, branch) in sessions_to_refresh {
-                        if !branch.is_empty()
-                            && let Ok(stats) = git::get_diff_stats(&cwd, &branch).await
-                            && let Some(session) = app.sessions.get_by_id_mut(&session_id)
-                        {
-                            session.diff_stats = Some(stats);
-                        }
-                    }
+                    // Fetch in the background (once per repo); the next refresh picks up the counts
+                    if app.take_auto_fetch_due() {
+                        let mut fetched_repos = std::collections::HashSet::new();
+                        for (_, cwd, branch, origin) in &sessions_to_refresh {
+                            let repo = origin.clone().unwrap_or_else(|| cwd.display().to_string());
+                            if branch.is_empty() || !fetched_repos.insert(repo) {
+                                continue;
+                            }
+                            let cwd = cwd.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = git::fetch_origin(&cwd).await {
+                                    log::debug(&format!("Auto-fetch in {} failed: {}", cwd.display(), e));
+                                }
+                            });
+                        }
+                    }
+
+                    // Refresh each session's diff stats and ahead/behind counts
+                    for (session_id, cwd, branch, _) in sessions_to_refresh {
+                        if branch.is_empty() {
+                            continue;
+                        }
+                        let stats = git::get_diff_stats(&cwd, &branch).await;
+                        let ahead_behind = git::ahead_behind(&cwd).await;
+                        if let Some(session) = app.sessions.get_by_id_mut(&session_id) {
+                            if let Ok(stats) = stats {
+                                session.diff_stats = Some(stats);
+                            }
+                            session.ahead_behind = ahead_behind;
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+/// Cancel running prompts and kill every agent, giving up after
+/// `SHUTDOWN_TIMEOUT` so a hung agent can't block quitting
+async fn shutdown_agents(agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>) {
+    let shutdowns = agent_commands.drain().map(|(_, cmd_tx)| async move {
+        let (done, done_rx) = tokio::sync::oneshot::channel();
+        if cmd_tx.send(AgentCommand::Shutdown { done }).await.is_ok() {
+            let _ = done_rx.await;
+        }
+    });
+    let all_done = futures::future::join_all(shutdowns);
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, all_done)
+        .await
+        .is_err()
+    {
+        log::warn(&format!(
+            "Agents did not shut down within {:?}",
+            SHUTDOWN_TIMEOUT
+        ));
+    }
+}
+
+/// Suspend the TUI, run `editor` in `cwd` until it exits, then restore the terminal.
+/// It opens `location` when given, the directory otherwise.
+/// The event stream is recreated so it doesn't steal the editor's keystrokes.
+async fn run_editor<B: Backend>(
+    terminal: &mut Terminal<B>,
+    event_stream: &mut EventStream,
+    inline: bool,
+    editor: &str,
+    cwd: &std::path::Path,
+    location: Option<&FileLocation>,
+) -> Result<()>
+where
+    B::Error: Send + Sync + 'static,
+{
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or_default();
+    let mut args: Vec<String> = parts.map(String::from).collect();
+    match location {
+        Some(location) => args.extend(file_ref::editor_args(program, location)),
+        None => args.push(".".to_string()),
+    }
+
+    // Tear down the terminal like on exit, and stop reading input
+    drop(std::mem::replace(event_stream, EventStream::new()));
+    disable_raw_mode()?;
+    execute!(stdout(), DisableMouseCapture, DisableBracketedPaste)?;
+    if !inline {
+        execute!(stdout(), LeaveAlternateScreen)?;
+    }
+
+    let status = tokio::process::Command::new(program)
+        .args(&args)
+        .current_dir(cwd)
+        .status()
+        .await;
+
+    // Restore the terminal before reporting any editor error
+    enable_raw_mode()?;
+    if !inline {
+        execute!(stdout(), EnterAlternateScreen)?;
+    }
+    execute!(stdout(), EnableBracketedPaste, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    let status = status?;
+    if !status.success() {
+        log::warn(&format!("Editor '{}' exited with {}", editor, status));
+    }
+    Ok(())
+}
+
+/// Start a new session, or ask first when it would go past `max_sessions`
 async fn spawn_agent_in_dir(
     app: &mut App,
     agent_tx: &mpsc::Sender<(String, AgentEvent)>,
@@ -1247,32 +1930,238 @@ async fn spawn_agent_in_dir(
     cwd: std::path::PathBuf,
     is_worktree: bool,
 ) -> Result<()> {
-    let session_id = app.spawn_session(agent_type, cwd.clone(), is_worktree);
+    if app.session_limit_reached() {
+        log::warn(&format!(
+            "{} sessions running (max_sessions = {}), asking before starting another",
+            app.sessions.sessions().len(),
+            app.max_sessions.unwrap_or_default()
+        ));
+        app.open_session_limit_confirm(PendingSpawn {
+            agent_type,
+            cwd,
+            is_worktree,
+        });
+        return Ok(());
+    }
+    start_session_in_dir(app, agent_tx, agent_commands, agent_type, cwd, is_worktree).await
+}
+
+async fn start_session_in_dir(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+    agent_type: AgentType,
+    cwd: std::path::PathBuf,
+    is_worktree: bool,
+) -> Result<()> {
+    let session_id = app.spawn_session(agent_type.clone(), cwd.clone(), is_worktree);
 
     // Detect git branch and origin
     let branch = get_git_branch(&cwd).await;
     let origin = git::get_origin_url(&cwd).await;
 
-    // Fetch diff stats (comparing current branch to base branch)
-    let diff_stats = if !branch.is_empty() {
-        git::get_diff_stats(&cwd, &branch).await.ok()
+    // Fetch diff stats (comparing current branch to base branch) and upstream counts
+    let (diff_stats, ahead_behind) = if !branch.is_empty() {
+        (
+            git::get_diff_stats(&cwd, &branch).await.ok(),
+            git::ahead_behind(&cwd).await,
+        )
     } else {
-        None
+        (None, None)
     };
 
     if let Some(session) = app.sessions.get_by_id_mut(&session_id) {
         session.git_branch = branch;
         session.git_origin = origin;
         session.diff_stats = diff_stats;
+        session.ahead_behind = ahead_behind;
+    }
+
+    spawn_agent_with_resume(
+        app,
+        agent_tx,
+        agent_commands,
+        session_id,
+        agent_type,
+        cwd,
+        None,
+    );
+    Ok(())
+}
+
+/// Respawn the agent for a session whose process died, resuming its ACP session
+fn reconnect_agent(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+    session_id: &str,
+) {
+    let Some(session) = app.sessions.get_by_id(session_id) else {
+        return;
+    };
+    let Some(acp_session_id) = session.acp_session_id.clone() else {
+        return;
+    };
+    let agent_type = session.agent_type.clone();
+    let cwd = session.cwd.clone();
+    log::log(&format!(
+        "Reconnecting session {} (ACP session {})",
+        session_id, acp_session_id
+    ));
+    spawn_agent_with_resume(
+        app,
+        agent_tx,
+        agent_commands,
+        session_id.to_string(),
+        agent_type,
+        cwd,
+        Some(acp_session_id),
+    );
+}
+
+/// Replace the selected session's agent process with a fresh one, keeping its
+/// output. The ACP session is resumed when the agent created one.
+fn restart_agent(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+) {
+    let Some(session) = app.sessions.selected_session_mut() else {
+        return;
+    };
+    let session_id = session.id.clone();
+    let agent_type = session.agent_type.clone();
+    let cwd = session.cwd.clone();
+    let acp_session_id = session.acp_session_id.clone();
+    session.reset_for_respawn();
+    session.queued_prompts.clear();
+    session.add_output("— agent restarted —".to_string(), OutputType::SystemMessage);
+    session.scroll_to_bottom();
+    log::log(&format!(
+        "Restarting agent for session {} (resuming: {:?})",
+        session_id, acp_session_id
+    ));
+
+    // Dropping the command channel stops the old agent
+    agent_commands.remove(&session_id);
+    spawn_agent_with_resume(
+        app,
+        agent_tx,
+        agent_commands,
+        session_id,
+        agent_type,
+        cwd,
+        acp_session_id,
+    );
+}
+
+/// Run a post-create hook in `cwd`, streaming its combined stdout and stderr
+/// as `HookOutput` events. Returns why it failed, if it did.
+async fn run_post_create_hook(
+    hook: &str,
+    cwd: &std::path::Path,
+    event_tx: &mpsc::Sender<AgentEvent>,
+) -> Option<String> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("exec 2>&1\n{}", hook))
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return Some(e.to_string()),
+    };
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = event_tx.send(AgentEvent::HookOutput { line }).await;
+        }
+    }
+
+    match child.wait().await {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(status.to_string()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Start the agent process and command loop for an existing session.
+/// With `resume_session_id`, the ACP session is loaded instead of created.
+fn spawn_agent_with_resume(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+    session_id: String,
+    agent_type: AgentType,
+    cwd: std::path::PathBuf,
+    resume_session_id: Option<String>,
+) {
+    // A new conversation starts with the repo's (or global) preamble
+    if resume_session_id.is_none()
+        && let Some(session) = app.sessions.get_by_id_mut(&session_id)
+    {
+        session.pending_preamble = config::session_preamble(&cwd, app.preamble.as_deref());
     }
 
+    // A worktree created just now gets its post-create hook before the agent starts
+    let hook = match app.new_worktree.take() {
+        Some(worktree) if resume_session_id.is_none() && worktree.path == cwd => {
+            let trusted = app.trusted_dirs.mode_for(&worktree.repo).is_some()
+                || app.trusted_dirs.mode_for(&cwd).is_some();
+            config::post_create_hook(&cwd, app.post_create_hook.as_deref(), trusted)
+        }
+        worktree => {
+            app.new_worktree = worktree;
+            None
+        }
+    };
+    let hook = match hook {
+        Some(config::PostCreateHook::Run(hook)) => {
+            if let Some(session) = app.sessions.get_by_id_mut(&session_id) {
+                session.add_output(
+                    format!("Running post-create hook: {}", hook),
+                    OutputType::SystemMessage,
+                );
+            }
+            Some(hook)
+        }
+        Some(config::PostCreateHook::Untrusted(hook)) => {
+            log::warn(&format!(
+                "Not running post-create hook {:?} from the .amux.toml of untrusted {}",
+                hook,
+                cwd.display()
+            ));
+            if let Some(session) = app.sessions.get_by_id_mut(&session_id) {
+                session.add_output(
+                    format!(
+                        "Skipped this repo's post-create hook ({}): add the repo to trusted_dirs to run it",
+                        hook
+                    ),
+                    OutputType::SystemMessage,
+                );
+            }
+            None
+        }
+        None => None,
+    };
+
     // Convert MCP servers from config format to protocol format
-    let mcp_servers: Vec<acp::McpServer> =
-        app.mcp_servers.iter().map(acp::McpServer::from).collect();
+    let mcp_servers: Vec<acp::McpServer> = app
+        .mcp_servers
+        .enabled()
+        .map(acp::McpServer::from)
+        .collect();
 
     // Channel for commands to this agent
     let (cmd_tx, mut cmd_rx) = mpsc::channel::<AgentCommand>(32);
-    agent_commands.insert(session_id.clone(), cmd_tx.clone());
+    let agent_alive = cmd_tx.downgrade();
+    agent_commands.insert(session_id.clone(), cmd_tx);
 
     // Event channel for this agent
     let (event_tx, mut event_rx) = mpsc::channel::<AgentEvent>(32);
@@ -1282,6 +2171,11 @@ async fn spawn_agent_in_dir(
     let session_id_for_events = session_id.clone();
     tokio::spawn(async move {
         while let Some(event) = event_rx.recv().await {
+            // Once the session drops this agent's command channel (killed, or
+            // restarted with a new agent) its last events must not reach the session
+            if agent_alive.upgrade().is_none() {
+                break;
+            }
             if main_tx
                 .send((session_id_for_events.clone(), event))
                 .await
@@ -1292,10 +2186,31 @@ async fn spawn_agent_in_dir(
         }
     });
 
+    // Record this session's raw messages, across agent restarts
+    let recorder = app
+        .sessions
+        .get_by_id(&session_id)
+        .map(|session| session.acp_messages.clone())
+        .unwrap_or_default();
+
     // Spawn the agent task
     let cwd_clone = cwd.clone();
+    let client_options = app.client_options.clone();
     tokio::spawn(async move {
-        match AgentConnection::spawn(agent_type, &cwd_clone, event_tx.clone()).await {
+        if let Some(hook) = hook {
+            let error = run_post_create_hook(&hook, &cwd_clone, &event_tx).await;
+            let _ = event_tx.send(AgentEvent::HookFinished { error }).await;
+        }
+
+        match AgentConnection::spawn(
+            agent_type,
+            &cwd_clone,
+            event_tx.clone(),
+            recorder,
+            client_options,
+        )
+        .await
+        {
             Ok(mut conn) => {
                 // Initialize
                 if let Err(e) = conn.initialize().await {
@@ -1307,11 +2222,16 @@ async fn spawn_agent_in_dir(
                     return;
                 }
 
-                // Create session with MCP servers
-                if let Err(e) = conn
-                    .new_session(cwd_clone.to_str().unwrap_or("."), mcp_servers)
-                    .await
-                {
+                // Create (or resume) session with MCP servers
+                let cwd_str = cwd_clone.to_str().unwrap_or(".");
+                let result = match &resume_session_id {
+                    Some(acp_session_id) => {
+                        conn.load_session(acp_session_id, cwd_str, mcp_servers)
+                            .await
+                    }
+                    None => conn.new_session(cwd_str, mcp_servers).await,
+                };
+                if let Err(e) = result {
                     let _ = event_tx
                         .send(AgentEvent::Error {
                             message: format!("Session failed: {}", e),
@@ -1378,6 +2298,18 @@ async fn spawn_agent_in_dir(
                                     .await;
                             }
                         }
+                        AgentCommand::SetMode {
+                            session_id,
+                            mode_id,
+                        } => {
+                            if let Err(e) = conn.set_mode(&session_id, &mode_id).await {
+                                let _ = event_tx
+                                    .send(AgentEvent::Error {
+                                        message: format!("Set mode failed: {}", e),
+                                    })
+                                    .await;
+                            }
+                        }
                         AgentCommand::CancelPrompt => {
                             if let Err(e) = conn.cancel_prompt().await {
                                 let _ = event_tx
@@ -1387,8 +2319,17 @@ async fn spawn_agent_in_dir(
                                     .await;
                             }
                         }
+                        AgentCommand::Shutdown { done } => {
+                            let _ = conn.cancel_prompt().await;
+                            let _ = conn.kill().await;
+                            let _ = done.send(());
+                            return;
+                        }
                     }
                 }
+
+                // Command channel closed: the session was killed or replaced by the user
+                let _ = conn.kill().await;
             }
             Err(e) => {
                 let _ = event_tx
@@ -1399,8 +2340,6 @@ async fn spawn_agent_in_dir(
             }
         }
     });
-
-    Ok(())
 }
 
 /// Process an action and apply it to the app state.
@@ -1419,6 +2358,20 @@ async fn process_action(
         Quit => {
             // Will be handled by main loop
         }
+        CloseQuitConfirm => {
+            app.close_quit_confirm();
+        }
+        ConfirmSessionLimit => {
+            if let Some(spawn) = app.close_session_limit_confirm() {
+                return Some(AsyncAction::StartSession(spawn));
+            }
+        }
+        CancelSessionLimit => {
+            app.close_session_limit_confirm();
+        }
+        RepeatLast => {
+            // Resolved to the repeated action before dispatch
+        }
 
         // === Mode switching ===
         EnterInsertMode => {
@@ -1438,6 +2391,12 @@ async fn process_action(
         CloseHelp => {
             app.close_help();
         }
+        HelpFilterChar(c) => {
+            app.help_filter.push(c);
+        }
+        HelpFilterBackspace => {
+            app.help_filter.pop();
+        }
 
         // === Session navigation ===
         NextSession => {
@@ -1449,6 +2408,12 @@ async fn process_action(
         SelectSession(idx) => {
             app.select_session(idx);
         }
+        JumpToAwaitingSession => {
+            app.jump_to_awaiting_session();
+        }
+        SelectPreviousSession => {
+            app.select_previous_session();
+        }
 
         // === Input handling ===
         InputChar(c) => {
@@ -1513,12 +2478,42 @@ async fn process_action(
         InputNewline => {
             app.input_char('\n');
         }
+        AcceptCompletion => {
+            app.accept_completion();
+        }
+        CompletionNext => {
+            app.completion_next();
+        }
+        CompletionPrev => {
+            app.completion_prev();
+        }
+        DismissCompletion => {
+            app.dismiss_completion();
+        }
         ClearInput => {
             app.take_input();
             app.clear_attachments();
         }
+        InterruptBashCommand => {
+            app.interrupt_bash_command();
+        }
         SubmitPrompt => {
-            return Some(AsyncAction::SubmitPrompt);
+            // Large prompts wait for confirmation (bash commands run locally)
+            if !app.is_bash_mode() && app.needs_large_prompt_confirm() {
+                app.open_large_prompt_confirm();
+            } else if app.can_submit_prompt() {
+                // Prompts with images the agent can't take aren't sent (the
+                // attachment bar says why)
+                return Some(AsyncAction::SubmitPrompt);
+            }
+        }
+        ConfirmLargePrompt => {
+            if app.confirm_large_prompt() {
+                return Some(AsyncAction::SubmitPrompt);
+            }
+        }
+        CancelLargePrompt => {
+            app.close_large_prompt_confirm();
         }
 
         // === Scrolling ===
@@ -1531,6 +2526,9 @@ async fn process_action(
         ScrollToTop => {
             app.scroll_to_top();
         }
+        ScrollTo(offset) => {
+            app.scroll_to(offset);
+        }
         ScrollToBottom => {
             app.scroll_to_bottom();
         }
@@ -1602,6 +2600,14 @@ async fn process_action(
                 perm.select_next();
             }
         }
+        HoverPermissionOption(idx) => {
+            if let Some(session) = app.sessions.selected_session_mut()
+                && let Some(perm) = &mut session.pending_permission
+                && idx < perm.options.len()
+            {
+                perm.selected = idx;
+            }
+        }
         SelectPermissionOption(idx) => {
             if let Some(session) = app.sessions.selected_session_mut()
                 && let Some(perm) = &mut session.pending_permission
@@ -1656,8 +2662,7 @@ async fn process_action(
                 if let Some(cmd_tx) = agent_commands.get(&session_id) {
                     let _ = cmd_tx.send(AgentCommand::CancelPrompt).await;
                 }
-                session.state = SessionState::Idle;
-                session.add_output("Cancelled".to_string(), OutputType::SystemMessage);
+                session.cancel_prompt();
             }
         }
 
@@ -1804,6 +2809,9 @@ async fn process_action(
                 }
             }
         }
+        CycleMode => {
+            cycle_mode(app, agent_commands).await;
+        }
         SetModel {
             session_id,
             model_id,
@@ -1828,6 +2836,9 @@ async fn process_action(
         SelectAttachments => {
             app.select_attachments();
         }
+        EditQueuedPrompt => {
+            app.edit_last_queued_prompt();
+        }
         DeselectAttachments => {
             app.deselect_attachments();
         }
@@ -1851,6 +2862,175 @@ async fn process_action(
             app.toggle_debug_tool_json();
         }
 
+        // === Editor ===
+        OpenEditor => {
+            app.request_editor();
+        }
+        OpenEditorAt(location) => {
+            app.request_editor_at(location);
+        }
+
+        // === Tool calls ===
+        ToggleCollapseTools => {
+            app.toggle_collapse_tools();
+        }
+        ToggleTimestamps => {
+            app.toggle_timestamps();
+        }
+        ToggleAbsolutePaths => {
+            app.toggle_absolute_paths();
+        }
+        ToggleZenMode => {
+            app.toggle_zen_mode();
+        }
+        ToggleArchived => {
+            app.toggle_archived();
+        }
+        ToggleShowArchived => {
+            app.toggle_show_archived();
+        }
+        ExportAcpLog => {
+            app.export_acp_log();
+        }
+        PrevToolCall => {
+            app.focus_adjacent_tool(false);
+        }
+        NextToolCall => {
+            app.focus_adjacent_tool(true);
+        }
+        PrevUserMessage => {
+            app.focus_adjacent_user_input(false);
+        }
+        NextUserMessage => {
+            app.focus_adjacent_user_input(true);
+        }
+
+        // === Diff view ===
+        OpenDiffView => {
+            app.open_diff_view();
+        }
+        CopyPath => {
+            app.copy_near_view(false);
+        }
+        CopyDiff => {
+            app.copy_near_view(true);
+        }
+        CopyPatch => {
+            app.copy_patch();
+        }
+        CloseDiffView => {
+            app.close_diff_view();
+        }
+        DiffViewScrollUp(n) => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_up(n);
+            }
+        }
+        DiffViewScrollDown(n) => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_down(n);
+            }
+        }
+        DiffViewScrollLeft => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_left();
+            }
+        }
+        DiffViewScrollRight => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_right();
+            }
+        }
+        DiffViewScrollToTop => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_to_top();
+            }
+        }
+        DiffViewScrollToBottom => {
+            if let Some(diff_view) = &mut app.diff_view {
+                diff_view.scroll_to_bottom();
+            }
+        }
+
+        // === MCP manager ===
+        OpenMcpManager => {
+            app.open_mcp_manager();
+        }
+        CloseMcpManager => {
+            app.close_mcp_manager();
+        }
+        McpManagerDown => {
+            app.mcp_servers.select_next();
+        }
+        McpManagerUp => {
+            app.mcp_servers.select_prev();
+        }
+        McpManagerToggle => {
+            app.mcp_servers.toggle_selected();
+        }
+
+        // === Log view ===
+        OpenLogView => {
+            app.open_log_view();
+        }
+        CloseLogView => {
+            app.close_log_view();
+        }
+        LogViewScrollUp(n) => {
+            if let Some(log_view) = &mut app.log_view {
+                log_view.scroll_up(n);
+            }
+        }
+        LogViewScrollDown(n) => {
+            if let Some(log_view) = &mut app.log_view {
+                log_view.scroll_down(n);
+            }
+        }
+        LogViewScrollToTop => {
+            if let Some(log_view) = &mut app.log_view {
+                log_view.scroll_to_top();
+            }
+        }
+        LogViewScrollToBottom => {
+            if let Some(log_view) = &mut app.log_view {
+                log_view.scroll_to_bottom();
+            }
+        }
+        OpenToolJson => {
+            if !app.open_tool_json()
+                && let Some(session) = app.sessions.selected_session_mut()
+            {
+                session.add_output(
+                    "No tool call to inspect".to_string(),
+                    OutputType::SystemMessage,
+                );
+                session.scroll_to_bottom();
+            }
+        }
+        CloseToolJson => {
+            app.close_tool_json();
+        }
+        ToolJsonScrollUp(n) => {
+            if let Some(tool_json) = &mut app.tool_json {
+                tool_json.scroll_up(n);
+            }
+        }
+        ToolJsonScrollDown(n) => {
+            if let Some(tool_json) = &mut app.tool_json {
+                tool_json.scroll_down(n);
+            }
+        }
+        ToolJsonScrollToTop => {
+            if let Some(tool_json) = &mut app.tool_json {
+                tool_json.scroll_to_top();
+            }
+        }
+        ToolJsonScrollToBottom => {
+            if let Some(tool_json) = &mut app.tool_json {
+                tool_json.scroll_to_bottom();
+            }
+        }
+
         // === Folder picker ===
         OpenFolderPicker(path) => {
             return Some(AsyncAction::OpenFolderPicker(path));
@@ -1868,6 +3048,12 @@ async fn process_action(
                 picker.select_prev();
             }
         }
+        PickerPageDown { half } => {
+            app.picker_page(true, half);
+        }
+        PickerPageUp { half } => {
+            app.picker_page(false, half);
+        }
         FolderPickerEnterDir => {
             if app.folder_picker_enter_dir() {
                 return Some(AsyncAction::RefreshFolderPicker);
@@ -1927,11 +3113,13 @@ async fn process_action(
         WorktreePickerDown => {
             if let Some(picker) = &mut app.worktree_picker {
                 picker.select_next();
+                picker.confirm_dirty = false;
             }
         }
         WorktreePickerUp => {
             if let Some(picker) = &mut app.worktree_picker {
                 picker.select_prev();
+                picker.confirm_dirty = false;
             }
         }
         WorktreePickerSelect => {
@@ -1945,6 +3133,9 @@ async fn process_action(
         OpenAgentPicker { cwd, is_worktree } => {
             return Some(AsyncAction::OpenAgentPicker { cwd, is_worktree });
         }
+        OpenParentRepoPicker => {
+            return Some(AsyncAction::OpenParentRepoPicker);
+        }
         CloseAgentPicker => {
             app.close_agent_picker();
         }
@@ -2019,11 +3210,23 @@ async fn process_action(
         CloseBranchInput => {
             app.close_branch_input();
         }
+        SubmitBranchInput if app.branch_input.as_ref().is_some_and(|b| b.picking_base) => {
+            if let Some(branch_input) = &mut app.branch_input {
+                branch_input.choose_base();
+            }
+        }
         SubmitBranchInput => {
-            return Some(AsyncAction::SubmitBranchInput);
+            // Refuse invalid names up front instead of surfacing git's error
+            if let Some(branch_input) = &mut app.branch_input
+                && branch_input.validate()
+            {
+                return Some(AsyncAction::SubmitBranchInput);
+            }
         }
         BranchInputAcceptAutocomplete => {
-            if let Some(branch_input) = &mut app.branch_input {
+            if let Some(branch_input) = &mut app.branch_input
+                && !branch_input.accept_suggestion()
+            {
                 branch_input.accept_selection();
             }
         }
@@ -2041,6 +3244,7 @@ async fn process_action(
             if let Some(branch_input) = &mut app.branch_input {
                 branch_input.input.insert(branch_input.cursor_position, c);
                 branch_input.cursor_position += 1;
+                branch_input.clear_error();
                 branch_input.update_filter();
                 branch_input.show_autocomplete = true;
             }
@@ -2051,6 +3255,7 @@ async fn process_action(
             {
                 branch_input.cursor_position -= 1;
                 branch_input.input.remove(branch_input.cursor_position);
+                branch_input.clear_error();
                 branch_input.update_filter();
                 branch_input.show_autocomplete = true;
             }
@@ -2104,9 +3309,22 @@ async fn process_action(
                 cleanup.toggle_delete_branches();
             }
         }
+        WorktreeCleanupToggleForce => {
+            if let Some(cleanup) = &mut app.worktree_cleanup {
+                cleanup.toggle_force();
+            }
+        }
         WorktreeCleanupExecute => {
             return Some(AsyncAction::WorktreeCleanupExecute);
         }
+        WorktreeCleanupRetryFailed => {
+            if let Some(cleanup) = &mut app.worktree_cleanup
+                && cleanup.failed_count() > 0
+            {
+                cleanup.select_failed();
+                return Some(AsyncAction::WorktreeCleanupExecute);
+            }
+        }
 
         // === Session management ===
         SpawnAgent {
@@ -2123,6 +3341,15 @@ async fn process_action(
         DuplicateSession => {
             return Some(AsyncAction::DuplicateSession);
         }
+        DuplicateIntoWorktree => {
+            return Some(AsyncAction::DuplicateIntoWorktree);
+        }
+        StartExperiment => {
+            return Some(AsyncAction::StartExperiment);
+        }
+        RestartAgent => {
+            return Some(AsyncAction::RestartAgent);
+        }
         ClearSession => {
             return Some(AsyncAction::ClearSession);
         }
@@ -2134,24 +3361,192 @@ async fn process_action(
         CloseClearConfirm => {
             app.close_clear_confirm();
         }
-        KillSession => {
-            return Some(AsyncAction::KillSession);
+        ClearOutput => {
+            app.clear_selected_output();
         }
-
-        // === Bug report ===
-        OpenBugReport => {
-            app.open_bug_report();
+        ClearQueuedPrompts => {
+            app.clear_queued_prompts();
         }
-        CloseBugReport => {
-            app.close_bug_report();
+        RetryLastPrompt => {
+            return Some(AsyncAction::RetryLastPrompt);
         }
-        SubmitBugReport => {
-            return Some(AsyncAction::SubmitBugReport);
+        ExplainError => {
+            return Some(AsyncAction::ExplainError);
         }
-        BugReportInputChar(c) => {
-            if let Some(bug_report) = &mut app.bug_report {
-                bug_report.input_char(c);
-            }
+        SendFollowUp(index) => {
+            return Some(AsyncAction::SendFollowUp(index));
+        }
+
+        // === Plan panel ===
+        FocusPlan => {
+            app.focus_plan();
+        }
+        UnfocusPlan => {
+            app.unfocus_plan();
+        }
+        PlanNext => {
+            app.plan_next();
+        }
+        PlanPrev => {
+            app.plan_prev();
+        }
+        JumpToPlanEntry => {
+            app.jump_to_plan_entry();
+        }
+
+        // === Output filter ===
+        OpenOutputFilter => {
+            app.open_output_filter();
+        }
+        OutputFilterChar(c) => {
+            app.output_filter_char(c);
+        }
+        OutputFilterBackspace => {
+            app.output_filter_backspace();
+        }
+        ConfirmOutputFilter => {
+            app.confirm_output_filter();
+        }
+        ClearOutputFilter => {
+            app.clear_output_filter();
+        }
+
+        // === Broadcast ===
+        OpenBroadcast => {
+            app.open_broadcast();
+        }
+        CloseBroadcast => {
+            app.close_broadcast();
+        }
+        BroadcastChar(c) => {
+            app.broadcast_char(c);
+        }
+        BroadcastBackspace => {
+            app.broadcast_backspace();
+        }
+        SubmitBroadcast => {
+            // Once the results are showing, Enter just closes the popup
+            if app.broadcast.as_ref().is_some_and(|b| b.results.is_some()) {
+                app.close_broadcast();
+            } else {
+                return Some(AsyncAction::SubmitBroadcast);
+            }
+        }
+
+        // === Snippets ===
+        OpenSnippetSave => {
+            app.open_snippet_save();
+        }
+        CloseSnippetSave => {
+            app.close_snippet_save();
+        }
+        SnippetNameChar(c) => {
+            app.snippet_name.push(c);
+        }
+        SnippetNameBackspace => {
+            app.snippet_name.pop();
+        }
+        SaveSnippet => {
+            app.save_snippet();
+        }
+        OpenSnippetPicker => {
+            app.open_snippet_picker();
+        }
+        CloseSnippetPicker => {
+            app.close_snippet_picker();
+        }
+        SnippetPickerDown => {
+            if let Some(picker) = &mut app.snippet_picker {
+                picker.select_next();
+            }
+        }
+        SnippetPickerUp => {
+            if let Some(picker) = &mut app.snippet_picker {
+                picker.select_prev();
+            }
+        }
+        SnippetPickerInputChar(c) => {
+            if let Some(picker) = &mut app.snippet_picker {
+                picker.query_input_char(c);
+            }
+        }
+        SnippetPickerInputBackspace => {
+            if let Some(picker) = &mut app.snippet_picker {
+                picker.query_backspace();
+            }
+        }
+        InsertSnippet => {
+            app.insert_snippet();
+        }
+        DeleteSnippet => {
+            app.delete_snippet();
+        }
+
+        // === Session switcher ===
+        OpenSessionSwitcher => {
+            app.open_session_switcher();
+        }
+        CloseSessionSwitcher => {
+            app.close_session_switcher();
+        }
+        SessionSwitcherDown => {
+            if let Some(switcher) = &mut app.session_switcher {
+                switcher.select_next();
+            }
+        }
+        SessionSwitcherUp => {
+            if let Some(switcher) = &mut app.session_switcher {
+                switcher.select_prev();
+            }
+        }
+        SessionSwitcherSelect => {
+            app.session_switcher_select();
+        }
+        SessionSwitcherInputChar(c) => {
+            if let Some(switcher) = &mut app.session_switcher {
+                switcher.query_input_char(c);
+            }
+        }
+        SessionSwitcherInputBackspace => {
+            if let Some(switcher) = &mut app.session_switcher {
+                switcher.query_backspace();
+            }
+        }
+        KillSession => {
+            return Some(AsyncAction::KillSession);
+        }
+
+        // === Bug report ===
+        OpenBugReport => {
+            app.open_bug_report();
+        }
+        CloseBugReport => {
+            app.close_bug_report();
+        }
+        PreviewBugReport => {
+            // Include recent agent stderr and protocol traffic to help diagnose crashes
+            let stderr_tail: Vec<String> = app
+                .selected_session()
+                .map(|s| s.stderr_tail.iter().cloned().collect())
+                .unwrap_or_default();
+            if let Some(bug_report) = &mut app.bug_report {
+                let stderr_tail: Vec<&str> = stderr_tail.iter().map(String::as_str).collect();
+                bug_report.preview =
+                    Some(bug_report.issue_body(&stderr_tail, &log::recent_protocol_messages()));
+            }
+        }
+        EditBugReport => {
+            if let Some(bug_report) = &mut app.bug_report {
+                bug_report.preview = Option::None;
+            }
+        }
+        SubmitBugReport => {
+            return Some(AsyncAction::SubmitBugReport);
+        }
+        BugReportInputChar(c) => {
+            if let Some(bug_report) = &mut app.bug_report {
+                bug_report.input_char(c);
+            }
         }
         BugReportInputBackspace => {
             if let Some(bug_report) = &mut app.bug_report {
@@ -2193,6 +3588,10 @@ async fn process_action(
 /// Async actions that need special handling outside the main action processor.
 enum AsyncAction {
     SubmitPrompt,
+    RetryLastPrompt,
+    ExplainError,
+    SendFollowUp(usize),
+    SubmitBroadcast,
     PasteClipboard,
     OpenFolderPicker(PathBuf),
     RefreshFolderPicker,
@@ -2200,6 +3599,7 @@ enum AsyncAction {
     OpenWorktreePicker,
     WorktreePickerSelect,
     WorktreePickerCleanup,
+    OpenParentRepoPicker,
     OpenAgentPicker {
         cwd: PathBuf,
         is_worktree: bool,
@@ -2214,9 +3614,23 @@ enum AsyncAction {
         is_worktree: bool,
     },
     DuplicateSession,
+    DuplicateIntoWorktree,
+    StartExperiment,
+    RestartAgent,
     ClearSession,
     KillSession,
     SubmitBugReport,
+    /// Start a session without checking `max_sessions` (already confirmed)
+    StartSession(PendingSpawn),
+}
+
+/// Kill every process in the given process group (SIGKILL)
+fn kill_process_group(pgid: u32) {
+    // SAFETY: killpg has no memory-safety preconditions; errors (e.g. group
+    // already gone) are reported through the return value, which we ignore.
+    unsafe {
+        libc::killpg(pgid as libc::pid_t, libc::SIGKILL);
+    }
 }
 
 /// Handle async actions in the main event loop.
@@ -2247,17 +3661,40 @@ async fn handle_async_in_loop(
                     }
 
                     // Start tracking the command
-                    app.start_bash_command(command.clone());
+                    let (interrupt_tx, interrupt_rx) = tokio::sync::oneshot::channel();
+                    app.start_bash_command(session_id.clone(), command.clone(), interrupt_tx);
 
                     // Execute asynchronously
                     let tx = app_event_tx.clone();
                     tokio::spawn(async move {
-                        let output = tokio::process::Command::new("sh")
+                        // Run in its own process group so Ctrl+C can kill the whole pipeline
+                        let child = tokio::process::Command::new("sh")
                             .arg("-c")
                             .arg(&command)
                             .current_dir(&cwd)
-                            .output()
-                            .await;
+                            .stdin(std::process::Stdio::null())
+                            .stdout(std::process::Stdio::piped())
+                            .stderr(std::process::Stdio::piped())
+                            .process_group(0)
+                            .kill_on_drop(true)
+                            .spawn();
+
+                        let output = match child {
+                            Ok(child) => {
+                                let pid = child.id();
+                                tokio::select! {
+                                    output = child.wait_with_output() => output,
+                                    Ok(()) = interrupt_rx => {
+                                        // Interrupted: the app already reported it, just kill
+                                        if let Some(pid) = pid {
+                                            kill_process_group(pid);
+                                        }
+                                        return;
+                                    }
+                                }
+                            }
+                            Err(e) => Err(e),
+                        };
 
                         let (output_text, success) = match output {
                             Ok(out) => {
@@ -2286,45 +3723,64 @@ async fn handle_async_in_loop(
                     });
                 }
             } else if !text.is_empty() || app.has_attachments() {
-                send_prompt(app, agent_commands, &text).await;
+                // While the agent is busy, line text prompts up for when it's done.
+                // Queued prompts are text only, so prompts with images still go out now.
+                let busy = app
+                    .selected_session()
+                    .is_some_and(|s| s.state != SessionState::Idle);
+                if busy && !app.has_attachments() {
+                    if let Some(session) = app.sessions.selected_session_mut() {
+                        session.queued_prompts.push_back(text);
+                        session.scroll_to_bottom();
+                    }
+                } else {
+                    send_prompt(app, agent_commands, &text).await;
+                }
             }
             app.exit_insert_mode();
         }
-        AsyncAction::PasteClipboard => {
-            // Ctrl+V: paste from clipboard
-            match clipboard::read_clipboard() {
-                Ok(ClipboardContent::Image { data, mime_type }) => {
-                    app.add_attachment(ImageAttachment {
-                        filename: "clipboard".to_string(),
-                        mime_type,
-                        data,
-                    });
-                }
-                Ok(ClipboardContent::Text(text)) => {
-                    // Check if it's a path to an image file
-                    if let Some(path) = clipboard::try_parse_image_path(&text) {
-                        if let Some((filename, mime_type, data)) =
-                            clipboard::load_image_from_path(&path)
-                        {
-                            app.add_attachment(ImageAttachment {
-                                filename,
-                                mime_type,
-                                data,
-                            });
-                        } else {
-                            // Not a valid image, paste as text
-                            for c in text.chars() {
-                                app.input_char(c);
-                            }
-                        }
-                    } else {
-                        // Regular text, paste it
-                        for c in text.chars() {
-                            app.input_char(c);
+        AsyncAction::RetryLastPrompt => {
+            retry_last_prompt(app, agent_commands).await;
+        }
+        AsyncAction::ExplainError => {
+            explain_error(app, agent_commands).await;
+        }
+        AsyncAction::SendFollowUp(index) => {
+            send_follow_up(app, agent_commands, index).await;
+        }
+        AsyncAction::SubmitBroadcast => {
+            let text = app
+                .broadcast
+                .as_ref()
+                .map(|b| b.input.trim().to_string())
+                .unwrap_or_default();
+            if !text.is_empty() {
+                let deliveries: Vec<_> = app
+                    .sessions
+                    .sessions()
+                    .iter()
+                    .map(|s| app.broadcast_delivery(s))
+                    .collect();
+                let mut results = Vec::new();
+                for (session, delivery) in app.sessions.sessions_mut().iter_mut().zip(deliveries) {
+                    match delivery {
+                        BroadcastDelivery::Send => {
+                            dispatch_prompt(session, agent_commands, &text, Vec::new()).await;
                         }
+                        BroadcastDelivery::Queue => session.queued_prompts.push_back(text.clone()),
+                        BroadcastDelivery::Skip => {}
                     }
+                    results.push((session.name.clone(), delivery));
+                }
+                if let Some(broadcast) = &mut app.broadcast {
+                    broadcast.results = Some(results);
                 }
-                Ok(ClipboardContent::None) | Err(_) => {}
+            }
+        }
+        AsyncAction::PasteClipboard => {
+            // Ctrl+V: paste from clipboard, attaching every image it holds
+            if let Ok(contents) = clipboard::read_clipboard() {
+                app.paste(contents);
             }
         }
         AsyncAction::OpenFolderPicker(path) => {
@@ -2350,10 +3806,15 @@ async fn handle_async_in_loop(
                         let entries = scan_folder_entries(&picker.current_dir).await;
                         app.set_folder_entries(entries);
                     }
+                } else if app.input_mode == InputMode::WorktreeFolderPicker {
+                    // New worktree: pick the base branch, then name the new one
+                    let path = entry.path.clone();
+                    app.close_folder_picker();
+                    open_worktree_branch_input(app, &path).await;
                 } else {
                     let path = entry.path.clone();
                     app.close_folder_picker();
-                    let agents = check_all_agents();
+                    let agents = check_all_agents(&app.custom_agents);
                     app.open_agent_picker(path, false, agents);
                 }
             }
@@ -2375,10 +3836,22 @@ async fn handle_async_in_loop(
                     let entries = scan_folder_entries(&start).await;
                     app.set_folder_entries(entries);
                 } else {
-                    // Open existing worktree
+                    // Open existing worktree, warning first if it has uncommitted
+                    // changes (re-checked, since the scan may be stale)
                     let path = entry.path.clone();
+                    let is_clean = git::is_worktree_clean(&path).await.unwrap_or(true);
+                    if let Some(picker) = &mut app.worktree_picker
+                        && !is_clean
+                        && !picker.confirm_dirty
+                    {
+                        if let Some(entry) = picker.entries.get_mut(picker.selected) {
+                            entry.is_clean = false;
+                        }
+                        picker.confirm_dirty = true;
+                        return Ok(());
+                    }
                     app.close_worktree_picker();
-                    let agents = check_all_agents();
+                    let agents = check_all_agents(&app.custom_agents);
                     app.open_agent_picker(path, true, agents);
                 }
             }
@@ -2387,27 +3860,35 @@ async fn handle_async_in_loop(
             let worktree_dir = app.worktree_config.worktree_dir.clone();
             app.close_worktree_picker();
             let worktree_entries = scan_worktrees(&worktree_dir, true).await;
-            let entries: Vec<CleanupEntry> = worktree_entries
-                .iter()
-                .filter(|e| !e.is_create_new)
-                .map(|e| {
-                    let branch = e.name.split_once('-').map(|(_, b)| b.to_string());
-                    CleanupEntry {
-                        path: e.path.clone(),
-                        branch,
-                        is_clean: e.is_clean,
-                        is_merged: e.is_merged,
-                        selected: false,
-                        is_deleting: false,
-                    }
-                })
-                .collect();
+            let mut entries = vec![];
+            for e in worktree_entries.iter().filter(|e| !e.is_create_new) {
+                let branch = e.name.split_once('-').map(|(_, b)| b.to_string());
+                // Experiments are recognized by the branch actually checked out
+                let experiment_branch = git::current_branch(&e.path)
+                    .await
+                    .filter(|branch| git::is_experiment_branch(branch));
+                entries.push(CleanupEntry {
+                    path: e.path.clone(),
+                    is_experiment: experiment_branch.is_some(),
+                    branch: experiment_branch.or(branch),
+                    is_clean: e.is_clean,
+                    is_merged: e.is_merged,
+                    selected: false,
+                    is_deleting: false,
+                    error: None,
+                });
+            }
+            // Throwaway experiments first, they're the likeliest to go
+            entries.sort_by_key(|e| !e.is_experiment);
             if !entries.is_empty() {
                 app.open_worktree_cleanup(worktree_dir, entries);
             }
         }
+        AsyncAction::OpenParentRepoPicker => {
+            open_parent_repo_picker(app).await;
+        }
         AsyncAction::OpenAgentPicker { cwd, is_worktree } => {
-            let agents = check_all_agents();
+            let agents = check_all_agents(&app.custom_agents);
             app.open_agent_picker(cwd, is_worktree, agents);
         }
         AsyncAction::AgentPickerSelect => {
@@ -2428,12 +3909,27 @@ async fn handle_async_in_loop(
             if let Some(branch_input) = &app.branch_input {
                 let repo_path = branch_input.repo_path.clone();
                 let branch = branch_input.branch_name().to_string();
+                let agent_type = branch_input.agent_type.clone();
+                let base = branch_input.base.clone();
 
                 // Construct worktree path
                 let repo_name = git::repo_name(&repo_path);
                 let worktree_path = app.worktree_config.worktree_path(&repo_name, &branch);
 
                 app.close_branch_input();
+                if worktree_blocked_by_session_limit(app) {
+                    return Ok(());
+                }
+
+                // Templates without {branch} are rejected, but two branches can still
+                // sanitize to the same directory; never reuse another worktree's path
+                if worktree_path.exists() {
+                    log::error(&format!(
+                        "Worktree path {} already exists",
+                        worktree_path.display()
+                    ));
+                    return Ok(());
+                }
 
                 // Check if branch exists locally or as remote
                 let local_exists = git::branch_exists(&repo_path, &branch)
@@ -2444,15 +3940,57 @@ async fn handle_async_in_loop(
                     .unwrap_or(false);
                 let create_branch = !local_exists && !remote_exists;
 
+                // Remote-only bases need their remote's prefix to resolve
+                let base = match base {
+                    Some(base) if base.is_remote => Some(
+                        git::remote_branch_ref(&repo_path, &base.name)
+                            .await
+                            .unwrap_or(base.name),
+                    ),
+                    base => base.map(|base| base.name),
+                };
+                if !create_branch && let Some(base) = &base {
+                    log::warn(&format!(
+                        "Branch {} already exists, ignoring base {}",
+                        branch, base
+                    ));
+                }
+
                 // Create worktree
-                match git::create_worktree(&repo_path, &worktree_path, &branch, create_branch).await
+                match git::create_worktree(
+                    &repo_path,
+                    &worktree_path,
+                    &branch,
+                    base.as_deref(),
+                    create_branch,
+                )
+                .await
                 {
                     Ok(()) => {
-                        let agents = check_all_agents();
-                        app.open_agent_picker(worktree_path, true, agents);
+                        app.new_worktree = Some(NewWorktree {
+                            path: worktree_path.clone(),
+                            repo: repo_path.clone(),
+                        });
+                        match agent_type {
+                            Some(agent_type) => {
+                                spawn_agent_in_dir(
+                                    app,
+                                    agent_tx,
+                                    agent_commands,
+                                    agent_type,
+                                    worktree_path,
+                                    true,
+                                )
+                                .await?;
+                            }
+                            None => {
+                                let agents = check_all_agents(&app.custom_agents);
+                                app.open_agent_picker(worktree_path, true, agents);
+                            }
+                        }
                     }
                     Err(e) => {
-                        log::log(&format!("Failed to create worktree: {}", e));
+                        log::error(&format!("Failed to create worktree: {}", e));
                     }
                 }
             }
@@ -2460,21 +3998,23 @@ async fn handle_async_in_loop(
         AsyncAction::WorktreeCleanupExecute => {
             if let Some(cleanup) = &mut app.worktree_cleanup {
                 let delete_branches = cleanup.delete_branches;
+                let force = cleanup.force;
                 let selected: Vec<_> = cleanup
                     .selected_entries()
                     .iter()
-                    .map(|e| (e.path.clone(), e.branch.clone()))
+                    .map(|e| (e.path.clone(), e.branch.clone(), e.is_experiment))
                     .collect();
 
                 // Mark selected entries as deleting
                 for entry in &mut cleanup.entries {
                     if entry.selected {
                         entry.is_deleting = true;
+                        entry.error = None;
                     }
                 }
 
                 // Spawn async deletion tasks for each selected worktree
-                for (worktree_path, branch) in selected {
+                for (worktree_path, branch, is_experiment) in selected {
                     let tx = app_event_tx.clone();
                     tokio::spawn(async move {
                         // Get the actual git repo for this worktree
@@ -2491,7 +4031,7 @@ async fn handle_async_in_loop(
 
                         // Remove worktree
                         if let Err(e) =
-                            git::remove_worktree(&parent_repo, &worktree_path, false).await
+                            git::remove_worktree(&parent_repo, &worktree_path, force).await
                         {
                             let _ = tx
                                 .send(AppEvent::WorktreeDeletionFailed(
@@ -2503,12 +4043,17 @@ async fn handle_async_in_loop(
                         }
                         log::log(&format!("Removed worktree: {}", worktree_path.display()));
 
-                        // Delete branch if requested
+                        // Delete branch if requested. An experiment's branch is
+                        // throwaway, so it goes even if unmerged.
                         if delete_branches && let Some(branch_name) = branch {
-                            if let Err(e) =
-                                git::delete_branch(&parent_repo, &branch_name, false).await
+                            if let Err(e) = git::delete_branch(
+                                &parent_repo,
+                                &branch_name,
+                                force || is_experiment,
+                            )
+                            .await
                             {
-                                log::log(&format!(
+                                log::warn(&format!(
                                     "Failed to delete branch {}: {}",
                                     branch_name, e
                                 ));
@@ -2532,16 +4077,36 @@ async fn handle_async_in_loop(
         }
         AsyncAction::DuplicateSession => {
             if let Some(session) = app.sessions.selected_session() {
-                let agent_type = session.agent_type;
+                let agent_type = session.agent_type.clone();
                 let cwd = session.cwd.clone();
                 let is_worktree = session.is_worktree;
                 spawn_agent_in_dir(app, agent_tx, agent_commands, agent_type, cwd, is_worktree)
                     .await?;
             }
         }
+        AsyncAction::DuplicateIntoWorktree => {
+            duplicate_into_worktree(app, agent_tx, agent_commands).await?;
+        }
+        AsyncAction::StartExperiment => {
+            start_experiment(app, agent_tx, agent_commands).await?;
+        }
+        AsyncAction::StartSession(spawn) => {
+            start_session_in_dir(
+                app,
+                agent_tx,
+                agent_commands,
+                spawn.agent_type,
+                spawn.cwd,
+                spawn.is_worktree,
+            )
+            .await?;
+        }
+        AsyncAction::RestartAgent => {
+            restart_agent(app, agent_tx, agent_commands);
+        }
         AsyncAction::ClearSession => {
             if let Some(session) = app.sessions.selected_session() {
-                let agent_type = session.agent_type;
+                let agent_type = session.agent_type.clone();
                 let cwd = session.cwd.clone();
                 let is_worktree = session.is_worktree;
                 let old_session_id = session.id.clone();
@@ -2568,18 +4133,92 @@ async fn handle_async_in_loop(
             app.kill_selected_session();
         }
         AsyncAction::SubmitBugReport => {
-            if let Some(bug_report) = &app.bug_report {
-                let description = bug_report.description.clone();
+            if let Some(mut bug_report) = app.bug_report.take()
+                && let Some(body) = bug_report.preview.take()
+            {
                 app.close_bug_report();
-
-                // TODO: Implement bug report submission
-                log::log(&format!("Bug report submitted: {}", description));
+                let repo = app.bug_report_repo.clone();
+                let message = match create_github_issue(&repo, &bug_report.title(), &body).await {
+                    Ok(url) => {
+                        log::log(&format!("Bug report filed: {}", url));
+                        format!("Bug report filed: {}", url)
+                    }
+                    Err(e) => {
+                        // Without gh, hand the report over for filing in the browser
+                        log::warn(&format!("Failed to file bug report with gh: {}", e));
+                        let new_issue_url = format!("https://github.com/{}/issues/new", repo);
+                        match clipboard::write_clipboard(&body) {
+                            Ok(()) => format!(
+                                "Couldn't file the bug report ({}). It's on the clipboard; paste it at {}",
+                                e, new_issue_url
+                            ),
+                            Err(_) => {
+                                log::log(&format!("Bug report:\n{}", body));
+                                format!(
+                                    "Couldn't file the bug report ({}). It's in the log; file it at {}",
+                                    e, new_issue_url
+                                )
+                            }
+                        }
+                    }
+                };
+                if let Some(session) = app.sessions.selected_session_mut() {
+                    session.add_output(message, OutputType::SystemMessage);
+                }
             }
         }
     }
     Ok(())
 }
 
+/// File an issue in `repo` with the GitHub CLI, returning the new issue's URL
+async fn create_github_issue(repo: &str, title: &str, body: &str) -> Result<String, String> {
+    use tokio::io::AsyncWriteExt;
+
+    let child = tokio::process::Command::new("gh")
+        .args([
+            "issue",
+            "create",
+            "--repo",
+            repo,
+            "--title",
+            title,
+            "--body-file",
+            "-",
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err("gh isn't installed".to_string());
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr
+            .trim()
+            .lines()
+            .last()
+            .unwrap_or("gh failed")
+            .to_string())
+    }
+}
+
 async fn send_prompt(
     app: &mut App,
     agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>,
@@ -2587,69 +4226,327 @@ async fn send_prompt(
 ) {
     // Take attachments before borrowing session
     let attachments = std::mem::take(&mut app.attachments);
-    let has_attachments = !attachments.is_empty();
 
     if let Some(session) = app.sessions.selected_session_mut() {
-        // Add spacing before user message
-        session.add_output(String::new(), OutputType::Text);
+        dispatch_prompt(session, agent_commands, text, attachments).await;
+    }
+}
+
+/// Switch the selected session to the agent's next mode (session/set_mode)
+async fn cycle_mode(app: &mut App, agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>) {
+    if let Some(session) = app.sessions.selected_session_mut()
+        && let Some(mode_id) = session.cycle_mode()
+    {
+        let name = session.current_mode_name().unwrap_or(&mode_id).to_string();
+        session.add_output(format!("Mode: {}", name), OutputType::SystemMessage);
+        session.scroll_to_bottom();
+        let local_id = session.id.clone();
+        let acp_session_id = session.acp_session_id.clone().unwrap_or_default();
+        if let Some(cmd_tx) = agent_commands.get(&local_id) {
+            let _ = cmd_tx
+                .send(AgentCommand::SetMode {
+                    session_id: acp_session_id,
+                    mode_id,
+                })
+                .await;
+        }
+    }
+}
+
+/// Open the branch input for a new worktree of the repository containing
+/// `path`, starting with the choice of base branch
+async fn open_worktree_branch_input(app: &mut App, path: &std::path::Path) {
+    let Some(repo_path) = git::main_repo_root(path).await else {
+        log::error(&format!("{} is not in a git repository", path.display()));
+        return;
+    };
+    let current_branch = get_git_branch(&repo_path).await;
+    let branches = git::list_branches(&repo_path)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn(&format!("{}", e));
+            vec![]
+        })
+        .into_iter()
+        .map(|(name, is_remote)| BranchEntry {
+            is_current: !is_remote && name == current_branch,
+            name,
+            is_remote,
+        })
+        .collect();
+    app.open_branch_input(repo_path, branches, None, true);
+}
+
+/// Open the agent picker in the selected session's main repo: the parent repo
+/// of a worktree, otherwise the root of the repo containing its directory
+async fn open_parent_repo_picker(app: &mut App) {
+    let Some(session) = app.sessions.selected_session() else {
+        return;
+    };
+    let cwd = session.cwd.clone();
+    let repo_path = if session.is_worktree {
+        get_worktree_parent_repo(&cwd).await
+    } else {
+        git::main_repo_root(&cwd).await
+    };
+    match repo_path {
+        Some(repo_path) => {
+            let agents = check_all_agents(&app.custom_agents);
+            app.open_agent_picker(repo_path, false, agents);
+        }
+        None => log::warn(&format!(
+            "Could not find the repository of {}",
+            cwd.display()
+        )),
+    }
+}
+
+/// Ask for a branch to duplicate the selected session into a new worktree of
+/// its repo, or duplicate it in place when its directory isn't in a git repo
+async fn duplicate_into_worktree(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+) -> Result<()> {
+    let Some(session) = app.sessions.selected_session() else {
+        return Ok(());
+    };
+    let agent_type = session.agent_type.clone();
+    let cwd = session.cwd.clone();
+    let is_worktree = session.is_worktree;
+    let current_branch = session.git_branch.clone();
+
+    let Some(repo_path) = git::main_repo_root(&cwd).await else {
+        log::log(&format!(
+            "{} is not in a git repository, duplicating in place",
+            cwd.display()
+        ));
+        return spawn_agent_in_dir(app, agent_tx, agent_commands, agent_type, cwd, is_worktree)
+            .await;
+    };
+
+    let branches = git::list_branches(&repo_path)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn(&format!("{}", e));
+            vec![]
+        })
+        .into_iter()
+        .map(|(name, is_remote)| BranchEntry {
+            is_current: !is_remote && name == current_branch,
+            name,
+            is_remote,
+        })
+        .collect();
+    app.open_branch_input(repo_path, branches, Some(agent_type), false);
+    Ok(())
+}
+
+/// Refuse to create a worktree for a new session once `max_sessions` is
+/// reached: cancelling the limit dialog afterwards would leave it behind
+fn worktree_blocked_by_session_limit(app: &mut App) -> bool {
+    if !app.session_limit_reached() {
+        return false;
+    }
+    let message = format!(
+        "{} sessions running (max_sessions = {}), close one before starting another in a new worktree",
+        app.sessions.sessions().len(),
+        app.max_sessions.unwrap_or_default()
+    );
+    log::warn(&message);
+    if let Some(session) = app.sessions.selected_session_mut() {
+        session.add_error(&message);
+    }
+    true
+}
 
-        // Show user input with attachment indicator
-        if has_attachments {
-            let attachment_names: Vec<_> =
-                attachments.iter().map(|a| a.filename.as_str()).collect();
+/// Start the selected session's agent in a new worktree on a throwaway branch,
+/// branched from what the session has checked out
+async fn start_experiment(
+    app: &mut App,
+    agent_tx: &mpsc::Sender<(String, AgentEvent)>,
+    agent_commands: &mut HashMap<String, mpsc::Sender<AgentCommand>>,
+) -> Result<()> {
+    let Some(session) = app.sessions.selected_session() else {
+        return Ok(());
+    };
+    let agent_type = session.agent_type.clone();
+    let cwd = session.cwd.clone();
+    if worktree_blocked_by_session_limit(app) {
+        return Ok(());
+    }
+
+    let Some(repo_path) = git::main_repo_root(&cwd).await else {
+        if let Some(session) = app.sessions.selected_session_mut() {
             session.add_output(
-                format!("> {} [+{}]", text, attachment_names.join(", ")),
-                OutputType::UserInput,
+                "Experiments need a git repository".to_string(),
+                OutputType::SystemMessage,
             );
-        } else {
-            session.add_output(format!("> {}", text), OutputType::UserInput);
         }
-        session.scroll_to_bottom(); // Scroll to show the user's input
-        session.state = SessionState::Prompting;
-        session.idle_notified = false; // Reset so we notify when this prompt completes
+        return Ok(());
+    };
+    let base = git::current_branch(&cwd).await;
+    let branch = git::experiment_branch_name(chrono::Local::now());
+    let worktree_path = app
+        .worktree_config
+        .worktree_path(&git::repo_name(&repo_path), &branch);
+    if worktree_path.exists() {
+        log::error(&format!(
+            "Worktree path {} already exists",
+            worktree_path.display()
+        ));
+        return Ok(());
+    }
 
-        // Use local ID for HashMap lookup, ACP session ID for protocol
-        let local_id = session.id.clone();
-        let acp_session_id = session.acp_session_id.clone().unwrap_or_default();
+    if let Err(e) =
+        git::create_worktree(&repo_path, &worktree_path, &branch, base.as_deref(), true).await
+    {
+        log::error(&format!("Failed to create experiment worktree: {}", e));
+        if let Some(session) = app.sessions.selected_session_mut() {
+            session.add_error(&format!("Failed to create experiment worktree: {}", e));
+        }
+        return Ok(());
+    }
+    log::log(&format!(
+        "Created experiment worktree {} on {}",
+        worktree_path.display(),
+        branch
+    ));
+    app.new_worktree = Some(NewWorktree {
+        path: worktree_path.clone(),
+        repo: repo_path.clone(),
+    });
+    spawn_agent_in_dir(
+        app,
+        agent_tx,
+        agent_commands,
+        agent_type,
+        worktree_path,
+        true,
+    )
+    .await
+}
 
-        // Build content blocks
-        if has_attachments {
-            let mut content: Vec<ContentBlock> = vec![];
+/// Resend the selected session's last prompt, if it has one and is idle
+async fn retry_last_prompt(
+    app: &mut App,
+    agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>,
+) {
+    if let Some(session) = app.sessions.selected_session_mut()
+        && let Some((text, attachments)) = session.retry_prompt()
+    {
+        dispatch_prompt(session, agent_commands, &text, attachments).await;
+    }
+}
 
-            // Add text if present
-            if !text.is_empty() {
-                content.push(ContentBlock::Text {
-                    text: text.to_string(),
-                });
-            }
+/// Ask the selected session's agent to explain and fix the error nearest the
+/// viewport, if it's idle
+async fn explain_error(
+    app: &mut App,
+    agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>,
+) {
+    let Some(session) = app.sessions.selected_session_mut() else {
+        return;
+    };
+    if !session.state.can_prompt() {
+        return;
+    }
+    match session.error_near_view() {
+        Some(error) => {
+            let text = config::explain_error_prompt(&app.explain_error_prompt, &error);
+            dispatch_prompt(session, agent_commands, &text, Vec::new()).await;
+        }
+        None => {
+            session.add_output("No error to explain".to_string(), OutputType::SystemMessage);
+            session.scroll_to_bottom();
+        }
+    }
+}
 
-            // Add image attachments
-            for attachment in attachments {
-                content.push(ContentBlock::Image {
-                    mime_type: attachment.mime_type,
-                    data: attachment.data,
-                });
-            }
+/// Send one of the follow-up prompts the selected session's agent suggested,
+/// if it's idle
+async fn send_follow_up(
+    app: &mut App,
+    agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>,
+    index: usize,
+) {
+    if let Some(session) = app.sessions.selected_session_mut()
+        && session.state.can_prompt()
+        && let Some(follow_up) = session.follow_ups.get(index)
+    {
+        let text = follow_up.prompt.clone();
+        dispatch_prompt(session, agent_commands, &text, Vec::new()).await;
+    }
+}
 
-            // Send with content blocks
-            if let Some(cmd_tx) = agent_commands.get(&local_id) {
-                let _ = cmd_tx
-                    .send(AgentCommand::PromptWithContent {
-                        session_id: acp_session_id,
-                        content,
-                    })
-                    .await;
-            }
-        } else {
-            // Send simple text prompt
-            if let Some(cmd_tx) = agent_commands.get(&local_id) {
-                let _ = cmd_tx
-                    .send(AgentCommand::Prompt {
-                        session_id: acp_session_id,
-                        text: text.to_string(),
-                    })
-                    .await;
-            }
+/// Show a prompt in a session's output and send it to the session's agent
+async fn dispatch_prompt(
+    session: &mut Session,
+    agent_commands: &HashMap<String, mpsc::Sender<AgentCommand>>,
+    text: &str,
+    attachments: Vec<ImageAttachment>,
+) {
+    let has_attachments = !attachments.is_empty();
+    session.last_prompt = Some((text.to_string(), attachments.clone()));
+    let sent_text = session.prompt_with_preamble(text);
+
+    // Add spacing before user message
+    session.add_output(String::new(), OutputType::Text);
+
+    // Show user input with attachment indicator
+    if has_attachments {
+        let attachment_names: Vec<_> = attachments.iter().map(|a| a.filename.as_str()).collect();
+        session.add_output(
+            format!("> {} [+{}]", text, attachment_names.join(", ")),
+            OutputType::UserInput,
+        );
+    } else {
+        session.add_output(format!("> {}", text), OutputType::UserInput);
+    }
+    session.scroll_to_bottom(); // Scroll to show the user's input
+    session.state = SessionState::Prompting;
+    session.follow_ups.clear(); // They answered the previous turn
+    session.idle_notified = false; // Reset so we notify when this prompt completes
+
+    // Use local ID for HashMap lookup, ACP session ID for protocol
+    let local_id = session.id.clone();
+    let acp_session_id = session.acp_session_id.clone().unwrap_or_default();
+
+    // Build content blocks
+    if has_attachments {
+        let mut content: Vec<ContentBlock> = vec![];
+
+        // Add text if present
+        if !sent_text.is_empty() {
+            content.push(ContentBlock::Text { text: sent_text });
+        }
+
+        // Add image attachments
+        for attachment in attachments {
+            content.push(ContentBlock::Image {
+                mime_type: attachment.mime_type,
+                data: attachment.data,
+            });
+        }
+
+        // Send with content blocks
+        if let Some(cmd_tx) = agent_commands.get(&local_id) {
+            let _ = cmd_tx
+                .send(AgentCommand::PromptWithContent {
+                    session_id: acp_session_id,
+                    content,
+                })
+                .await;
+        }
+    } else {
+        // Send simple text prompt
+        if let Some(cmd_tx) = agent_commands.get(&local_id) {
+            let _ = cmd_tx
+                .send(AgentCommand::Prompt {
+                    session_id: acp_session_id,
+                    text: sent_text,
+                })
+                .await;
         }
     }
 }
@@ -2689,14 +4586,27 @@ fn process_notification(
     }
 }
 
+/// Maximum automatic reconnects after an agent crash
+const MAX_RECONNECT_ATTEMPTS: u32 = 2;
+
 /// Result of handling an agent event - may contain a command to send back
 enum EventResult {
     None,
+    /// Agent process died unexpectedly; respawn it and resume the session
+    Reconnect,
     AutoAcceptPermission {
         request_id: u64,
         option_id: PermissionOptionId,
     },
+    /// Answer a permission request the user can't act on as cancelled
+    CancelPermission {
+        request_id: u64,
+    },
     Notification(NotificationEvent),
+    /// Switch the recreated session back to the model the user picked
+    SetModel {
+        model_id: String,
+    },
     #[allow(dead_code)] // Reserved for future use
     AutoAcceptWithNotification {
         request_id: u64,
@@ -2710,6 +4620,11 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
     let is_insert_mode = app.input_mode == InputMode::Insert;
     let input_buffer = app.input_buffer.clone();
     let cursor_position = app.cursor_position;
+    let show_agent_stderr = app.show_agent_stderr;
+    let auto_reconnect = app.auto_reconnect;
+    let tool_rules = &app.tool_permission_rules;
+    let mut reconnect = false;
+    let mut set_model = None;
 
     // Check if this session is the currently selected one
     let is_selected_session = app
@@ -2718,7 +4633,29 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
         .map(|s| s.id == session_id)
         .unwrap_or(false);
 
+    // Events that add output worth switching to a background session for
+    let produces_output = matches!(
+        &event,
+        AgentEvent::Update {
+            update: SessionUpdate::AgentMessageChunk { .. }
+                | SessionUpdate::ToolCall { .. }
+                | SessionUpdate::ToolCallUpdate { .. },
+            ..
+        } | AgentEvent::PromptComplete { .. }
+            | AgentEvent::Error { .. }
+    );
+
     if let Some(session) = app.sessions.get_by_id_mut(session_id) {
+        if produces_output && !is_selected_session && !session.reconnecting {
+            session.has_unread = true;
+        }
+        // Any other event ends a run of repeated errors (stderr often accompanies them)
+        if !matches!(
+            event,
+            AgentEvent::Error { .. } | AgentEvent::Stderr { .. } | AgentEvent::StdoutNoise { .. }
+        ) {
+            session.last_error = None;
+        }
         match event {
             AgentEvent::Initialized {
                 agent_info,
@@ -2731,25 +4668,53 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                     session.add_output(format!("Connected to {}", name), OutputType::Text);
                 }
                 if let Some(caps) = agent_capabilities {
+                    session.accepts_images = Some(acp::accepts_images(&caps));
                     // Format capabilities nicely
                     let formatted = format_agent_capabilities(&caps);
                     session.add_output(formatted, OutputType::Text);
                 }
             }
-            AgentEvent::SessionCreated { session_id, models } => {
+            AgentEvent::SessionCreated {
+                session_id,
+                models,
+                modes,
+            } => {
                 // Store the ACP session ID (used in protocol messages)
                 // Keep session.id as the local stable ID (used for HashMap keys)
-                session.acp_session_id = Some(session_id);
+                // session/load returns an empty ID - keep the one we resumed
+                if !session_id.is_empty() {
+                    session.acp_session_id = Some(session_id);
+                }
                 session.state = SessionState::Idle;
                 // Store model info if available
                 if let Some(models_state) = models {
                     session.available_models = models_state.available_models;
                     session.current_model_id = Some(models_state.current_model_id);
                 }
-                session.add_output(
-                    "Session ready. Press [i] to type.".to_string(),
-                    OutputType::Text,
-                );
+                set_model = session.restore_chosen_model();
+                // Store mode info if available
+                if let Some(modes_state) = modes {
+                    session.available_modes = modes_state.available_modes;
+                    session.current_mode = Some(modes_state.current_mode_id);
+                }
+                if session.reconnecting {
+                    session.reconnecting = false;
+                    session.add_output("Reconnected".to_string(), OutputType::SystemMessage);
+                } else {
+                    session.add_output(
+                        "Session ready. Press [i] to type.".to_string(),
+                        OutputType::Text,
+                    );
+                    if let Some(preamble) = &session.pending_preamble {
+                        session.add_output(
+                            format!("Preamble (sent with your first prompt): {}", preamble),
+                            OutputType::Preamble,
+                        );
+                    }
+                }
+            }
+            AgentEvent::Update { .. } if session.reconnecting => {
+                // Skip history replayed by session/load - it is already in the output
             }
             AgentEvent::Update { update, .. } => {
                 match update {
@@ -2811,7 +4776,7 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                         }
                     }
                     SessionUpdate::Plan { entries } => {
-                        session.plan_entries = entries;
+                        session.set_plan_entries(entries);
                     }
                     SessionUpdate::CurrentModeUpdate { current_mode_id } => {
                         session.current_mode = Some(current_mode_id);
@@ -2819,6 +4784,9 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                     SessionUpdate::AvailableCommandsUpdate { commands } => {
                         session.available_commands = commands;
                     }
+                    SessionUpdate::FollowUpSuggestions { suggestions } => {
+                        session.follow_ups = suggestions;
+                    }
                     SessionUpdate::Other { raw_type } => {
                         session.add_output(
                             format!("[Unknown update: {}]", raw_type.as_deref().unwrap_or("?")),
@@ -2831,14 +4799,36 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                 request_id,
                 tool_call_id,
                 title,
+                kind,
                 options,
                 ..
             } => {
+                // With nothing to choose, a dialog would wait forever; cancel
+                // the request so the agent can carry on
+                if options.is_empty() {
+                    log::warn(&format!(
+                        "Session {} got a permission request without options (tool call {}, title {:?}), cancelling it",
+                        session.name, tool_call_id, title
+                    ));
+                    session.add_error(&format!(
+                        "Agent asked for permission for {} without any options to choose; cancelled the request",
+                        title.as_deref().unwrap_or("a tool")
+                    ));
+                    return EventResult::CancelPermission { request_id };
+                }
+
                 let session_name = session.name.clone();
                 let tool_name = title.clone().unwrap_or_else(|| "Tool".to_string());
 
-                // Check if we should auto-accept (AcceptAll or Yolo mode)
-                if session.permission_mode.auto_accepts() {
+                // Check if we should auto-accept (AcceptAll/Yolo, AcceptEdits for file
+                // edits, or a tool listed in `auto_accept_tools`)
+                let is_edit = is_edit_permission(kind.as_ref(), title.as_deref());
+                if tool_rules.auto_accepts(
+                    session.permission_mode,
+                    is_edit,
+                    kind.as_ref(),
+                    title.as_deref(),
+                ) {
                     // Find the first allow_once option
                     if let Some(option) = options
                         .iter()
@@ -2910,6 +4900,7 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                 let should_notify = !session.idle_notified;
 
                 session.state = SessionState::Idle;
+                session.reconnect_attempts = 0;
                 session.pending_permission = None;
                 session.complete_active_tool();
                 session.clear_thought(); // Clear any remaining thought
@@ -2924,23 +4915,163 @@ fn handle_agent_event(app: &mut App, session_id: &str, event: AgentEvent) -> Eve
                     });
                 }
             }
-            AgentEvent::FileWritten { diff, .. } => {
+            AgentEvent::FileWritten {
+                path,
+                created,
+                old_content,
+                new_content,
+                diff,
+                ..
+            } => {
                 // Show the diff (file path is already shown in the tool call)
-                session.add_tool_output(diff);
+                // and keep the full change so it can be opened side by side
+                session.add_file_diff(
+                    FileDiff {
+                        path,
+                        created,
+                        old_content,
+                        new_content,
+                    },
+                    diff,
+                );
             }
             AgentEvent::Error { message } => {
                 session.state = SessionState::Idle;
-                session.add_output(format!("Error: {}", message), OutputType::Error);
+                session.reconnecting = false;
+                session.add_error(&message);
+            }
+            AgentEvent::Stderr { line } => {
+                if show_agent_stderr {
+                    session.add_output(line.clone(), OutputType::AgentStderr);
+                }
+                session.push_stderr(line);
+            }
+            AgentEvent::StdoutNoise { line } => {
+                // Unlike stderr this is rare and usually explains a misbehaving
+                // agent, so it's always shown, dimmed
+                session.add_output(line.clone(), OutputType::AgentStderr);
+                session.push_stderr(line);
+            }
+            AgentEvent::HookOutput { line } => {
+                session.add_output(line, OutputType::BashOutput);
+            }
+            AgentEvent::HookFinished { error } => {
+                if let Some(error) = error {
+                    log::warn(&format!("Post-create hook failed: {}", error));
+                    session.add_output(
+                        format!(
+                            "Post-create hook failed ({}), starting the agent anyway",
+                            error
+                        ),
+                        OutputType::SystemMessage,
+                    );
+                }
             }
             AgentEvent::Disconnected => {
-                session.state = SessionState::Idle;
-                session.add_output("Disconnected".to_string(), OutputType::Text);
+                // Killed sessions are removed before their agent exits, so a
+                // disconnect for a live session means the agent died unexpectedly
+                if auto_reconnect
+                    && session.acp_session_id.is_some()
+                    && session.reconnect_attempts < MAX_RECONNECT_ATTEMPTS
+                {
+                    session.reconnect_attempts += 1;
+                    session.reset_for_respawn();
+                    session.add_output(
+                        format!(
+                            "Disconnected, reconnecting… (attempt {}/{})",
+                            session.reconnect_attempts, MAX_RECONNECT_ATTEMPTS
+                        ),
+                        OutputType::SystemMessage,
+                    );
+                    reconnect = true;
+                } else {
+                    session.state = SessionState::Idle;
+                    session.add_output("Disconnected".to_string(), OutputType::Text);
+                }
             }
         }
         // Auto-scroll to bottom only if already at bottom (not scrolled up)
         if session.scroll_offset == usize::MAX {
             session.scroll_to_bottom();
         }
+        if reconnect {
+            return EventResult::Reconnect;
+        }
+        if let Some(model_id) = set_model {
+            return EventResult::SetModel { model_id };
+        }
     }
     EventResult::None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::NotificationConfig;
+    use crate::session::Session;
+    use crate::ui_state::UiState;
+
+    fn app() -> App {
+        App::new(
+            PathBuf::from("/tmp"),
+            WorktreeConfig {
+                worktree_dir: PathBuf::from("/tmp/worktrees"),
+                template: git::DEFAULT_WORKTREE_TEMPLATE.to_string(),
+            },
+            vec![],
+            NotificationConfig::default(),
+            UiState::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_post_create_hook() {
+        let dir = std::env::temp_dir().join(format!("amux_hook_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (event_tx, mut event_rx) = mpsc::channel(32);
+
+        // Both streams are shown, in the worktree, and a failing exit is reported
+        let error = run_post_create_hook("pwd; echo oops >&2; exit 3", &dir, &event_tx).await;
+        assert_eq!(error.as_deref(), Some("exit status: 3"));
+        drop(event_tx);
+        let mut lines = Vec::new();
+        while let Some(event) = event_rx.recv().await {
+            if let AgentEvent::HookOutput { line } = event {
+                lines.push(line);
+            }
+        }
+        let pwd = std::fs::canonicalize(&dir).unwrap();
+        assert_eq!(lines, vec![pwd.display().to_string(), "oops".to_string()]);
+
+        let (event_tx, _event_rx) = mpsc::channel(32);
+        assert_eq!(run_post_create_hook("true", &dir, &event_tx).await, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_permission_request_without_options_is_cancelled() {
+        let mut app = app();
+        let session = Session::mock("1", "test", AgentType::ClaudeCode, "main");
+        app.sessions.add_session(session);
+
+        let result = handle_agent_event(
+            &mut app,
+            "1",
+            AgentEvent::PermissionRequest {
+                request_id: 7,
+                session_id: "acp-1".to_string(),
+                tool_call_id: "tool-1".to_string(),
+                title: Some("Run cargo test".to_string()),
+                kind: None,
+                options: vec![],
+            },
+        );
+        assert!(matches!(
+            result,
+            EventResult::CancelPermission { request_id: 7 }
+        ));
+        let session = app.sessions.selected_session().unwrap();
+        assert!(session.pending_permission.is_none());
+    }
+}
@@ -0,0 +1,245 @@
+//! `@`-mention file completion for the prompt.
+//!
+//! Typing `@` followed by a path fragment offers files from the session's
+//! directory. The fragment's directory part (up to the last `/`) picks the
+//! directory that is scanned; the rest is fuzzy-matched against the files in
+//! it and below.
+
+use std::path::{Path, PathBuf};
+
+use crate::picker::{Picker, fuzzy_match};
+
+/// Deepest directory level scanned below the fragment's directory
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// Most files collected by one scan
+const MAX_SCAN_FILES: usize = 5000;
+
+/// Most matches listed in the popup
+const MAX_MATCHES: usize = 50;
+
+/// Directories never worth mentioning (besides hidden ones)
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// The `@` mention being typed at the cursor: the byte index of the `@` and
+/// the fragment between it and the cursor.
+///
+/// The `@` must start the input or follow whitespace (so e-mail addresses
+/// don't count), and the fragment can't contain whitespace.
+pub fn mention_at_cursor(input: &str, cursor: usize) -> Option<(usize, &str)> {
+    let before = input.get(..cursor)?;
+    let start = before.rfind(|c: char| c == '@' || c.is_whitespace())?;
+    if !before[start..].starts_with('@') {
+        return None;
+    }
+    let at_word_start = before[..start]
+        .chars()
+        .next_back()
+        .is_none_or(char::is_whitespace);
+    at_word_start.then(|| (start, &before[start + 1..]))
+}
+
+/// Split a fragment into its directory part (ending in `/`, or empty) and the query
+pub fn split_fragment(fragment: &str) -> (&str, &str) {
+    match fragment.rfind('/') {
+        Some(slash) => fragment.split_at(slash + 1),
+        None => ("", fragment),
+    }
+}
+
+/// Files below `cwd/dir`, as paths relative to `cwd`, sorted.
+///
+/// Hidden entries and build directories are skipped; the scan stops after
+/// [`MAX_SCAN_FILES`] files.
+pub async fn scan_files(cwd: PathBuf, dir: String) -> Vec<String> {
+    tokio::task::spawn_blocking(move || {
+        let mut files = vec![];
+        collect_files(&cwd, &cwd.join(&dir), 0, &mut files);
+        files.sort();
+        files
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn collect_files(cwd: &Path, dir: &Path, depth: usize, files: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if files.len() >= MAX_SCAN_FILES {
+            return;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if depth < MAX_SCAN_DEPTH && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                collect_files(cwd, &path, depth + 1, files);
+            }
+        } else if let Ok(relative) = path.strip_prefix(cwd) {
+            files.push(relative.to_string_lossy().into_owned());
+        }
+    }
+}
+
+/// Open `@` completion in the prompt
+#[derive(Debug, Clone)]
+pub struct MentionCompletion {
+    /// Byte index of the `@` in the input
+    pub start: usize,
+    /// Directory the files were scanned from
+    pub cwd: PathBuf,
+    /// Directory part of the fragment (ending in `/`, or empty)
+    pub dir: String,
+    /// Rest of the fragment, fuzzy-matched against the files
+    pub query: String,
+    /// Scanned files relative to `cwd`; None while the scan runs
+    pub files: Option<Vec<String>>,
+    /// Files matching the query, best first
+    pub matches: Vec<String>,
+    pub selected: usize,
+}
+
+impl MentionCompletion {
+    pub fn new(start: usize, cwd: PathBuf, dir: &str, query: &str) -> Self {
+        Self {
+            start,
+            cwd,
+            dir: dir.to_string(),
+            query: query.to_string(),
+            files: None,
+            matches: vec![],
+            selected: 0,
+        }
+    }
+
+    /// Whether a scan of `dir` in `cwd` serves this completion
+    pub fn wants(&self, cwd: &Path, dir: &str) -> bool {
+        self.cwd == cwd && self.dir == dir
+    }
+
+    /// Files from the scan arrived
+    pub fn set_files(&mut self, files: Vec<String>) {
+        self.files = Some(files);
+        self.update_matches();
+    }
+
+    /// The fragment after the directory part changed
+    pub fn set_query(&mut self, query: &str) {
+        if self.query != query {
+            self.query = query.to_string();
+            self.update_matches();
+        }
+    }
+
+    /// Rank files by how well the path below the fragment's directory matches;
+    /// ties go to the shorter path
+    fn update_matches(&mut self) {
+        let Some(files) = &self.files else {
+            return;
+        };
+        let mut scored: Vec<(i64, &String)> = files
+            .iter()
+            .filter_map(|file| {
+                let below = file.strip_prefix(&self.dir).unwrap_or(file);
+                fuzzy_match(&self.query, below).map(|score| (score, file))
+            })
+            .collect();
+        scored.sort_by_key(|(score, file)| (std::cmp::Reverse(*score), file.len()));
+        self.matches = scored
+            .into_iter()
+            .take(MAX_MATCHES)
+            .map(|(_, file)| file.clone())
+            .collect();
+        self.selected = 0;
+    }
+}
+
+impl Picker for MentionCompletion {
+    type Item = String;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.matches
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.selected = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mention_at_cursor() {
+        assert_eq!(
+            mention_at_cursor("look at @src/ma", 15),
+            Some((8, "src/ma"))
+        );
+        assert_eq!(mention_at_cursor("@", 1), Some((0, "")));
+        // The cursor must be inside the mention
+        assert_eq!(mention_at_cursor("@src done", 9), None);
+        // Not at a word start
+        assert_eq!(mention_at_cursor("mail me@host", 12), None);
+        assert_eq!(mention_at_cursor("no mention", 10), None);
+    }
+
+    #[test]
+    fn test_split_fragment() {
+        assert_eq!(split_fragment("src/tui/co"), ("src/tui/", "co"));
+        assert_eq!(split_fragment("main"), ("", "main"));
+    }
+
+    #[test]
+    fn test_matches_rank_best_and_shortest_first() {
+        let mut completion = MentionCompletion::new(0, PathBuf::from("/repo"), "src/", "app");
+        completion.set_files(vec![
+            "src/tui/app_view.rs".to_string(),
+            "src/app.rs".to_string(),
+            "src/main.rs".to_string(),
+        ]);
+        assert_eq!(
+            completion.matches,
+            vec!["src/app.rs", "src/tui/app_view.rs"]
+        );
+
+        completion.set_query("");
+        assert_eq!(completion.matches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_scan_skips_hidden_and_build_dirs() {
+        let root = std::env::temp_dir().join(format!("amux_mention_{}", std::process::id()));
+        for dir in ["src/nested", ".git", "target"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "README.md",
+            "src/lib.rs",
+            "src/nested/a.rs",
+            ".git/HEAD",
+            "target/x",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let files = scan_files(root.clone(), String::new()).await;
+        assert_eq!(files, vec!["README.md", "src/lib.rs", "src/nested/a.rs"]);
+        let files = scan_files(root.clone(), "src/nested/".to_string()).await;
+        assert_eq!(files, vec!["src/nested/a.rs"]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
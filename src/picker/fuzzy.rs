@@ -0,0 +1,114 @@
+//! Fuzzy matching for picker filters
+//!
+//! Subsequence matching with scoring, so "dtp" matches "desktop" and
+//! contiguous or word-boundary matches rank above scattered ones.
+
+/// Bonus for a match at the very start of the candidate
+const START_BONUS: i64 = 10;
+/// Bonus for a match right after a separator or at a camelCase boundary
+const BOUNDARY_BONUS: i64 = 8;
+/// Bonus for a match directly following the previous match
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Base score for every matched character
+const MATCH_SCORE: i64 = 1;
+
+/// Match `query` as a case-insensitive subsequence of `candidate`.
+///
+/// Returns `None` if not every query character appears in order,
+/// otherwise a score where higher means a better match.
+/// An empty query matches everything with a score of 0.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<i64> {
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    if query_chars.peek().is_none() {
+        return Some(0);
+    }
+
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (i, &c) in candidate.iter().enumerate() {
+        let Some(&q) = query_chars.peek() else {
+            break;
+        };
+        if !c.to_lowercase().eq(q.to_lowercase()) {
+            continue;
+        }
+        query_chars.next();
+
+        score += MATCH_SCORE;
+        if i == 0 {
+            score += START_BONUS;
+        } else {
+            let prev = candidate[i - 1];
+            if matches!(prev, '-' | '_' | '.' | '/' | ' ')
+                || (prev.is_lowercase() && c.is_uppercase())
+            {
+                score += BOUNDARY_BONUS;
+            }
+        }
+        match last_match {
+            Some(last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+            // Penalize the gap since the previous match
+            Some(last) => score -= (i - last - 1) as i64,
+            None => {}
+        }
+        last_match = Some(i);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsequence_matches() {
+        assert!(fuzzy_match("dtp", "desktop").is_some());
+        assert!(fuzzy_match("dsk", "desktop").is_some());
+        assert!(fuzzy_match("ptd", "desktop").is_none());
+        assert!(fuzzy_match("desktopx", "desktop").is_none());
+    }
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        assert_eq!(fuzzy_match("", "anything"), Some(0));
+        assert_eq!(fuzzy_match("", ""), Some(0));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert!(fuzzy_match("DESK", "desktop").is_some());
+        assert!(fuzzy_match("desk", "Desktop").is_some());
+        assert_eq!(
+            fuzzy_match("Desk", "desktop"),
+            fuzzy_match("desk", "DESKTOP")
+        );
+    }
+
+    #[test]
+    fn test_exact_prefix_beats_scattered_match() {
+        let prefix = fuzzy_match("doc", "documents").unwrap();
+        let scattered = fuzzy_match("doc", "downloads-cache").unwrap();
+        assert!(prefix > scattered);
+    }
+
+    #[test]
+    fn test_word_boundary_beats_mid_word() {
+        let boundary = fuzzy_match("c", "my-code").unwrap();
+        let mid_word = fuzzy_match("c", "mycode").unwrap();
+        assert!(boundary > mid_word);
+    }
+
+    #[test]
+    fn test_sort_by_score() {
+        let mut candidates = vec!["downloads-cache", "documents", "dotfiles"];
+        candidates.retain(|c| fuzzy_match("doc", c).is_some());
+        candidates.sort_by_key(|c| std::cmp::Reverse(fuzzy_match("doc", c)));
+        assert_eq!(candidates[0], "documents");
+    }
+}
@@ -3,6 +3,8 @@
 //! Provides a trait and implementations for list-based selection UI components.
 //! This eliminates duplicate select_next/select_prev logic across picker types.
 
+mod fuzzy;
 mod traits;
 
+pub use fuzzy::fuzzy_match;
 pub use traits::Picker;
@@ -73,6 +73,20 @@ pub trait Picker {
         }
     }
 
+    /// Move the selection `page` items down, stopping at the last item
+    fn select_page_down(&mut self, page: usize) {
+        if !self.is_empty() {
+            let next = (self.selected_index() + page).min(self.len() - 1);
+            self.set_selected_index(next);
+        }
+    }
+
+    /// Move the selection `page` items up, stopping at the first item
+    fn select_page_up(&mut self, page: usize) {
+        let prev = self.selected_index().saturating_sub(page);
+        self.set_selected_index(prev);
+    }
+
     /// Get the currently selected item
     fn selected_item(&self) -> Option<&Self::Item> {
         self.items().get(self.selected_index())
@@ -93,3 +107,53 @@ pub trait Picker {
         self.set_selected_index(0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Numbers {
+        items: Vec<u32>,
+        selected: usize,
+    }
+
+    impl Picker for Numbers {
+        type Item = u32;
+
+        fn items(&self) -> &[u32] {
+            &self.items
+        }
+
+        fn selected_index(&self) -> usize {
+            self.selected
+        }
+
+        fn set_selected_index(&mut self, index: usize) {
+            self.selected = index;
+        }
+    }
+
+    #[test]
+    fn test_paging_stops_at_the_ends() {
+        let mut picker = Numbers {
+            items: (0..25).collect(),
+            selected: 0,
+        };
+        picker.select_page_down(10);
+        assert_eq!(picker.selected, 10);
+        picker.select_page_down(10);
+        picker.select_page_down(10);
+        assert_eq!(picker.selected, 24);
+        picker.select_page_up(5);
+        assert_eq!(picker.selected, 19);
+        picker.select_page_up(50);
+        assert_eq!(picker.selected, 0);
+
+        let mut empty = Numbers {
+            items: vec![],
+            selected: 0,
+        };
+        empty.select_page_down(10);
+        assert_eq!(empty.selected, 0);
+    }
+}
@@ -1,8 +1,11 @@
-//! Scroll event debouncing for smooth scrolling.
+//! Scroll event debouncing for smooth scrolling, and scroll anchoring across reflows.
 //!
 //! Accumulates scroll deltas over a time window to prevent jittery scrolling
 //! from high-resolution scroll events (e.g., trackpads, precision mice).
 //!
+//! A [`ScrollAnchor`] records which output line is at the top of the viewport,
+//! so the same content stays in view when a resize rewraps the output.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -114,11 +117,67 @@ impl ScrollHelper {
     }
 }
 
+/// Position of the viewport's top line in terms of the output rather than
+/// wrapped lines: an output line, and how many of its wrapped lines are above it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollAnchor {
+    pub output_index: usize,
+    pub line_offset: usize,
+}
+
+impl ScrollAnchor {
+    /// Anchor for a scroll offset, given the wrapped line where each output line starts
+    pub fn from_offset(output_starts: &[usize], offset: usize) -> Self {
+        let output_index = output_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1);
+        let start = output_starts.get(output_index).copied().unwrap_or(0);
+        Self {
+            output_index,
+            line_offset: offset.saturating_sub(start),
+        }
+    }
+
+    /// Scroll offset showing the anchored content at the top after a reflow.
+    /// The offset within the output line is kept as far as it still has lines.
+    pub fn to_offset(self, output_starts: &[usize], total_lines: usize) -> usize {
+        let Some(&start) = output_starts.get(self.output_index) else {
+            return total_lines;
+        };
+        let end = output_starts
+            .get(self.output_index + 1)
+            .copied()
+            .unwrap_or(total_lines);
+        start + self.line_offset.min(end.saturating_sub(start + 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::thread;
 
+    #[test]
+    fn test_scroll_anchor_survives_width_change() {
+        // Three output lines wrapping to 2, 3 and 1 lines at the old width...
+        let wide = [0, 2, 5];
+        let anchor = ScrollAnchor::from_offset(&wide, 3);
+        assert_eq!(
+            anchor,
+            ScrollAnchor {
+                output_index: 1,
+                line_offset: 1
+            }
+        );
+        // ...and 4, 6 and 2 lines once the terminal is narrower
+        let narrow = [0, 4, 10];
+        assert_eq!(anchor.to_offset(&narrow, 12), 5);
+
+        // Content that now wraps to fewer lines clamps to its last line
+        let anchor = ScrollAnchor::from_offset(&narrow, 8);
+        assert_eq!(anchor.to_offset(&wide, 6), 4);
+    }
+
     #[test]
     fn test_accumulate_within_debounce() {
         let mut helper = ScrollHelper::new(100, 3);
@@ -3,6 +3,8 @@
 //! This module provides functionality to detect which agents are available
 //! on the system by checking their preconditions (commands installed, etc.)
 
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
 use std::process::Command;
 
 use super::AgentType;
@@ -21,6 +23,8 @@ pub struct Precondition {
 pub struct AgentAvailability {
     pub agent_type: AgentType,
     pub preconditions: Vec<Precondition>,
+    /// Resolved path of the agent's command, if found in PATH
+    pub binary_path: Option<PathBuf>,
 }
 
 impl AgentAvailability {
@@ -51,6 +55,19 @@ fn command_exists(cmd: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Resolve a command to the first executable file of that name in PATH
+pub fn resolve_command(cmd: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(cmd))
+        .find(|candidate| {
+            candidate
+                .metadata()
+                .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        })
+}
+
 /// Check if an npm package is globally installed
 fn npm_global_package_exists(package: &str) -> bool {
     Command::new("npm")
@@ -83,48 +100,91 @@ fn check_gemini_cli() -> Vec<Precondition> {
     }]
 }
 
+/// Check preconditions for a user-defined agent
+fn check_custom_agent(command: &str) -> Vec<Precondition> {
+    vec![Precondition {
+        description: "command found in PATH",
+        satisfied: command_exists(command),
+    }]
+}
+
 /// Check availability for a specific agent type
 pub fn check_agent(agent_type: AgentType) -> AgentAvailability {
-    let preconditions = match agent_type {
+    let preconditions = match &agent_type {
         AgentType::ClaudeCode => check_claude_code(),
         AgentType::GeminiCli => check_gemini_cli(),
+        AgentType::Custom(agent) => check_custom_agent(&agent.command),
     };
+    let binary_path = resolve_command(agent_type.command());
 
     AgentAvailability {
         agent_type,
         preconditions,
+        binary_path,
     }
 }
 
-/// Check availability for all supported agents
-pub fn check_all_agents() -> Vec<AgentAvailability> {
-    vec![
-        check_agent(AgentType::ClaudeCode),
-        check_agent(AgentType::GeminiCli),
-    ]
+/// Check availability for the built-in agents followed by the given custom agents
+pub fn check_all_agents(custom_agents: &[AgentType]) -> Vec<AgentAvailability> {
+    AgentType::BUILT_IN
+        .into_iter()
+        .chain(custom_agents.iter().cloned())
+        .map(check_agent)
+        .collect()
 }
 
 /// Get all agent types with their availability status
 #[allow(dead_code)]
 pub fn get_agents_with_status() -> Vec<(AgentType, bool)> {
-    check_all_agents()
+    check_all_agents(&[])
         .into_iter()
-        .map(|a| (a.agent_type, a.is_available()))
+        .map(|a| (a.agent_type.clone(), a.is_available()))
         .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::CustomAgent;
+    use std::sync::Arc;
 
     #[test]
     fn test_check_all_agents_returns_both() {
-        let agents = check_all_agents();
+        let agents = check_all_agents(&[]);
         assert_eq!(agents.len(), 2);
         assert!(agents.iter().any(|a| a.agent_type == AgentType::ClaudeCode));
         assert!(agents.iter().any(|a| a.agent_type == AgentType::GeminiCli));
     }
 
+    #[test]
+    fn test_check_all_agents_includes_custom() {
+        let custom = AgentType::Custom(Arc::new(CustomAgent {
+            name: "In-house".to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string()],
+            env: Default::default(),
+        }));
+        let agents = check_all_agents(std::slice::from_ref(&custom));
+        assert_eq!(agents.len(), 3);
+        let last = &agents[2];
+        assert_eq!(last.agent_type, custom);
+        assert!(last.is_available());
+        assert!(last.binary_path.is_some());
+    }
+
+    #[test]
+    fn test_missing_custom_agent_is_unavailable() {
+        let custom = AgentType::Custom(Arc::new(CustomAgent {
+            name: "Ghost".to_string(),
+            command: "amux-definitely-not-a-command".to_string(),
+            args: vec![],
+            env: Default::default(),
+        }));
+        let availability = check_agent(custom);
+        assert!(!availability.is_available());
+        assert!(availability.binary_path.is_none());
+    }
+
     #[test]
     fn test_availability_calculation() {
         let available = AgentAvailability {
@@ -139,6 +199,7 @@ mod tests {
                     satisfied: true,
                 },
             ],
+            binary_path: None,
         };
         assert!(available.is_available());
         assert_eq!(available.satisfied_count(), 2);
@@ -155,8 +216,17 @@ mod tests {
                     satisfied: false,
                 },
             ],
+            binary_path: None,
         };
         assert!(!unavailable.is_available());
         assert_eq!(unavailable.satisfied_count(), 1);
     }
+
+    #[test]
+    fn test_resolve_command() {
+        let sh = resolve_command("sh").expect("sh should be in PATH");
+        assert!(sh.is_absolute());
+        assert!(sh.ends_with("sh"));
+        assert!(resolve_command("amux-definitely-not-a-command").is_none());
+    }
 }
@@ -1,11 +1,47 @@
 #![allow(dead_code)]
 
-use super::state::{AgentType, Session};
+use std::fmt;
+
+use super::state::{AgentType, Session, SessionState};
 use crate::picker::Picker;
 
+/// How many sessions are in each state, e.g. for the terminal title
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SessionSummary {
+    pub idle: usize,
+    pub starting: usize,
+    pub prompting: usize,
+    pub needs_permission: usize,
+    pub asking: usize,
+}
+
+impl fmt::Display for SessionSummary {
+    /// "3 idle, 1 prompting, 1 needs permission", leaving out empty states
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [
+            (self.idle, "idle"),
+            (self.starting, "starting"),
+            (self.prompting, "prompting"),
+            (self.needs_permission, "needs permission"),
+            (self.asking, "asking"),
+        ]
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+        if parts.is_empty() {
+            write!(f, "no sessions")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
 pub struct SessionManager {
     sessions: Vec<Session>,
     selected: usize,
+    /// ID and index of the session selected before the current one
+    previous: Option<(String, usize)>,
 }
 
 impl Picker for SessionManager {
@@ -20,6 +56,11 @@ impl Picker for SessionManager {
     }
 
     fn set_selected_index(&mut self, index: usize) {
+        if index != self.selected
+            && let Some(current) = self.sessions.get(self.selected)
+        {
+            self.previous = Some((current.id.clone(), self.selected));
+        }
         self.selected = index;
     }
 }
@@ -29,6 +70,7 @@ impl SessionManager {
         Self {
             sessions: vec![],
             selected: 0,
+            previous: None,
         }
     }
 
@@ -43,6 +85,7 @@ impl SessionManager {
         Self {
             sessions,
             selected: 0,
+            previous: None,
         }
     }
 
@@ -65,7 +108,7 @@ impl SessionManager {
     pub fn add_session(&mut self, session: Session) {
         self.sessions.push(session);
         // Select the new session
-        self.selected = self.sessions.len() - 1;
+        self.set_selected_index(self.sessions.len() - 1);
     }
 
     pub fn remove_selected(&mut self) -> Option<Session> {
@@ -83,6 +126,54 @@ impl SessionManager {
         Some(removed)
     }
 
+    /// Select the next (or previous) session, wrapping around and skipping
+    /// archived sessions unless `include_archived`
+    pub fn select_adjacent(&mut self, forward: bool, include_archived: bool) {
+        let len = self.sessions.len();
+        for step in 1..=len {
+            let index = if forward {
+                (self.selected + step) % len
+            } else {
+                (self.selected + len - step) % len
+            };
+            if include_archived || !self.sessions[index].archived {
+                self.set_selected_index(index);
+                return;
+            }
+        }
+    }
+
+    /// Index of the session selected before the current one, for flipping
+    /// back and forth. If that session is gone, its nearest neighbour.
+    pub fn previous_index(&self) -> Option<usize> {
+        let (id, index) = self.previous.as_ref()?;
+        if let Some(index) = self.sessions.iter().position(|s| &s.id == id) {
+            return (index != self.selected).then_some(index);
+        }
+        let len = self.sessions.len();
+        let nearest = (*index).min(len.checked_sub(1)?);
+        if nearest != self.selected {
+            Some(nearest)
+        } else if nearest + 1 < len {
+            Some(nearest + 1)
+        } else {
+            nearest.checked_sub(1)
+        }
+    }
+
+    /// Remove a session by its unique ID, keeping the same session selected
+    pub fn remove_by_id(&mut self, id: &str) -> Option<Session> {
+        let index = self.sessions.iter().position(|s| s.id == id)?;
+        if index == self.selected {
+            return self.remove_selected();
+        }
+        let removed = self.sessions.remove(index);
+        if index < self.selected {
+            self.selected -= 1;
+        }
+        Some(removed)
+    }
+
     /// Find a session by its unique ID and return a mutable reference
     pub fn get_by_id_mut(&mut self, id: &str) -> Option<&mut Session> {
         self.sessions.iter_mut().find(|s| s.id == id)
@@ -92,4 +183,120 @@ impl SessionManager {
     pub fn get_by_id(&self, id: &str) -> Option<&Session> {
         self.sessions.iter().find(|s| s.id == id)
     }
+
+    /// Count the sessions in each state. A pending permission or question
+    /// counts over the state it interrupted.
+    pub fn summary(&self) -> SessionSummary {
+        let mut summary = SessionSummary::default();
+        for session in &self.sessions {
+            let count = if session.pending_permission.is_some() {
+                &mut summary.needs_permission
+            } else if session.pending_question.is_some() {
+                &mut summary.asking
+            } else {
+                match session.state {
+                    SessionState::Idle => &mut summary.idle,
+                    SessionState::Spawning | SessionState::Initializing => &mut summary.starting,
+                    SessionState::Prompting => &mut summary.prompting,
+                    SessionState::AwaitingPermission => &mut summary.needs_permission,
+                    SessionState::AwaitingUserInput => &mut summary.asking,
+                }
+            };
+            *count += 1;
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager_with(ids: &[&str]) -> SessionManager {
+        let mut manager = SessionManager::new();
+        for id in ids {
+            manager.add_session(Session::mock(id, id, AgentType::ClaudeCode, "main"));
+        }
+        manager
+    }
+
+    #[test]
+    fn test_previous_index_flips_between_two_sessions() {
+        let mut manager = manager_with(&["1", "2", "3", "4"]);
+        manager.select_index(0);
+        manager.select_index(2);
+        assert_eq!(manager.previous_index(), Some(0));
+        manager.select_index(0);
+        assert_eq!(manager.previous_index(), Some(2));
+
+        // Removing a session before it shifts indices, not the session
+        manager.remove_by_id("2");
+        assert_eq!(
+            manager.sessions()[manager.previous_index().unwrap()].id,
+            "3"
+        );
+    }
+
+    #[test]
+    fn test_previous_index_falls_back_to_neighbour() {
+        let mut manager = manager_with(&["1", "2", "3"]);
+        manager.select_index(1);
+        manager.select_index(0);
+        manager.remove_by_id("2");
+        // "3" moved into the removed session's place
+        assert_eq!(manager.previous_index(), Some(1));
+
+        manager.remove_by_id("3");
+        assert_eq!(manager.previous_index(), None);
+    }
+
+    #[test]
+    fn test_select_adjacent_skips_archived() {
+        let mut manager = SessionManager::new();
+        for id in ["1", "2", "3"] {
+            manager.add_session(Session::mock(id, id, AgentType::ClaudeCode, "main"));
+        }
+        manager.sessions_mut()[1].archived = true;
+        manager.select_index(0);
+
+        manager.select_adjacent(true, false);
+        assert_eq!(manager.selected_index(), 2);
+        manager.select_adjacent(true, false);
+        assert_eq!(manager.selected_index(), 0);
+        manager.select_adjacent(false, false);
+        assert_eq!(manager.selected_index(), 2);
+        manager.select_adjacent(false, true);
+        assert_eq!(manager.selected_index(), 1);
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut manager = SessionManager::new();
+        assert_eq!(manager.summary().to_string(), "no sessions");
+
+        for id in ["1", "2", "3", "4", "5"] {
+            manager.add_session(Session::mock(id, id, AgentType::ClaudeCode, "main"));
+        }
+        let sessions = manager.sessions_mut();
+        sessions[0].state = SessionState::Idle;
+        sessions[1].state = SessionState::Idle;
+        sessions[2].state = SessionState::Idle;
+        sessions[3].state = SessionState::Prompting;
+        sessions[4].state = SessionState::AwaitingPermission;
+        assert_eq!(
+            manager.summary().to_string(),
+            "3 idle, 1 prompting, 1 needs permission"
+        );
+    }
+
+    #[test]
+    fn test_select_adjacent_stays_when_nothing_else_is_visible() {
+        let mut manager = SessionManager::new();
+        manager.add_session(Session::mock("1", "1", AgentType::ClaudeCode, "main"));
+        manager.add_session(Session::mock("2", "2", AgentType::ClaudeCode, "main"));
+        manager.sessions_mut()[1].archived = true;
+        manager.select_index(0);
+        manager.select_adjacent(true, false);
+        assert_eq!(manager.selected_index(), 0);
+    }
 }
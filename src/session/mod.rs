@@ -1,12 +1,15 @@
 mod detection;
 mod manager;
 mod state;
+mod transcript;
 // mod scanner; // TODO: Enable when session/load ACP is supported
 
 pub use detection::{AgentAvailability, check_all_agents};
-pub use manager::SessionManager;
+pub use manager::{SessionManager, SessionSummary};
 pub use state::{
-    AgentType, OutputType, PendingPermission, PendingQuestion, PermissionMode, Session,
-    SessionState,
+    AgentType, CustomAgent, DEFAULT_MAX_SCROLLBACK, OutputLine, OutputType, PendingPermission,
+    PendingQuestion, PermissionMode, Session, SessionState, ToolPermissionRules,
+    is_edit_permission,
 };
+pub use transcript::Transcript;
 // pub use scanner::scan_resumable_sessions;
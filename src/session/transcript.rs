@@ -0,0 +1,253 @@
+//! Live transcripts of session output on disk.
+//!
+//! With `save_transcripts` set, each session's output is appended to
+//! `~/.amux/transcripts/<started>-<name>-<id>.log` as it arrives: one
+//! timestamped entry per output line, with streamed agent text written a line
+//! at a time as it streams. Thoughts are ephemeral and left out, and secrets
+//! are redacted like in the log.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+
+use crate::{log, state_file};
+
+use super::OutputType;
+
+/// Buffered writes reach the file at least this often while output arrives
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+struct TranscriptFile {
+    path: PathBuf,
+    /// `None` after a write error: the transcript stops, the session goes on
+    writer: Option<BufWriter<File>>,
+    last_flush: Instant,
+    /// Text of the current line, held back until it's complete so secrets
+    /// streamed in pieces are still redacted
+    pending: String,
+}
+
+impl TranscriptFile {
+    /// Add text, writing out the lines it completes
+    fn push(&mut self, text: &str) {
+        self.pending.push_str(text);
+        if let Some(end) = self.pending.rfind('\n') {
+            let lines: String = self.pending.drain(..=end).collect();
+            self.write(&lines);
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let mut result = writer.write_all(log::redact_secrets(text).as_bytes());
+        if result.is_ok() && self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            result = writer.flush();
+            self.last_flush = Instant::now();
+        }
+        if let Err(e) = result {
+            self.fail(e);
+        }
+    }
+
+    fn flush(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if !pending.is_empty() {
+            self.write(&pending);
+        }
+        if let Some(writer) = &mut self.writer
+            && let Err(e) = writer.flush()
+        {
+            self.fail(e);
+        }
+        self.last_flush = Instant::now();
+    }
+
+    fn fail(&mut self, e: std::io::Error) {
+        log::warn(&format!(
+            "Stopped writing transcript {}: {}",
+            self.path.display(),
+            e
+        ));
+        self.writer = None;
+    }
+}
+
+/// Shared handle to a session's transcript file
+#[derive(Debug, Clone)]
+pub struct Transcript(Arc<Mutex<TranscriptFile>>);
+
+impl Transcript {
+    /// Directory transcripts are written to
+    pub fn dir() -> PathBuf {
+        state_file::path("transcripts")
+    }
+
+    /// Start a transcript for a session in `dir`, logging (and otherwise
+    /// ignoring) failure to create the file
+    pub fn create(dir: &Path, session_name: &str, session_id: &str) -> Option<Self> {
+        let path = dir.join(file_name(session_name, session_id));
+        let file = std::fs::create_dir_all(dir)
+            .and_then(|()| File::options().create(true).append(true).open(&path));
+        match file {
+            Ok(file) => Some(Self(Arc::new(Mutex::new(TranscriptFile {
+                path,
+                writer: Some(BufWriter::new(file)),
+                last_flush: Instant::now(),
+                pending: String::new(),
+            })))),
+            Err(e) => {
+                log::warn(&format!(
+                    "Failed to create transcript {}: {}",
+                    path.display(),
+                    e
+                ));
+                None
+            }
+        }
+    }
+
+    /// Path of the transcript file
+    pub fn path(&self) -> PathBuf {
+        self.0
+            .lock()
+            .map(|file| file.path.clone())
+            .unwrap_or_default()
+    }
+
+    /// Start a new entry for an output line
+    pub fn add_line(&self, line_type: &OutputType, content: &str) {
+        let Some(text) = entry_text(line_type, content) else {
+            return;
+        };
+        let time = Local::now().format("%H:%M:%S");
+        self.push(&format!("\n[{}] {}", time, text));
+    }
+
+    /// Continue the current entry with streamed text
+    pub fn append(&self, text: &str) {
+        self.push(text);
+    }
+
+    /// Write out anything still buffered
+    pub fn flush(&self) {
+        if let Ok(mut file) = self.0.lock() {
+            file.flush();
+        }
+    }
+
+    fn push(&self, text: &str) {
+        if let Ok(mut file) = self.0.lock() {
+            file.push(text);
+        }
+    }
+}
+
+/// File name for a session's transcript, unique across runs
+fn file_name(session_name: &str, session_id: &str) -> String {
+    let name: String = format!("{}-{}", session_name, session_id)
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}-{}.log", Local::now().format("%Y%m%d-%H%M%S"), name)
+}
+
+/// How an output line reads in the transcript, or `None` to leave it out
+fn entry_text(line_type: &OutputType, content: &str) -> Option<String> {
+    let text = match line_type {
+        OutputType::Thought => return None,
+        OutputType::ToolCall {
+            name, description, ..
+        } => match description {
+            Some(description) => format!("● {} ({})", name, description),
+            None => format!("● {}", name),
+        },
+        OutputType::ToolOutput
+        | OutputType::DiffAdd
+        | OutputType::DiffRemove
+        | OutputType::DiffContext
+        | OutputType::DiffHeader => format!("  └ {}", content),
+        OutputType::BashCommand => format!("! {}", content),
+        OutputType::SystemMessage => format!("— {}", content),
+        OutputType::AgentStderr => format!("stderr: {}", content),
+        OutputType::Preamble => format!("preamble: {}", content),
+        OutputType::Text | OutputType::UserInput | OutputType::Error | OutputType::BashOutput => {
+            content.to_string()
+        }
+    };
+    // Spacing lines carry nothing worth a timestamp
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_timestamped_entries_and_streamed_text() {
+        let dir = std::env::temp_dir().join(format!("amux_transcript_test_{}", std::process::id()));
+        let transcript = Transcript::create(&dir, "my repo", "session_1").unwrap();
+        let path = transcript.path();
+        assert!(
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .ends_with("-my_repo-session_1.log")
+        );
+
+        transcript.add_line(&OutputType::UserInput, "> fix it");
+        transcript.add_line(&OutputType::Text, "");
+        transcript.add_line(&OutputType::Text, "On ");
+        transcript.append("it.");
+        transcript.add_line(&OutputType::Thought, "hmm");
+        transcript.add_line(&OutputType::ToolOutput, "ok");
+        transcript.flush();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<&str> = contents
+            .lines()
+            .skip(1)
+            .map(|line| &line["[00:00:00] ".len()..])
+            .collect();
+        assert_eq!(entries, vec!["> fix it", "On it.", "  └ ok"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_secrets_are_redacted_even_when_streamed_in_pieces() {
+        let dir =
+            std::env::temp_dir().join(format!("amux_transcript_redact_{}", std::process::id()));
+        let transcript = Transcript::create(&dir, "repo", "session_1").unwrap();
+        transcript.add_line(&OutputType::BashCommand, "GITHUB_TOKEN=abc123 gh pr list");
+        transcript.add_line(&OutputType::Text, "Using key sk-ant-");
+        transcript.append("abcdefghijklmnopqrstuvwxyz now");
+        transcript.flush();
+
+        let contents = std::fs::read_to_string(transcript.path()).unwrap();
+        assert!(contents.contains("GITHUB_TOKEN=[REDACTED] gh pr list"));
+        assert!(contents.contains("Using key [REDACTED] now"));
+        assert!(!contents.contains("abc123") && !contents.contains("abcdefghij"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unwritable_dir_gives_no_transcript() {
+        let file =
+            std::env::temp_dir().join(format!("amux_transcript_not_a_dir_{}", std::process::id()));
+        std::fs::write(&file, "").unwrap();
+        assert!(Transcript::create(&file, "repo", "session_1").is_none());
+        let _ = std::fs::remove_file(&file);
+    }
+}
@@ -0,0 +1,154 @@
+//! `/` completion for the agent's slash commands.
+//!
+//! Agents advertise their commands (like `/compact` or `/review`) with an
+//! `available_commands_update`. Typing `/` at the start of the prompt lists
+//! them, fuzzy-filtered by the name typed so far.
+
+use crate::acp::AgentCommand;
+use crate::picker::{Picker, fuzzy_match};
+
+/// The command name being typed: the text after a leading `/`, up to the cursor.
+///
+/// None once the cursor has moved past the command name.
+pub fn command_at_cursor(input: &str, cursor: usize) -> Option<&str> {
+    let name = input.get(..cursor)?.strip_prefix('/')?;
+    (!name.contains(char::is_whitespace)).then_some(name)
+}
+
+/// Commands named in the empty prompt's hint
+const HINT_COMMANDS: usize = 3;
+
+/// Hint naming the agent's first few commands, for an empty prompt:
+/// `/compact  /review  /init  …`. As many as fit in `width`, or `None`.
+pub fn command_hint(commands: &[AgentCommand], width: usize) -> Option<String> {
+    let mut hint = String::new();
+    let mut shown = 0;
+    for command in commands.iter().take(HINT_COMMANDS) {
+        let name = format!("/{}", command.name);
+        let separator = if hint.is_empty() { "" } else { "  " };
+        // Leave room for a trailing "  …"
+        if hint.chars().count() + separator.len() + name.chars().count() + 3 > width {
+            break;
+        }
+        hint.push_str(separator);
+        hint.push_str(&name);
+        shown += 1;
+    }
+    if shown == 0 {
+        return None;
+    }
+    if shown < commands.len() {
+        hint.push_str("  …");
+    }
+    Some(hint)
+}
+
+/// Open `/` completion in the prompt
+#[derive(Debug, Clone)]
+pub struct SlashCommandCompletion {
+    /// Name typed after the `/`
+    pub query: String,
+    /// Commands matching the query, best first
+    pub matches: Vec<AgentCommand>,
+    pub selected: usize,
+}
+
+impl SlashCommandCompletion {
+    pub fn new(query: &str, commands: &[AgentCommand]) -> Self {
+        let mut completion = Self {
+            query: query.to_string(),
+            matches: vec![],
+            selected: 0,
+        };
+        completion.update_matches(commands);
+        completion
+    }
+
+    /// The name typed after the `/` changed
+    pub fn set_query(&mut self, query: &str, commands: &[AgentCommand]) {
+        if self.query != query {
+            self.query = query.to_string();
+            self.update_matches(commands);
+        }
+    }
+
+    /// Rank commands by how well their name matches; ties keep the agent's order
+    fn update_matches(&mut self, commands: &[AgentCommand]) {
+        let mut scored: Vec<(i64, &AgentCommand)> = commands
+            .iter()
+            .filter_map(|command| fuzzy_match(&self.query, &command.name).map(|s| (s, command)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, c)| c.clone()).collect();
+        self.selected = 0;
+    }
+}
+
+impl Picker for SlashCommandCompletion {
+    type Item = AgentCommand;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.matches
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.selected = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(name: &str) -> AgentCommand {
+        AgentCommand {
+            name: name.to_string(),
+            description: String::new(),
+            input: None,
+        }
+    }
+
+    #[test]
+    fn test_command_at_cursor() {
+        assert_eq!(command_at_cursor("/comp", 5), Some("comp"));
+        assert_eq!(command_at_cursor("/", 1), Some(""));
+        assert_eq!(command_at_cursor("/review main", 12), None);
+        assert_eq!(command_at_cursor("see /review", 11), None);
+    }
+
+    #[test]
+    fn test_command_hint() {
+        let commands = [
+            command("compact"),
+            command("review"),
+            command("init"),
+            command("cost"),
+        ];
+        assert_eq!(
+            command_hint(&commands, 80).as_deref(),
+            Some("/compact  /review  /init  …")
+        );
+        assert_eq!(
+            command_hint(&commands[..2], 80).as_deref(),
+            Some("/compact  /review")
+        );
+        assert_eq!(command_hint(&commands, 16).as_deref(), Some("/compact  …"));
+        assert_eq!(command_hint(&commands, 5), None);
+        assert_eq!(command_hint(&[], 80), None);
+    }
+
+    #[test]
+    fn test_matches_filter_by_name() {
+        let commands = [command("compact"), command("review"), command("init")];
+        let mut completion = SlashCommandCompletion::new("", &commands);
+        assert_eq!(completion.matches.len(), 3);
+
+        completion.set_query("rev", &commands);
+        let names: Vec<_> = completion.matches.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["review"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Saved prompt snippets.
+//!
+//! Prompts worth reusing ("write tests for the changed files") are saved by
+//! name from the input and inserted again from a fuzzy picker. They live in
+//! `~/.amux/snippets.json`, written by amux whenever one is saved or deleted.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::picker::{Picker, fuzzy_match};
+use crate::{log, state_file};
+
+/// A named prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub text: String,
+}
+
+/// Every saved snippet, in the order they were saved
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snippets {
+    pub snippets: Vec<Snippet>,
+}
+
+impl Snippets {
+    /// Path of the snippets file
+    pub fn path() -> PathBuf {
+        state_file::path("snippets.json")
+    }
+
+    /// Load the saved snippets, falling back to none if missing or unreadable
+    pub fn load() -> Self {
+        Self::load_from(&Self::path())
+    }
+
+    fn load_from(path: &Path) -> Self {
+        state_file::load(path, "snippets")
+    }
+
+    /// Save the snippets, logging (but otherwise ignoring) failures
+    pub fn save(&self) {
+        if let Err(e) = state_file::save(self, &Self::path()) {
+            log::warn(&format!("Failed to save snippets: {}", e));
+        }
+    }
+
+    /// Add a snippet, replacing the text of one with the same name
+    pub fn insert(&mut self, name: &str, text: &str) {
+        match self.snippets.iter_mut().find(|s| s.name == name) {
+            Some(snippet) => snippet.text = text.to_string(),
+            None => self.snippets.push(Snippet {
+                name: name.to_string(),
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    /// Remove the snippet with this name
+    pub fn remove(&mut self, name: &str) {
+        self.snippets.retain(|s| s.name != name);
+    }
+}
+
+/// State for the snippet picker
+#[derive(Debug, Clone)]
+pub struct SnippetPickerState {
+    /// Every saved snippet
+    pub snippets: Vec<Snippet>,
+    /// Indices into `snippets` matching the query, best match first
+    pub filtered: Vec<usize>,
+    /// Filter query string
+    pub query: String,
+    pub selected: usize,
+}
+
+impl SnippetPickerState {
+    pub fn new(snippets: Vec<Snippet>) -> Self {
+        let filtered = (0..snippets.len()).collect();
+        Self {
+            snippets,
+            filtered,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Update the filtered list based on the current query.
+    /// A snippet scores by its better match of name and text; ties keep saved order.
+    pub fn update_filter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .snippets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| {
+                let name = fuzzy_match(&self.query, &s.name);
+                let text = fuzzy_match(&self.query, &s.text);
+                name.max(text).map(|score| (score, i))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.filtered = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    /// Add a character to the query
+    pub fn query_input_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_filter();
+    }
+
+    /// Delete the last character of the query
+    pub fn query_backspace(&mut self) {
+        if self.query.pop().is_some() {
+            self.update_filter();
+        }
+    }
+
+    /// The highlighted snippet
+    pub fn selected_snippet(&self) -> Option<&Snippet> {
+        self.selected_item().and_then(|&i| self.snippets.get(i))
+    }
+}
+
+impl Picker for SnippetPickerState {
+    type Item = usize;
+
+    fn items(&self) -> &[Self::Item] {
+        &self.filtered
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    fn set_selected_index(&mut self, index: usize) {
+        self.selected = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        state_file::temp_path("snippets", name)
+    }
+
+    #[test]
+    fn test_round_trip_and_replace_by_name() {
+        let path = temp_path("round_trip.json");
+        let mut snippets = Snippets::default();
+        snippets.insert("tests", "write tests");
+        snippets.insert("review", "review the diff");
+        snippets.insert("tests", "write tests for the changed files");
+        assert_eq!(snippets.snippets.len(), 2);
+        assert_eq!(
+            snippets.snippets[0].text,
+            "write tests for the changed files"
+        );
+
+        state_file::save(&snippets, &path).unwrap();
+        assert_eq!(Snippets::load_from(&path), snippets);
+        let _ = std::fs::remove_file(&path);
+
+        snippets.remove("tests");
+        assert_eq!(snippets.snippets[0].name, "review");
+    }
+
+    #[test]
+    fn test_missing_file_has_no_snippets() {
+        assert!(
+            Snippets::load_from(&temp_path("missing.json"))
+                .snippets
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_picker_filters_by_name_and_text() {
+        let mut snippets = Snippets::default();
+        snippets.insert("tests", "write tests for the changed files");
+        snippets.insert("review", "review the diff");
+        let mut picker = SnippetPickerState::new(snippets.snippets);
+
+        "diff".chars().for_each(|c| picker.query_input_char(c));
+        assert_eq!(picker.selected_snippet().unwrap().name, "review");
+
+        picker.query = "zzz".to_string();
+        picker.update_filter();
+        assert!(picker.selected_snippet().is_none());
+    }
+}
@@ -0,0 +1,52 @@
+//! JSON files amux writes itself in `~/.amux`, such as the UI preferences
+//! and saved snippets.
+//!
+//! Loading never fails: a missing file gives the defaults, and an unreadable
+//! one is logged and ignored so a bad write can't keep amux from starting.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::log;
+
+/// Path of the state file `name` in `~/.amux` (a temporary directory in
+/// tests, so they never touch the real one)
+pub fn path(name: &str) -> PathBuf {
+    if cfg!(test) {
+        return temp_path("home", name);
+    }
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".amux")
+        .join(name)
+}
+
+/// Read a state file, falling back to the default if it's missing or
+/// unreadable. `what` names the contents in the log.
+pub fn load<T: DeserializeOwned + Default>(path: &Path, what: &str) -> T {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return T::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        log::warn(&format!("Ignoring unreadable {} {:?}: {}", what, path, e));
+        T::default()
+    })
+}
+
+/// Write a state file, creating its directory if needed
+pub fn save<T: Serialize>(value: &T, path: &Path) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, json)
+}
+
+/// A path for a test's state file, in a directory per test module and process
+pub fn temp_path(module: &str, name: &str) -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("amux_{}_test_{}", module, std::process::id()))
+        .join(name)
+}
@@ -13,7 +13,7 @@ use crate::session::AgentType;
 use crate::tui::theme::*;
 
 /// Render the agent picker as a centered popup.
-pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
+pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &mut App) {
     // Calculate centered popup area
     let popup_width = 50u16.min(area.width.saturating_sub(4));
     let popup_height = 16u16.min(area.height.saturating_sub(4));
@@ -29,6 +29,9 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
     // Track cursor position for filter input
     let mut cursor_position: Option<(u16, u16)> = None;
 
+    // Agents that fit in the list, used for paging
+    let mut shown_agents = 0;
+
     if let Some(picker) = &app.agent_picker {
         // Header with selected directory
         let folder_name = picker
@@ -64,7 +67,10 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
             ));
         }
 
+        // Lines of each agent, so whole agents can be scrolled out of view
+        let mut entries: Vec<Vec<Line>> = vec![];
         for (i, availability) in picker.filtered.iter().enumerate() {
+            let mut entry: Vec<Line> = vec![];
             let is_selected = i == picker.selected;
             let is_available = availability.is_available();
             let cursor = if is_selected { "> " } else { "  " };
@@ -72,6 +78,7 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
             let (icon, color) = match availability.agent_type {
                 AgentType::ClaudeCode => ("", LOGO_CORAL), // Anthropic orange-ish
                 AgentType::GeminiCli => ("", LOGO_LIGHT_BLUE), // Google blue
+                AgentType::Custom(_) => ("󰚩", LOGO_GOLD),  // Robot for user-defined agents
             };
 
             let name = availability.agent_type.display_name();
@@ -85,7 +92,7 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
                 Style::new().fg(TEXT_WHITE)
             };
 
-            lines.push(Line::from(vec![
+            entry.push(Line::from(vec![
                 Span::styled(
                     cursor,
                     if is_selected {
@@ -113,7 +120,7 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
                     ("✗", Color::Red)
                 };
 
-                lines.push(Line::from(vec![
+                entry.push(Line::from(vec![
                     Span::raw("      "), // indent
                     Span::styled(mark, Style::new().fg(mark_color)),
                     Span::raw(" "),
@@ -123,8 +130,27 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
 
             // Add spacing between agents
             if i < picker.filtered.len() - 1 {
-                lines.push(Line::raw(""));
+                entry.push(Line::raw(""));
             }
+            entries.push(entry);
+        }
+
+        // Scroll just far enough to keep the selected agent in view
+        let available_height = popup_height.saturating_sub(8) as usize; // header, filter, spacing, help
+        let fits =
+            |range: &[Vec<Line>]| range.iter().map(Vec::len).sum::<usize>() <= available_height;
+        let mut scroll_offset = 0;
+        while scroll_offset < picker.selected
+            && !fits(&entries[scroll_offset..=picker.selected.min(entries.len() - 1)])
+        {
+            scroll_offset += 1;
+        }
+        for entry in entries.into_iter().skip(scroll_offset) {
+            if lines.len() + entry.len() > available_height + 4 {
+                break;
+            }
+            lines.extend(entry);
+            shown_agents += 1;
         }
 
         // Pad to fill available space
@@ -154,6 +180,11 @@ pub fn render_agent_picker(frame: &mut Frame, area: Rect, app: &App) {
     let paragraph = Paragraph::new(lines).block(block);
     frame.render_widget(paragraph, popup_area);
 
+    if let Some(picker) = &mut app.agent_picker {
+        // Update viewport height for paging
+        picker.viewport_height = shown_agents;
+    }
+
     // Set cursor position for filter input
     if let Some((x, y)) = cursor_position {
         frame.set_cursor_position(Position::new(x, y));
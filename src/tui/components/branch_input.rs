@@ -23,18 +23,49 @@ pub fn render_branch_input(frame: &mut Frame, area: Rect, app: &App) {
             .unwrap_or("unknown");
 
         // Header
-        lines.push(Line::from(vec![
-            Span::styled("Create worktree in ", Style::new().fg(TEXT_DIM)),
+        let action = if branch_state.agent_type.is_some() {
+            "Duplicate session into worktree of "
+        } else {
+            "Create worktree in "
+        };
+        let mut header = vec![
+            Span::styled(action, Style::new().fg(TEXT_DIM)),
             Span::styled(repo_name, Style::new().fg(LOGO_LIGHT_BLUE).bold()),
-        ]));
+        ];
+        if let Some(base) = &branch_state.base {
+            header.push(Span::styled(" from ", Style::new().fg(TEXT_DIM)));
+            header.push(Span::styled(&base.name, Style::new().fg(BRANCH_GREEN)));
+        }
+        lines.push(Line::from(header));
         lines.push(Line::raw(""));
 
-        // Branch input line
+        // Branch input line ("Base:  " lines up with "Branch: ")
+        let label = if branch_state.picking_base {
+            "Base:   "
+        } else {
+            "Branch: "
+        };
         lines.push(Line::from(vec![
-            Span::styled("Branch: ", Style::new().fg(TEXT_DIM)),
+            Span::styled(label, Style::new().fg(TEXT_DIM)),
             Span::styled(&branch_state.input, Style::new().fg(TEXT_WHITE)),
         ]));
 
+        // Validation error, with a sanitized suggestion when one exists
+        if let Some(error) = branch_state.error {
+            lines.push(Line::from(vec![Span::styled(
+                format!("✗ {}", error),
+                Style::new().fg(LOGO_CORAL),
+            )]));
+            if let Some(suggestion) = &branch_state.suggestion {
+                lines.push(Line::from(vec![
+                    Span::styled("  Suggestion: ", Style::new().fg(TEXT_DIM)),
+                    Span::styled(suggestion, Style::new().fg(BRANCH_GREEN)),
+                    Span::styled(" [Tab]", Style::new().fg(TEXT_WHITE)),
+                    Span::styled(" accept", Style::new().fg(TEXT_DIM)),
+                ]));
+            }
+        }
+
         // Autocomplete dropdown
         if branch_state.show_autocomplete && !branch_state.filtered.is_empty() {
             lines.push(Line::raw(""));
@@ -92,7 +123,14 @@ pub fn render_branch_input(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("[Tab]", Style::new().fg(TEXT_WHITE)),
             Span::styled(" complete · ", Style::new().fg(TEXT_DIM)),
             Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
-            Span::styled(" create · ", Style::new().fg(TEXT_DIM)),
+            Span::styled(
+                if branch_state.picking_base {
+                    " branch from · "
+                } else {
+                    " create · "
+                },
+                Style::new().fg(TEXT_DIM),
+            ),
             Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
             Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
         ]));
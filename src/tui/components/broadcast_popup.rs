@@ -0,0 +1,133 @@
+//! Broadcast prompt popup component.
+
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, BroadcastDelivery};
+use crate::tui::theme::*;
+
+/// Maximum number of sessions listed before collapsing the rest
+const MAX_LISTED_SESSIONS: usize = 12;
+
+/// Render the popup for sending one prompt to every session.
+pub fn render_broadcast_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(broadcast) = &app.broadcast else {
+        return;
+    };
+
+    // Either what happened, or what would happen if sent now
+    let targets: Vec<(String, BroadcastDelivery)> = match &broadcast.results {
+        Some(results) => results.clone(),
+        None => app
+            .sessions
+            .sessions()
+            .iter()
+            .map(|s| (s.name.clone(), app.broadcast_delivery(s)))
+            .collect(),
+    };
+    let sent = broadcast.results.is_some();
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Title
+    let title = if sent {
+        "Broadcast Sent"
+    } else {
+        "Broadcast to All Sessions"
+    };
+    lines.push(Line::from(vec![Span::styled(
+        title,
+        Style::new().fg(LOGO_LIGHT_BLUE).bold(),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Prompt input
+    lines.push(Line::from(vec![
+        Span::styled("> ", Style::new().fg(LOGO_LIGHT_BLUE)),
+        Span::styled(broadcast.input.as_str(), Style::new().fg(TEXT_WHITE)),
+    ]));
+    lines.push(Line::raw(""));
+
+    // Per-session delivery
+    for (name, delivery) in targets.iter().take(MAX_LISTED_SESSIONS) {
+        let (icon, label, color) = match (delivery, sent) {
+            (BroadcastDelivery::Send, true) => ("✓", "sent", LOGO_MINT),
+            (BroadcastDelivery::Send, false) => ("→", "idle", LOGO_MINT),
+            (BroadcastDelivery::Queue, true) => ("⏸", "queued", LOGO_GOLD),
+            (BroadcastDelivery::Queue, false) => ("⏸", "busy, will queue", LOGO_GOLD),
+            (BroadcastDelivery::Skip, true) => ("–", "skipped", TEXT_DIM),
+            (BroadcastDelivery::Skip, false) => ("–", "busy, will skip", TEXT_DIM),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", icon), Style::new().fg(color)),
+            Span::styled(name.as_str(), Style::new().fg(TEXT_WHITE)),
+            Span::styled(format!("  {}", label), Style::new().fg(TEXT_DIM)),
+        ]));
+    }
+    if targets.len() > MAX_LISTED_SESSIONS {
+        lines.push(Line::styled(
+            format!("  … {} more", targets.len() - MAX_LISTED_SESSIONS),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+    lines.push(Line::raw(""));
+
+    // Footer
+    if sent {
+        lines.push(Line::from(vec![
+            Span::styled("[Enter/Esc]", Style::new().fg(TEXT_WHITE)),
+            Span::styled(" close", Style::new().fg(TEXT_DIM)),
+        ]));
+    } else {
+        let reachable = targets
+            .iter()
+            .filter(|(_, d)| *d != BroadcastDelivery::Skip)
+            .count();
+        lines.push(Line::from(vec![
+            Span::styled("[Enter]", Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(
+                format!(" send to {} session(s)  ", reachable),
+                Style::new().fg(TEXT_DIM),
+            ),
+            Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+            Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
+        ]));
+    }
+
+    // Calculate centered popup area, tall enough for every line plus borders
+    let popup_width = 60u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+
+    // Cursor at the end of the prompt while typing
+    if !sent {
+        let cursor_x = popup_area.x + 1 + 2 + broadcast.input.chars().count() as u16;
+        frame.set_cursor_position(Position::new(
+            cursor_x.min(popup_area.right().saturating_sub(2)),
+            popup_area.y + 1 + 2, // +1 for border, +2 for title and blank line
+        ));
+    }
+}
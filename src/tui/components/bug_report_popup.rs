@@ -13,8 +13,18 @@ use crate::tui::theme::*;
 
 use super::wrap_text;
 
+/// Body lines shown in the preview before the rest is summarized
+const PREVIEW_BODY_LINES: usize = 10;
+
 /// Render the bug report popup.
 pub fn render_bug_report_popup(frame: &mut Frame, area: Rect, app: &App) {
+    if let Some(bug_report) = &app.bug_report
+        && let Some(body) = &bug_report.preview
+    {
+        render_preview(frame, area, &app.bug_report_repo, &bug_report.title(), body);
+        return;
+    }
+
     // Calculate centered popup area
     let popup_width = 60u16;
     let popup_height = 12u16;
@@ -88,7 +98,7 @@ pub fn render_bug_report_popup(frame: &mut Frame, area: Rect, app: &App) {
     // Footer
     lines.push(Line::from(vec![
         Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
-        Span::styled(" submit  ", Style::new().fg(TEXT_DIM)),
+        Span::styled(" preview  ", Style::new().fg(TEXT_DIM)),
         Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
         Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
     ]));
@@ -117,3 +127,62 @@ pub fn render_bug_report_popup(frame: &mut Frame, area: Rect, app: &App) {
         frame.set_cursor_position(Position::new(cursor_x, cursor_y));
     }
 }
+
+/// Render the issue about to be filed, asking for confirmation.
+fn render_preview(frame: &mut Frame, area: Rect, repo: &str, title: &str, body: &str) {
+    let popup_width = 72u16.min(area.width);
+    let popup_height = (PREVIEW_BODY_LINES as u16 + 10).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width.saturating_sub(popup_width)) / 2,
+        area.y + (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+    frame.render_widget(Clear, popup_area);
+
+    let text_width = popup_width.saturating_sub(2) as usize;
+    let truncate = |text: &str| -> String {
+        if text.chars().count() > text_width {
+            let short: String = text.chars().take(text_width.saturating_sub(1)).collect();
+            format!("{}…", short)
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            Span::styled("File this issue in ", Style::new().fg(LOGO_CORAL).bold()),
+            Span::styled(repo.to_string(), Style::new().fg(LOGO_LIGHT_BLUE).bold()),
+            Span::styled("?", Style::new().fg(LOGO_CORAL).bold()),
+        ]),
+        Line::raw(""),
+        Line::styled(truncate(title), Style::new().fg(TEXT_WHITE).bold()),
+        Line::raw(""),
+    ];
+
+    let body_lines: Vec<&str> = body.lines().collect();
+    for line in body_lines.iter().take(PREVIEW_BODY_LINES) {
+        lines.push(Line::styled(truncate(line), Style::new().fg(TEXT_DIM)));
+    }
+    if body_lines.len() > PREVIEW_BODY_LINES {
+        lines.push(Line::styled(
+            format!("… {} more lines", body_lines.len() - PREVIEW_BODY_LINES),
+            Style::new().fg(TEXT_DIM).italic(),
+        ));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" submit with gh  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" edit", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_CORAL))
+        .style(Style::new().bg(Color::Black));
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -21,7 +21,7 @@ pub fn render_clear_confirm_popup(frame: &mut Frame, area: Rect, app: &App) {
 
     // Calculate centered popup area
     let popup_width = 50u16;
-    let popup_height = 8u16;
+    let popup_height = 9u16;
     let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
     let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
     let popup_area = Rect::new(
@@ -52,6 +52,11 @@ pub fn render_clear_confirm_popup(frame: &mut Frame, area: Rect, app: &App) {
         "All conversation history will be lost.",
         Style::new().fg(TEXT_DIM),
     )]));
+    lines.push(Line::from(vec![
+        Span::styled("Use ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[C]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" to clear the output only.", Style::new().fg(TEXT_DIM)),
+    ]));
     lines.push(Line::raw(""));
 
     // Footer with options
@@ -0,0 +1,108 @@
+//! `@` file and `/` command completion popup component.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::tui::theme::*;
+
+/// Maximum number of matches listed at once
+const MAX_LISTED_MATCHES: usize = 8;
+
+/// A completion row: what gets inserted, and a dim description after it
+type Row<'a> = (String, &'a str);
+
+/// Render file or command completions just above the prompt area.
+pub fn render_completion_popup(frame: &mut Frame, prompt_area: Rect, app: &App) {
+    let (title, rows, selected, placeholder): (_, Vec<Row>, _, _) =
+        if let Some(mention) = &app.mention {
+            let placeholder = if mention.files.is_none() {
+                "  Scanning…"
+            } else {
+                "  (no matching files)"
+            };
+            let rows = mention.matches.iter().map(|p| (p.clone(), "")).collect();
+            (" Files ", rows, mention.selected, placeholder)
+        } else if let Some(completion) = &app.slash_command {
+            let rows = completion
+                .matches
+                .iter()
+                .map(|c| (format!("/{}", c.name), c.description.as_str()))
+                .collect();
+            (
+                " Commands ",
+                rows,
+                completion.selected,
+                "  (no matching commands)",
+            )
+        } else {
+            return;
+        };
+
+    let mut lines: Vec<Line> = vec![];
+    if rows.is_empty() {
+        lines.push(Line::styled(placeholder, Style::new().fg(TEXT_DIM)));
+    }
+
+    // Keep the selection visible by scrolling the window of matches
+    let start = selected.saturating_sub(MAX_LISTED_MATCHES.saturating_sub(1));
+    for (i, (label, description)) in rows.iter().enumerate().skip(start).take(MAX_LISTED_MATCHES) {
+        let is_selected = i == selected;
+        let cursor = if is_selected { "> " } else { "  " };
+        let label_style = if is_selected {
+            Style::new().fg(TEXT_WHITE).bold()
+        } else {
+            Style::new().fg(TEXT_WHITE)
+        };
+        let mut spans = vec![
+            Span::styled(cursor, Style::new().fg(LOGO_MINT)),
+            Span::styled(label.clone(), label_style),
+        ];
+        if !description.is_empty() {
+            spans.push(Span::styled(
+                format!("  {}", description),
+                Style::new().fg(TEXT_DIM),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if rows.len() > start + MAX_LISTED_MATCHES {
+        lines.push(Line::styled(
+            format!("  … {} more", rows.len() - start - MAX_LISTED_MATCHES),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    // Sit on top of the prompt, as wide as the longest line allows
+    let widest = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let popup_width = (widest + 4).clamp(30, prompt_area.width);
+    let popup_height = (lines.len() as u16 + 2).min(prompt_area.y);
+    let popup_area = Rect::new(
+        prompt_area.x,
+        prompt_area.y - popup_height,
+        popup_width,
+        popup_height,
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::new().fg(LOGO_MINT).bold())
+        .title_bottom(Line::styled(
+            " Tab insert · Esc close ",
+            Style::new().fg(TEXT_DIM),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_MINT))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+}
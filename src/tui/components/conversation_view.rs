@@ -1,27 +1,218 @@
 //! Conversation view component - main chat/output display with markdown rendering.
 
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::Style,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::Paragraph,
+    widgets::{Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
 };
 
-use crate::app::{App, ClickRegion};
+use crate::app::{App, ClickRegion, HeadingStyle, InputMode};
 use crate::events::Action;
-use crate::session::{OutputType, SessionState};
+use crate::file_ref::{FileLocation, find_file_refs, relative_paths};
+use crate::scroll::ScrollAnchor;
+use crate::session::{OutputLine, OutputType, Session, SessionState};
 use crate::tui::theme::*;
 
+use super::markdown::render_markdown;
 use super::wrap_text;
 
+/// Width of the `HH:MM:SS ` prefix shown when timestamps are on
+const TIMESTAMP_WIDTH: usize = 9;
+
+/// Dim time prefix for the first visual line of an output entry
+fn timestamp_span(time: SystemTime) -> Span<'static> {
+    let local: chrono::DateTime<chrono::Local> = time.into();
+    Span::styled(
+        format!("{} ", local.format("%H:%M:%S")),
+        Style::new().fg(TEXT_DIM),
+    )
+}
+
+/// Settings every rendered line depends on; changing one rewraps everything
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RenderSettings {
+    /// Width available to content (after the timestamp column, if shown)
+    width: usize,
+    debug_tool_json: bool,
+    show_timestamps: bool,
+    heading_style: HeadingStyle,
+    /// Tool paths under the session dir are shown relative to it
+    relative_paths: bool,
+}
+
+/// Outdated cache entries tolerated before they are swept out
+const STALE_ENTRIES: usize = 64;
+
+/// Rendered lines of one output line
+#[derive(Debug, Clone)]
+struct CachedLines {
+    /// Whether it was rendered as a collapsed tool call
+    collapsed: bool,
+    lines: Arc<Vec<Line<'static>>>,
+}
+
+/// What a laid-out conversation depends on besides the output lines
+#[derive(Debug, Clone, PartialEq)]
+struct ViewKey {
+    settings: RenderSettings,
+    collapse_tools: bool,
+    expanded_tool: Option<String>,
+    /// Output filter, while applied
+    filter: Option<String>,
+}
+
+/// Layout state just before an output line, where laying out can resume
+#[derive(Debug, Clone, Copy)]
+struct ResumePoint {
+    /// Revision of the output line when it was laid out
+    revision: u64,
+    chunks: usize,
+    total_lines: usize,
+    /// Whether the tool call above is collapsed
+    in_collapsed_tool: bool,
+    /// Last output line that took up space
+    last_shown: Option<usize>,
+}
+
+/// A session's rendered output, reused across frames.
+///
+/// Rendered lines are keyed by [`OutputLine::revision`], so a frame only wraps
+/// output lines that are new or changed since the last one; entries for lines
+/// that changed or left the output are swept out now and then. The layout is
+/// kept too, and redone only from the first output line that changed.
+#[derive(Debug, Clone, Default)]
+pub struct ConversationCache {
+    view: Option<ViewKey>,
+    entries: HashMap<u64, CachedLines>,
+    layout: OutputLayout,
+    /// Resume point of each output line, plus one for the end of the output
+    resume: Vec<ResumePoint>,
+    /// Output line of the tool call that had a spinner in the last frame
+    spinning: Option<usize>,
+    /// Output lines rendered rather than taken from the cache in the last frame
+    misses: usize,
+}
+
+/// Everything besides the output line itself that its rendering depends on
+struct EntryContext<'a> {
+    settings: RenderSettings,
+    /// Tool call folded to its title
+    collapsed: bool,
+    /// Spinner frame, for the tool call that is running
+    spinner: Option<&'a str>,
+    /// Directory tool paths are shown relative to, if they are
+    cwd: Option<&'a Path>,
+}
+
+/// The output as rendered chunks of lines, laid out without copying them
+#[derive(Debug, Clone, Default)]
+struct OutputLayout {
+    chunks: Vec<Arc<Vec<Line<'static>>>>,
+    /// Visual line index where each chunk starts
+    chunk_starts: Vec<usize>,
+    /// Visual line index where each output line starts
+    output_starts: Vec<usize>,
+    total_lines: usize,
+}
+
+impl OutputLayout {
+    /// Drop everything after the first `chunks` chunks
+    fn truncate(&mut self, chunks: usize, total_lines: usize) {
+        self.chunks.truncate(chunks);
+        self.chunk_starts.truncate(chunks);
+        self.total_lines = total_lines;
+    }
+
+    fn push(&mut self, lines: Arc<Vec<Line<'static>>>) {
+        self.chunk_starts.push(self.total_lines);
+        self.total_lines += lines.len();
+        self.chunks.push(lines);
+    }
+
+    /// Copy out the visual lines in `range`
+    fn lines(&self, range: Range<usize>) -> Vec<Line<'static>> {
+        let first = self
+            .chunk_starts
+            .partition_point(|&start| start <= range.start)
+            .saturating_sub(1);
+        let mut lines = Vec::with_capacity(range.len());
+        for (chunk, &chunk_start) in self.chunks[first..].iter().zip(&self.chunk_starts[first..]) {
+            if chunk_start >= range.end {
+                break;
+            }
+            let skip = range.start.saturating_sub(chunk_start);
+            let take = (range.end - chunk_start)
+                .min(chunk.len())
+                .saturating_sub(skip);
+            lines.extend(chunk.iter().skip(skip).take(take).cloned());
+        }
+        lines
+    }
+}
+
 /// Render the conversation view showing agent messages.
 pub fn render_conversation_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    // On wide terminals, a centered column keeps lines short enough to read
+    let area = match app.max_conversation_width {
+        Some(max_width) if area.width > max_width => Rect {
+            x: area.x + (area.width - max_width) / 2,
+            width: max_width,
+            ..area
+        },
+        _ => area,
+    };
+    // Reserve the top line for the filter bar while filtering or typing a filter
+    let show_filter_bar = app.input_mode == InputMode::OutputFilter
+        || app.selected_session().is_some_and(|s| s.filter_active);
+    let area = if show_filter_bar {
+        let layout = Layout::vertical([
+            Constraint::Length(1), // Filter bar
+            Constraint::Min(0),    // Output
+        ])
+        .split(area);
+        render_filter_bar(frame, layout[0], app);
+        app.viewport_height = layout[1].height as usize;
+        layout[1]
+    } else {
+        area
+    };
+
     let inner_height = area.height as usize;
     let inner_width = area.width.saturating_sub(2) as usize; // Account for border
 
     // Track total rendered lines to update session afterwards
     let mut computed_total_lines: Option<usize> = None;
+    let mut computed_last_visible: Option<usize> = None;
+    let mut computed_scroll: Option<usize> = None;
+    let mut computed_anchor: Option<ScrollAnchor> = None;
+    // First visible line and total lines, when the output overflows the viewport
+    let mut scroll_position: Option<(usize, usize)> = None;
+
+    // Timestamps take a fixed column, so content wraps to what's left
+    let settings = RenderSettings {
+        width: if app.show_timestamps {
+            inner_width.saturating_sub(TIMESTAMP_WIDTH)
+        } else {
+            inner_width
+        },
+        debug_tool_json: app.debug_tool_json,
+        show_timestamps: app.show_timestamps,
+        heading_style: app.heading_style,
+        relative_paths: !app.absolute_paths,
+    };
+    let mut cache = app
+        .sessions
+        .selected_session_mut()
+        .map(|session| std::mem::take(&mut session.render_cache))
+        .unwrap_or_default();
 
     let lines: Vec<Line> = if let Some(session) = app.selected_session() {
         if session.output.is_empty() {
@@ -39,316 +230,41 @@ pub fn render_conversation_view(frame: &mut Frame, area: Rect, app: &mut App) {
             };
             vec![Line::styled(status, Style::new().fg(TEXT_DIM))]
         } else {
-            // Get active tool call ID and spinner for rendering
-            let active_tool_id = session.active_tool_call_id.as_deref();
-            let spinner = app.spinner();
-            let debug_tool_json = app.debug_tool_json;
-
-            // First expand all output to visual lines
-            let mut all_lines: Vec<Line> = vec![];
-            let mut last_line_type: Option<&OutputType> = None;
-
-            for output_line in session.output.iter() {
-                let mut lines_for_output: Vec<Line> = match &output_line.line_type {
-                    OutputType::Text => {
-                        // Empty lines for spacing
-                        if output_line.content.is_empty() {
-                            vec![Line::raw("")]
-                        } else {
-                            // Agent response - render as markdown using ratskin/termimad
-                            let skin = ratskin::RatSkin::default();
-                            skin.parse(
-                                ratskin::RatSkin::parse_text(&output_line.content),
-                                inner_width as u16,
-                            )
-                        }
-                    }
-
-                    OutputType::UserInput => {
-                        // User prompt - cyan/blue
-                        let wrapped = wrap_text(&output_line.content, inner_width);
-                        wrapped
-                            .into_iter()
-                            .map(|text| {
-                                Line::from(vec![Span::styled(
-                                    text,
-                                    Style::new().fg(LOGO_LIGHT_BLUE).bold(),
-                                )])
-                            })
-                            .collect()
-                    }
-
-                    OutputType::Thought => {
-                        // Agent thinking - just show lightbulb and "Thinking..."
-                        vec![Line::from(vec![
-                            Span::styled("💡 ", Style::new().fg(LOGO_GOLD)),
-                            Span::styled("Thinking...", Style::new().fg(LOGO_GOLD).italic()),
-                        ])]
-                    }
-                    OutputType::ToolCall {
-                        tool_call_id,
-                        name,
-                        description,
-                        failed,
-                        raw_json,
-                    } => {
-                        // Tool call - spinner if active, red dot if failed, green dot if complete
-                        let is_active = active_tool_id == Some(tool_call_id.as_str());
-                        let (indicator, indicator_color) = if is_active {
-                            (format!("{} ", spinner), TOOL_DOT)
-                        } else if *failed {
-                            ("● ".to_string(), LOGO_CORAL)
-                        } else {
-                            ("● ".to_string(), TOOL_DOT)
-                        };
-                        // Use the name (title) directly, rendered as markdown
-                        let _ = description; // unused for now
-                        let skin = ratskin::RatSkin::default();
-                        let parsed_lines = skin.parse(
-                            ratskin::RatSkin::parse_text(name),
-                            inner_width.saturating_sub(2) as u16,
-                        );
-                        let mut lines: Vec<Line> = parsed_lines
-                            .into_iter()
-                            .enumerate()
-                            .map(|(i, mut line)| {
-                                let prefix = if i == 0 {
-                                    Span::styled(
-                                        indicator.clone(),
-                                        Style::new().fg(indicator_color),
-                                    )
-                                } else {
-                                    Span::styled("  ", Style::new().fg(indicator_color))
-                                };
-                                line.spans.insert(0, prefix);
-                                line
-                            })
-                            .collect();
-
-                        // If debug mode is on, render all raw JSON requests below the tool call
-                        if debug_tool_json {
-                            for json in raw_json {
-                                for json_line in json.lines() {
-                                    // Truncate long lines rather than wrap to preserve indentation
-                                    let max_len = inner_width.saturating_sub(4);
-                                    let display_line = if json_line.len() > max_len {
-                                        format!("{}…", &json_line[..max_len.saturating_sub(1)])
-                                    } else {
-                                        json_line.to_string()
-                                    };
-                                    lines.push(Line::from(vec![
-                                        Span::styled("  │ ", Style::new().fg(TEXT_DIM)),
-                                        Span::styled(display_line, Style::new().fg(TEXT_DIM)),
-                                    ]));
-                                }
-                            }
-                        }
-
-                        lines
-                    }
-                    OutputType::ToolOutput => {
-                        // Tool output - └ connector, plain text (no markdown)
-                        let wrapped =
-                            wrap_text(&output_line.content, inner_width.saturating_sub(2));
-                        wrapped
-                            .into_iter()
-                            .enumerate()
-                            .map(|(i, text)| {
-                                let prefix = if i == 0 {
-                                    Span::styled("└ ", Style::new().fg(TOOL_CONNECTOR))
-                                } else {
-                                    Span::styled("  ", Style::new().fg(TOOL_CONNECTOR))
-                                };
-                                Line::from(vec![
-                                    prefix,
-                                    Span::styled(text, Style::new().fg(TEXT_DIM)),
-                                ])
-                            })
-                            .collect()
-                    }
-                    OutputType::DiffAdd => {
-                        // Added line - green background, no padding
-                        vec![Line::from(vec![
-                            Span::styled("  ", Style::new()),
-                            Span::styled(
-                                &output_line.content,
-                                Style::new().fg(DIFF_ADD_FG).bg(DIFF_ADD_BG),
-                            ),
-                        ])]
-                    }
-                    OutputType::DiffRemove => {
-                        // Removed line - red background, no padding
-                        vec![Line::from(vec![
-                            Span::styled("  ", Style::new()),
-                            Span::styled(
-                                &output_line.content,
-                                Style::new().fg(DIFF_REMOVE_FG).bg(DIFF_REMOVE_BG),
-                            ),
-                        ])]
-                    }
-                    OutputType::DiffContext => {
-                        // Context line - dim
-                        let content = &output_line.content;
-                        vec![Line::from(vec![
-                            Span::styled("  ", Style::new()),
-                            Span::styled(
-                                format!(
-                                    "{:width$}",
-                                    content,
-                                    width = inner_width.saturating_sub(2)
-                                ),
-                                Style::new().fg(TEXT_DIM),
-                            ),
-                        ])]
-                    }
-                    OutputType::DiffHeader => {
-                        // Diff header - dim, indented to align with diff content
-                        let content = &output_line.content;
-                        vec![Line::from(vec![
-                            Span::styled("  ", Style::new()),
-                            Span::styled(
-                                format!(
-                                    "{:width$}",
-                                    content,
-                                    width = inner_width.saturating_sub(2)
-                                ),
-                                Style::new().fg(TEXT_DIM),
-                            ),
-                        ])]
-                    }
-                    OutputType::Error => {
-                        // Error - red
-                        let wrapped =
-                            wrap_text(&output_line.content, inner_width.saturating_sub(2));
-                        wrapped
-                            .into_iter()
-                            .map(|text| {
-                                Line::from(vec![
-                                    Span::styled("✗ ", Style::new().fg(LOGO_CORAL)),
-                                    Span::styled(text, Style::new().fg(LOGO_CORAL)),
-                                ])
-                            })
-                            .collect()
-                    }
-                    OutputType::BashCommand => {
-                        // Bash command - gold with $ prefix
-                        let wrapped =
-                            wrap_text(&output_line.content, inner_width.saturating_sub(2));
-                        wrapped
-                            .into_iter()
-                            .enumerate()
-                            .map(|(i, text)| {
-                                if i == 0 {
-                                    Line::from(vec![Span::styled(
-                                        text,
-                                        Style::new().fg(LOGO_GOLD).bold(),
-                                    )])
-                                } else {
-                                    Line::from(vec![
-                                        Span::styled("  ", Style::new()),
-                                        Span::styled(text, Style::new().fg(LOGO_GOLD).bold()),
-                                    ])
-                                }
-                            })
-                            .collect()
-                    }
-                    OutputType::BashOutput => {
-                        // Bash output - dim text with connector
-                        let wrapped =
-                            wrap_text(&output_line.content, inner_width.saturating_sub(2));
-                        wrapped
-                            .into_iter()
-                            .map(|text| {
-                                let prefix = Span::styled("│ ", Style::new().fg(LOGO_GOLD));
-                                Line::from(vec![
-                                    prefix,
-                                    Span::styled(text, Style::new().fg(TEXT_DIM)),
-                                ])
-                            })
-                            .collect()
-                    }
-                    OutputType::SystemMessage => {
-                        // System message - light red/coral, italic
-                        let wrapped =
-                            wrap_text(&output_line.content, inner_width.saturating_sub(2));
-                        wrapped
-                            .into_iter()
-                            .map(|text| {
-                                Line::from(vec![Span::styled(
-                                    text,
-                                    Style::new().fg(LOGO_CORAL).italic(),
-                                )])
-                            })
-                            .collect()
-                    }
-                };
-
-                // Trim leading empty lines from this message
-                while let Some(line) = lines_for_output.first() {
-                    if line.spans.is_empty()
-                        || line.spans.iter().all(|s| s.content.trim().is_empty())
-                    {
-                        lines_for_output.remove(0);
-                    } else {
-                        break;
-                    }
-                }
-
-                // Trim trailing empty lines from this message
-                while let Some(line) = lines_for_output.last() {
-                    if line.spans.is_empty()
-                        || line.spans.iter().all(|s| s.content.trim().is_empty())
-                    {
-                        lines_for_output.pop();
-                    } else {
-                        break;
-                    }
-                }
-
-                // Add spacing when transitioning between different message types
-                // This keeps diff lines together, tool output together, etc.
-                let should_add_spacing = match (&last_line_type, &output_line.line_type) {
-                    // Add spacing after user input
-                    (Some(OutputType::UserInput), _) => true,
-                    // Note: Thinking is now ephemeral and removed when new content arrives,
-                    // so we don't need spacing rules for it anymore
-                    // Add spacing after tool calls (before next content)
-                    (
-                        Some(OutputType::ToolCall { .. }),
-                        OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
-                    ) => true,
-                    // Add spacing after text (agent response) before new user input or tool calls
-                    (
-                        Some(OutputType::Text),
-                        OutputType::UserInput | OutputType::ToolCall { .. },
-                    ) => true,
-                    // Add spacing after tool output before new messages
-                    (
-                        Some(OutputType::ToolOutput),
-                        OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
-                    ) => true,
-                    // Add spacing after bash output
-                    (
-                        Some(OutputType::BashOutput),
-                        OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
-                    ) => true,
-                    // Don't add spacing between consecutive diff lines or within tool sequences
-                    _ => false,
-                };
-
-                if should_add_spacing && !all_lines.is_empty() {
-                    all_lines.push(Line::raw(""));
-                }
-
-                all_lines.extend(lines_for_output);
-                last_line_type = Some(&output_line.line_type);
-            }
+            let layout = layout_output(
+                session,
+                &mut cache,
+                settings,
+                app.collapse_tools,
+                app.spinner(),
+            );
 
             // Apply scroll offset to visual lines
             // usize::MAX means "scroll to bottom"
-            let total_lines = all_lines.len();
+            let total_lines = layout.total_lines;
+            let output_starts = &layout.output_starts;
             computed_total_lines = Some(total_lines);
-            let scroll_offset = session.scroll_offset;
+            let scroll_offset = match session.scroll_to_output {
+                // Jump to a requested output line (e.g. a plan entry's work)
+                Some(output_idx) => {
+                    let target = output_starts
+                        .get(output_idx)
+                        .copied()
+                        .unwrap_or(total_lines)
+                        .min(total_lines.saturating_sub(inner_height));
+                    computed_scroll = Some(target);
+                    target
+                }
+                // After a resize, find the content that was at the top in the new wrapping
+                None if session.reflow_pending && session.scroll_offset != usize::MAX => {
+                    let target = session
+                        .scroll_anchor
+                        .to_offset(output_starts, total_lines)
+                        .min(total_lines.saturating_sub(inner_height));
+                    computed_scroll = Some(target);
+                    target
+                }
+                None => session.scroll_offset,
+            };
             let start = if scroll_offset == usize::MAX {
                 // Scroll to bottom: show last viewport worth of lines
                 total_lines.saturating_sub(inner_height)
@@ -356,7 +272,17 @@ pub fn render_conversation_view(frame: &mut Frame, area: Rect, app: &mut App) {
                 scroll_offset.min(total_lines.saturating_sub(1))
             };
             let end = (start + inner_height).min(total_lines);
-            all_lines[start..end].to_vec()
+            if total_lines > inner_height {
+                scroll_position = Some((start, total_lines));
+            }
+            computed_anchor = Some(ScrollAnchor::from_offset(output_starts, start));
+            computed_last_visible = Some(
+                output_starts
+                    .partition_point(|&line_start| line_start < end)
+                    .saturating_sub(1),
+            );
+            // Only the visible lines are copied out of the layout
+            layout.lines(start..end)
         }
     } else {
         vec![Line::styled(
@@ -365,9 +291,53 @@ pub fn render_conversation_view(frame: &mut Frame, area: Rect, app: &mut App) {
         )]
     };
 
+    // Underline file references (e.g. in stack traces); clicking one opens the editor there
+    let cwd = app.selected_session().map(|s| s.cwd.clone());
+    let mut lines = lines;
+    let links = cwd
+        .map(|cwd| link_file_refs(&mut lines, &cwd))
+        .unwrap_or_default();
+    for (row, range, location) in links {
+        let x = area.x.saturating_add(range.start);
+        if row >= area.height || x >= area.right() {
+            continue;
+        }
+        let width = (range.end - range.start).min(area.right() - x);
+        app.interactions.register_click(
+            "file_ref",
+            ClickRegion::new(x, area.y + row, width, 1),
+            Action::OpenEditorAt(location),
+        );
+    }
+
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, area);
 
+    // Scroll indicator in the right border column; clicking it jumps there
+    if let Some((start, total_lines)) = scroll_position {
+        let max_offset = total_lines.saturating_sub(inner_height);
+        let mut state = ScrollbarState::new(max_offset)
+            .position(start)
+            .viewport_content_length(inner_height);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(Some("│"))
+            .track_style(Style::new().fg(TOOL_CONNECTOR))
+            .thumb_symbol("┃")
+            .thumb_style(Style::new().fg(TEXT_DIM));
+        frame.render_stateful_widget(scrollbar, area, &mut state);
+
+        for row in 0..area.height {
+            let offset = scrollbar_jump_offset(row as usize, area.height as usize, max_offset);
+            app.interactions.register_click(
+                "scrollbar",
+                ClickRegion::new(area.right().saturating_sub(1), area.y + row, 1, 1),
+                Action::ScrollTo(offset),
+            );
+        }
+    }
+
     // Register output area as scrollable region
     let output_bounds = ClickRegion::new(area.x, area.y, area.width, area.height);
     app.interactions.register_scroll(
@@ -377,10 +347,848 @@ pub fn render_conversation_view(frame: &mut Frame, area: Rect, app: &mut App) {
         Action::ScrollDown(3),
     );
 
+    let Some(session) = app.sessions.selected_session_mut() else {
+        return;
+    };
+    session.render_cache = cache;
+
     // Update total_rendered_lines for accurate scroll calculations
-    if let Some(total_lines) = computed_total_lines
-        && let Some(session) = app.sessions.selected_session_mut()
-    {
+    if let Some(total_lines) = computed_total_lines {
         session.total_rendered_lines = total_lines;
+        if let Some(last_visible) = computed_last_visible {
+            session.last_visible_output = last_visible;
+        }
+        if let Some(offset) = computed_scroll {
+            session.scroll_offset = offset;
+            session.scroll_to_output = None;
+        }
+        if let Some(anchor) = computed_anchor {
+            session.scroll_anchor = anchor;
+            session.reflow_pending = false;
+        }
+    }
+}
+
+/// Scroll offset a click on `row` of a scrollbar `height` rows tall jumps to:
+/// the top row shows the start, the bottom row the end
+fn scrollbar_jump_offset(row: usize, height: usize, max_offset: usize) -> usize {
+    if height <= 1 {
+        return max_offset;
+    }
+    (row.min(height - 1) * max_offset + (height - 1) / 2) / (height - 1)
+}
+
+/// Lay out a session's output as visual lines, wrapping only the output
+/// lines that aren't already in `cache`.
+fn layout_output<'c>(
+    session: &Session,
+    cache: &'c mut ConversationCache,
+    settings: RenderSettings,
+    collapse_tools: bool,
+    spinner: &str,
+) -> &'c OutputLayout {
+    let view = ViewKey {
+        settings,
+        collapse_tools,
+        expanded_tool: session.expanded_tool.clone(),
+        filter: session
+            .output_filter
+            .as_ref()
+            .filter(|_| session.filter_active)
+            .map(|regex| regex.as_str().to_string()),
+    };
+    // Different settings change every line, other view changes only the layout
+    if cache.view.as_ref().map(|view| view.settings) != Some(settings) {
+        cache.entries.clear();
+    }
+    if cache.view.as_ref() != Some(&view) {
+        cache.view = Some(view);
+        cache.resume.clear();
+    }
+    cache.misses = 0;
+
+    let spinning = session.active_tool_call_id.as_deref().and_then(|active| {
+        session.output.iter().rposition(|line| {
+            matches!(&line.line_type, OutputType::ToolCall { tool_call_id, .. } if tool_call_id == active)
+        })
+    });
+    // Resume at the first output line that changed, or at a spinner (which
+    // changes every frame, or just stopped), whichever comes first
+    let unchanged = cache
+        .resume
+        .iter()
+        .zip(&session.output)
+        .take_while(|(point, line)| point.revision == line.revision)
+        .count();
+    let from = [Some(unchanged), cache.spinning, spinning]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(0)
+        .min(cache.resume.len().saturating_sub(1));
+    cache.spinning = spinning;
+
+    let ConversationCache {
+        entries,
+        layout,
+        resume,
+        misses,
+        ..
+    } = cache;
+    // Whether the tool call above the current line is collapsed
+    let mut in_collapsed_tool = false;
+    let mut last_shown: Option<usize> = None;
+    match resume.get(from) {
+        Some(point) => {
+            layout.truncate(point.chunks, point.total_lines);
+            in_collapsed_tool = point.in_collapsed_tool;
+            last_shown = point.last_shown;
+        }
+        None => *layout = OutputLayout::default(),
+    }
+    layout.output_starts.truncate(from);
+    resume.truncate(from);
+
+    let blank = Arc::new(vec![Line::raw("")]);
+    for (index, output_line) in session.output.iter().enumerate().skip(from) {
+        resume.push(ResumePoint {
+            revision: output_line.revision,
+            chunks: layout.chunks.len(),
+            total_lines: layout.total_lines,
+            in_collapsed_tool,
+            last_shown,
+        });
+
+        // Lines hidden by the output filter take up no space
+        if !session.matches_filter(output_line) {
+            layout.output_starts.push(layout.total_lines);
+            continue;
+        }
+
+        // A collapsed tool call hides its output and diff
+        if let OutputType::ToolCall { tool_call_id, .. } = &output_line.line_type {
+            in_collapsed_tool =
+                collapse_tools && session.expanded_tool.as_deref() != Some(tool_call_id.as_str());
+        } else if !output_line.line_type.is_tool_detail() {
+            in_collapsed_tool = false;
+        } else if in_collapsed_tool {
+            layout.output_starts.push(layout.total_lines);
+            continue;
+        }
+
+        let collapsed = in_collapsed_tool && !output_line.line_type.is_tool_detail();
+        let is_active = spinning == Some(index);
+        // The running tool call's spinner changes every frame, so it's never cached
+        let cached = entries
+            .get(&output_line.revision)
+            .filter(|cached| cached.collapsed == collapsed && !is_active)
+            .map(|cached| cached.lines.clone());
+        let lines_for_output = match cached {
+            Some(lines) => lines,
+            None => {
+                *misses += 1;
+                let lines = Arc::new(render_entry(
+                    output_line,
+                    &EntryContext {
+                        settings,
+                        collapsed,
+                        spinner: is_active.then_some(spinner),
+                        cwd: settings.relative_paths.then_some(session.cwd.as_path()),
+                    },
+                ));
+                if !is_active {
+                    entries.insert(
+                        output_line.revision,
+                        CachedLines {
+                            collapsed,
+                            lines: lines.clone(),
+                        },
+                    );
+                }
+                lines
+            }
+        };
+
+        // Add spacing when transitioning between different message types
+        // This keeps diff lines together, tool output together, etc.
+        let last_line_type = last_shown.map(|shown| &session.output[shown].line_type);
+        let should_add_spacing = match (&last_line_type, &output_line.line_type) {
+            // Collapsed tool calls stack without spacing
+            (Some(OutputType::ToolCall { .. }), OutputType::ToolCall { .. }) if collapse_tools => {
+                false
+            }
+            // Add spacing after user input
+            (Some(OutputType::UserInput), _) => true,
+            // Note: Thinking is now ephemeral and removed when new content arrives,
+            // so we don't need spacing rules for it anymore
+            // Add spacing after tool calls (before next content)
+            (
+                Some(OutputType::ToolCall { .. }),
+                OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
+            ) => true,
+            // Add spacing after text (agent response) before new user input or tool calls
+            (Some(OutputType::Text), OutputType::UserInput | OutputType::ToolCall { .. }) => true,
+            // Add spacing after tool output before new messages
+            (
+                Some(OutputType::ToolOutput),
+                OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
+            ) => true,
+            // Add spacing after bash output
+            (
+                Some(OutputType::BashOutput),
+                OutputType::Text | OutputType::UserInput | OutputType::ToolCall { .. },
+            ) => true,
+            // Don't add spacing between consecutive diff lines or within tool sequences
+            _ => false,
+        };
+
+        if should_add_spacing && layout.total_lines > 0 {
+            layout.push(blank.clone());
+        }
+
+        layout.output_starts.push(layout.total_lines);
+        layout.push(lines_for_output);
+        last_shown = Some(index);
+    }
+    resume.push(ResumePoint {
+        revision: u64::MAX,
+        chunks: layout.chunks.len(),
+        total_lines: layout.total_lines,
+        in_collapsed_tool,
+        last_shown,
+    });
+
+    // Everything below is redone every frame, after the end resume point
+
+    if layout.total_lines == 0 && session.filter_active {
+        layout.push(Arc::new(vec![Line::styled(
+            "No lines match the filter.",
+            Style::new().fg(TEXT_DIM),
+        )]));
+    }
+
+    // Drop lines that changed or left the output once enough have piled up
+    if entries.len() > session.output.len() + STALE_ENTRIES {
+        let live: HashSet<u64> = session.output.iter().map(|line| line.revision).collect();
+        entries.retain(|revision, _| live.contains(revision));
+    }
+
+    // Follow-up prompts waiting for the agent to finish
+    if !session.queued_prompts.is_empty() {
+        let mut all_lines = vec![Line::raw("")];
+        for prompt in &session.queued_prompts {
+            let first_line = prompt.lines().next().unwrap_or_default();
+            let more = if prompt.lines().nth(1).is_some() {
+                " …"
+            } else {
+                ""
+            };
+            all_lines.push(Line::from(vec![
+                Span::styled("⏸ queued  ", Style::new().fg(LOGO_GOLD)),
+                Span::styled(
+                    format!("> {}{}", first_line, more),
+                    Style::new().fg(TEXT_DIM),
+                ),
+            ]));
+        }
+        all_lines.push(Line::styled(
+            "  [↑] edit last (insert mode) · [Q] clear queue · [Esc] cancel all",
+            Style::new().fg(TEXT_DIM),
+        ));
+        layout.push(Arc::new(all_lines));
+    }
+
+    layout
+}
+
+/// Render one output line to visual lines, without surrounding spacing
+fn render_entry(output_line: &OutputLine, ctx: &EntryContext) -> Vec<Line<'static>> {
+    let inner_width = ctx.settings.width;
+    let mut lines_for_output: Vec<Line<'static>> = match &output_line.line_type {
+        OutputType::Text => {
+            // Empty lines for spacing
+            if output_line.content.is_empty() {
+                vec![Line::raw("")]
+            } else {
+                // Agent response - render as markdown (ratskin plus tables)
+                render_markdown(
+                    &output_line.content,
+                    inner_width,
+                    ctx.settings.heading_style,
+                )
+            }
+        }
+
+        OutputType::UserInput => {
+            // User prompt - cyan/blue
+            let wrapped = wrap_text(&output_line.content, inner_width);
+            wrapped
+                .into_iter()
+                .map(|text| {
+                    Line::from(vec![Span::styled(
+                        text,
+                        Style::new().fg(LOGO_LIGHT_BLUE).bold(),
+                    )])
+                })
+                .collect()
+        }
+
+        OutputType::Thought => {
+            // Agent thinking - just show lightbulb and "Thinking..."
+            vec![Line::from(vec![
+                Span::styled("💡 ", Style::new().fg(LOGO_GOLD)),
+                Span::styled("Thinking...", Style::new().fg(LOGO_GOLD).italic()),
+            ])]
+        }
+        OutputType::ToolCall {
+            name,
+            description,
+            failed,
+            raw_json,
+            ..
+        } => {
+            // Tool call - spinner if active, red dot if failed, green dot if complete
+            let (indicator, indicator_color) = if let Some(spinner) = ctx.spinner {
+                (format!("{} ", spinner), TOOL_DOT)
+            } else if *failed {
+                ("● ".to_string(), LOGO_CORAL)
+            } else {
+                ("● ".to_string(), TOOL_DOT)
+            };
+            // Use the name (title) directly, rendered as markdown
+            let _ = description; // unused for now
+            let name = match ctx.cwd {
+                Some(cwd) => relative_paths(name, cwd),
+                None => name.into(),
+            };
+            let skin = ratskin::RatSkin::default();
+            let parsed_lines = skin.parse(
+                ratskin::RatSkin::parse_text(&name),
+                inner_width.saturating_sub(2) as u16,
+            );
+            // Collapsed: only the title's first line, marked as folded
+            let collapsed = ctx.collapsed;
+            let title_lines = if collapsed { 1 } else { usize::MAX };
+            let mut lines: Vec<Line<'static>> = parsed_lines
+                .into_iter()
+                .take(title_lines)
+                .enumerate()
+                .map(|(i, mut line)| {
+                    let prefix = if i == 0 {
+                        Span::styled(indicator.clone(), Style::new().fg(indicator_color))
+                    } else {
+                        Span::styled("  ", Style::new().fg(indicator_color))
+                    };
+                    line.spans.insert(0, prefix);
+                    line
+                })
+                .collect();
+            if collapsed && let Some(line) = lines.first_mut() {
+                line.spans
+                    .push(Span::styled(" ▸", Style::new().fg(TEXT_DIM)));
+            }
+
+            // If debug mode is on, render all raw JSON requests below the tool call
+            if ctx.settings.debug_tool_json && !collapsed {
+                for json in raw_json {
+                    for json_line in json.lines() {
+                        // Truncate long lines rather than wrap to preserve indentation
+                        let max_len = inner_width.saturating_sub(4);
+                        let display_line = if json_line.len() > max_len {
+                            format!("{}…", &json_line[..max_len.saturating_sub(1)])
+                        } else {
+                            json_line.to_string()
+                        };
+                        lines.push(Line::from(vec![
+                            Span::styled("  │ ", Style::new().fg(TEXT_DIM)),
+                            Span::styled(display_line, Style::new().fg(TEXT_DIM)),
+                        ]));
+                    }
+                }
+            }
+
+            lines
+        }
+        OutputType::ToolOutput => {
+            // Tool output - └ connector, plain text (no markdown)
+            let content = match ctx.cwd {
+                Some(cwd) => relative_paths(&output_line.content, cwd),
+                None => output_line.content.as_str().into(),
+            };
+            let wrapped = wrap_text(&content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let prefix = if i == 0 {
+                        Span::styled("└ ", Style::new().fg(TOOL_CONNECTOR))
+                    } else {
+                        Span::styled("  ", Style::new().fg(TOOL_CONNECTOR))
+                    };
+                    Line::from(vec![prefix, Span::styled(text, Style::new().fg(TEXT_DIM))])
+                })
+                .collect()
+        }
+        OutputType::DiffAdd => {
+            // Added line - green background, changed words brighter
+            let mut spans = vec![Span::styled("  ", Style::new())];
+            spans.extend(diff_line_spans(
+                output_line,
+                Style::new().fg(DIFF_ADD_FG).bg(DIFF_ADD_BG),
+                DIFF_ADD_WORD_BG,
+            ));
+            vec![Line::from(spans)]
+        }
+        OutputType::DiffRemove => {
+            // Removed line - red background, changed words brighter
+            let mut spans = vec![Span::styled("  ", Style::new())];
+            spans.extend(diff_line_spans(
+                output_line,
+                Style::new().fg(DIFF_REMOVE_FG).bg(DIFF_REMOVE_BG),
+                DIFF_REMOVE_WORD_BG,
+            ));
+            vec![Line::from(spans)]
+        }
+        OutputType::DiffContext => {
+            // Context line - dim
+            let content = &output_line.content;
+            vec![Line::from(vec![
+                Span::styled("  ", Style::new()),
+                Span::styled(
+                    format!("{:width$}", content, width = inner_width.saturating_sub(2)),
+                    Style::new().fg(TEXT_DIM),
+                ),
+            ])]
+        }
+        OutputType::DiffHeader => {
+            // Diff header - dim, indented to align with diff content
+            let content = &output_line.content;
+            vec![Line::from(vec![
+                Span::styled("  ", Style::new()),
+                Span::styled(
+                    format!("{:width$}", content, width = inner_width.saturating_sub(2)),
+                    Style::new().fg(TEXT_DIM),
+                ),
+            ])]
+        }
+        OutputType::Error => {
+            // Error - red
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .map(|text| {
+                    Line::from(vec![
+                        Span::styled("✗ ", Style::new().fg(LOGO_CORAL)),
+                        Span::styled(text, Style::new().fg(LOGO_CORAL)),
+                    ])
+                })
+                .collect()
+        }
+        OutputType::BashCommand => {
+            // Bash command - gold with $ prefix
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    if i == 0 {
+                        Line::from(vec![Span::styled(text, Style::new().fg(LOGO_GOLD).bold())])
+                    } else {
+                        Line::from(vec![
+                            Span::styled("  ", Style::new()),
+                            Span::styled(text, Style::new().fg(LOGO_GOLD).bold()),
+                        ])
+                    }
+                })
+                .collect()
+        }
+        OutputType::BashOutput => {
+            // Bash output - dim text with connector
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .map(|text| {
+                    let prefix = Span::styled("│ ", Style::new().fg(LOGO_GOLD));
+                    Line::from(vec![prefix, Span::styled(text, Style::new().fg(TEXT_DIM))])
+                })
+                .collect()
+        }
+        OutputType::SystemMessage => {
+            // System message - light red/coral, italic
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .map(|text| {
+                    Line::from(vec![Span::styled(
+                        text,
+                        Style::new().fg(LOGO_CORAL).italic(),
+                    )])
+                })
+                .collect()
+        }
+        OutputType::Preamble => {
+            // Preamble - dim, marked so it isn't mistaken for a user turn
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let prefix = if i == 0 { "⚙ " } else { "  " };
+                    Line::from(vec![
+                        Span::styled(prefix, Style::new().fg(TEXT_DIM)),
+                        Span::styled(text, Style::new().fg(TEXT_DIM).italic()),
+                    ])
+                })
+                .collect()
+        }
+        OutputType::AgentStderr => {
+            // Agent stderr - dim with connector
+            let wrapped = wrap_text(&output_line.content, inner_width.saturating_sub(2));
+            wrapped
+                .into_iter()
+                .map(|text| {
+                    Line::from(vec![
+                        Span::styled("│ ", Style::new().fg(TOOL_CONNECTOR)),
+                        Span::styled(text, Style::new().fg(TEXT_DIM).italic()),
+                    ])
+                })
+                .collect()
+        }
+    };
+
+    // Trim leading empty lines from this message
+    while let Some(line) = lines_for_output.first() {
+        if line.spans.is_empty() || line.spans.iter().all(|s| s.content.trim().is_empty()) {
+            lines_for_output.remove(0);
+        } else {
+            break;
+        }
+    }
+
+    // Trim trailing empty lines from this message
+    while let Some(line) = lines_for_output.last() {
+        if line.spans.is_empty() || line.spans.iter().all(|s| s.content.trim().is_empty()) {
+            lines_for_output.pop();
+        } else {
+            break;
+        }
+    }
+
+    if ctx.settings.show_timestamps {
+        for (i, line) in lines_for_output.iter_mut().enumerate() {
+            let prefix = if i == 0 {
+                timestamp_span(output_line.timestamp)
+            } else {
+                Span::raw(" ".repeat(TIMESTAMP_WIDTH))
+            };
+            line.spans.insert(0, prefix);
+        }
+    }
+    lines_for_output
+}
+
+/// Render the output filter bar: the regex being typed or applied,
+/// with the number of matching lines or the regex error.
+fn render_filter_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(session) = app.selected_session() else {
+        return;
+    };
+
+    let mut spans = vec![Span::styled("& ", Style::new().fg(LOGO_GOLD).bold())];
+    if let Some(filter) = &app.output_filter {
+        // Typing a filter
+        spans.push(Span::styled(
+            filter.input.clone(),
+            Style::new().fg(TEXT_WHITE),
+        ));
+        spans.push(Span::styled("█", Style::new().fg(TEXT_DIM)));
+        if let Some(error) = &filter.error {
+            spans.push(Span::styled(
+                format!("  {}", error),
+                Style::new().fg(LOGO_CORAL),
+            ));
+        }
+    } else if let Some(regex) = &session.output_filter {
+        spans.push(Span::styled(
+            regex.as_str().to_string(),
+            Style::new().fg(TEXT_WHITE),
+        ));
+    }
+
+    if session.filter_active {
+        let matching = session
+            .output
+            .iter()
+            .filter(|line| session.matches_filter(line))
+            .count();
+        spans.push(Span::styled(
+            format!("  {} matching lines", matching),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    spans.push(Span::raw("  "));
+    if app.output_filter.is_some() {
+        spans.push(Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)));
+        spans.push(Span::styled(" keep  ", Style::new().fg(TEXT_DIM)));
+    } else {
+        spans.push(Span::styled("[&]", Style::new().fg(TEXT_WHITE)));
+        spans.push(Span::styled(" edit  ", Style::new().fg(TEXT_DIM)));
+    }
+    spans.push(Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)));
+    spans.push(Span::styled(" clear", Style::new().fg(TEXT_DIM)));
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Split a diff line into spans, emphasizing its changed words (if any)
+/// on top of the whole-line style.
+fn diff_line_spans(output_line: &OutputLine, style: Style, word_bg: Color) -> Vec<Span<'static>> {
+    let content = output_line.content.as_str();
+    let word_style = style.bg(word_bg).bold();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for range in &output_line.word_changes {
+        let (Some(before), Some(changed)) =
+            (content.get(pos..range.start), content.get(range.clone()))
+        else {
+            break;
+        };
+        if !before.is_empty() {
+            spans.push(Span::styled(before.to_string(), style));
+        }
+        spans.push(Span::styled(changed.to_string(), word_style));
+        pos = range.end;
+    }
+    if let Some(rest) = content.get(pos..)
+        && (!rest.is_empty() || spans.is_empty())
+    {
+        spans.push(Span::styled(rest.to_string(), style));
+    }
+    spans
+}
+
+/// Underline the file references in `lines` that point at existing files
+/// (absolute, or relative to `cwd`). Returns each one's row, column range and location.
+fn link_file_refs(lines: &mut [Line<'static>], cwd: &Path) -> Vec<(u16, Range<u16>, FileLocation)> {
+    let mut links = vec![];
+    for (row, line) in lines.iter_mut().enumerate() {
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        for file_ref in find_file_refs(&text) {
+            let Some(location) = file_ref.resolve(cwd) else {
+                continue;
+            };
+            let start = Span::raw(&text[..file_ref.range.start]).width() as u16;
+            let width = Span::raw(&text[file_ref.range.clone()]).width() as u16;
+            underline_range(line, file_ref.range);
+            links.push((row as u16, start..start + width, location));
+        }
+    }
+    links
+}
+
+/// Underline the byte range `range` of a line's text, splitting spans at its ends
+fn underline_range(line: &mut Line<'static>, range: Range<usize>) {
+    let mut spans = Vec::with_capacity(line.spans.len() + 2);
+    let mut offset = 0;
+    for span in line.spans.drain(..) {
+        let content = span.content.as_ref();
+        let span_range = offset..offset + content.len();
+        offset = span_range.end;
+        let start = range.start.clamp(span_range.start, span_range.end) - span_range.start;
+        let end = range.end.clamp(span_range.start, span_range.end) - span_range.start;
+        if start == end {
+            spans.push(span);
+            continue;
+        }
+        let underlined = span.style.add_modifier(Modifier::UNDERLINED);
+        for (part, style) in [
+            (&content[..start], span.style),
+            (&content[start..end], underlined),
+            (&content[end..], span.style),
+        ] {
+            if !part.is_empty() {
+                spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    line.spans = spans;
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+    use crate::session::AgentType;
+    use crate::tui::components::plain;
+
+    const SETTINGS: RenderSettings = RenderSettings {
+        width: 80,
+        debug_tool_json: false,
+        show_timestamps: false,
+        heading_style: HeadingStyle::Colored,
+        relative_paths: true,
+    };
+
+    fn session_with_output(entries: usize) -> Session {
+        let mut session = Session::mock("1", "test", AgentType::ClaudeCode, "main");
+        session.output.clear();
+        session.max_scrollback = 0;
+        for i in 0..entries {
+            if i % 2 == 0 {
+                session.add_output(format!("> question {}", i), OutputType::UserInput);
+            } else {
+                session.add_output(
+                    format!(
+                        "Answer **{}** with enough words to wrap at a narrow width",
+                        i
+                    ),
+                    OutputType::Text,
+                );
+            }
+        }
+        session
+    }
+
+    #[test]
+    fn test_frame_cost_does_not_grow_with_scrollback() {
+        // Benchmark: once laid out, a frame only redoes what changed, so its
+        // cost stays flat however long the conversation gets
+        for entries in [100, 20_000] {
+            let mut session = session_with_output(entries);
+            let mut cache = ConversationCache::default();
+
+            layout_output(&session, &mut cache, SETTINGS, false, "");
+            assert_eq!(cache.misses, entries);
+
+            layout_output(&session, &mut cache, SETTINGS, false, "");
+            assert_eq!(cache.misses, 0);
+
+            // Streaming into the last answer rewraps just that answer
+            session.append_text(" and more".to_string());
+            let visible = layout_output(&session, &mut cache, SETTINGS, false, "").lines(0..40);
+            assert_eq!(visible.len(), 40);
+            assert_eq!(cache.misses, 1);
+            assert_eq!(cache.resume.len(), entries + 1);
+        }
+    }
+
+    /// Everything a fresh render of the session lays out
+    fn fresh_lines(session: &Session, collapse_tools: bool) -> Vec<String> {
+        let mut cache = ConversationCache::default();
+        let layout = layout_output(session, &mut cache, SETTINGS, collapse_tools, "*");
+        plain(&layout.lines(0..layout.total_lines))
+    }
+
+    fn cached_lines(
+        session: &Session,
+        cache: &mut ConversationCache,
+        collapse_tools: bool,
+    ) -> Vec<String> {
+        let layout = layout_output(session, cache, SETTINGS, collapse_tools, "*");
+        plain(&layout.lines(0..layout.total_lines))
+    }
+
+    #[test]
+    fn test_cached_layout_matches_fresh_render() {
+        let mut session = session_with_output(50);
+        let mut cache = ConversationCache::default();
+        cached_lines(&session, &mut cache, false);
+
+        session.add_error("boom");
+        session.add_error("boom"); // Same line, now "(×2)"
+        let all = cached_lines(&session, &mut cache, false);
+        assert_eq!(all, fresh_lines(&session, false));
+        assert!(all.last().unwrap().ends_with("(×2)"));
+
+        // Any window is the same slice of the whole
+        let layout = layout_output(&session, &mut cache, SETTINGS, false, "*");
+        assert_eq!(plain(&layout.lines(7..19)), all[7..19].to_vec());
+        let starts = layout.output_starts.clone();
+        let mut fresh = ConversationCache::default();
+        assert_eq!(
+            layout_output(&session, &mut fresh, SETTINGS, false, "*").output_starts,
+            starts
+        );
+    }
+
+    #[test]
+    fn test_cached_layout_follows_tools_and_view_changes() {
+        let mut session = session_with_output(4);
+        let mut cache = ConversationCache::default();
+        session.add_tool_call("t1".to_string(), "Read a.rs".to_string(), None, None);
+        session.add_output("fn main() {}".to_string(), OutputType::ToolOutput);
+        session.active_tool_call_id = Some("t1".to_string());
+        let running = cached_lines(&session, &mut cache, false);
+        assert_eq!(running, fresh_lines(&session, false));
+        assert!(running.iter().any(|line| line.starts_with("* Read a.rs")));
+
+        // The spinner stops without the tool call line itself changing
+        session.complete_active_tool();
+        let done = cached_lines(&session, &mut cache, false);
+        assert_eq!(done, fresh_lines(&session, false));
+        assert!(done.iter().any(|line| line.starts_with("● Read a.rs")));
+
+        // Collapsing and filtering change the layout, not the lines
+        assert_eq!(
+            cached_lines(&session, &mut cache, true),
+            fresh_lines(&session, true)
+        );
+        session.output_filter = Some(Regex::new("question").unwrap());
+        session.filter_active = true;
+        assert_eq!(
+            cached_lines(&session, &mut cache, true),
+            fresh_lines(&session, true)
+        );
+        session.filter_active = false;
+        assert_eq!(
+            cached_lines(&session, &mut cache, false),
+            fresh_lines(&session, false)
+        );
+    }
+
+    #[test]
+    fn test_width_change_rewraps_everything() {
+        let session = session_with_output(10);
+        let mut cache = ConversationCache::default();
+        layout_output(&session, &mut cache, SETTINGS, false, "");
+        let narrow = RenderSettings {
+            width: 20,
+            ..SETTINGS
+        };
+        layout_output(&session, &mut cache, narrow, false, "");
+        assert_eq!(cache.misses, 10);
+    }
+
+    #[test]
+    fn test_file_refs_are_underlined_across_spans() {
+        let cwd = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut lines = vec![Line::from(vec![
+            Span::raw("error at ✗ src/ma"),
+            Span::styled("in.rs:3:1 here", Style::new().fg(Color::Red)),
+        ])];
+        let links = link_file_refs(&mut lines, cwd);
+
+        assert_eq!(links.len(), 1);
+        let (row, columns, location) = &links[0];
+        assert_eq!((*row, columns.clone()), (0, 11..26));
+        assert_eq!(location.path, cwd.join("src/main.rs"));
+        let underlined: String = lines[0]
+            .spans
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::UNDERLINED))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(underlined, "src/main.rs:3:1");
+        assert_eq!(lines[0].spans.last().unwrap().content, " here");
+    }
+
+    #[test]
+    fn test_scrollbar_jump_offset() {
+        assert_eq!(scrollbar_jump_offset(0, 11, 500), 0);
+        assert_eq!(scrollbar_jump_offset(5, 11, 500), 250);
+        assert_eq!(scrollbar_jump_offset(10, 11, 500), 500);
+        assert_eq!(scrollbar_jump_offset(0, 1, 500), 500);
     }
 }
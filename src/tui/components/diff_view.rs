@@ -0,0 +1,166 @@
+//! Diff view component - full-screen side-by-side file diff.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, ClickRegion};
+use crate::diff::RowKind;
+use crate::events::Action;
+use crate::file_ref::relative_paths;
+use crate::tui::interaction::InteractiveRegion;
+use crate::tui::theme::*;
+
+/// Width of the line number gutter on each side
+const GUTTER_WIDTH: usize = 5;
+
+/// Render the side-by-side diff view over the whole screen.
+pub fn render_diff_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    // Shorten the path like the tool calls do, unless full paths are toggled on
+    let cwd = app
+        .sessions
+        .selected_session()
+        .filter(|_| !app.absolute_paths)
+        .map(|s| s.cwd.clone());
+    let Some(diff_view) = &mut app.diff_view else {
+        return;
+    };
+    let path = match &cwd {
+        Some(cwd) => relative_paths(&diff_view.path, cwd),
+        None => diff_view.path.as_str().into(),
+    };
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
+        .title(Span::styled(
+            format!(" {} ", path),
+            Style::new().fg(TEXT_WHITE).bold(),
+        ))
+        .style(Style::new().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::vertical([
+        Constraint::Min(0),    // Diff rows
+        Constraint::Length(1), // Footer hints
+    ])
+    .split(inner);
+
+    let columns = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(1), // Separator
+        Constraint::Fill(1),
+    ])
+    .split(layout[0]);
+
+    // Update viewport height for scroll calculations
+    diff_view.viewport_height = layout[0].height as usize;
+    let max_scroll = diff_view
+        .rows
+        .len()
+        .saturating_sub(diff_view.viewport_height);
+    diff_view.scroll = diff_view.scroll.min(max_scroll);
+
+    let visible = diff_view
+        .rows
+        .iter()
+        .skip(diff_view.scroll)
+        .take(diff_view.viewport_height);
+
+    let mut left_lines: Vec<Line> = vec![];
+    let mut right_lines: Vec<Line> = vec![];
+    let mut separator_lines: Vec<Line> = vec![];
+    for row in visible {
+        let (left_style, right_style) = match row.kind {
+            RowKind::Equal => (Style::new().fg(TEXT_DIM), Style::new().fg(TEXT_DIM)),
+            RowKind::Delete | RowKind::Insert | RowKind::Replace => (
+                Style::new().fg(DIFF_REMOVE_FG).bg(DIFF_REMOVE_BG),
+                Style::new().fg(DIFF_ADD_FG).bg(DIFF_ADD_BG),
+            ),
+        };
+        left_lines.push(render_side(
+            &row.left,
+            diff_view.h_scroll,
+            columns[0].width as usize,
+            left_style,
+        ));
+        right_lines.push(render_side(
+            &row.right,
+            diff_view.h_scroll,
+            columns[2].width as usize,
+            right_style,
+        ));
+        separator_lines.push(Line::styled("│", Style::new().fg(TOOL_CONNECTOR)));
+    }
+
+    frame.render_widget(Paragraph::new(left_lines), columns[0]);
+    frame.render_widget(Paragraph::new(separator_lines), columns[1]);
+    frame.render_widget(Paragraph::new(right_lines), columns[2]);
+
+    // Footer with position and key hints
+    let position = if diff_view.rows.is_empty() {
+        "no changes".to_string()
+    } else {
+        format!(
+            "{}-{}/{}",
+            diff_view.scroll + 1,
+            (diff_view.scroll + diff_view.viewport_height).min(diff_view.rows.len()),
+            diff_view.rows.len()
+        )
+    };
+    let footer = Line::from(vec![
+        Span::styled(position, Style::new().fg(TEXT_DIM)),
+        Span::raw("  "),
+        Span::styled("[j/k]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" scroll  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[h/l]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" pan  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[g/G]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" top/bottom  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" close", Style::new().fg(TEXT_DIM)),
+    ]);
+    frame.render_widget(Paragraph::new(footer), layout[1]);
+
+    // Scrolling anywhere on screen scrolls the diff, not the conversation below
+    let bounds = ClickRegion::new(area.x, area.y, area.width, area.height);
+    app.interactions.register(
+        InteractiveRegion::scrollable(
+            "diff_view",
+            bounds,
+            Action::DiffViewScrollUp(3),
+            Action::DiffViewScrollDown(3),
+        )
+        .with_priority(10),
+    );
+}
+
+/// Render one side of a row: line number gutter plus horizontally scrolled text
+fn render_side(
+    side: &Option<(usize, String)>,
+    h_scroll: usize,
+    width: usize,
+    style: Style,
+) -> Line<'static> {
+    let text_width = width.saturating_sub(GUTTER_WIDTH);
+    match side {
+        Some((line_number, text)) => {
+            let visible: String = text.chars().skip(h_scroll).take(text_width).collect();
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>4} ", line_number),
+                    Style::new().fg(TOOL_CONNECTOR),
+                ),
+                Span::styled(format!("{:text_width$}", visible), style),
+            ])
+        }
+        None => Line::raw(""),
+    }
+}
@@ -12,7 +12,7 @@ use crate::app::App;
 use crate::tui::theme::*;
 
 /// Render the folder picker as a centered popup.
-pub fn render_folder_picker(frame: &mut Frame, area: Rect, app: &App) {
+pub fn render_folder_picker(frame: &mut Frame, area: Rect, app: &mut App) {
     // Calculate centered popup area
     let popup_width = 70u16.min(area.width.saturating_sub(4));
     let popup_height = 20u16.min(area.height.saturating_sub(4));
@@ -20,6 +20,13 @@ pub fn render_folder_picker(frame: &mut Frame, area: Rect, app: &App) {
     let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
     let popup_area = Rect::new(x, y, popup_width, popup_height);
 
+    // Calculate how many entries we can show
+    let available_height = popup_height.saturating_sub(7) as usize; // title, path, filter, spacing, help
+    if let Some(picker) = &mut app.folder_picker {
+        // Update viewport height for paging
+        picker.viewport_height = available_height;
+    }
+
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
 
@@ -57,9 +64,6 @@ pub fn render_folder_picker(frame: &mut Frame, area: Rect, app: &App) {
 
         lines.push(Line::raw("")); // spacing
 
-        // Calculate how many entries we can show
-        let available_height = popup_height.saturating_sub(7) as usize; // title, path, filter, spacing, help
-
         // List entries with scrolling
         let total_entries = picker.entries.len();
         let selected = picker.selected;
@@ -0,0 +1,52 @@
+//! Follow-up chips - prompts the agent suggested sending next, below the conversation.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::app::{App, ClickRegion};
+use crate::events::Action;
+use crate::tui::theme::*;
+
+/// Most suggestions shown: one per Alt+number key
+const MAX_CHIPS: usize = 9;
+
+/// Render the selected session's follow-up suggestions on one line, while
+/// it's idle. Chips that don't fit are left out.
+pub fn render_follow_ups(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(session) = app.selected_session() else {
+        return;
+    };
+    if !session.state.can_prompt() || session.follow_ups.is_empty() {
+        return;
+    }
+
+    let mut spans = Vec::new();
+    let mut chips = Vec::new();
+    let mut x = area.x;
+    let right = area.x + area.width;
+    for (index, follow_up) in session.follow_ups.iter().take(MAX_CHIPS).enumerate() {
+        let key = Span::styled(format!("Alt+{} ", index + 1), Style::new().fg(TEXT_DIM));
+        let label = Span::styled(
+            format!(" {} ", follow_up.label.lines().next().unwrap_or_default()),
+            Style::new().fg(TEXT_WHITE).bg(TOOL_CONNECTOR),
+        );
+        let width = (key.width() + label.width()) as u16;
+        if x + width > right {
+            break;
+        }
+        chips.push((index, ClickRegion::new(x, area.y, width, 1)));
+        spans.extend([key, label, Span::raw("  ")]);
+        x += width + 2;
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+
+    for (index, bounds) in chips {
+        app.interactions
+            .register_click("follow_up", bounds, Action::SendFollowUp(index));
+    }
+}
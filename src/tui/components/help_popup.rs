@@ -2,33 +2,121 @@
 
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Position, Rect},
     style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
 
 use crate::app::App;
+use crate::picker::fuzzy_match;
 use crate::tui::theme::*;
 
-/// Render the help popup with keyboard shortcuts.
-#[allow(clippy::vec_init_then_push)]
-pub fn render_help_popup(frame: &mut Frame, area: Rect, app: &App) {
-    // Calculate centered popup area
-    let popup_width = 50u16;
-    let popup_height = 28u16; // Increased to fit bug report line
-    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
-    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
-    let popup_area = Rect::new(
-        x,
-        y,
-        popup_width.min(area.width),
-        popup_height.min(area.height),
-    );
+/// A key and what it does. An empty key continues the previous binding's description.
+type Binding = (&'static str, &'static str);
 
-    // Clear the area behind the popup
-    frame.render_widget(Clear, popup_area);
+const NORMAL_MODE: &[Binding] = &[
+    ("i", "Enter insert mode"),
+    ("n", "New session"),
+    ("N", "New session in main repo"),
+    ("w", "New worktree session"),
+    ("x", "Kill session"),
+    ("d", "Duplicate session"),
+    ("W", "Duplicate into new worktree"),
+    ("X", "Experiment in a throwaway worktree"),
+    ("c", "Clear session (restart agent)"),
+    ("C", "Clear output (keep agent)"),
+    ("r", "Restart agent (keep output)"),
+    ("Q", "Clear queued prompts"),
+    ("R", "Resend last prompt"),
+    ("e", "Explain error in view"),
+    ("v", "Cycle sort mode"),
+    ("j/k", "Navigate sessions"),
+    ("1-9", "Select session by number"),
+    ("C-p", "Fuzzy-find session"),
+    ("!", "Jump to session awaiting input"),
+    ("`", "Previous session"),
+    ("Alt+1-9", "Send suggested follow-up"),
+    ("&", "Filter output by regex"),
+    ("b", "Broadcast prompt to all sessions"),
+    ("p", "Focus plan (Enter jumps to step)"),
+    ("C-u/C-d", "Scroll half page"),
+    ("g/G", "Scroll to top/bottom"),
+    (".", "Repeat last scroll/cycle/duplicate"),
+    ("o", "Open editor in session dir"),
+    ("z", "Collapse tool calls"),
+    ("T", "Toggle timestamps"),
+    ("P", "Full/relative tool paths"),
+    ("Z", "Zen mode (hide sidebar)"),
+    ("a", "Archive/unarchive session"),
+    ("A", "Show archived sessions"),
+    ("E", "Export ACP messages (JSONL)"),
+    ("[/]", "Expand previous/next tool call"),
+    ("{/}", "Previous/next message you sent"),
+    ("D", "Side-by-side diff view"),
+    ("y/Y", "Copy nearest file path/diff"),
+    ("Ctrl+y", "Copy session changes as patch"),
+    ("L", "Log viewer (follows new lines)"),
+    ("J", "Inspect tool call JSON"),
+    ("S", "MCP servers for new sessions"),
+    ("Tab", "Cycle permission mode"),
+    ("", "normal/plan/edits/all/yolo"),
+    ("m", "Cycle model"),
+    ("M", "Cycle agent mode"),
+    ("C-c", "Interrupt bash command"),
+    ("q", "Quit"),
+];
+
+const BUG_REPORTS: &[Binding] = &[("B", "Report bug")];
+
+const INSERT_MODE: &[Binding] = &[
+    ("Enter", "Send message (queued while busy)"),
+    ("↑", "Edit last queued message"),
+    ("Esc", "Cancel / Normal mode"),
+    ("C-v", "Paste (text or image)"),
+    ("@", "Complete a file path (Tab inserts)"),
+    ("/", "Pick an agent command (at start)"),
+    ("C-s", "Save prompt as a snippet"),
+    ("C-o", "Insert a saved snippet"),
+];
+
+/// Whether a binding matches the filter, by key (exact part) or description (fuzzy).
+/// A single character is taken to be a key, since it would appear in most descriptions.
+fn binding_matches(query: &str, (key, description): Binding) -> bool {
+    query.is_empty()
+        || key.split('/').any(|k| k == query)
+        || (query.chars().count() > 1 && fuzzy_match(query, description).is_some())
+}
+
+/// Bindings matching the filter; continuation lines follow their binding
+fn filter_bindings(query: &str, bindings: &[Binding]) -> Vec<Binding> {
+    let mut matched = vec![];
+    let mut previous_matched = false;
+    for &binding in bindings {
+        if binding.0.is_empty() {
+            if previous_matched {
+                matched.push(binding);
+            }
+            continue;
+        }
+        previous_matched = binding_matches(query, binding);
+        if previous_matched {
+            matched.push(binding);
+        }
+    }
+    matched
+}
+
+fn binding_line(key: &'static str, description: &'static str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {:<8}", key), Style::new().fg(TEXT_WHITE)),
+        Span::styled(description, Style::new().fg(TEXT_DIM)),
+    ])
+}
 
+/// Render the help popup with keyboard shortcuts.
+pub fn render_help_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let query = app.help_filter.as_str();
     let mut lines: Vec<Line> = vec![];
 
     // Title
@@ -36,106 +124,57 @@ pub fn render_help_popup(frame: &mut Frame, area: Rect, app: &App) {
         "Keyboard Shortcuts",
         Style::new().fg(TEXT_WHITE).bold(),
     )]));
-    lines.push(Line::raw(""));
 
-    // Normal mode
-    lines.push(Line::styled(
-        "Normal Mode",
-        Style::new().fg(LOGO_LIGHT_BLUE).bold(),
-    ));
-    lines.push(Line::from(vec![
-        Span::styled("  i       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Enter insert mode", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  n       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("New session", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  w       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("New worktree session", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  x       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Kill session", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  d       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Duplicate session", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  c       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Clear session (restart)", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  v       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Cycle sort mode", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  j/k     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Navigate sessions", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  1-9     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Select session by number", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  C-u/C-d ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Scroll half page", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  g/G     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Scroll to top/bottom", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  Tab     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Cycle permission mode", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  m       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Cycle model", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  q       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Quit", Style::new().fg(TEXT_DIM)),
-    ]));
+    // Filter input
+    lines.push(if query.is_empty() {
+        Line::from(vec![
+            Span::styled("/ ", Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled("type to filter", Style::new().fg(TEXT_DIM)),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("/ ", Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(query, Style::new().fg(TEXT_WHITE)),
+        ])
+    });
     lines.push(Line::raw(""));
 
-    // Bug report section with session ID
-    lines.push(Line::styled(
-        "Bug Reports",
-        Style::new().fg(LOGO_CORAL).bold(),
-    ));
-    lines.push(Line::from(vec![
-        Span::styled("  B       ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Report bug", Style::new().fg(TEXT_DIM)),
-    ]));
-    if let Some(sid) = &app.session_id {
-        lines.push(Line::from(vec![
-            Span::styled("  Session ", Style::new().fg(TEXT_DIM)),
-            Span::styled(sid.clone(), Style::new().fg(LOGO_GOLD)),
-        ]));
+    let sections = [
+        ("Normal Mode", LOGO_LIGHT_BLUE, NORMAL_MODE),
+        ("Bug Reports", LOGO_CORAL, BUG_REPORTS),
+        ("Insert Mode", LOGO_MINT, INSERT_MODE),
+    ];
+    let mut any_match = false;
+    for (title, color, bindings) in sections {
+        let matched = filter_bindings(query, bindings);
+        if matched.is_empty() {
+            continue;
+        }
+        any_match = true;
+
+        lines.push(Line::styled(title, Style::new().fg(color).bold()));
+        for (key, description) in matched {
+            lines.push(binding_line(key, description));
+        }
+        // Bug report section with session ID
+        if title == "Bug Reports"
+            && let Some(sid) = &app.session_id
+        {
+            lines.push(Line::from(vec![
+                Span::styled("  Session ", Style::new().fg(TEXT_DIM)),
+                Span::styled(sid.clone(), Style::new().fg(LOGO_GOLD)),
+            ]));
+        }
+        lines.push(Line::raw(""));
     }
-    lines.push(Line::raw(""));
 
-    // Insert mode
-    lines.push(Line::styled(
-        "Insert Mode",
-        Style::new().fg(LOGO_MINT).bold(),
-    ));
-    lines.push(Line::from(vec![
-        Span::styled("  Enter   ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Send message", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  Esc     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Cancel / Normal mode", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("  C-v     ", Style::new().fg(TEXT_WHITE)),
-        Span::styled("Paste (text or image)", Style::new().fg(TEXT_DIM)),
-    ]));
-    lines.push(Line::raw(""));
+    if !any_match {
+        lines.push(Line::styled(
+            "  (no matching shortcuts)",
+            Style::new().fg(TEXT_DIM),
+        ));
+        lines.push(Line::raw(""));
+    }
 
     // Footer
     lines.push(Line::from(vec![
@@ -146,6 +185,21 @@ pub fn render_help_popup(frame: &mut Frame, area: Rect, app: &App) {
         Span::styled(" to close", Style::new().fg(TEXT_DIM)),
     ]));
 
+    // Calculate centered popup area, tall enough for every line plus borders
+    let popup_width = 50u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
@@ -153,4 +207,48 @@ pub fn render_help_popup(frame: &mut Frame, area: Rect, app: &App) {
 
     let paragraph = Paragraph::new(lines).block(block);
     frame.render_widget(paragraph, popup_area);
+
+    // Cursor at the end of the filter
+    let cursor_x = popup_area.x + 1 + 2 + query.chars().count() as u16;
+    frame.set_cursor_position(Position::new(
+        cursor_x.min(popup_area.right().saturating_sub(2)),
+        popup_area.y + 2, // +1 for border, +1 for title
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_by_description_and_key() {
+        let matched = filter_bindings("sort mode", NORMAL_MODE);
+        assert_eq!(matched, vec![("v", "Cycle sort mode")]);
+
+        // Descriptions match fuzzily, like the pickers
+        let matched = filter_bindings("permsn", NORMAL_MODE);
+        assert_eq!(matched[0], ("Tab", "Cycle permission mode"));
+
+        // A single character only matches keys, case-sensitively
+        let matched = filter_bindings("G", NORMAL_MODE);
+        assert_eq!(matched, vec![("g/G", "Scroll to top/bottom")]);
+    }
+
+    #[test]
+    fn test_filter_keeps_continuation_lines() {
+        let matched = filter_bindings("permission", NORMAL_MODE);
+        assert_eq!(
+            matched,
+            vec![
+                ("Tab", "Cycle permission mode"),
+                ("", "normal/plan/edits/all/yolo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_no_matches() {
+        assert!(filter_bindings("zzzz", NORMAL_MODE).is_empty());
+        assert_eq!(filter_bindings("", INSERT_MODE).len(), INSERT_MODE.len());
+    }
 }
@@ -0,0 +1,91 @@
+//! Large prompt confirmation popup component.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::tui::theme::*;
+
+/// Render the confirmation popup shown before sending a large prompt.
+pub fn render_large_prompt_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let bytes = app.prompt_bytes();
+    let line_count = app.input_buffer.lines().count().max(1);
+    let attachment_count = app.attachments.len();
+
+    // Calculate centered popup area
+    let popup_width = 50u16;
+    let popup_height = if attachment_count > 0 { 9u16 } else { 8u16 };
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Title
+    lines.push(Line::from(vec![Span::styled(
+        "Send Large Prompt",
+        Style::new().fg(LOGO_GOLD).bold(),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Size summary
+    lines.push(Line::from(vec![Span::styled(
+        format!(
+            "This prompt is {} ({} lines).",
+            format_bytes(bytes),
+            line_count
+        ),
+        Style::new().fg(TEXT_WHITE),
+    )]));
+    if attachment_count > 0 {
+        lines.push(Line::from(vec![Span::styled(
+            format!("Includes {} image attachment(s).", attachment_count),
+            Style::new().fg(TEXT_DIM),
+        )]));
+    }
+    lines.push(Line::from(vec![Span::styled(
+        "Send it to the agent anyway?",
+        Style::new().fg(TEXT_DIM),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Footer with options
+    lines.push(Line::from(vec![
+        Span::styled("[Enter]", Style::new().fg(LOGO_GOLD)),
+        Span::styled(" send  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" keep editing", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_GOLD))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// Format a byte count as B, KB or MB
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
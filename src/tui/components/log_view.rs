@@ -0,0 +1,118 @@
+//! Log view component - full-screen tail of the current log file.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, ClickRegion};
+use crate::events::Action;
+use crate::log::LogLevel;
+use crate::tui::interaction::InteractiveRegion;
+use crate::tui::theme::*;
+
+/// Color for a log line, based on its level tag
+fn line_style(line: &str) -> Style {
+    let tagged = |level: LogLevel| line.contains(&format!("] [{}] ", level.tag()));
+    if tagged(LogLevel::Error) {
+        Style::new().fg(LOGO_CORAL)
+    } else if tagged(LogLevel::Warn) {
+        Style::new().fg(LOGO_GOLD)
+    } else if tagged(LogLevel::Debug) {
+        Style::new().fg(TEXT_DIM)
+    } else {
+        Style::new().fg(TEXT_WHITE)
+    }
+}
+
+/// Render the log viewer over the whole screen.
+pub fn render_log_view(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(log_view) = &mut app.log_view else {
+        return;
+    };
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
+        .title(Span::styled(
+            format!(" {} ", log_view.path.display()),
+            Style::new().fg(TEXT_WHITE).bold(),
+        ))
+        .style(Style::new().bg(Color::Black));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::vertical([
+        Constraint::Min(0),    // Log lines
+        Constraint::Length(1), // Footer hints
+    ])
+    .split(inner);
+
+    // Update viewport height for scroll calculations
+    log_view.viewport_height = layout[0].height as usize;
+    let max_scroll = log_view
+        .lines
+        .len()
+        .saturating_sub(log_view.viewport_height);
+    log_view.scroll = if log_view.follow {
+        max_scroll
+    } else {
+        log_view.scroll.min(max_scroll)
+    };
+
+    let width = layout[0].width as usize;
+    let lines: Vec<Line> = log_view
+        .lines
+        .iter()
+        .skip(log_view.scroll)
+        .take(log_view.viewport_height)
+        .map(|line| {
+            // Long lines are cut rather than wrapped so scrolling stays line-based
+            let visible: String = line.chars().take(width).collect();
+            Line::styled(visible, line_style(line))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), layout[0]);
+
+    // Footer: position, follow state and key hints
+    let position = format!(
+        "{}/{}",
+        (log_view.scroll + log_view.viewport_height).min(log_view.lines.len()),
+        log_view.lines.len()
+    );
+    let follow = if log_view.follow {
+        Span::styled("following", Style::new().fg(LOGO_MINT))
+    } else {
+        Span::styled("paused", Style::new().fg(LOGO_GOLD))
+    };
+    let footer = Line::from(vec![
+        Span::styled(position, Style::new().fg(TEXT_DIM)),
+        Span::raw("  "),
+        follow,
+        Span::raw("  "),
+        Span::styled("[j/k]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" scroll  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[g/G]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" top/follow  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" close", Style::new().fg(TEXT_DIM)),
+    ]);
+    frame.render_widget(Paragraph::new(footer), layout[1]);
+
+    // Scrolling anywhere on screen scrolls the log, not the conversation below
+    let bounds = ClickRegion::new(area.x, area.y, area.width, area.height);
+    app.interactions.register(
+        InteractiveRegion::scrollable(
+            "log_view",
+            bounds,
+            Action::LogViewScrollUp(3),
+            Action::LogViewScrollDown(3),
+        )
+        .with_priority(10),
+    );
+}
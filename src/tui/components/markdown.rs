@@ -0,0 +1,582 @@
+//! Markdown rendering for agent text.
+//!
+//! Text is rendered with ratskin (termimad), except for blocks it doesn't
+//! handle well, which are detected here and drawn separately:
+//! GitHub-flavored tables become aligned columns with box-drawing borders,
+//! `#` headings are left-aligned in the configured [`HeadingStyle`], and list
+//! items get one bullet style with wrapped lines hanging under their text.
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::app::HeadingStyle;
+use crate::tui::theme::*;
+
+use super::wrap_text;
+
+/// Column alignment from a table's delimiter row
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A parsed table: header cells, column alignments and body rows (possibly ragged)
+#[derive(Debug, PartialEq)]
+struct Table {
+    header: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+/// A run of lines that is plain markdown or a table, or a single heading
+enum Block<'a> {
+    Text(String),
+    Table(&'a [&'a str]),
+    /// Heading level (1-6) and text
+    Heading(usize, &'a str),
+    /// List item with its continuation lines joined
+    ListItem {
+        /// Nesting level, 0 for a top-level item
+        depth: usize,
+        /// Number of an ordered item, `None` for a bullet
+        number: Option<&'a str>,
+        text: String,
+    },
+}
+
+/// Bullets by nesting level (the last repeats for deeper levels)
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// Render agent markdown to lines at most `width` wide.
+pub fn render_markdown(
+    text: &str,
+    width: usize,
+    heading_style: HeadingStyle,
+) -> Vec<Line<'static>> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let mut rendered = vec![];
+    for block in split_blocks(&lines) {
+        match block {
+            Block::Text(text) => rendered.extend(render_text(&text, width)),
+            Block::Heading(level, text) => {
+                rendered.extend(render_heading(level, text, width, heading_style))
+            }
+            Block::ListItem {
+                depth,
+                number,
+                text,
+            } => rendered.extend(render_list_item(depth, number, &text, width)),
+            Block::Table(table_lines) => match parse_table(table_lines) {
+                Some(table) if table_fits(&table, width) => {
+                    rendered.extend(render_table(&table, width))
+                }
+                // Too narrow to draw columns: leave it to ratskin
+                _ => rendered.extend(render_text(&table_lines.join("\n"), width)),
+            },
+        }
+    }
+    rendered
+}
+
+/// Render plain markdown with ratskin
+fn render_text(text: &str, width: usize) -> Vec<Line<'static>> {
+    let skin = ratskin::RatSkin::default();
+    skin.parse(ratskin::RatSkin::parse_text(text), width as u16)
+        .into_iter()
+        .map(|line| {
+            Line::from(
+                line.spans
+                    .into_iter()
+                    .map(|span| Span::styled(span.content.into_owned(), span.style))
+                    .collect::<Vec<_>>(),
+            )
+            .style(line.style)
+        })
+        .collect()
+}
+
+impl HeadingStyle {
+    /// Style of a heading at `level`, patched over its inline formatting
+    fn style(self, level: usize) -> Style {
+        let color = match level {
+            1 => LOGO_MINT,
+            2 => LOGO_LIGHT_BLUE,
+            _ => LOGO_GOLD,
+        };
+        match self {
+            HeadingStyle::Bold => Style::new().bold(),
+            HeadingStyle::Colored => Style::new().fg(color).bold(),
+            HeadingStyle::Underlined => Style::new().fg(color).bold().underlined(),
+        }
+    }
+}
+
+/// Render a heading's text (inline markdown included), wrapped like any text
+fn render_heading(
+    level: usize,
+    text: &str,
+    width: usize,
+    heading_style: HeadingStyle,
+) -> Vec<Line<'static>> {
+    let style = heading_style.style(level);
+    render_text(text, width)
+        .into_iter()
+        .map(|mut line| {
+            for span in &mut line.spans {
+                span.style = span.style.patch(style);
+            }
+            line
+        })
+        .collect()
+}
+
+/// Level and text of an ATX heading like `## Title ##`
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    // A closing run of `#` only counts after whitespace
+    let text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        text
+    };
+    Some((level, text))
+}
+
+/// Indent (in columns), number (for ordered items) and text of a list item
+/// like `  - text` or `3. text`
+fn parse_list_item(line: &str) -> Option<(usize, Option<&str>, &str)> {
+    let trimmed = line.trim_start_matches([' ', '\t']);
+    let indent = line[..line.len() - trimmed.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    let (number, rest) = match trimmed.strip_prefix(['-', '*', '+']) {
+        Some(rest) => (None, rest),
+        None => {
+            let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+            if !(1..=9).contains(&digits) {
+                return None;
+            }
+            let rest = trimmed[digits..].strip_prefix(['.', ')'])?;
+            (Some(&trimmed[..digits]), rest)
+        }
+    };
+    let text = rest.trim();
+    (rest.starts_with([' ', '\t']) && !text.is_empty()).then_some((indent, number, text))
+}
+
+/// Render a list item with its marker, wrapping under the text rather than the marker
+fn render_list_item(
+    depth: usize,
+    number: Option<&str>,
+    text: &str,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let marker = match number {
+        Some(number) => format!("{}.", number),
+        None => BULLETS[depth.min(BULLETS.len() - 1)].to_string(),
+    };
+    let indent = "  ".repeat(depth);
+    let hang = indent.len() + marker.chars().count() + 1;
+    render_text(text, width.saturating_sub(hang).max(1))
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut spans = if i == 0 {
+                vec![
+                    Span::raw(indent.clone()),
+                    Span::styled(marker.clone(), Style::new().fg(TEXT_DIM)),
+                    Span::raw(" "),
+                ]
+            } else {
+                vec![Span::raw(" ".repeat(hang))]
+            };
+            spans.extend(line.spans);
+            Line::from(spans).style(line.style)
+        })
+        .collect()
+}
+
+/// Split lines into tables, headings, list items and the text between them,
+/// skipping fenced code blocks
+fn split_blocks<'a>(lines: &'a [&'a str]) -> Vec<Block<'a>> {
+    let mut blocks = vec![];
+    let mut text: Vec<&str> = vec![];
+    let mut in_fence = false;
+    // Indents of the enclosing list items, outermost first
+    let mut list_indents: Vec<usize> = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && let Some((indent, number, item)) = parse_list_item(line) {
+            if !text.is_empty() {
+                blocks.push(Block::Text(std::mem::take(&mut text).join("\n")));
+            }
+            while list_indents.last().is_some_and(|&open| open >= indent) {
+                list_indents.pop();
+            }
+            let depth = list_indents.len();
+            list_indents.push(indent);
+
+            // Indented lines that aren't items themselves continue this one
+            let mut item = item.to_string();
+            i += 1;
+            while let Some(next) = lines.get(i) {
+                let next_indent = next.len() - next.trim_start().len();
+                if next.trim().is_empty()
+                    || next_indent <= indent
+                    || parse_list_item(next).is_some()
+                    || next.trim_start().starts_with("```")
+                {
+                    break;
+                }
+                item.push(' ');
+                item.push_str(next.trim());
+                i += 1;
+            }
+            blocks.push(Block::ListItem {
+                depth,
+                number,
+                text: item,
+            });
+            continue;
+        }
+        if !line.trim().is_empty() {
+            list_indents.clear();
+        }
+        if !in_fence && let Some((level, heading)) = parse_heading(line) {
+            if !text.is_empty() {
+                blocks.push(Block::Text(std::mem::take(&mut text).join("\n")));
+            }
+            blocks.push(Block::Heading(level, heading));
+            i += 1;
+            continue;
+        }
+        let is_table_start = !in_fence
+            && line.contains('|')
+            && lines.get(i + 1).is_some_and(|next| is_delimiter_row(next));
+        if !is_table_start {
+            text.push(line);
+            i += 1;
+            continue;
+        }
+
+        // Header, delimiter, then every following row containing a pipe
+        let end = (i + 2..lines.len())
+            .find(|&j| !lines[j].contains('|') || lines[j].trim().is_empty())
+            .unwrap_or(lines.len());
+        if !text.is_empty() {
+            blocks.push(Block::Text(std::mem::take(&mut text).join("\n")));
+        }
+        blocks.push(Block::Table(&lines[i..end]));
+        i = end;
+    }
+    if !text.is_empty() {
+        blocks.push(Block::Text(text.join("\n")));
+    }
+    blocks
+}
+
+/// Whether a line is a table delimiter row like `|---|:--:|--:|`
+fn is_delimiter_row(line: &str) -> bool {
+    let cells = split_row(line);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+/// Split a table row into trimmed cells, honoring `\|` escapes
+fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = match trimmed.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => trimmed,
+    };
+
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+fn parse_table(lines: &[&str]) -> Option<Table> {
+    let (header, rest) = lines.split_first()?;
+    let (delimiter, rows) = rest.split_first()?;
+    let aligns = split_row(delimiter)
+        .iter()
+        .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+            (true, true) => Align::Center,
+            (false, true) => Align::Right,
+            _ => Align::Left,
+        })
+        .collect();
+    Some(Table {
+        header: split_row(header),
+        aligns,
+        rows: rows.iter().map(|row| split_row(row)).collect(),
+    })
+}
+
+impl Table {
+    /// Number of columns, counting the longest ragged row
+    fn column_count(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.header.len()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn align(&self, column: usize) -> Align {
+        self.aligns.get(column).copied().unwrap_or(Align::Left)
+    }
+}
+
+/// Width taken by borders and cell padding: `│ a │ b │`
+fn border_width(columns: usize) -> usize {
+    3 * columns + 1
+}
+
+/// Narrowest a column gets before the table is left to ratskin instead
+const MIN_COLUMN_WIDTH: usize = 3;
+
+fn table_fits(table: &Table, width: usize) -> bool {
+    let columns = table.column_count();
+    columns > 0 && border_width(columns) + columns * MIN_COLUMN_WIDTH <= width
+}
+
+/// Column widths from the content, shrinking the widest columns to fit `width`
+fn column_widths(table: &Table, width: usize) -> Vec<usize> {
+    let columns = table.column_count();
+    let mut widths = vec![1; columns];
+    for row in std::iter::once(&table.header).chain(&table.rows) {
+        for (column, cell) in row.iter().enumerate() {
+            widths[column] = widths[column].max(cell.chars().count());
+        }
+    }
+
+    let available = width.saturating_sub(border_width(columns));
+    while widths.iter().sum::<usize>() > available {
+        let Some(widest) = widths
+            .iter_mut()
+            .filter(|w| **w > MIN_COLUMN_WIDTH)
+            .max_by_key(|w| **w)
+        else {
+            break;
+        };
+        *widest -= 1;
+    }
+    widths
+}
+
+/// Pad a cell line to the column width according to its alignment
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let space = width.saturating_sub(text.chars().count());
+    let (left, right) = match align {
+        Align::Left => (0, space),
+        Align::Right => (space, 0),
+        Align::Center => (space / 2, space - space / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// Horizontal border like `├─────┼───┤`
+fn border_line(widths: &[usize], left: &str, middle: &str, right: &str) -> Line<'static> {
+    let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+    Line::styled(
+        format!("{}{}{}", left, segments.join(middle), right),
+        Style::new().fg(TEXT_DIM),
+    )
+}
+
+/// Lines for one table row, with cells wrapped to their column widths
+fn row_lines(table: &Table, row: &[String], widths: &[usize], style: Style) -> Vec<Line<'static>> {
+    let wrapped: Vec<Vec<String>> = widths
+        .iter()
+        .enumerate()
+        .map(|(column, &width)| {
+            let cell = row.get(column).map(String::as_str).unwrap_or("");
+            wrap_text(cell, width)
+        })
+        .collect();
+    let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+    (0..height)
+        .map(|i| {
+            let mut spans = vec![Span::styled("│", Style::new().fg(TEXT_DIM))];
+            for (column, cell_lines) in wrapped.iter().enumerate() {
+                let text = cell_lines.get(i).map(String::as_str).unwrap_or("");
+                spans.push(Span::styled(
+                    format!(" {} ", pad(text, widths[column], table.align(column))),
+                    style,
+                ));
+                spans.push(Span::styled("│", Style::new().fg(TEXT_DIM)));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn render_table(table: &Table, width: usize) -> Vec<Line<'static>> {
+    let widths = column_widths(table, width);
+    let mut lines = vec![border_line(&widths, "┌", "┬", "┐")];
+    lines.extend(row_lines(
+        table,
+        &table.header,
+        &widths,
+        Style::new().fg(TEXT_WHITE).bold(),
+    ));
+    lines.push(border_line(&widths, "├", "┼", "┤"));
+    for row in &table.rows {
+        lines.extend(row_lines(table, row, &widths, Style::new().fg(TEXT_WHITE)));
+    }
+    lines.push(border_line(&widths, "└", "┴", "┘"));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::components::plain;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn test_renders_aligned_table() {
+        let text = "| name | n |\n|:-----|--:|\n| a | 1 |\n| bcd | 22 |";
+        assert_eq!(
+            plain(&render_markdown(text, 80, HeadingStyle::Colored)),
+            vec![
+                "┌──────┬────┐",
+                "│ name │  n │",
+                "├──────┼────┤",
+                "│ a    │  1 │",
+                "│ bcd  │ 22 │",
+                "└──────┴────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ragged_rows_are_padded() {
+        let table = parse_table(&["a | b", "---|---", "1", "1 | 2 | 3"]).unwrap();
+        assert_eq!(table.column_count(), 3);
+        let lines = plain(&render_table(&table, 80));
+        assert_eq!(lines[3], "│ 1 │   │   │");
+        assert_eq!(lines[4], "│ 1 │ 2 │ 3 │");
+    }
+
+    #[test]
+    fn test_wide_cells_wrap_to_fit() {
+        let text = "| key | description |\n|---|---|\n| x | a fairly long description here |";
+        let lines = plain(&render_markdown(text, 24, HeadingStyle::Colored));
+        assert!(lines.iter().all(|line| line.chars().count() <= 24));
+        assert!(lines.len() > 5, "description should wrap: {lines:?}");
+    }
+
+    #[test]
+    fn test_headings_drop_hashes_and_take_the_style() {
+        let text = "# Title #\nsome text\n### Sub `code`\n```\n# comment\n```\n#hashtag";
+        let lines = render_markdown(text, 40, HeadingStyle::Underlined);
+        assert_eq!(
+            plain(&lines)
+                .iter()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>(),
+            vec!["Title", "some text", "Sub code", "# comment", "#hashtag"]
+        );
+        let title = &lines[0].spans[0].style;
+        assert_eq!(title.fg, Some(LOGO_MINT));
+        assert!(title.add_modifier.contains(Modifier::UNDERLINED));
+        assert!(lines[2].spans.iter().all(|s| s.style.fg == Some(LOGO_GOLD)));
+
+        let bold = render_markdown("## Plain", 40, HeadingStyle::Bold);
+        assert_eq!(bold[0].spans[0].style, Style::new().bold());
+    }
+
+    #[test]
+    fn test_long_headings_wrap_to_width() {
+        let lines = render_markdown(
+            "## a heading long enough to wrap",
+            12,
+            HeadingStyle::Colored,
+        );
+        assert!(lines.len() > 1);
+        assert!(plain(&lines).iter().all(|line| line.chars().count() <= 12));
+    }
+
+    #[test]
+    fn test_list_items_hang_under_their_text() {
+        let text = "Steps:\n- first item that wraps around\n  - nested *item*\n    continued\n* star\n1. one\n10) ten\n\n-not a list";
+        assert_eq!(
+            plain(&render_markdown(text, 20, HeadingStyle::Colored))
+                .iter()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>(),
+            vec![
+                "Steps:",
+                "• first item that",
+                "  wraps around",
+                "  ◦ nested item",
+                "    continued",
+                "• star",
+                "1. one",
+                "10. ten",
+                "",
+                "-not a list",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrapped_list_items_fit_the_width() {
+        let text = "- a\n  - b\n    - c\n      - deeply nested item with enough words to wrap";
+        let lines = plain(&render_markdown(text, 16, HeadingStyle::Colored));
+        assert!(
+            lines.iter().all(|line| line.chars().count() <= 16),
+            "{lines:?}"
+        );
+        assert!(lines[3].starts_with("      ▪ deeply"), "{lines:?}");
+    }
+
+    #[test]
+    fn test_pipes_in_code_blocks_and_text_are_not_tables() {
+        let text = "```\n| a | b |\n|---|---|\n```\nuse a | b here";
+        let lines: Vec<&str> = text.split('\n').collect();
+        let blocks = split_blocks(&lines);
+        assert!(blocks.iter().all(|b| matches!(b, Block::Text(_))));
+        assert!(!is_delimiter_row("a | b"));
+        assert!(is_delimiter_row("| --- | :-: |"));
+    }
+}
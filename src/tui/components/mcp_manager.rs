@@ -0,0 +1,100 @@
+//! MCP server manager component.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::config::McpTransport;
+use crate::tui::theme::*;
+
+/// Render the MCP server manager popup.
+pub fn render_mcp_manager(frame: &mut Frame, area: Rect, app: &App) {
+    let list = &app.mcp_servers;
+    let mut lines: Vec<Line> = vec![
+        Line::styled("MCP Servers", Style::new().fg(TEXT_WHITE).bold()),
+        Line::styled(
+            "Enabled servers are passed to new sessions",
+            Style::new().fg(TEXT_DIM),
+        ),
+        Line::raw(""),
+    ];
+
+    if list.servers.is_empty() {
+        lines.push(Line::styled(
+            "  (no [[mcp_servers]] in config)",
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    for (i, entry) in list.servers.iter().enumerate() {
+        let selected = i == list.selected;
+        let marker = if selected { "> " } else { "  " };
+        let (check, check_color) = if entry.enabled {
+            ("[x] ", LOGO_MINT)
+        } else {
+            ("[ ] ", TEXT_DIM)
+        };
+        let name_style = if selected {
+            Style::new().fg(TEXT_WHITE).bold()
+        } else {
+            Style::new().fg(TEXT_WHITE)
+        };
+        let config = &entry.config;
+        let target = match config.transport {
+            McpTransport::Stdio => std::iter::once(config.command.as_str())
+                .chain(config.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => config.url.clone(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(marker, Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(check, Style::new().fg(check_color)),
+            Span::styled(config.name.clone(), name_style),
+            Span::styled(
+                format!(" ({})", config.transport.label()),
+                Style::new().fg(LOGO_GOLD),
+            ),
+        ]));
+        lines.push(Line::styled(
+            format!("      {}", target),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        Span::styled("[j/k]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" move  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Space]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" toggle  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" close", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    // Centered popup, tall enough for every line plus borders
+    let popup_width = 60u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
+        .style(Style::new().bg(Color::Black));
+
+    frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
@@ -7,6 +7,8 @@
 //! - `sidebar` - Logo, session list, hotkeys, and plan entries
 //! - `conversation_view` - Main conversation/chat area with markdown rendering
 //! - `prompt` - Prompt input with attachments and mode indicators
+//! - `completion_popup` - `@` file and `/` command completion above the prompt
+//! - `follow_ups` - Follow-up prompts the agent suggested, as chips below the conversation
 //! - `permission_dialog` - Permission request dialog
 //! - `question_dialog` - Agent question dialog
 //! - `folder_picker` - Folder selection picker
@@ -15,41 +17,77 @@
 //! - `worktree_cleanup` - Worktree cleanup dialog
 //! - `agent_picker` - Agent type selection picker
 //! - `session_picker` - Session resume picker
+//! - `session_switcher` - Fuzzy quick switcher between open sessions
 //! - `help_popup` - Help overlay with keybindings
 //! - `bug_report_popup` - Bug report dialog
 //! - `clear_confirm_popup` - Clear session confirmation
+//! - `quit_confirm_popup` - Quit confirmation while sessions are working
+//! - `session_limit_popup` - Confirmation before going past `max_sessions`
+//! - `snippet_popup` - Saving the prompt as a snippet, and picking one to insert
+//! - `large_prompt_popup` - Confirmation before sending a large prompt
+//! - `broadcast_popup` - Prompt sent to every session at once
+//! - `diff_view` - Full-screen side-by-side file diff
+//! - `log_view` - Full-screen tail of the log file
+//! - `tool_json_popup` - Raw JSON of one tool call
+//! - `mcp_manager` - Toggle which MCP servers new sessions get
 //! - `separators` - Vertical and horizontal line separators
+//! - `markdown` - Markdown rendering for agent text (tables on top of ratskin)
 
 mod agent_picker;
 mod branch_input;
+mod broadcast_popup;
 mod bug_report_popup;
 mod clear_confirm_popup;
+mod completion_popup;
+mod conversation_view;
+mod diff_view;
 mod folder_picker;
+mod follow_ups;
 mod help_popup;
-mod prompt;
-mod conversation_view;
+mod large_prompt_popup;
+mod log_view;
+mod markdown;
+mod mcp_manager;
 mod permission_dialog;
+mod prompt;
 mod question_dialog;
+mod quit_confirm_popup;
 mod separators;
+mod session_limit_popup;
 mod session_picker;
+mod session_switcher;
 mod sidebar;
+mod snippet_popup;
+mod tool_json_popup;
 mod worktree_cleanup;
 mod worktree_picker;
 
 // Re-export all render functions for use in ui.rs
 pub use agent_picker::render_agent_picker;
 pub use branch_input::render_branch_input;
+pub use broadcast_popup::render_broadcast_popup;
 pub use bug_report_popup::render_bug_report_popup;
 pub use clear_confirm_popup::render_clear_confirm_popup;
+pub use completion_popup::render_completion_popup;
+pub use conversation_view::{ConversationCache, render_conversation_view};
+pub use diff_view::render_diff_view;
 pub use folder_picker::render_folder_picker;
+pub use follow_ups::render_follow_ups;
 pub use help_popup::render_help_popup;
-pub use prompt::render_prompt;
-pub use conversation_view::render_conversation_view;
+pub use large_prompt_popup::render_large_prompt_popup;
+pub use log_view::render_log_view;
+pub use mcp_manager::render_mcp_manager;
 pub use permission_dialog::render_permission_dialog;
+pub use prompt::render_prompt;
 pub use question_dialog::render_question_dialog;
+pub use quit_confirm_popup::render_quit_confirm_popup;
 pub use separators::{render_horizontal_separator, render_separator};
+pub use session_limit_popup::render_session_limit_popup;
 pub use session_picker::render_session_picker;
+pub use session_switcher::render_session_switcher;
 pub use sidebar::{render_logo, render_session_list};
+pub use snippet_popup::{render_snippet_picker, render_snippet_save_popup};
+pub use tool_json_popup::render_tool_json_popup;
 pub use worktree_cleanup::render_worktree_cleanup;
 pub use worktree_picker::render_worktree_picker;
 
@@ -137,3 +175,12 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
 
     result
 }
+
+/// The text of rendered lines without their styles, for tests
+#[cfg(test)]
+fn plain(lines: &[ratatui::text::Line]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+        .collect()
+}
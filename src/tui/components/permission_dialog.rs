@@ -14,10 +14,19 @@ use crate::events::Action;
 use crate::tui::interaction::InteractiveRegion;
 use crate::tui::theme::*;
 
+/// Allow button label and key hint in the footer
+const ALLOW_BUTTON: &str = "[y/Enter] allow";
+/// Separator between the footer buttons
+const BUTTON_SEPARATOR: &str = " • ";
+/// Deny button label and key hint in the footer
+const DENY_BUTTON: &str = "[n/Esc] deny";
+
 /// Render the permission request dialog.
 pub fn render_permission_dialog(frame: &mut Frame, area: Rect, app: &mut App) {
     let mut lines: Vec<Line> = vec![];
     let mut option_count = 0;
+    // Line index of the first option, after the (possibly wrapped) title and a blank line
+    let mut options_start = 0;
 
     if let Some(session) = app.selected_session()
         && let Some(perm) = &session.pending_permission
@@ -46,6 +55,7 @@ pub fn render_permission_dialog(frame: &mut Frame, area: Rect, app: &mut App) {
             lines.push(line);
         }
         lines.push(Line::raw(""));
+        options_start = lines.len();
 
         // Options
         for (i, option) in perm.options.iter().enumerate() {
@@ -76,13 +86,17 @@ pub fn render_permission_dialog(frame: &mut Frame, area: Rect, app: &mut App) {
 
         // Help text
         lines.push(Line::raw(""));
+        let (allow_key, allow_label) = ALLOW_BUTTON.split_at(ALLOW_BUTTON.find(' ').unwrap_or(0));
+        let (deny_key, deny_label) = DENY_BUTTON.split_at(DENY_BUTTON.find(' ').unwrap_or(0));
         lines.push(Line::from(vec![
-            Span::styled("[y/Enter]", Style::new().fg(TEXT_WHITE)),
-            Span::styled(" allow • ", Style::new().fg(TEXT_DIM)),
-            Span::styled("[n/Esc]", Style::new().fg(TEXT_WHITE)),
-            Span::styled(" deny", Style::new().fg(TEXT_DIM)),
+            Span::styled(allow_key, Style::new().fg(TEXT_WHITE)),
+            Span::styled(allow_label, Style::new().fg(TEXT_DIM)),
+            Span::styled(BUTTON_SEPARATOR, Style::new().fg(TEXT_DIM)),
+            Span::styled(deny_key, Style::new().fg(TEXT_WHITE)),
+            Span::styled(deny_label, Style::new().fg(TEXT_DIM)),
         ]));
     }
+    let footer_line = lines.len().saturating_sub(1);
 
     let block = Block::default()
         .borders(Borders::TOP)
@@ -91,10 +105,13 @@ pub fn render_permission_dialog(frame: &mut Frame, area: Rect, app: &mut App) {
     let paragraph = Paragraph::new(lines).block(block);
     frame.render_widget(paragraph, area);
 
-    // Register click regions for each option
-    // Options start at line 2 (after header line and empty line), accounting for top border
+    if option_count == 0 {
+        return;
+    }
+
+    // Register click and hover regions for each option
     let content_y = area.y + 1; // +1 for top border
-    let options_start_y = content_y + 2; // +2 for header and empty line
+    let options_start_y = content_y + options_start as u16;
 
     for i in 0..option_count {
         let option_y = options_start_y + i as u16;
@@ -106,8 +123,32 @@ pub fn render_permission_dialog(frame: &mut Frame, area: Rect, app: &mut App) {
                     bounds,
                     Action::SelectPermissionOption(i),
                 )
+                .with_hover(Action::HoverPermissionOption(i))
                 .with_priority(100), // High priority so it captures clicks over other regions
             );
         }
     }
+
+    // Register the allow/deny buttons in the footer
+    let footer_y = content_y + footer_line as u16;
+    if footer_y < area.y + area.height {
+        let allow_width = ALLOW_BUTTON.chars().count() as u16;
+        let deny_x = area.x + allow_width + BUTTON_SEPARATOR.chars().count() as u16;
+        app.interactions.register(
+            InteractiveRegion::clickable(
+                "permission_allow",
+                ClickRegion::new(area.x, footer_y, allow_width, 1),
+                Action::AllowPermission,
+            )
+            .with_priority(100),
+        );
+        app.interactions.register(
+            InteractiveRegion::clickable(
+                "permission_deny",
+                ClickRegion::new(deny_x, footer_y, DENY_BUTTON.chars().count() as u16, 1),
+                Action::DenyPermission,
+            )
+            .with_priority(100),
+        );
+    }
 }
@@ -8,9 +8,10 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use crate::app::{App, ClickRegion, InputMode};
+use crate::app::{App, ClickRegion, InputMode, MAX_ATTACHMENTS};
 use crate::events::Action;
 use crate::session::PermissionMode;
+use crate::slash_command::command_hint;
 use crate::tui::theme::*;
 
 use super::wrap_text;
@@ -76,6 +77,24 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
             }
         }
 
+        if app.images_rejected() {
+            let agent = app
+                .selected_session()
+                .map(|s| s.agent_type.display_name())
+                .unwrap_or_default();
+            spans.push(Span::styled(
+                format!(" ⚠ {} doesn't accept images (Ctrl+x to remove)", agent),
+                Style::new().fg(LOGO_CORAL),
+            ));
+        }
+
+        if app.attachments.len() >= MAX_ATTACHMENTS {
+            spans.push(Span::styled(
+                format!(" (max {})", MAX_ATTACHMENTS),
+                Style::new().fg(TEXT_DIM),
+            ));
+        }
+
         // Add hint when attachment is selected
         if app.selected_attachment.is_some() {
             spans.push(Span::styled(
@@ -115,8 +134,19 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
         }
     }
 
-    // Add empty line between prompt and mode indicator
-    lines.push(Line::raw(""));
+    // Line between prompt and mode indicator: empty, or while nothing is typed
+    // a hint at the agent's slash commands
+    let command_hint = app
+        .selected_session()
+        .filter(|_| app.input_buffer.is_empty() && !is_bash_mode)
+        .and_then(|s| command_hint(&s.available_commands, width.saturating_sub(2)));
+    match command_hint {
+        Some(hint) => lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(hint, Style::new().fg(TOOL_CONNECTOR)),
+        ])),
+        None => lines.push(Line::raw("")),
+    }
 
     // Track where the mode line starts for click regions (add 1 for the empty line)
     let mode_line_y = area.y + attachment_line_count as u16 + input_line_count as u16 + 1;
@@ -129,6 +159,7 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
             let mode_str = match mode {
                 PermissionMode::Normal => "normal",
                 PermissionMode::Plan => "plan",
+                PermissionMode::AcceptEdits => "accept edits",
                 PermissionMode::AcceptAll => "accept all",
                 PermissionMode::Yolo => "yolo",
             };
@@ -160,6 +191,7 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
         let (mode_text, mode_color) = match mode {
             PermissionMode::Normal => ("normal", TEXT_DIM),
             PermissionMode::Plan => ("plan", LOGO_GOLD),
+            PermissionMode::AcceptEdits => ("accept edits", LOGO_LIGHT_BLUE),
             PermissionMode::AcceptAll => ("accept all", LOGO_MINT),
             PermissionMode::Yolo => ("yolo", Color::Red),
         };
@@ -168,6 +200,7 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
         let agent_color = match session.agent_type {
             crate::session::AgentType::ClaudeCode => LOGO_CORAL,
             crate::session::AgentType::GeminiCli => LOGO_LIGHT_BLUE,
+            crate::session::AgentType::Custom(_) => LOGO_GOLD,
         };
 
         let mut spans = vec![
@@ -188,6 +221,11 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
             ));
         }
 
+        // Sessions in a trusted directory started out auto-accepting
+        if session.trusted {
+            spans.push(Span::styled("  trusted", Style::new().fg(LOGO_MINT)));
+        }
+
         // Add running bash command timer if present
         if let Some((command, elapsed)) = &running_bash_info {
             // Truncate command if too long
@@ -284,7 +322,7 @@ pub fn render_prompt(frame: &mut Frame, area: Rect, app: &mut App) {
 
         let cursor_x = area.x + x_offset as u16 + cursor_col as u16;
         let cursor_y = area.y + attachment_line_count as u16 + cursor_line as u16;
-        crate::log::log(&format!(
+        crate::log::debug(&format!(
             "Cursor render: byte_pos={}, char_pos={}, cursor_col={}, cursor_line={}, x={}, y={}, wrapped={:?}",
             app.cursor_position,
             char_position,
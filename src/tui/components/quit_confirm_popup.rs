@@ -0,0 +1,86 @@
+//! Quit confirmation popup component.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::tui::theme::*;
+
+/// Render the quit confirmation popup, listing the sessions that are still working.
+pub fn render_quit_confirm_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let busy = app.busy_sessions();
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Title
+    lines.push(Line::from(vec![Span::styled(
+        "Quit amux",
+        Style::new().fg(LOGO_CORAL).bold(),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Warning message with the busy sessions
+    lines.push(Line::from(vec![Span::styled(
+        format!(
+            "{} {} still working:",
+            busy.len(),
+            if busy.len() == 1 {
+                "session is"
+            } else {
+                "sessions are"
+            }
+        ),
+        Style::new().fg(TEXT_WHITE),
+    )]));
+    for session in &busy {
+        lines.push(Line::from(vec![
+            Span::styled("  • ", Style::new().fg(LOGO_GOLD)),
+            Span::styled(session.name.as_str(), Style::new().fg(TEXT_WHITE)),
+            Span::styled(
+                format!("  {}", session.state.display()),
+                Style::new().fg(TEXT_DIM),
+            ),
+        ]));
+    }
+    lines.push(Line::from(vec![Span::styled(
+        "Quitting stops their agents mid-prompt.",
+        Style::new().fg(TEXT_DIM),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Footer with options
+    lines.push(Line::from(vec![
+        Span::styled("[q/y]", Style::new().fg(LOGO_CORAL)),
+        Span::styled(" quit  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[n]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" stay", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    // Calculate centered popup area, tall enough for every line plus borders
+    let popup_width = 50u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_CORAL))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+}
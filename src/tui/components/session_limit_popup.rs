@@ -0,0 +1,85 @@
+//! Session limit confirmation popup component.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::tui::theme::*;
+
+/// Render the popup asking whether to start a session past `max_sessions`.
+pub fn render_session_limit_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(spawn) = &app.pending_spawn else {
+        return;
+    };
+    let running = app.sessions.sessions().len();
+    let max = app.max_sessions.unwrap_or_default();
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Title
+    lines.push(Line::from(vec![Span::styled(
+        "Session Limit Reached",
+        Style::new().fg(LOGO_GOLD).bold(),
+    )]));
+    lines.push(Line::raw(""));
+
+    // Count vs. limit, and what would be started
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{} of {}", running, max),
+            Style::new().fg(LOGO_GOLD).bold(),
+        ),
+        Span::styled(" sessions are running.", Style::new().fg(TEXT_WHITE)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Start ", Style::new().fg(TEXT_DIM)),
+        Span::styled(spawn.agent_type.display_name(), Style::new().fg(TEXT_WHITE)),
+        Span::styled(" in ", Style::new().fg(TEXT_DIM)),
+        Span::styled(
+            spawn
+                .cwd
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| spawn.cwd.display().to_string()),
+            Style::new().fg(LOGO_LIGHT_BLUE),
+        ),
+        Span::styled(" anyway?", Style::new().fg(TEXT_DIM)),
+    ]));
+    lines.push(Line::raw(""));
+
+    // Footer with options
+    lines.push(Line::from(vec![
+        Span::styled("[y/Enter]", Style::new().fg(LOGO_GOLD)),
+        Span::styled(" start  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[n/Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    // Calculate centered popup area, tall enough for every line plus borders
+    let popup_width = 50u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_GOLD))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+}
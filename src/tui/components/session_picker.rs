@@ -8,9 +8,36 @@ use ratatui::{
     widgets::Paragraph,
 };
 
+use chrono::{DateTime, Utc};
+
 use crate::app::App;
 use crate::tui::theme::*;
 
+/// Describe how long ago `time` was, e.g. "just now", "5m ago", "2h ago", "3d ago"
+fn format_relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(time);
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_weeks() < 5 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        time.format("%b %d %Y").to_string()
+    }
+}
+
+/// Cut `text` to at most `max_chars` characters, ending in an ellipsis if shortened
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
 /// Render the session picker for resuming sessions.
 pub fn render_session_picker(frame: &mut Frame, area: Rect, app: &App) {
     let mut lines: Vec<Line> = vec![];
@@ -24,6 +51,7 @@ pub fn render_session_picker(frame: &mut Frame, area: Rect, app: &App) {
         lines.push(Line::raw("")); // spacing
 
         // List sessions
+        let now = Utc::now();
         for (i, session) in picker.sessions.iter().enumerate() {
             let is_selected = i == picker.selected;
             let cursor = if is_selected { "> " } else { "  " };
@@ -37,7 +65,7 @@ pub fn render_session_picker(frame: &mut Frame, area: Rect, app: &App) {
 
             let timestamp = session
                 .timestamp
-                .map(|t| t.format("%b %d %H:%M").to_string())
+                .map(|t| format_relative_time(t, now))
                 .unwrap_or_default();
 
             let first_spans = vec![
@@ -59,17 +87,7 @@ pub fn render_session_picker(frame: &mut Frame, area: Rect, app: &App) {
             // Second line: first prompt (truncated)
             if let Some(prompt) = &session.first_prompt {
                 // Truncate to fit
-                let max_len = area.width.saturating_sub(6) as usize;
-                let display = if prompt.len() > max_len {
-                    // Find valid char boundary
-                    let mut end = max_len.saturating_sub(3);
-                    while end > 0 && !prompt.is_char_boundary(end) {
-                        end -= 1;
-                    }
-                    format!("{}...", &prompt[..end])
-                } else {
-                    prompt.clone()
-                };
+                let display = truncate_chars(prompt, area.width.saturating_sub(6) as usize);
 
                 lines.push(Line::from(vec![
                     Span::raw("     "),
@@ -92,3 +110,29 @@ pub fn render_session_picker(frame: &mut Frame, area: Rect, app: &App) {
 
     frame.render_widget(paragraph, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_format_relative_time() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 15, 12, 0, 0).unwrap();
+        let ago = |d: Duration| format_relative_time(now - d, now);
+        assert_eq!(ago(Duration::seconds(20)), "just now");
+        assert_eq!(ago(Duration::minutes(5)), "5m ago");
+        assert_eq!(ago(Duration::minutes(150)), "2h ago");
+        assert_eq!(ago(Duration::days(3)), "3d ago");
+        assert_eq!(ago(Duration::days(60)), "Apr 16 2025");
+        // Clock skew shouldn't produce negative ages
+        assert_eq!(ago(Duration::minutes(-5)), "just now");
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("fix the login bug", 8), "fix the…");
+        assert_eq!(truncate_chars("über straße", 6), "über …");
+    }
+}
@@ -0,0 +1,135 @@
+//! Session quick switcher component.
+
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::session::SessionState;
+use crate::tui::theme::*;
+
+/// Maximum number of matches listed at once
+const MAX_LISTED_MATCHES: usize = 12;
+
+/// Render the fuzzy quick switcher as a centered popup.
+pub fn render_session_switcher(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(switcher) = &app.session_switcher else {
+        return;
+    };
+    let sessions = app.sessions.sessions();
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Filter input line
+    lines.push(Line::from(vec![
+        Span::styled("> ", Style::new().fg(LOGO_LIGHT_BLUE)),
+        Span::styled(switcher.query.as_str(), Style::new().fg(TEXT_WHITE)),
+    ]));
+    lines.push(Line::raw(""));
+
+    if switcher.filtered.is_empty() {
+        lines.push(Line::styled(
+            "  (no matching sessions)",
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    // Keep the selection visible by scrolling the window of matches
+    let start = switcher
+        .selected
+        .saturating_sub(MAX_LISTED_MATCHES.saturating_sub(1));
+    for (i, &index) in switcher
+        .filtered
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(MAX_LISTED_MATCHES)
+    {
+        let Some(session) = sessions.get(index) else {
+            continue;
+        };
+        let is_selected = i == switcher.selected;
+        let cursor = if is_selected { "> " } else { "  " };
+        let state_color = match session.state {
+            SessionState::AwaitingPermission | SessionState::AwaitingUserInput => LOGO_GOLD,
+            SessionState::Idle => TEXT_DIM,
+            _ => LOGO_MINT,
+        };
+        let name_style = if is_selected {
+            Style::new().fg(TEXT_WHITE).bold()
+        } else {
+            Style::new().fg(TEXT_WHITE)
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(cursor, Style::new().fg(LOGO_MINT)),
+            Span::styled(session.name.as_str(), name_style),
+            Span::styled(
+                format!("  {}", session.git_branch),
+                Style::new().fg(LOGO_LIGHT_BLUE),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("    "),
+            Span::styled(session.agent_type.display_name(), Style::new().fg(TEXT_DIM)),
+            Span::styled(" · ", Style::new().fg(TEXT_DIM)),
+            Span::styled(session.state.display(), Style::new().fg(state_color)),
+        ]));
+    }
+    if switcher.filtered.len() > start + MAX_LISTED_MATCHES {
+        lines.push(Line::styled(
+            format!(
+                "  … {} more",
+                switcher.filtered.len() - start - MAX_LISTED_MATCHES
+            ),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+    lines.push(Line::raw(""));
+
+    // Footer
+    lines.push(Line::from(vec![
+        Span::styled("[↑/↓]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" navigate · ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" switch · ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    // Calculate centered popup area, tall enough for every line plus borders
+    let popup_width = 60u16;
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(popup_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        popup_width.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Switch Session ")
+        .title_style(Style::new().fg(LOGO_MINT).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_MINT))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+
+    // Cursor at the end of the query
+    let cursor_x = popup_area.x + 1 + 2 + switcher.query.chars().count() as u16;
+    frame.set_cursor_position(Position::new(
+        cursor_x.min(popup_area.right().saturating_sub(2)),
+        popup_area.y + 1,
+    ));
+}
@@ -11,7 +11,7 @@ use ratatui::{
 };
 
 use crate::acp::PlanStatus;
-use crate::app::{App, ClickRegion, SortMode};
+use crate::app::{App, ClickRegion, InputMode, SortMode};
 use crate::events::Action;
 use crate::picker::Picker;
 use crate::session::{Session, SessionState};
@@ -45,6 +45,8 @@ pub fn render_session_entry<'a>(
     show_number: bool,
 ) -> Vec<Line<'a>> {
     let cursor = if is_selected { "> " } else { "  " };
+    // Tells the agents of a mixed session list apart at a glance
+    let agent_glyph = Span::styled("◆ ", Style::new().fg(agent_color(&session.agent_type)));
 
     // Activity indicator for working sessions
     let (activity, activity_color) = if session.pending_permission.is_some() {
@@ -56,6 +58,19 @@ pub fn render_session_entry<'a>(
     } else {
         (String::new(), LOGO_MINT)
     };
+    // Output arrived since this session was last viewed
+    let unread = if session.has_unread && !is_selected {
+        " ●"
+    } else {
+        ""
+    };
+    let archived = if session.archived { " archived" } else { "" };
+    // Recent output volume, while working or until the output has scrolled off
+    let sparkline = if session.state.is_active() || !session.activity.is_quiet() {
+        format!(" {}", session.activity.sparkline())
+    } else {
+        String::new()
+    };
 
     // Compute relative path from start_dir, or use session name as fallback
     let display_path = if let Ok(rel) = session.cwd.strip_prefix(start_dir) {
@@ -74,6 +89,7 @@ pub fn render_session_entry<'a>(
         Line::from(vec![
             Span::raw(cursor),
             Span::styled(format!("{}. ", index + 1), Style::new().fg(TEXT_DIM)),
+            agent_glyph,
             Span::styled(
                 display_path,
                 if is_selected {
@@ -83,10 +99,14 @@ pub fn render_session_entry<'a>(
                 },
             ),
             Span::styled(activity.clone(), Style::new().fg(activity_color)),
+            Span::styled(sparkline.clone(), Style::new().fg(LOGO_MINT)),
+            Span::styled(unread, Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(archived, Style::new().fg(TEXT_DIM)),
         ])
     } else {
         Line::from(vec![
             Span::raw(cursor),
+            agent_glyph,
             Span::styled(
                 display_path,
                 if is_selected {
@@ -96,6 +116,9 @@ pub fn render_session_entry<'a>(
                 },
             ),
             Span::styled(activity.clone(), Style::new().fg(activity_color)),
+            Span::styled(sparkline.clone(), Style::new().fg(LOGO_MINT)),
+            Span::styled(unread, Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(archived, Style::new().fg(TEXT_DIM)),
         ])
     };
 
@@ -133,8 +156,30 @@ pub fn render_session_entry<'a>(
         }
     }
 
+    // Show commits ahead/behind upstream (e.g., "↑2 ↓1"), nothing when in sync
+    if let Some((ahead, behind)) = session.ahead_behind
+        && (ahead > 0 || behind > 0)
+    {
+        second_spans.push(Span::raw("  "));
+        if ahead > 0 {
+            second_spans.push(Span::styled(
+                format!("↑{}", ahead),
+                Style::new().fg(LOGO_MINT),
+            ));
+        }
+        if behind > 0 {
+            if ahead > 0 {
+                second_spans.push(Span::raw(" "));
+            }
+            second_spans.push(Span::styled(
+                format!("↓{}", behind),
+                Style::new().fg(LOGO_GOLD),
+            ));
+        }
+    }
+
     // Show mode if set (e.g., "plan")
-    if let Some(mode) = &session.current_mode {
+    if let Some(mode) = session.current_mode_name() {
         second_spans.push(Span::raw("  "));
         second_spans.push(Span::styled(
             format!("[{}]", mode),
@@ -156,8 +201,27 @@ fn origin_display_name(origin: &str) -> String {
 
 /// Render the session list with hotkeys and plan at bottom.
 pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
-    // Start with empty line for padding after logo
-    let mut session_lines: Vec<Line> = vec![Line::raw("")];
+    // Start with a banner counting sessions that need attention, or else a
+    // summary of what the sessions are doing
+    let awaiting = app.awaiting_session_count();
+    let mut session_lines: Vec<Line> = if awaiting > 0 {
+        let noun = if awaiting == 1 { "session" } else { "sessions" };
+        vec![Line::from(vec![
+            Span::styled(
+                format!("⚠ {} {} awaiting input ", awaiting, noun),
+                Style::new().fg(LOGO_GOLD).bold(),
+            ),
+            Span::styled("[!]", Style::new().fg(TEXT_WHITE)),
+            Span::styled(" jump", Style::new().fg(TEXT_DIM)),
+        ])]
+    } else if app.sessions.sessions().is_empty() {
+        vec![Line::raw("")]
+    } else {
+        vec![Line::styled(
+            app.sessions.summary().to_string(),
+            Style::new().fg(TEXT_DIM),
+        )]
+    };
 
     let spinner = app.spinner();
     let start_dir = app.start_dir.clone();
@@ -165,7 +229,9 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
 
     // Build a sorted list of (original_index, session) pairs based on sort mode
     let sessions = app.sessions.sessions();
-    let mut sorted_indices: Vec<usize> = (0..sessions.len()).collect();
+    let mut sorted_indices: Vec<usize> = (0..sessions.len())
+        .filter(|&i| app.show_archived || !sessions[i].archived)
+        .collect();
 
     match app.sort_mode {
         SortMode::List => {
@@ -212,9 +278,10 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
         }
         SortMode::Priority => {
             // Priority: permission prompts first, questions next, idle next, running last
+            let unread_first = app.unread_first;
             sorted_indices.sort_by(|&a, &b| {
-                let priority = |s: &Session| -> u8 {
-                    if s.pending_permission.is_some() {
+                let priority = |s: &Session| -> (u8, bool) {
+                    let rank = if s.pending_permission.is_some() {
                         0 // Highest priority
                     } else if s.pending_question.is_some() {
                         1
@@ -222,7 +289,9 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
                         2
                     } else {
                         3 // Running sessions last
-                    }
+                    };
+                    // Optionally unread sessions first within each rank
+                    (rank, unread_first && !s.has_unread)
                 };
                 priority(&sessions[a]).cmp(&priority(&sessions[b]))
             });
@@ -262,8 +331,15 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
                 origin_display_name(group_key)
             };
 
+            // Agent groups take their agent's color
+            let dot_color = match group_sessions.first() {
+                Some((_, _, session)) if app.sort_mode == SortMode::ByAgent => {
+                    agent_color(&session.agent_type)
+                }
+                _ => LOGO_GOLD,
+            };
             session_lines.push(Line::from(vec![
-                Span::styled("● ", Style::new().fg(LOGO_GOLD)),
+                Span::styled("● ", Style::new().fg(dot_color)),
                 Span::styled(display_name, Style::new().fg(TEXT_WHITE).bold()),
                 Span::styled(
                     format!(" ({})", group_sessions.len()),
@@ -315,7 +391,17 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
     // Update display order mapping for hotkey selection (1-9)
     app.session_display_order.display_to_internal = sorted_indices;
 
-    if session_lines.is_empty() {
+    if session_lines.is_empty() && !sessions.is_empty() {
+        // Every session is archived
+        session_lines.push(Line::styled(
+            format!("{} archived", sessions.len()),
+            Style::new().fg(TEXT_DIM),
+        ));
+        session_lines.push(Line::styled(
+            "Press [A] to show them",
+            Style::new().fg(TEXT_DIM),
+        ));
+    } else if session_lines.is_empty() {
         session_lines.push(Line::styled("No sessions", Style::new().fg(TEXT_DIM)));
         session_lines.push(Line::styled(
             "Press [n] to create one",
@@ -341,14 +427,29 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
         // Separator and header before plan
         let separator = "─".repeat(area.width.saturating_sub(1) as usize);
         plan_lines.push(Line::styled(separator, Style::new().fg(TEXT_DIM)));
-        plan_lines.push(Line::styled("Tasks", Style::new().fg(TEXT_WHITE).bold()));
+        let plan_focused = app.input_mode == InputMode::PlanFocus;
+        if plan_focused {
+            plan_lines.push(Line::from(vec![
+                Span::styled("Tasks ", Style::new().fg(TEXT_WHITE).bold()),
+                Span::styled("[j/k]", Style::new().fg(TEXT_WHITE)),
+                Span::styled(" move ", Style::new().fg(TEXT_DIM)),
+                Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+                Span::styled(" jump", Style::new().fg(TEXT_DIM)),
+            ]));
+        } else {
+            plan_lines.push(Line::from(vec![
+                Span::styled("Tasks ", Style::new().fg(TEXT_WHITE).bold()),
+                Span::styled("[p]", Style::new().fg(TEXT_DIM)),
+            ]));
+        }
         plan_lines.push(Line::raw("")); // Empty line after header
 
         // Plan entries
-        for entry in &session.plan_entries {
-            let (icon, style) = match entry.status {
+        for (entry_idx, entry) in session.plan_entries.iter().enumerate() {
+            let (icon, mut style) = match entry.status {
                 PlanStatus::Pending => ("○", Style::new().fg(TEXT_DIM)),
-                PlanStatus::InProgress => ("◐", Style::new().fg(LOGO_MINT)),
+                // Highlight the step the agent is working on
+                PlanStatus::InProgress => ("◐", Style::new().fg(LOGO_MINT).bold()),
                 PlanStatus::Completed => (
                     "●",
                     Style::new()
@@ -357,6 +458,9 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &mut App) {
                 ),
                 PlanStatus::Unknown => ("?", Style::new().fg(TEXT_DIM)),
             };
+            if plan_focused && entry_idx == app.plan_selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
 
             // Wrap content to fit sidebar (icon takes 2 chars)
             let max_width = area.width.saturating_sub(4) as usize;
@@ -0,0 +1,181 @@
+//! Snippet save and picker popup components.
+
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::tui::theme::*;
+
+/// Maximum number of snippets listed at once
+const MAX_LISTED_SNIPPETS: usize = 10;
+
+/// Popup width, also used to cut snippet previews short
+const POPUP_WIDTH: u16 = 60;
+
+/// First line of a snippet, cut to fit after `indent` columns
+fn preview(text: &str, indent: usize) -> String {
+    let first_line = text.lines().next().unwrap_or_default();
+    let room = (POPUP_WIDTH as usize).saturating_sub(indent + 4);
+    if first_line.chars().count() > room || text.lines().nth(1).is_some() {
+        let cut: String = first_line.chars().take(room.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Render a centered popup tall enough for every line, with the cursor on the
+/// first line after `cursor_column` characters.
+fn render_popup(frame: &mut Frame, area: Rect, title: &str, lines: Vec<Line>, cursor_column: u16) {
+    let popup_height = lines.len() as u16 + 2;
+    let x = area.x + (area.width.saturating_sub(POPUP_WIDTH)) / 2;
+    let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(
+        x,
+        y,
+        POPUP_WIDTH.min(area.width),
+        popup_height.min(area.height),
+    );
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::new().fg(LOGO_MINT).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_MINT))
+        .style(Style::new().bg(Color::Black));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    frame.render_widget(paragraph, popup_area);
+
+    let cursor_x = popup_area.x + 1 + cursor_column;
+    frame.set_cursor_position(Position::new(
+        cursor_x.min(popup_area.right().saturating_sub(2)),
+        popup_area.y + 1,
+    ));
+}
+
+/// Render the popup asking for the name to save the prompt under.
+pub fn render_snippet_save_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let name = app.snippet_name.as_str();
+    let replaces = app.snippets.snippets.iter().any(|s| s.name == name.trim());
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            Span::styled("Name: ", Style::new().fg(LOGO_LIGHT_BLUE)),
+            Span::styled(name, Style::new().fg(TEXT_WHITE)),
+        ]),
+        Line::raw(""),
+        Line::styled(
+            format!("  {}", preview(&app.input_buffer, 2)),
+            Style::new().fg(TEXT_DIM),
+        ),
+        Line::raw(""),
+    ];
+    if replaces {
+        lines.push(Line::styled(
+            "Replaces the snippet with this name",
+            Style::new().fg(LOGO_GOLD),
+        ));
+    }
+
+    // Footer
+    lines.push(Line::from(vec![
+        Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" save · ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    let cursor_column = 6 + name.chars().count() as u16;
+    render_popup(frame, area, " Save Snippet ", lines, cursor_column);
+}
+
+/// Render the fuzzy picker over saved snippets.
+pub fn render_snippet_picker(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(picker) = &app.snippet_picker else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = vec![];
+
+    // Filter input line
+    lines.push(Line::from(vec![
+        Span::styled("> ", Style::new().fg(LOGO_LIGHT_BLUE)),
+        Span::styled(picker.query.as_str(), Style::new().fg(TEXT_WHITE)),
+    ]));
+    lines.push(Line::raw(""));
+
+    if picker.snippets.is_empty() {
+        lines.push(Line::styled(
+            "  (no snippets yet: C-s in insert mode saves the prompt)",
+            Style::new().fg(TEXT_DIM),
+        ));
+    } else if picker.filtered.is_empty() {
+        lines.push(Line::styled(
+            "  (no matching snippets)",
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+
+    // Keep the selection visible by scrolling the window of matches
+    let start = picker
+        .selected
+        .saturating_sub(MAX_LISTED_SNIPPETS.saturating_sub(1));
+    for (i, &index) in picker
+        .filtered
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(MAX_LISTED_SNIPPETS)
+    {
+        let Some(snippet) = picker.snippets.get(index) else {
+            continue;
+        };
+        let is_selected = i == picker.selected;
+        let cursor = if is_selected { "> " } else { "  " };
+        let name_style = if is_selected {
+            Style::new().fg(TEXT_WHITE).bold()
+        } else {
+            Style::new().fg(TEXT_WHITE)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(cursor, Style::new().fg(LOGO_MINT)),
+            Span::styled(snippet.name.as_str(), name_style),
+        ]));
+        lines.push(Line::styled(
+            format!("    {}", preview(&snippet.text, 4)),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+    if picker.filtered.len() > start + MAX_LISTED_SNIPPETS {
+        lines.push(Line::styled(
+            format!(
+                "  … {} more",
+                picker.filtered.len() - start - MAX_LISTED_SNIPPETS
+            ),
+            Style::new().fg(TEXT_DIM),
+        ));
+    }
+    lines.push(Line::raw(""));
+
+    // Footer
+    lines.push(Line::from(vec![
+        Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" insert · ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[C-d]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" delete · ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
+    ]));
+
+    let cursor_column = 2 + picker.query.chars().count() as u16;
+    render_popup(frame, area, " Snippets ", lines, cursor_column);
+}
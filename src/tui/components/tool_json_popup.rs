@@ -0,0 +1,134 @@
+//! Tool call JSON inspector - one tool call's raw ACP messages, wrapped and scrollable.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, ClickRegion};
+use crate::events::Action;
+use crate::tui::interaction::InteractiveRegion;
+use crate::tui::theme::*;
+
+/// Render the JSON inspector popup over most of the screen.
+pub fn render_tool_json_popup(frame: &mut Frame, area: Rect, app: &mut App) {
+    let Some(tool_json) = &mut app.tool_json else {
+        return;
+    };
+
+    // Centered, leaving a margin of the conversation visible around it
+    let popup_width = (area.width * 4 / 5).max(40).min(area.width);
+    let popup_height = (area.height * 4 / 5).max(10).min(area.height);
+    let popup_area = Rect::new(
+        area.x + (area.width - popup_width) / 2,
+        area.y + (area.height - popup_height) / 2,
+        popup_width,
+        popup_height,
+    );
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(LOGO_LIGHT_BLUE))
+        .title(Span::styled(
+            format!(" {} ", tool_json.title.lines().next().unwrap_or_default()),
+            Style::new().fg(TEXT_WHITE).bold(),
+        ))
+        .style(Style::new().bg(Color::Black));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let layout = Layout::vertical([
+        Constraint::Min(0),    // JSON lines
+        Constraint::Length(1), // Footer hints
+    ])
+    .split(inner);
+
+    let rows: Vec<String> = tool_json
+        .lines
+        .iter()
+        .flat_map(|line| wrap_json_line(line, layout[0].width as usize))
+        .collect();
+
+    // Update viewport height and row count for scroll calculations
+    tool_json.viewport_height = layout[0].height as usize;
+    tool_json.row_count = rows.len();
+    let max_scroll = rows.len().saturating_sub(tool_json.viewport_height);
+    tool_json.scroll = tool_json.scroll.min(max_scroll);
+
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .skip(tool_json.scroll)
+        .take(tool_json.viewport_height)
+        .map(|row| Line::styled(row, Style::new().fg(TEXT_WHITE)))
+        .collect();
+    frame.render_widget(Paragraph::new(lines), layout[0]);
+
+    let position = format!(
+        "{}/{}",
+        (tool_json.scroll + tool_json.viewport_height).min(tool_json.row_count),
+        tool_json.row_count
+    );
+    let footer = Line::from(vec![
+        Span::styled(position, Style::new().fg(TEXT_DIM)),
+        Span::raw("  "),
+        Span::styled("[j/k]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" scroll  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[g/G]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" top/bottom  ", Style::new().fg(TEXT_DIM)),
+        Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
+        Span::styled(" close", Style::new().fg(TEXT_DIM)),
+    ]);
+    frame.render_widget(Paragraph::new(footer), layout[1]);
+
+    // Scrolling anywhere on screen scrolls the JSON, not the conversation below
+    let bounds = ClickRegion::new(area.x, area.y, area.width, area.height);
+    app.interactions.register(
+        InteractiveRegion::scrollable(
+            "tool_json",
+            bounds,
+            Action::ToolJsonScrollUp(3),
+            Action::ToolJsonScrollDown(3),
+        )
+        .with_priority(10),
+    );
+}
+
+/// Split a line into rows of at most `width` chars, continuing under its own
+/// indentation so nesting stays readable
+fn wrap_json_line(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= width || width == 0 {
+        return vec![line.to_string()];
+    }
+    // Keep at least half the width for content on continuation rows
+    let indent = chars
+        .iter()
+        .take_while(|c| **c == ' ')
+        .count()
+        .min(width / 2);
+    let mut rows = vec![chars[..width].iter().collect::<String>()];
+    for chunk in chars[width..].chunks(width - indent) {
+        rows.push(" ".repeat(indent) + &chunk.iter().collect::<String>());
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_json_line() {
+        assert_eq!(wrap_json_line("  \"a\": 1", 20), vec!["  \"a\": 1"]);
+        assert_eq!(
+            wrap_json_line("  \"command\": \"cargo test\"", 12),
+            vec!["  \"command\":", "   \"cargo te", "  st\""]
+        );
+        assert_eq!(wrap_json_line("abcdef", 0), vec!["abcdef"]);
+    }
+}
@@ -106,13 +106,13 @@ pub fn render_worktree_cleanup(frame: &mut Frame, area: Rect, app: &App) {
 
                 let name_style = if is_cursor {
                     Style::new().fg(TEXT_WHITE).bold()
-                } else if entry.is_clean && entry.is_merged {
+                } else if entry.is_clean && (entry.is_merged || entry.is_experiment) {
                     Style::new().fg(LOGO_MINT)
                 } else {
                     Style::new().fg(TEXT_DIM)
                 };
 
-                lines.push(Line::from(vec![
+                let mut spans = vec![
                     Span::styled(cursor, Style::new().fg(TEXT_WHITE)),
                     Span::styled(
                         checkbox,
@@ -125,7 +125,21 @@ pub fn render_worktree_cleanup(frame: &mut Frame, area: Rect, app: &App) {
                     Span::styled(clean_icon, Style::new().fg(clean_color)),
                     Span::styled(merged_icon, Style::new().fg(merged_color)),
                     Span::styled(display_name, name_style),
-                ]));
+                ];
+                if entry.is_experiment {
+                    spans.push(Span::styled(" experiment", Style::new().fg(LOGO_GOLD)));
+                }
+
+                // Why the last deletion failed (git's message, e.g. a locked worktree)
+                if let Some(error) = &entry.error {
+                    let reason = error.lines().next().unwrap_or_default();
+                    spans.push(Span::styled(
+                        format!("  ✗ {}", reason),
+                        Style::new().fg(LOGO_CORAL),
+                    ));
+                }
+
+                lines.push(Line::from(spans));
             }
         }
 
@@ -156,6 +170,27 @@ pub fn render_worktree_cleanup(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled(" Delete branches too ", Style::new().fg(TEXT_DIM)),
             Span::styled("[b]", Style::new().fg(TEXT_WHITE)),
         ]));
+        let force_checkbox = if cleanup.force { "[x]" } else { "[ ]" };
+        lines.push(Line::from(vec![
+            Span::styled("  ", Style::new()),
+            Span::styled(
+                force_checkbox,
+                if cleanup.force {
+                    Style::new().fg(LOGO_CORAL)
+                } else {
+                    Style::new().fg(TEXT_DIM)
+                },
+            ),
+            Span::styled(" Force delete ", Style::new().fg(TEXT_DIM)),
+            Span::styled("[f]", Style::new().fg(TEXT_WHITE)),
+        ]));
+        if cleanup.force {
+            // Dirty entries are never selected by [a], only one by one
+            lines.push(Line::styled(
+                "  ⚠ Uncommitted changes and unmerged branches of selected worktrees will be lost",
+                Style::new().fg(LOGO_CORAL).bold(),
+            ));
+        }
 
         // Legend
         lines.push(Line::raw(""));
@@ -173,7 +208,7 @@ pub fn render_worktree_cleanup(frame: &mut Frame, area: Rect, app: &App) {
 
         // Help text
         lines.push(Line::raw(""));
-        lines.push(Line::from(vec![
+        let mut help = vec![
             Span::styled("[Space]", Style::new().fg(TEXT_WHITE)),
             Span::styled(" toggle · ", Style::new().fg(TEXT_DIM)),
             Span::styled("[a]", Style::new().fg(TEXT_WHITE)),
@@ -182,9 +217,14 @@ pub fn render_worktree_cleanup(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled(" none · ", Style::new().fg(TEXT_DIM)),
             Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
             Span::styled(" cleanup · ", Style::new().fg(TEXT_DIM)),
-            Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)),
-            Span::styled(" cancel", Style::new().fg(TEXT_DIM)),
-        ]));
+        ];
+        if cleanup.failed_count() > 0 {
+            help.push(Span::styled("[r]", Style::new().fg(TEXT_WHITE)));
+            help.push(Span::styled(" retry failed · ", Style::new().fg(TEXT_DIM)));
+        }
+        help.push(Span::styled("[Esc]", Style::new().fg(TEXT_WHITE)));
+        help.push(Span::styled(" cancel", Style::new().fg(TEXT_DIM)));
+        lines.push(Line::from(help));
     }
 
     let paragraph = Paragraph::new(lines).style(Style::new().fg(TEXT_WHITE));
@@ -12,7 +12,7 @@ use crate::app::App;
 use crate::tui::theme::*;
 
 /// Render the worktree picker as a centered popup.
-pub fn render_worktree_picker(frame: &mut Frame, area: Rect, app: &App) {
+pub fn render_worktree_picker(frame: &mut Frame, area: Rect, app: &mut App) {
     // Calculate centered popup area
     let popup_width = 60u16.min(area.width.saturating_sub(4));
     let popup_height = 18u16.min(area.height.saturating_sub(4));
@@ -20,6 +20,13 @@ pub fn render_worktree_picker(frame: &mut Frame, area: Rect, app: &App) {
     let y = area.y + (area.height.saturating_sub(popup_height)) / 2;
     let popup_area = Rect::new(x, y, popup_width, popup_height);
 
+    // Calculate how many entries we can show
+    let available_height = popup_height.saturating_sub(9) as usize; // title, header, spacing, help, legend
+    if let Some(picker) = &mut app.worktree_picker {
+        // Update viewport height for paging
+        picker.viewport_height = available_height;
+    }
+
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
 
@@ -40,9 +47,6 @@ pub fn render_worktree_picker(frame: &mut Frame, area: Rect, app: &App) {
             .filter(|e| !e.is_create_new && e.is_clean && e.is_merged)
             .count();
 
-        // Calculate how many entries we can show
-        let available_height = popup_height.saturating_sub(9) as usize; // title, header, spacing, help, legend
-
         // Calculate scroll offset to keep selected item visible
         let total_entries = picker.entries.len();
         let selected = picker.selected;
@@ -133,8 +137,16 @@ pub fn render_worktree_picker(frame: &mut Frame, area: Rect, app: &App) {
             lines.push(Line::raw(""));
         }
 
-        // Help text
-        lines.push(Line::raw(""));
+        // Help text, preceded by a warning when confirming a dirty worktree
+        if picker.confirm_dirty {
+            lines.push(Line::from(vec![
+                Span::styled("⚠ Uncommitted changes · ", Style::new().fg(LOGO_CORAL)),
+                Span::styled("[Enter]", Style::new().fg(TEXT_WHITE)),
+                Span::styled(" open anyway", Style::new().fg(TEXT_DIM)),
+            ]));
+        } else {
+            lines.push(Line::raw(""));
+        }
         let mut help_spans = vec![
             Span::styled("[↑/↓]", Style::new().fg(TEXT_WHITE)),
             Span::styled(" navigate · ", Style::new().fg(TEXT_DIM)),
@@ -16,6 +16,7 @@
 //!     on_click: Some(Action::SelectSession(0)),
 //!     on_scroll_up: None,
 //!     on_scroll_down: None,
+//!     on_hover: None,
 //!     priority: 0,
 //! });
 //!
@@ -45,6 +46,9 @@ pub struct InteractiveRegion {
     /// Action to dispatch on scroll down (None = not scrollable)
     pub on_scroll_down: Option<Action>,
 
+    /// Action to dispatch when the mouse moves over the region (None = no hover effect)
+    pub on_hover: Option<Action>,
+
     /// Priority for overlapping regions (higher = checked first)
     /// Use this for popups/dialogs that should capture clicks over underlying content
     pub priority: i32,
@@ -59,6 +63,7 @@ impl InteractiveRegion {
             on_click: Some(action),
             on_scroll_up: None,
             on_scroll_down: None,
+            on_hover: None,
             priority: 0,
         }
     }
@@ -76,6 +81,7 @@ impl InteractiveRegion {
             on_click: None,
             on_scroll_up: Some(scroll_up),
             on_scroll_down: Some(scroll_down),
+            on_hover: None,
             priority: 0,
         }
     }
@@ -94,10 +100,17 @@ impl InteractiveRegion {
             on_click: Some(on_click),
             on_scroll_up: Some(scroll_up),
             on_scroll_down: Some(scroll_down),
+            on_hover: None,
             priority: 0,
         }
     }
 
+    /// Set the action dispatched on mouse hover (for builder pattern)
+    pub fn with_hover(mut self, action: Action) -> Self {
+        self.on_hover = Some(action);
+        self
+    }
+
     /// Set the priority (for builder pattern)
     pub fn with_priority(mut self, priority: i32) -> Self {
         self.priority = priority;
@@ -179,7 +192,7 @@ impl InteractionRegistry {
             .filter(|r| r.contains(x, y) && r.on_click.is_some())
             .collect();
 
-        candidates.sort_by(|a, b| b.priority.cmp(&a.priority));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.priority));
 
         candidates
             .first()
@@ -195,7 +208,7 @@ impl InteractionRegistry {
             .filter(|r| r.contains(x, y) && r.on_scroll_up.is_some())
             .collect();
 
-        candidates.sort_by(|a, b| b.priority.cmp(&a.priority));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.priority));
 
         candidates
             .first()
@@ -203,6 +216,22 @@ impl InteractionRegistry {
             .unwrap_or(Action::None)
     }
 
+    /// Find the action to dispatch for the mouse moving to (x, y)
+    pub fn handle_hover(&self, x: u16, y: u16) -> Action {
+        let mut candidates: Vec<_> = self
+            .regions
+            .iter()
+            .filter(|r| r.contains(x, y) && r.on_hover.is_some())
+            .collect();
+
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.priority));
+
+        candidates
+            .first()
+            .and_then(|r| r.on_hover.clone())
+            .unwrap_or(Action::None)
+    }
+
     /// Find the action to dispatch for a scroll down at (x, y)
     pub fn handle_scroll_down(&self, x: u16, y: u16) -> Action {
         let mut candidates: Vec<_> = self
@@ -211,7 +240,7 @@ impl InteractionRegistry {
             .filter(|r| r.contains(x, y) && r.on_scroll_down.is_some())
             .collect();
 
-        candidates.sort_by(|a, b| b.priority.cmp(&a.priority));
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.priority));
 
         candidates
             .first()
@@ -268,4 +297,32 @@ mod tests {
         // Click outside popup should return background's action
         assert!(matches!(registry.handle_click(5, 5), Action::ScrollToTop));
     }
+
+    #[test]
+    fn test_hover_only_hits_hover_regions() {
+        let mut registry = InteractionRegistry::new();
+        registry.register(
+            InteractiveRegion::clickable(
+                "option",
+                ClickRegion::new(0, 5, 40, 1),
+                Action::SelectPermissionOption(2),
+            )
+            .with_hover(Action::HoverPermissionOption(2)),
+        );
+        registry.register_click(
+            "button",
+            ClickRegion::new(0, 8, 10, 1),
+            Action::AllowPermission,
+        );
+
+        assert!(matches!(
+            registry.handle_hover(10, 5),
+            Action::HoverPermissionOption(2)
+        ));
+        assert!(matches!(registry.handle_hover(3, 8), Action::None));
+        assert!(matches!(
+            registry.handle_click(3, 8),
+            Action::AllowPermission
+        ));
+    }
 }
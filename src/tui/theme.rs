@@ -1,5 +1,7 @@
 use ratatui::style::Color;
 
+use crate::session::AgentType;
+
 // Logo colors (circumflex-inspired)
 pub const LOGO_CORAL: Color = Color::Rgb(232, 131, 136); // #E88388
 pub const LOGO_GOLD: Color = Color::Rgb(219, 171, 121); // #DBAB79
@@ -16,7 +18,73 @@ pub const DIFF_ADD_BG: Color = Color::Rgb(35, 60, 35); // Dark green background
 pub const DIFF_ADD_FG: Color = Color::Rgb(130, 200, 130); // Light green text
 pub const DIFF_REMOVE_BG: Color = Color::Rgb(70, 35, 35); // Dark red background
 pub const DIFF_REMOVE_FG: Color = Color::Rgb(230, 130, 130); // Light red text
+pub const DIFF_ADD_WORD_BG: Color = Color::Rgb(55, 105, 55); // Brighter green behind changed words
+pub const DIFF_REMOVE_WORD_BG: Color = Color::Rgb(125, 50, 50); // Brighter red behind changed words
 
 // Tool output colors
 pub const TOOL_DOT: Color = Color::Rgb(161, 193, 129); // Green dot for tools (same as LOGO_MINT)
 pub const TOOL_CONNECTOR: Color = Color::Rgb(100, 100, 100); // Dim connector └
+
+// Agent glyph colors in the session list
+pub const AGENT_CLAUDE: Color = Color::Rgb(217, 119, 87); // #D97757 Claude orange
+pub const AGENT_GEMINI: Color = Color::Rgb(138, 180, 248); // #8AB4F8 Gemini blue
+/// Custom agents get one of these, picked by their name
+pub const AGENT_CUSTOM: [Color; 4] = [
+    LOGO_MINT,
+    LOGO_GOLD,
+    Color::Rgb(198, 160, 246), // #C6A0F6 lavender
+    Color::Rgb(125, 196, 180), // #7DC4B4 teal
+];
+
+/// Color of an agent's glyph, stable across runs for custom agents
+pub fn agent_color(agent_type: &AgentType) -> Color {
+    match agent_type {
+        AgentType::ClaudeCode => AGENT_CLAUDE,
+        AgentType::GeminiCli => AGENT_GEMINI,
+        AgentType::Custom(agent) => {
+            // FNV-1a rather than the std hasher, whose output may change between releases
+            let hash = agent.name.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+                (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+            });
+            AGENT_CUSTOM[hash as usize % AGENT_CUSTOM.len()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::session::CustomAgent;
+
+    fn custom(name: &str, command: &str) -> AgentType {
+        AgentType::Custom(Arc::new(CustomAgent {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: vec![],
+            env: HashMap::new(),
+        }))
+    }
+
+    #[test]
+    fn test_agent_color_is_stable() {
+        assert_eq!(agent_color(&AgentType::ClaudeCode), AGENT_CLAUDE);
+        assert_eq!(agent_color(&AgentType::GeminiCli), AGENT_GEMINI);
+
+        // Pinned so a change of hash shows up as a change of colors
+        assert_eq!(agent_color(&custom("aider", "aider")), AGENT_CUSTOM[2]);
+        assert_eq!(agent_color(&custom("goose", "goose")), AGENT_CUSTOM[0]);
+        assert_eq!(
+            agent_color(&custom("In-house", "my-agent")),
+            AGENT_CUSTOM[3]
+        );
+
+        // Only the name counts
+        assert_eq!(
+            agent_color(&custom("aider", "aider")),
+            agent_color(&custom("aider", "/opt/bin/aider"))
+        );
+    }
+}
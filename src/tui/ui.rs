@@ -9,14 +9,18 @@ use crate::app::{App, InputMode};
 
 // Re-export components for external use
 pub use super::components::{
-    render_agent_picker, render_branch_input, render_bug_report_popup, render_clear_confirm_popup,
-    render_conversation_view, render_folder_picker, render_help_popup, render_horizontal_separator,
-    render_logo, render_permission_dialog, render_prompt, render_question_dialog, render_separator,
-    render_session_list, render_session_picker, render_worktree_cleanup, render_worktree_picker,
+    render_agent_picker, render_branch_input, render_broadcast_popup, render_bug_report_popup,
+    render_clear_confirm_popup, render_completion_popup, render_conversation_view,
+    render_diff_view, render_folder_picker, render_follow_ups, render_help_popup,
+    render_horizontal_separator, render_large_prompt_popup, render_log_view, render_logo,
+    render_mcp_manager, render_permission_dialog, render_prompt, render_question_dialog,
+    render_quit_confirm_popup, render_separator, render_session_limit_popup, render_session_list,
+    render_session_picker, render_session_switcher, render_snippet_picker,
+    render_snippet_save_popup, render_tool_json_popup, render_worktree_cleanup,
+    render_worktree_picker,
 };
 
 // Layout constants
-const SIDEBAR_WIDTH: u16 = 40;
 const SIDEBAR_LEFT_PADDING: u16 = 1;
 const SEPARATOR_WIDTH: u16 = 1;
 const CONTENT_LEFT_PADDING: u16 = 1;
@@ -32,37 +36,44 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
 
     // Horizontal split: sidebar | left padding | separator | content left padding | main content | content right padding
+    // In zen mode the sidebar, its padding and the separator take no width
+    let sidebar_width = |width: u16| if app.zen_mode { 0 } else { width };
     let content_layout = Layout::horizontal([
-        Constraint::Length(SIDEBAR_WIDTH),
-        Constraint::Length(SIDEBAR_LEFT_PADDING),
-        Constraint::Length(SEPARATOR_WIDTH),
+        Constraint::Length(sidebar_width(app.sidebar_width.columns(area.width))),
+        Constraint::Length(sidebar_width(SIDEBAR_LEFT_PADDING)),
+        Constraint::Length(sidebar_width(SEPARATOR_WIDTH)),
         Constraint::Length(CONTENT_LEFT_PADDING),
         Constraint::Min(0), // Main content
         Constraint::Length(CONTENT_RIGHT_PADDING),
     ])
     .split(area);
 
-    // Sidebar with 1-char padding on left/right, no top padding
-    let sidebar_outer = content_layout[0];
-    let sidebar_inner = Rect {
-        x: sidebar_outer.x + SIDEBAR_INNER_PADDING,
-        y: sidebar_outer.y,
-        width: sidebar_outer.width.saturating_sub(BORDER_WIDTH),
-        height: sidebar_outer.height,
-    };
+    if !app.zen_mode {
+        // Sidebar with 1-char padding on left/right, no top padding
+        let sidebar_outer = content_layout[0];
+        let sidebar_inner = Rect {
+            x: sidebar_outer.x + SIDEBAR_INNER_PADDING,
+            y: sidebar_outer.y,
+            width: sidebar_outer.width.saturating_sub(BORDER_WIDTH),
+            height: sidebar_outer.height,
+        };
 
-    // Sidebar: logo + session list (includes hotkeys and plan at bottom)
-    let sidebar_layout = Layout::vertical([
-        Constraint::Length(1), // Logo (single line)
-        Constraint::Min(0),    // Session list + hotkeys + plan
-    ])
-    .split(sidebar_inner);
+        // Sidebar: logo + session list (includes hotkeys and plan at bottom)
+        let sidebar_layout = Layout::vertical([
+            Constraint::Length(1), // Logo (single line)
+            Constraint::Min(0),    // Session list + hotkeys + plan
+        ])
+        .split(sidebar_inner);
 
-    // Render logo at top of sidebar
-    render_logo(frame, sidebar_layout[0]);
+        // Render logo at top of sidebar
+        render_logo(frame, sidebar_layout[0]);
 
-    // Render session list with hotkeys and plan at bottom
-    render_session_list(frame, sidebar_layout[1], app);
+        // Render session list with hotkeys and plan at bottom
+        render_session_list(frame, sidebar_layout[1], app);
+
+        // Render vertical separator
+        render_separator(frame, content_layout[2]);
+    }
 
     // Check if there's a pending permission or question
     let has_permission = app
@@ -75,9 +86,6 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         .map(|s| s.pending_question.is_some())
         .unwrap_or(false);
 
-    // Render vertical separator
-    render_separator(frame, content_layout[2]);
-
     // Calculate input bar height based on content wrapping
     let input_area_width = content_layout[4].width.saturating_sub(2) as usize; // Account for prompt "> "
     let input_height = if has_permission || has_question {
@@ -159,9 +167,14 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     } else if has_question {
         render_question_dialog(frame, right_layout[1], app);
     } else {
-        // Render horizontal separator (index 1 is empty, 2 is separator, 3 is empty, 4 is input)
+        // Render horizontal separator (index 1 is empty or holds follow-up
+        // chips, 2 is separator, 3 is empty, 4 is input)
+        render_follow_ups(frame, right_layout[1], app);
         render_horizontal_separator(frame, right_layout[2]);
         render_prompt(frame, right_layout[4], app);
+        if app.input_mode == InputMode::Insert {
+            render_completion_popup(frame, right_layout[4], app);
+        }
     }
 
     // === Popup overlays (rendered on top of everything) ===
@@ -189,13 +202,66 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_bug_report_popup(frame, area, app);
     }
 
+    // Render large prompt confirmation popup on top
+    if app.input_mode == InputMode::ConfirmLargePrompt {
+        render_large_prompt_popup(frame, area, app);
+    }
+
+    // Render broadcast prompt popup on top
+    if app.input_mode == InputMode::Broadcast {
+        render_broadcast_popup(frame, area, app);
+    }
+
+    // Render session quick switcher on top
+    if app.input_mode == InputMode::SessionSwitcher {
+        render_session_switcher(frame, area, app);
+    }
+
     // Render clear session confirmation popup on top if in ClearConfirm mode
     if app.input_mode == InputMode::ClearConfirm {
         render_clear_confirm_popup(frame, area, app);
     }
 
+    // Render quit confirmation popup on top if in QuitConfirm mode
+    if app.input_mode == InputMode::QuitConfirm {
+        render_quit_confirm_popup(frame, area, app);
+    }
+
+    // Render snippet popups on top
+    if app.input_mode == InputMode::SnippetSave {
+        render_snippet_save_popup(frame, area, app);
+    }
+    if app.input_mode == InputMode::SnippetPicker {
+        render_snippet_picker(frame, area, app);
+    }
+
+    // Render session limit confirmation popup on top
+    if app.input_mode == InputMode::SessionLimitConfirm {
+        render_session_limit_popup(frame, area, app);
+    }
+
     // Render worktree picker popup on top
     if app.input_mode == InputMode::WorktreePicker {
         render_worktree_picker(frame, area, app);
     }
+
+    // Render side-by-side diff view over the whole screen
+    if app.input_mode == InputMode::DiffView {
+        render_diff_view(frame, area, app);
+    }
+
+    // Render MCP server manager popup on top
+    if app.input_mode == InputMode::McpManager {
+        render_mcp_manager(frame, area, app);
+    }
+
+    // Render log viewer over the whole screen
+    if app.input_mode == InputMode::LogView {
+        render_log_view(frame, area, app);
+    }
+
+    // Render tool call JSON inspector popup on top
+    if app.input_mode == InputMode::ToolJson {
+        render_tool_json_popup(frame, area, app);
+    }
 }
@@ -0,0 +1,100 @@
+//! Persisted UI preferences.
+//!
+//! Small view toggles changed from inside the TUI are remembered across
+//! restarts in `~/.amux/ui_state.json`. Unlike the config file this is written
+//! by amux itself whenever one of the preferences changes.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::SortMode;
+use crate::{log, state_file};
+
+/// UI preferences restored on startup
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Show raw tool call JSON ([t])
+    pub debug_tool_json: bool,
+    /// Session list sort mode ([v])
+    pub sort_mode: SortMode,
+    /// Collapse tool calls to one line ([z])
+    pub collapse_tools: bool,
+    /// Prefix output entries with the time they arrived ([T])
+    pub show_timestamps: bool,
+    /// Show tool call paths in full instead of relative to the session ([P])
+    pub absolute_paths: bool,
+}
+
+impl UiState {
+    /// Path of the state file
+    pub fn path() -> PathBuf {
+        state_file::path("ui_state.json")
+    }
+
+    /// Load the saved preferences, falling back to defaults if missing or unreadable
+    pub fn load() -> Self {
+        Self::load_from(&Self::path())
+    }
+
+    fn load_from(path: &Path) -> Self {
+        state_file::load(path, "UI state")
+    }
+
+    /// Save the preferences, logging (but otherwise ignoring) failures
+    pub fn save(&self) {
+        if let Err(e) = state_file::save(self, &Self::path()) {
+            log::warn(&format!("Failed to save UI state: {}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        state_file::temp_path("ui_state", name)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = temp_path("round_trip.json");
+        let state = UiState {
+            debug_tool_json: true,
+            sort_mode: SortMode::Priority,
+            collapse_tools: true,
+            show_timestamps: true,
+            absolute_paths: true,
+        };
+        state_file::save(&state, &path).unwrap();
+        assert_eq!(UiState::load_from(&path), state);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_or_invalid_file_uses_defaults() {
+        assert_eq!(
+            UiState::load_from(&temp_path("missing.json")),
+            UiState::default()
+        );
+
+        let path = temp_path("invalid.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(UiState::load_from(&path), UiState::default());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let path = temp_path("partial.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{ "sort_mode": "ByName" }"#).unwrap();
+        let state = UiState::load_from(&path);
+        assert_eq!(state.sort_mode, SortMode::ByName);
+        assert!(!state.debug_tool_json);
+        let _ = std::fs::remove_file(&path);
+    }
+}